@@ -0,0 +1,48 @@
+//! Full BFS over a random graph with 1M nodes and 8M edges, adjacency-list
+//! `Graph` against `CsrGraph`. Run with `cargo bench --bench csr_bfs`.
+
+use std::time::Instant;
+
+use graph_traversal::sampling::Rng;
+use graph_traversal::{BfsIterator, CsrGraph, Graph, NodeId};
+
+const NODES: usize = 1_000_000;
+const EDGES_PER_NODE: usize = 8;
+const ROUNDS: usize = 5;
+
+/// Milliseconds per round of `run`, best of `ROUNDS`
+fn time(mut run: impl FnMut() -> usize) -> (f64, usize) {
+    let mut best = f64::INFINITY;
+    let mut visited = 0;
+    for _ in 0..ROUNDS {
+        let started = Instant::now();
+        visited = std::hint::black_box(run());
+        best = best.min(started.elapsed().as_secs_f64() * 1e3);
+    }
+    (best, visited)
+}
+
+fn main() {
+    let mut rng = Rng::new(7);
+    let edges: Vec<(usize, usize)> = (0..NODES * EDGES_PER_NODE)
+        .map(|i| (i / EDGES_PER_NODE, rng.below(NODES)))
+        .collect();
+    let graph = Graph::bulk_from_edges(edges.iter().copied());
+    let csr = CsrGraph::from_graph(&graph);
+    let start = NodeId::new(0);
+
+    let (list_ms, list_visited) =
+        time(|| BfsIterator::with_visited(&graph, start, vec![false; NODES]).count());
+    let (csr_ms, csr_visited) =
+        time(|| BfsIterator::with_visited(&csr, start, vec![false; NODES]).count());
+    assert_eq!(list_visited, csr_visited);
+
+    println!("{:<14} {:>10}", "layout", "bfs ms");
+    println!("{:<14} {:>10.1}", "adjacency list", list_ms);
+    println!("{:<14} {:>10.1}", "csr", csr_ms);
+    println!(
+        "speedup {:.2}x over {} nodes",
+        list_ms / csr_ms,
+        csr_visited
+    );
+}
@@ -0,0 +1,103 @@
+//! Criterion suite for BFS, DFS and NextSmallest over random and grid graphs
+//! of 1e3 to 1e6 nodes, each with `HashSet` and `BitVisited` visited sets on
+//! the adjacency-list `Graph` and on `CsrGraph`. Run with
+//! `cargo bench --bench traversals`; Criterion keeps the last results under
+//! `target/criterion` and reports changes against them.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use graph_traversal::sampling::Rng;
+use graph_traversal::{
+    BfsIterator, BitVisited, CsrGraph, DfsIterator, Graph, NextSmallestIterator, NodeId, VisitedSet,
+};
+
+const SIZES: [usize; 4] = [1_000, 10_000, 100_000, 1_000_000];
+const EDGES_PER_NODE: usize = 8;
+
+/// `n` nodes, each with `EDGES_PER_NODE` out-edges to uniformly random nodes
+fn random_graph(n: usize) -> Graph {
+    let mut rng = Rng::new(7);
+    let edges: Vec<(usize, usize)> = (0..n * EDGES_PER_NODE)
+        .map(|i| (i / EDGES_PER_NODE, rng.below(n)))
+        .collect();
+    Graph::bulk_from_edges(edges.iter().copied())
+}
+
+/// Square `Graph::grid` of about `n` nodes
+fn grid_graph(n: usize) -> Graph {
+    let side = (n as f64).sqrt().round() as usize;
+    Graph::grid(side, side)
+}
+
+#[derive(Clone, Copy)]
+enum Order {
+    Bfs,
+    Dfs,
+    NextSmallest,
+}
+
+/// Nodes reached from node 0 by `order` on `graph`, with visited sets from
+/// `visited`
+fn run<G, S>(order: Order, graph: &G, visited: S) -> usize
+where
+    G: graph_traversal::GraphBase<Value = i32>,
+    S: VisitedSet,
+{
+    let start = NodeId::new(0);
+    match order {
+        Order::Bfs => BfsIterator::with_visited(graph, start, visited).count(),
+        Order::Dfs => DfsIterator::with_visited(graph, start, visited).count(),
+        Order::NextSmallest => NextSmallestIterator::with_visited(graph, start, visited).count(),
+    }
+}
+
+fn traversals(c: &mut Criterion) {
+    let orders = [
+        ("bfs", Order::Bfs),
+        ("dfs", Order::Dfs),
+        ("next_smallest", Order::NextSmallest),
+    ];
+    for shape in ["random", "grid"] {
+        // Built once per shape, as the 1e6-node graphs take a while
+        let graphs: Vec<(usize, Graph, CsrGraph)> = SIZES
+            .into_iter()
+            .map(|n| {
+                let graph = match shape {
+                    "random" => random_graph(n),
+                    _ => grid_graph(n),
+                };
+                let csr = CsrGraph::from_graph(&graph);
+                (n, graph, csr)
+            })
+            .collect();
+        for (name, order) in orders {
+            let mut group = c.benchmark_group(format!("{}/{}", name, shape));
+            group.sample_size(10);
+            group.warm_up_time(Duration::from_millis(500));
+            group.measurement_time(Duration::from_secs(2));
+            for (n, graph, csr) in &graphs {
+                let n = *n;
+                let len = graph.len();
+                group.throughput(Throughput::Elements(graph.edge_count() as u64));
+                group.bench_with_input(BenchmarkId::new("list/HashSet", n), graph, |b, g| {
+                    b.iter(|| run(order, g, HashSet::new()))
+                });
+                group.bench_with_input(BenchmarkId::new("list/BitVisited", n), graph, |b, g| {
+                    b.iter(|| run(order, g, BitVisited::with_len(len)))
+                });
+                group.bench_with_input(BenchmarkId::new("csr/HashSet", n), csr, |b, g| {
+                    b.iter(|| run(order, g, HashSet::new()))
+                });
+                group.bench_with_input(BenchmarkId::new("csr/BitVisited", n), csr, |b, g| {
+                    b.iter(|| run(order, g, BitVisited::with_len(len)))
+                });
+            }
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, traversals);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+//! Full BFS, DFS and NextSmallest over a random graph with 1M nodes and 8M
+//! edges under each visited-set strategy. Run with `cargo bench --bench visited`.
+
+use std::collections::HashSet;
+use std::time::Instant;
+
+use graph_traversal::sampling::Rng;
+use graph_traversal::{
+    BfsIterator, BitVisited, DfsIterator, Graph, NextSmallestIterator, NodeId, VisitedSet,
+};
+
+const NODES: usize = 1_000_000;
+const EDGES_PER_NODE: usize = 8;
+const ROUNDS: usize = 3;
+
+/// Milliseconds per round of `run`, best of `ROUNDS`, and what it returned
+fn time(mut run: impl FnMut() -> usize) -> (f64, usize) {
+    let mut best = f64::INFINITY;
+    let mut visited = 0;
+    for _ in 0..ROUNDS {
+        let started = Instant::now();
+        visited = std::hint::black_box(run());
+        best = best.min(started.elapsed().as_secs_f64() * 1e3);
+    }
+    (best, visited)
+}
+
+/// Milliseconds for each traversal with visited sets from `visited`
+fn row<S: VisitedSet>(graph: &Graph, visited: impl Fn() -> S) -> [(f64, usize); 3] {
+    let start = NodeId::new(0);
+    [
+        time(|| BfsIterator::with_visited(graph, start, visited()).count()),
+        time(|| DfsIterator::with_visited(graph, start, visited()).count()),
+        time(|| NextSmallestIterator::with_visited(graph, start, visited()).count()),
+    ]
+}
+
+fn main() {
+    let mut rng = Rng::new(7);
+    let edges: Vec<(usize, usize)> = (0..NODES * EDGES_PER_NODE)
+        .map(|i| (i / EDGES_PER_NODE, rng.below(NODES)))
+        .collect();
+    let graph = Graph::bulk_from_edges(edges.iter().copied());
+
+    let rows = [
+        ("HashSet", row(&graph, HashSet::new)),
+        ("Vec<bool>", row(&graph, || vec![false; NODES])),
+        ("BitVisited", row(&graph, || BitVisited::with_len(NODES))),
+    ];
+    println!(
+        "{:<12} {:>10} {:>10} {:>18}",
+        "visited", "bfs ms", "dfs ms", "next-smallest ms"
+    );
+    for (name, [bfs, dfs, next_smallest]) in &rows {
+        assert_eq!(
+            (bfs.1, dfs.1, next_smallest.1),
+            (rows[0].1[0].1, rows[0].1[1].1, rows[0].1[2].1)
+        );
+        println!(
+            "{:<12} {:>10.1} {:>10.1} {:>18.1}",
+            name, bfs.0, dfs.0, next_smallest.0
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Python bindings for `graph_traversal`: a `Graph` of integer-valued nodes
+//! built from Python, and its BFS, DFS, IDDFS and NextSmallest traversals as
+//! Python iterators over node indices, each driving the Rust iterator.
+
+use std::sync::Arc;
+
+use graph_traversal::{
+    BfsIterator, DfsIterator, Graph, GraphError, IddfsIterator, NextSmallestIterator, NodeId,
+    Traversal,
+};
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use self_cell::self_cell;
+
+fn to_py_err(error: GraphError) -> PyErr {
+    match error {
+        GraphError::NodeOutOfRange { .. } => PyIndexError::new_err(error.to_string()),
+        _ => PyValueError::new_err(error.to_string()),
+    }
+}
+
+/// Directed graph with an integer value per node. Iterators work on the
+/// graph as it was when they were created; changing the graph afterwards
+/// copies it rather than disturbing them.
+#[pyclass(name = "Graph")]
+#[derive(Default)]
+struct PyGraph {
+    graph: Arc<Graph>,
+}
+
+#[pymethods]
+impl PyGraph {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Graph with nodes valued 0..n and the given (from, to) edges
+    #[staticmethod]
+    fn from_edges(n: usize, edges: Vec<(usize, usize)>) -> PyResult<Self> {
+        let mut graph = Graph::new();
+        for value in 0..n {
+            let value = i32::try_from(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            graph.add_node(value);
+        }
+        for (from, to) in edges {
+            graph
+                .add_edge(NodeId::new(from), NodeId::new(to))
+                .map_err(to_py_err)?;
+        }
+        Ok(PyGraph {
+            graph: Arc::new(graph),
+        })
+    }
+
+    /// Parse "from to" lines, as `Graph::from_edge_list` does
+    #[staticmethod]
+    #[pyo3(signature = (text, undirected = false))]
+    fn from_edge_list(text: &str, undirected: bool) -> PyResult<Self> {
+        let graph = Graph::from_edge_list(text, undirected)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyGraph {
+            graph: Arc::new(graph),
+        })
+    }
+
+    /// Append a node and return its index
+    fn add_node(&mut self, value: i32) -> usize {
+        Arc::make_mut(&mut self.graph).add_node(value).index()
+    }
+
+    /// Add a directed edge from -> to
+    fn add_edge(&mut self, from: usize, to: usize) -> PyResult<()> {
+        Arc::make_mut(&mut self.graph)
+            .add_edge(NodeId::new(from), NodeId::new(to))
+            .map_err(to_py_err)
+    }
+
+    fn __len__(&self) -> usize {
+        self.graph.len()
+    }
+
+    fn value(&self, node: usize) -> PyResult<i32> {
+        let id = self.checked(node)?;
+        Ok(*self.graph[id].value())
+    }
+
+    /// Out-neighbors of `node` in insertion order
+    fn neighbors(&self, node: usize) -> PyResult<Vec<usize>> {
+        let id = self.checked(node)?;
+        Ok(self.graph[id].neighbors().map(NodeId::index).collect())
+    }
+
+    /// Every edge as (from, to), in node order
+    fn edges(&self) -> Vec<(usize, usize)> {
+        self.graph
+            .edges()
+            .map(|(from, to)| (from.index(), to.index()))
+            .collect()
+    }
+
+    /// Breadth-first traversal from `start`, optionally bounded
+    #[pyo3(signature = (start, max_depth = None, max_nodes = None))]
+    fn bfs(
+        &self,
+        start: usize,
+        max_depth: Option<usize>,
+        max_nodes: Option<usize>,
+    ) -> PyResult<Bfs> {
+        let start = self.checked(start)?;
+        let cell = BfsCell::new(Arc::clone(&self.graph), |graph| {
+            let mut bfs = BfsIterator::new(&**graph, start);
+            if let Some(max_depth) = max_depth {
+                bfs = bfs.max_depth(max_depth);
+            }
+            if let Some(max_nodes) = max_nodes {
+                bfs = bfs.max_nodes(max_nodes);
+            }
+            bfs
+        });
+        Ok(Bfs { cell })
+    }
+
+    /// Depth-first traversal from `start`, optionally bounded
+    #[pyo3(signature = (start, max_depth = None, max_nodes = None))]
+    fn dfs(
+        &self,
+        start: usize,
+        max_depth: Option<usize>,
+        max_nodes: Option<usize>,
+    ) -> PyResult<Dfs> {
+        let start = self.checked(start)?;
+        let cell = DfsCell::new(Arc::clone(&self.graph), |graph| {
+            let mut dfs = DfsIterator::new(&**graph, start);
+            if let Some(max_depth) = max_depth {
+                dfs = dfs.max_depth(max_depth);
+            }
+            if let Some(max_nodes) = max_nodes {
+                dfs = dfs.max_nodes(max_nodes);
+            }
+            dfs
+        });
+        Ok(Dfs { cell })
+    }
+
+    /// Iterative deepening DFS from `start`: nodes in BFS depth order
+    fn iddfs(&self, start: usize) -> PyResult<Iddfs> {
+        let start = self.checked(start)?;
+        let cell = IddfsCell::new(Arc::clone(&self.graph), |graph| {
+            IddfsIterator::new(&**graph, start)
+        });
+        Ok(Iddfs { cell })
+    }
+
+    /// Always visits the smallest-valued node among those reached so far
+    fn next_smallest(&self, start: usize) -> PyResult<NextSmallest> {
+        let start = self.checked(start)?;
+        let cell = NextSmallestCell::new(Arc::clone(&self.graph), |graph| {
+            NextSmallestIterator::new(&**graph, start)
+        });
+        Ok(NextSmallest { cell })
+    }
+}
+
+impl PyGraph {
+    fn checked(&self, node: usize) -> PyResult<NodeId> {
+        let id = NodeId::new(node);
+        self.graph.check_node(id).map_err(to_py_err)?;
+        Ok(id)
+    }
+}
+
+// Each Python iterator owns a handle to the graph together with the Rust
+// iterator borrowing it
+type BfsOf<'a> = BfsIterator<'a, i32>;
+type DfsOf<'a> = DfsIterator<'a, i32>;
+type IddfsOf<'a> = IddfsIterator<'a, i32>;
+type NextSmallestOf<'a> = NextSmallestIterator<'a, i32>;
+
+self_cell!(
+    struct BfsCell {
+        owner: Arc<Graph>,
+        #[not_covariant]
+        dependent: BfsOf,
+    }
+);
+
+self_cell!(
+    struct DfsCell {
+        owner: Arc<Graph>,
+        #[not_covariant]
+        dependent: DfsOf,
+    }
+);
+
+self_cell!(
+    struct IddfsCell {
+        owner: Arc<Graph>,
+        #[not_covariant]
+        dependent: IddfsOf,
+    }
+);
+
+self_cell!(
+    struct NextSmallestCell {
+        owner: Arc<Graph>,
+        #[not_covariant]
+        dependent: NextSmallestOf,
+    }
+);
+
+/// Python iterator class over the node indices a traversal visits
+macro_rules! node_iterator {
+    ($name:ident, $cell:ident, $python_name:literal) => {
+        #[pyclass(name = $python_name, unsendable)]
+        struct $name {
+            cell: $cell,
+        }
+
+        #[pymethods]
+        impl $name {
+            fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+                slf
+            }
+
+            fn __next__(&mut self) -> Option<usize> {
+                self.cell
+                    .with_dependent_mut(|_, traversal| traversal.next_index())
+            }
+
+            /// Index of the node the last one was reached from, or None for
+            /// the start
+            #[getter]
+            fn parent(&self) -> Option<usize> {
+                self.cell.with_dependent(|_, traversal| traversal.parent())
+            }
+        }
+    };
+}
+
+node_iterator!(Bfs, BfsCell, "Bfs");
+node_iterator!(Dfs, DfsCell, "Dfs");
+node_iterator!(Iddfs, IddfsCell, "Iddfs");
+node_iterator!(NextSmallest, NextSmallestCell, "NextSmallest");
+
+#[pymodule]
+#[pyo3(name = "graph_traversal")]
+fn python_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyGraph>()?;
+    module.add_class::<Bfs>()?;
+    module.add_class::<Dfs>()?;
+    module.add_class::<Iddfs>()?;
+    module.add_class::<NextSmallest>()?;
+    Ok(())
+}
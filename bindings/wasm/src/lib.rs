@@ -0,0 +1,158 @@
+//! WebAssembly bindings for `graph_traversal`, via wasm-bindgen: build a
+//! graph of integer-valued nodes from JavaScript, save and load it as JSON,
+//! and run traversals and shortest paths that return node indices as
+//! `Uint32Array`s.
+
+use graph_traversal::{
+    BfsIterator, DfsIterator, DijkstraIterator, EdgeWeights, Graph as RustGraph, NodeId, Traversal,
+};
+use wasm_bindgen::prelude::*;
+
+/// Directed graph with an integer value per node
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Graph {
+    graph: RustGraph,
+}
+
+#[wasm_bindgen]
+impl Graph {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Graph {
+        Graph::default()
+    }
+
+    /// Parse "from to" lines, as `Graph::from_edge_list` does
+    #[wasm_bindgen(js_name = fromEdgeList)]
+    pub fn from_edge_list(text: &str, undirected: bool) -> Result<Graph, JsError> {
+        let graph = RustGraph::from_edge_list(text, undirected).map_err(js_error)?;
+        Ok(Graph { graph })
+    }
+
+    /// Load the JSON layout of the `serde` feature (see the README)
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<Graph, JsError> {
+        let graph = serde_json::from_str(json)?;
+        Ok(Graph { graph })
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        Ok(serde_json::to_string(&self.graph)?)
+    }
+
+    /// Graphviz DOT source, for rendering
+    #[wasm_bindgen(js_name = toDot)]
+    pub fn to_dot(&self) -> String {
+        self.graph.to_dot()
+    }
+
+    /// Append a node and return its index
+    #[wasm_bindgen(js_name = addNode)]
+    pub fn add_node(&mut self, value: i32) -> u32 {
+        self.graph.add_node(value).index() as u32
+    }
+
+    /// Add a directed edge from -> to
+    #[wasm_bindgen(js_name = addEdge)]
+    pub fn add_edge(&mut self, from: u32, to: u32) -> Result<(), JsError> {
+        self.graph.add_edge(id(from), id(to)).map_err(js_error)?;
+        Ok(())
+    }
+
+    /// Number of nodes
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> u32 {
+        self.graph.len() as u32
+    }
+
+    pub fn value(&self, node: u32) -> Result<i32, JsError> {
+        self.graph.check_node(id(node)).map_err(js_error)?;
+        Ok(*self.graph[id(node)].value())
+    }
+
+    /// Out-neighbors of `node` in insertion order
+    pub fn neighbors(&self, node: u32) -> Result<Vec<u32>, JsError> {
+        self.graph.check_node(id(node)).map_err(js_error)?;
+        Ok(indices(self.graph[id(node)].neighbors()))
+    }
+
+    /// Every edge as consecutive (from, to) pairs, in node order
+    pub fn edges(&self) -> Vec<u32> {
+        self.graph
+            .edges()
+            .flat_map(|(from, to)| [from.index() as u32, to.index() as u32])
+            .collect()
+    }
+
+    /// Nodes reachable from `start` in breadth-first order
+    pub fn bfs(&self, start: u32) -> Result<Vec<u32>, JsError> {
+        self.graph.check_node(id(start)).map_err(js_error)?;
+        Ok(visit_order(BfsIterator::new(&self.graph, id(start))))
+    }
+
+    /// Nodes reachable from `start` in depth-first order
+    pub fn dfs(&self, start: u32) -> Result<Vec<u32>, JsError> {
+        self.graph.check_node(id(start)).map_err(js_error)?;
+        Ok(visit_order(DfsIterator::new(&self.graph, id(start))))
+    }
+
+    /// Fewest-hop path from `from` to `to`, both included; undefined if `to`
+    /// is unreachable
+    #[wasm_bindgen(js_name = shortestPath)]
+    pub fn shortest_path(&self, from: u32, to: u32) -> Result<Option<Vec<u32>>, JsError> {
+        self.graph.check_node(id(from)).map_err(js_error)?;
+        self.graph.check_node(id(to)).map_err(js_error)?;
+        let path = self.graph.shortest_path(id(from), id(to));
+        Ok(path.map(|path| indices(path.into_iter())))
+    }
+
+    /// Lightest path from `from` to `to` by Dijkstra, where `weights[i]` is
+    /// the weight of the i-th edge of `edges()` (parallel edges share the
+    /// last weight given); undefined if `to` is unreachable
+    #[wasm_bindgen(js_name = weightedShortestPath)]
+    pub fn weighted_shortest_path(
+        &self,
+        from: u32,
+        to: u32,
+        weights: Vec<u32>,
+    ) -> Result<Option<Vec<u32>>, JsError> {
+        self.graph.check_node(id(from)).map_err(js_error)?;
+        self.graph.check_node(id(to)).map_err(js_error)?;
+        if weights.len() != self.graph.edges().count() {
+            return Err(JsError::new("expected one weight per edge"));
+        }
+        let mut edge_weights = EdgeWeights::default();
+        for ((source, target), &weight) in self.graph.edges().zip(&weights) {
+            edge_weights.set(source, target, u64::from(weight));
+        }
+        // A node's path is final once it is yielded, so stop at the goal
+        let mut dijkstra = DijkstraIterator::new(&self.graph, id(from), &edge_weights);
+        while let Some(index) = dijkstra.next_index() {
+            if index == to as usize {
+                break;
+            }
+        }
+        let path = dijkstra.into_paths().path_to(id(to));
+        Ok(path.map(|path| indices(path.into_iter())))
+    }
+}
+
+/// The crate's errors implement `Display`, which is all a `JsError` needs
+fn js_error(error: impl std::fmt::Display) -> JsError {
+    JsError::new(&error.to_string())
+}
+
+fn id(index: u32) -> NodeId {
+    NodeId::new(index as usize)
+}
+
+fn indices(ids: impl Iterator<Item = NodeId>) -> Vec<u32> {
+    ids.map(|id| id.index() as u32).collect()
+}
+
+fn visit_order<'a>(mut traversal: impl Traversal<'a>) -> Vec<u32> {
+    std::iter::from_fn(|| traversal.next_index())
+        .map(|index| index as u32)
+        .collect()
+}
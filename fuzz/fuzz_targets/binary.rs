@@ -0,0 +1,14 @@
+//! Arbitrary bytes through `Graph::read_binary`, whose checks must catch any
+//! offset or target out of range
+#![no_main]
+
+use graph_traversal::{DfsIterator, Graph, NodeId};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(graph) = Graph::read_binary(data) {
+        for start in 0..graph.len().min(4) {
+            let _ = DfsIterator::new(&graph, NodeId::new(start)).count();
+        }
+    }
+});
@@ -0,0 +1,12 @@
+//! Arbitrary text through the DOT parser
+#![no_main]
+
+use graph_traversal::dot::DotGraph;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|source: &str| {
+    if let Ok(dot) = DotGraph::parse(source) {
+        // Whatever parses must also print
+        let _ = dot.graph.to_dot();
+    }
+});
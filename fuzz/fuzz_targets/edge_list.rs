@@ -0,0 +1,23 @@
+//! Arbitrary bytes through every edge-list reader: each must return an error
+//! rather than panic
+#![no_main]
+
+use graph_traversal::io::{EdgeListOptions, SnapDataset};
+use graph_traversal::Graph;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = SnapDataset::parse(data, false);
+    let _ = SnapDataset::parse(data, true);
+    let options = EdgeListOptions {
+        undirected: true,
+        sort_neighbors: true,
+        ..EdgeListOptions::default()
+    };
+    let _ = Graph::read_edge_list(data, &EdgeListOptions::default());
+    let _ = Graph::read_edge_list(data, &options);
+    if let Ok(text) = std::str::from_utf8(data) {
+        let _ = Graph::from_edge_list(text, false);
+        let _ = Graph::from_edge_list(text, true);
+    }
+});
@@ -0,0 +1,66 @@
+//! Arbitrary sequences of builder and mutation calls on a `Graph`, node
+//! indices included, so that out-of-range ones must come back as errors
+#![no_main]
+
+use arbitrary::Arbitrary;
+use graph_traversal::{BfsIterator, DfsIterator, Graph, NextSmallestIterator, NodeId};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    AddNode(i32),
+    AddEdge(u8, u8),
+    AddEdgeSorted(u8, u8),
+    AddUndirectedEdge(u8, u8),
+    RemoveEdge(u8, u8),
+    SortNeighbors,
+    SortNeighborsByValue,
+    DedupEdges,
+    RemoveSelfLoops,
+    ReverseEdges,
+    Traverse(u8),
+}
+
+fn id(index: u8) -> NodeId {
+    NodeId::new(index.into())
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut graph = Graph::new();
+    for op in ops {
+        match op {
+            Op::AddNode(value) => {
+                graph.add_node(value);
+            }
+            Op::AddEdge(from, to) => {
+                let _ = graph.add_edge(id(from), id(to));
+            }
+            Op::AddEdgeSorted(from, to) => {
+                let _ = graph.add_edge_sorted(id(from), id(to));
+            }
+            Op::AddUndirectedEdge(a, b) => {
+                let _ = graph.add_undirected_edge(id(a), id(b));
+            }
+            Op::RemoveEdge(from, to) => {
+                let _ = graph.remove_edge(id(from), id(to));
+            }
+            Op::SortNeighbors => graph.sort_neighbors(),
+            Op::SortNeighborsByValue => graph.sort_neighbors_by_value(),
+            Op::DedupEdges => {
+                graph.dedup_edges();
+            }
+            Op::RemoveSelfLoops => {
+                graph.remove_self_loops();
+            }
+            Op::ReverseEdges => graph.reverse_edges(),
+            Op::Traverse(start) => {
+                if graph.check_node(id(start)).is_ok() {
+                    let _ = BfsIterator::new(&graph, id(start)).count();
+                    let _ = DfsIterator::new(&graph, id(start)).count();
+                    let _ = NextSmallestIterator::new(&graph, id(start)).count();
+                }
+            }
+        }
+    }
+    assert!(graph.validate().is_ok());
+});
@@ -0,0 +1,9 @@
+//! Arbitrary text through the GraphML parser
+#![no_main]
+
+use graph_traversal::graphml::GraphMlGraph;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|source: &str| {
+    let _ = GraphMlGraph::parse(source);
+});
@@ -0,0 +1,17 @@
+//! Arbitrary text through `Graph::from_json`, which must reject neighbor
+//! indices naming no node
+#![no_main]
+
+use graph_traversal::{BfsIterator, Graph, NodeId};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    if let Ok(graph) = Graph::<i32>::from_json(text) {
+        for (from, to) in graph.edges() {
+            assert!(graph.check_node(from).is_ok() && graph.check_node(to).is_ok());
+        }
+        if !graph.is_empty() {
+            let _ = BfsIterator::new(&graph, NodeId::new(0)).count();
+        }
+    }
+});
@@ -0,0 +1,343 @@
+use std::cmp::Ordering;
+
+use crate::graph::{Graph, NodeId};
+
+impl<T> Graph<T> {
+    /// Weakly connected components as a component id per node, numbered in
+    /// order of their smallest node
+    pub(crate) fn weak_components(&self) -> Vec<usize> {
+        let adjacency = self.undirected_adjacency();
+        let mut component = vec![usize::MAX; self.nodes.len()];
+        let mut count = 0;
+        for start in 0..self.nodes.len() {
+            if component[start] != usize::MAX {
+                continue;
+            }
+            component[start] = count;
+            let mut stack = vec![start];
+            while let Some(v) = stack.pop() {
+                for &w in &adjacency[v] {
+                    if component[w] == usize::MAX {
+                        component[w] = count;
+                        stack.push(w);
+                    }
+                }
+            }
+            count += 1;
+        }
+        component
+    }
+
+    /// Number of triangles, reading edges as undirected and ignoring
+    /// self-loops and parallel edges
+    pub fn triangle_count(&self) -> usize {
+        triangles_per_node(&self.undirected_adjacency())
+            .iter()
+            .sum::<usize>()
+            / 3
+    }
+
+    /// Local clustering coefficient of `node`, reading edges as undirected:
+    /// the fraction of pairs of its neighbors that are adjacent, 0 below two
+    /// neighbors. Panics if `node` is out of range.
+    pub fn clustering_coefficient(&self, node: NodeId) -> f64 {
+        let adjacency = self.undirected_adjacency();
+        let neighbors = &adjacency[node.index()];
+        let mut links = 0;
+        for &a in neighbors {
+            // Count each adjacent pair once, from its smaller end
+            for_each_common(neighbors, &adjacency[a], |b| links += usize::from(b > a));
+        }
+        local_clustering(links, neighbors.len())
+    }
+
+    /// Local clustering coefficient of every node, reading edges as undirected
+    pub fn clustering_coefficients(&self) -> Vec<f64> {
+        let adjacency = self.undirected_adjacency();
+        triangles_per_node(&adjacency)
+            .into_iter()
+            .zip(adjacency.iter().map(Vec::len))
+            .map(|(triangles, degree)| local_clustering(triangles, degree))
+            .collect()
+    }
+
+    /// Mean local clustering coefficient over all nodes; 0 for an empty graph
+    pub fn average_clustering(&self) -> f64 {
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+        self.clustering_coefficients().iter().sum::<f64>() / self.nodes.len() as f64
+    }
+
+    /// PageRank by power iteration, spreading the rank of dangling nodes
+    /// uniformly; stops after `iterations` steps or once the L1 change drops
+    /// below 1e-12
+    pub fn pagerank(&self, damping: f64, iterations: usize) -> Vec<f64> {
+        self.pagerank_with(
+            damping,
+            PageRankStop::Converged {
+                tolerance: 1e-12,
+                max_iterations: iterations,
+            },
+        )
+    }
+
+    /// PageRank by power iteration with an explicit stopping rule. Scores sum
+    /// to 1; the rank of dangling nodes is spread uniformly.
+    pub fn pagerank_with(&self, damping: f64, stop: PageRankStop) -> Vec<f64> {
+        let n = self.nodes.len();
+        power_iteration(n, stop, |rank, next| {
+            pagerank_step(
+                n,
+                |v| self.nodes[v].neighbors.iter().copied(),
+                damping,
+                rank,
+                next,
+            )
+        })
+    }
+}
+
+/// Power iteration from the uniform vector over `n` nodes, `step` writing the
+/// next scores from the current ones, until `stop`; empty for no nodes. Shared
+/// by `Graph::pagerank_with` and the compute backends.
+pub(crate) fn power_iteration<F>(n: usize, stop: PageRankStop, mut step: F) -> Vec<f64>
+where
+    F: FnMut(&[f64], &mut [f64]),
+{
+    if n == 0 {
+        return Vec::new();
+    }
+    let (iterations, tolerance) = match stop {
+        PageRankStop::Iterations(iterations) => (iterations, None),
+        PageRankStop::Converged {
+            tolerance,
+            max_iterations,
+        } => (max_iterations, Some(tolerance)),
+    };
+    let mut rank = vec![1.0 / n as f64; n];
+    let mut next = vec![0.0; n];
+    for _ in 0..iterations {
+        step(&rank, &mut next);
+        let change: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+        std::mem::swap(&mut rank, &mut next);
+        if tolerance.is_some_and(|tolerance| change < tolerance) {
+            break;
+        }
+    }
+    rank
+}
+
+/// One PageRank step over `n` nodes with out-neighbors `neighbors(v)`, the
+/// rank of dangling nodes spread uniformly
+pub(crate) fn pagerank_step<N, I>(
+    n: usize,
+    neighbors: N,
+    damping: f64,
+    rank: &[f64],
+    next: &mut [f64],
+) where
+    N: Fn(usize) -> I,
+    I: ExactSizeIterator<Item = usize>,
+{
+    let dangling: f64 = (0..n)
+        .filter(|&v| neighbors(v).len() == 0)
+        .map(|v| rank[v])
+        .sum();
+    next.fill((1.0 - damping + damping * dangling) / n as f64);
+    for (v, &r) in rank.iter().enumerate() {
+        let out = neighbors(v);
+        let share = damping * r / out.len().max(1) as f64;
+        for w in out {
+            next[w] += share;
+        }
+    }
+}
+
+/// Triangles through each node, by the forward algorithm: edges are
+/// oriented from lower to higher (degree, index), so every triangle is
+/// found once, at its lowest edge, by intersecting two sorted out-lists.
+/// O(E^1.5) rather than the O(sum of squared degrees) of pair checking.
+fn triangles_per_node(adjacency: &[Vec<usize>]) -> Vec<usize> {
+    let rank = |v: usize| (adjacency[v].len(), v);
+    // Lists stay sorted by index, as `Graph::undirected_adjacency` gives them
+    let forward: Vec<Vec<usize>> = adjacency
+        .iter()
+        .enumerate()
+        .map(|(v, neighbors)| {
+            neighbors
+                .iter()
+                .copied()
+                .filter(|&w| rank(w) > rank(v))
+                .collect()
+        })
+        .collect();
+
+    let mut triangles = vec![0; adjacency.len()];
+    for (u, out) in forward.iter().enumerate() {
+        for &v in out {
+            for_each_common(out, &forward[v], |w| {
+                for corner in [u, v, w] {
+                    triangles[corner] += 1;
+                }
+            });
+        }
+    }
+    triangles
+}
+
+/// 2 * links / (degree * (degree - 1)), or 0 below degree 2
+fn local_clustering(links: usize, degree: usize) -> f64 {
+    if degree < 2 {
+        return 0.0;
+    }
+    2.0 * links as f64 / (degree * (degree - 1)) as f64
+}
+
+/// Call `found` with each element of both sorted lists, in order
+fn for_each_common(a: &[usize], b: &[usize], mut found: impl FnMut(usize)) {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                found(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+}
+
+/// When `Graph::pagerank_with` stops iterating
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageRankStop {
+    /// Exactly this many power-iteration steps
+    Iterations(usize),
+    /// Until the L1 change between successive rank vectors drops below
+    /// `tolerance`, or after `max_iterations` steps if it never does (with
+    /// damping 1 the iteration can oscillate forever)
+    Converged {
+        tolerance: f64,
+        max_iterations: usize,
+    },
+}
+
+/// Summary metrics for dataset triage, produced by `Graph::analyze`
+#[derive(Debug, Clone)]
+pub struct AnalysisReport {
+    pub nodes: usize,
+    pub edges: usize,
+    pub self_loops: usize,
+    pub density: f64,
+    pub degree_histogram: Vec<(usize, usize)>,
+    pub mean_degree: f64,
+    pub components: usize,
+    pub largest_component: usize,
+    pub average_clustering: f64,
+    pub top_pagerank: Vec<(NodeId, i32, f64)>,
+}
+
+impl Graph {
+    /// Compute the analysis report, keeping the `top` highest-PageRank nodes
+    pub fn analyze(&self, top: usize) -> AnalysisReport {
+        let n = self.nodes.len();
+        let edges = self.edge_count();
+        let self_loops = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(v, node)| node.neighbors.iter().filter(|&&w| w == v).count())
+            .sum();
+
+        let degree_histogram = self.degree_distribution();
+
+        let component = self.weak_components();
+        let mut sizes = vec![0; component.iter().map(|&c| c + 1).max().unwrap_or(0)];
+        for &c in &component {
+            sizes[c] += 1;
+        }
+
+        let rank = self.pagerank(0.85, 100);
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| rank[b].total_cmp(&rank[a]).then(a.cmp(&b)));
+        let top_pagerank = order
+            .into_iter()
+            .take(top)
+            .map(|v| (NodeId::new(v), self.nodes[v].value, rank[v]))
+            .collect();
+
+        AnalysisReport {
+            nodes: n,
+            edges,
+            self_loops,
+            density: self.density(),
+            degree_histogram,
+            mean_degree: self.average_degree(),
+            components: sizes.len(),
+            largest_component: sizes.iter().copied().max().unwrap_or(0),
+            average_clustering: self.average_clustering(),
+            top_pagerank,
+        }
+    }
+}
+
+impl AnalysisReport {
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        out += &format!(
+            "nodes: {}\nedges: {}\nself loops: {}\n",
+            self.nodes, self.edges, self.self_loops
+        );
+        out += &format!(
+            "density: {:.6}\nmean out-degree: {:.3}\n",
+            self.density, self.mean_degree
+        );
+        out += &format!(
+            "weak components: {} (largest {})\n",
+            self.components, self.largest_component
+        );
+        out += &format!("average clustering: {:.6}\n", self.average_clustering);
+        out += "out-degree distribution:\n";
+        for (degree, count) in &self.degree_histogram {
+            out += &format!("  {:>6}: {}\n", degree, count);
+        }
+        out += "top PageRank:\n";
+        for (index, value, rank) in &self.top_pagerank {
+            out += &format!("  node {} (value {}): {:.6}\n", index, value, rank);
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let histogram: Vec<String> = self
+            .degree_histogram
+            .iter()
+            .map(|(degree, count)| format!("{{\"degree\":{},\"count\":{}}}", degree, count))
+            .collect();
+        let top: Vec<String> = self
+            .top_pagerank
+            .iter()
+            .map(|(index, value, rank)| {
+                format!(
+                    "{{\"node\":{},\"value\":{},\"pagerank\":{}}}",
+                    index, value, rank
+                )
+            })
+            .collect();
+        format!(
+            "{{\"nodes\":{},\"edges\":{},\"self_loops\":{},\"density\":{},\"mean_degree\":{},\"components\":{},\"largest_component\":{},\"average_clustering\":{},\"degree_histogram\":[{}],\"top_pagerank\":[{}]}}",
+            self.nodes,
+            self.edges,
+            self.self_loops,
+            self.density,
+            self.mean_degree,
+            self.components,
+            self.largest_component,
+            self.average_clustering,
+            histogram.join(","),
+            top.join(",")
+        )
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+/// Future returned by `AsyncNeighbors::neighbors`
+pub type NeighborsFuture<'g, N, E> = Pin<Box<dyn Future<Output = Result<Vec<N>, E>> + 'g>>;
+
+/// A graph whose out-neighbors are fetched asynchronously, such as web pages
+/// and their links or records behind an API. Like `ImplicitGraph`, nodes are
+/// identified by value.
+pub trait AsyncNeighbors {
+    /// Node handle, e.g. a URL or a record id
+    type Node: Clone + Hash + Eq;
+
+    /// Why fetching the neighbors of a node failed
+    type Error;
+
+    /// Out-neighbors of `node` in order
+    fn neighbors(&self, node: &Self::Node) -> NeighborsFuture<'_, Self::Node, Self::Error>;
+}
+
+/// BFS or DFS over an `AsyncNeighbors` graph as a `Stream`, visiting nodes in
+/// the order `BfsIterator` and `DfsIterator` do. A node is yielded as soon as
+/// it is reached, and its neighbors are fetched when the stream is next
+/// polled, so at most one fetch is in flight. A failed fetch is yielded as
+/// an `Err` item and the traversal carries on without that node's neighbors.
+pub struct AsyncTraversal<'g, G: AsyncNeighbors> {
+    graph: &'g G,
+    depth_first: bool,
+    pending: VecDeque<(G::Node, usize)>, // (node, depth)
+    visited: HashSet<G::Node>,
+    expand: Option<(G::Node, usize)>, // Yielded node whose neighbors are due
+    fetch: Option<NeighborsFuture<'g, G::Node, G::Error>>,
+    fetch_depth: usize, // Depth of the node being fetched
+    depth: usize,
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+}
+
+impl<'g, G: AsyncNeighbors> AsyncTraversal<'g, G> {
+    /// Breadth-first traversal from `start`
+    pub fn bfs(graph: &'g G, start: G::Node) -> Self {
+        Self::new(graph, start, false)
+    }
+
+    /// Depth-first traversal from `start`
+    pub fn dfs(graph: &'g G, start: G::Node) -> Self {
+        Self::new(graph, start, true)
+    }
+
+    fn new(graph: &'g G, start: G::Node, depth_first: bool) -> Self {
+        AsyncTraversal {
+            graph,
+            depth_first,
+            pending: VecDeque::from([(start, 0)]),
+            visited: HashSet::new(),
+            expand: None,
+            fetch: None,
+            fetch_depth: 0,
+            depth: 0,
+            max_depth: None,
+            max_nodes: None,
+        }
+    }
+
+    /// Never fetch the neighbors of nodes `max_depth` edges from the start
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// End after yielding `max_nodes` nodes, without fetching their neighbors
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Length of the tree path from the start to the node last yielded
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Whether `node` has been yielded
+    pub fn visited(&self, node: &G::Node) -> bool {
+        self.visited.contains(node)
+    }
+
+    /// Add the fetched neighbors of a node at `depth`, unvisited ones only
+    fn push_neighbors(&mut self, neighbors: Vec<G::Node>, depth: usize) {
+        let unvisited = neighbors
+            .into_iter()
+            .filter(|neighbor| !self.visited.contains(neighbor));
+        if self.depth_first {
+            // Stack them in reverse order, so the first neighbor pops first
+            let unvisited: Vec<G::Node> = unvisited.collect();
+            for neighbor in unvisited.into_iter().rev() {
+                self.pending.push_back((neighbor, depth + 1));
+            }
+        } else {
+            for neighbor in unvisited {
+                self.pending.push_back((neighbor, depth + 1));
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Option<(G::Node, usize)> {
+        if self.depth_first {
+            self.pending.pop_back()
+        } else {
+            self.pending.pop_front()
+        }
+    }
+}
+
+impl<G: AsyncNeighbors> Stream for AsyncTraversal<'_, G>
+where
+    G::Node: Unpin,
+{
+    type Item = Result<G::Node, G::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this
+            .max_nodes
+            .is_some_and(|max_nodes| this.visited.len() >= max_nodes)
+        {
+            return Poll::Ready(None);
+        }
+
+        // Fetch the neighbors of the node yielded last, if it is not too deep
+        if let Some((node, depth)) = this.expand.take() {
+            if this.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                this.fetch = Some(this.graph.neighbors(&node));
+                this.fetch_depth = depth;
+            }
+        }
+        if let Some(fetch) = &mut this.fetch {
+            let fetched = match fetch.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(fetched) => fetched,
+            };
+            this.fetch = None;
+            match fetched {
+                Ok(neighbors) => this.push_neighbors(neighbors, this.fetch_depth),
+                Err(error) => return Poll::Ready(Some(Err(error))),
+            }
+        }
+
+        while let Some((node, depth)) = this.pop() {
+            // If the node has already been visited, skip it
+            if !this.visited.insert(node.clone()) {
+                continue;
+            }
+            this.expand = Some((node.clone(), depth));
+            this.depth = depth;
+            return Poll::Ready(Some(Ok(node)));
+        }
+        Poll::Ready(None)
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+use crate::graph::NodeId;
+
+/// String-keyed metadata for the nodes and edges of a graph, kept beside it
+/// like `EdgeWeights`: the graph itself only stores one value per node. Edge
+/// attributes belong to the pair (from, to), so parallel edges share them.
+/// DOT and GraphML export write them (`DotStyle::with_attributes`,
+/// `to_graphml_with`) and import reads them back.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphAttributes {
+    nodes: BTreeMap<usize, BTreeMap<String, String>>,
+    edges: BTreeMap<(usize, usize), BTreeMap<String, String>>,
+}
+
+impl GraphAttributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether no node or edge has an attribute
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+
+    pub fn node_attr(&self, node: NodeId, name: &str) -> Option<&str> {
+        self.nodes.get(&node.index())?.get(name).map(String::as_str)
+    }
+
+    /// Set an attribute of `node`, returning the value it replaces
+    pub fn set_node_attr(
+        &mut self,
+        node: NodeId,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Option<String> {
+        self.nodes
+            .entry(node.index())
+            .or_default()
+            .insert(name.into(), value.into())
+    }
+
+    pub fn remove_node_attr(&mut self, node: NodeId, name: &str) -> Option<String> {
+        remove(&mut self.nodes, node.index(), name)
+    }
+
+    /// Attributes of `node` as (name, value), by name
+    pub fn node_attrs(&self, node: NodeId) -> impl Iterator<Item = (&str, &str)> + '_ {
+        pairs(self.nodes.get(&node.index()))
+    }
+
+    pub fn edge_attr(&self, from: NodeId, to: NodeId, name: &str) -> Option<&str> {
+        self.edges
+            .get(&(from.index(), to.index()))?
+            .get(name)
+            .map(String::as_str)
+    }
+
+    /// Set an attribute of the edge from -> to, returning the value it replaces
+    pub fn set_edge_attr(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Option<String> {
+        self.edges
+            .entry((from.index(), to.index()))
+            .or_default()
+            .insert(name.into(), value.into())
+    }
+
+    pub fn remove_edge_attr(&mut self, from: NodeId, to: NodeId, name: &str) -> Option<String> {
+        remove(&mut self.edges, (from.index(), to.index()), name)
+    }
+
+    /// Attributes of the edge from -> to as (name, value), by name
+    pub fn edge_attrs(&self, from: NodeId, to: NodeId) -> impl Iterator<Item = (&str, &str)> + '_ {
+        pairs(self.edges.get(&(from.index(), to.index())))
+    }
+
+    /// Every node attribute name in use, sorted
+    pub fn node_attr_names(&self) -> Vec<&str> {
+        names(self.nodes.values())
+    }
+
+    /// Every edge attribute name in use, sorted
+    pub fn edge_attr_names(&self) -> Vec<&str> {
+        names(self.edges.values())
+    }
+}
+
+fn remove<K: Ord>(
+    maps: &mut BTreeMap<K, BTreeMap<String, String>>,
+    key: K,
+    name: &str,
+) -> Option<String> {
+    let map = maps.get_mut(&key)?;
+    let value = map.remove(name);
+    if map.is_empty() {
+        maps.remove(&key);
+    }
+    value
+}
+
+fn pairs(map: Option<&BTreeMap<String, String>>) -> impl Iterator<Item = (&str, &str)> {
+    map.into_iter()
+        .flatten()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+}
+
+fn names<'a>(maps: impl Iterator<Item = &'a BTreeMap<String, String>>) -> Vec<&'a str> {
+    let mut names: Vec<&str> = maps
+        .flat_map(|map| map.keys().map(String::as_str))
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
@@ -0,0 +1,145 @@
+use crate::analysis::{pagerank_step, power_iteration, PageRankStop};
+use crate::csr::CsrGraph;
+use crate::error::GraphError;
+use crate::graph::NodeId;
+
+#[cfg(feature = "gpu")]
+pub use crate::gpu::GpuBackend;
+
+/// Borrowed adjacency arrays of a `CsrGraph`, which is all a backend computes
+/// on: the neighbors of node v are `targets[offsets[v]..offsets[v + 1]]`
+#[derive(Debug, Clone, Copy)]
+pub struct Adjacency<'a> {
+    offsets: &'a [usize],
+    targets: &'a [u32],
+}
+
+impl<'a> Adjacency<'a> {
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The `len + 1` offsets into `targets`
+    pub fn offsets(&self) -> &'a [usize] {
+        self.offsets
+    }
+
+    /// Every edge target, node by node
+    pub fn targets(&self) -> &'a [u32] {
+        self.targets
+    }
+
+    /// Out-neighbors of node `v`; panics if `v` is out of range
+    pub fn neighbors(&self, v: usize) -> &'a [u32] {
+        &self.targets[self.offsets[v]..self.offsets[v + 1]]
+    }
+}
+
+impl<T> CsrGraph<T> {
+    /// The adjacency arrays, for a `ComputeBackend`
+    pub fn adjacency(&self) -> Adjacency<'_> {
+        Adjacency {
+            offsets: &self.offsets,
+            targets: &self.targets,
+        }
+    }
+
+    /// BFS depth of every node from `start` (None if unreachable), run by
+    /// `backend`; errors if `start` is out of range
+    pub fn bfs_levels(
+        &self,
+        backend: &dyn ComputeBackend,
+        start: NodeId,
+    ) -> Result<Vec<Option<u32>>, GraphError> {
+        if start.index() >= self.len() {
+            return Err(GraphError::NodeOutOfRange {
+                node: start.index(),
+                len: self.len(),
+            });
+        }
+        Ok(backend.bfs_levels(self.adjacency(), start.index()))
+    }
+
+    /// PageRank as `Graph::pagerank_with` computes it, run by `backend`
+    pub fn pagerank(
+        &self,
+        backend: &dyn ComputeBackend,
+        damping: f64,
+        stop: PageRankStop,
+    ) -> Vec<f64> {
+        backend.pagerank(self.adjacency(), damping, stop)
+    }
+}
+
+/// Data-parallel kernels over a CSR graph. An accelerator implementation runs
+/// each BFS level and each PageRank iteration as a single dispatch over whole
+/// arrays.
+pub trait ComputeBackend {
+    fn name(&self) -> &'static str;
+
+    /// BFS depth of every node from `start` (None if unreachable), level by
+    /// level; `start` is in range
+    fn bfs_levels(&self, graph: Adjacency<'_>, start: usize) -> Vec<Option<u32>>;
+
+    /// PageRank from the uniform vector until `stop`, the rank of dangling
+    /// nodes spread uniformly
+    fn pagerank(&self, graph: Adjacency<'_>, damping: f64, stop: PageRankStop) -> Vec<f64>;
+}
+
+/// Reference backend; also the fallback when no accelerator is available
+pub struct CpuBackend;
+
+impl ComputeBackend for CpuBackend {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn bfs_levels(&self, graph: Adjacency<'_>, start: usize) -> Vec<Option<u32>> {
+        let mut levels = vec![None; graph.len()];
+        levels[start] = Some(0);
+        let mut frontier = vec![start as u32];
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            depth += 1;
+            let mut next = Vec::new();
+            for &v in &frontier {
+                for &w in graph.neighbors(v as usize) {
+                    if levels[w as usize].is_none() {
+                        levels[w as usize] = Some(depth);
+                        next.push(w);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        levels
+    }
+
+    fn pagerank(&self, graph: Adjacency<'_>, damping: f64, stop: PageRankStop) -> Vec<f64> {
+        let n = graph.len();
+        power_iteration(n, stop, |rank, next| {
+            pagerank_step(
+                n,
+                |v| graph.neighbors(v).iter().map(|&w| w as usize),
+                damping,
+                rank,
+                next,
+            )
+        })
+    }
+}
+
+/// Best available backend: with the `gpu` feature, a `GpuBackend` on a
+/// hardware adapter if one is found, and otherwise the CPU
+pub fn default_backend() -> Box<dyn ComputeBackend> {
+    #[cfg(feature = "gpu")]
+    if let Some(gpu) = GpuBackend::new().filter(|gpu| !gpu.is_software()) {
+        return Box::new(gpu);
+    }
+    Box::new(CpuBackend)
+}
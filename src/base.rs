@@ -0,0 +1,90 @@
+use crate::csr::CsrGraph;
+use crate::graph::{Graph, Node, NodeId};
+use crate::matrix::AdjMatrixGraph;
+use crate::undirected::UnGraph;
+
+/// Read access to a graph's storage: nodes by index and their out-neighbors.
+/// `BfsIterator`, `DfsIterator` and `NextSmallestIterator` are generic over it,
+/// so the same traversal code runs over the adjacency-list `Graph`, a
+/// `CsrGraph` or any other layout that numbers its nodes `0..node_count`.
+pub trait GraphBase {
+    /// Value carried by each node
+    type Value;
+
+    /// What traversals yield for a node: `Node<T>` for a `Graph<T>`, the value
+    /// itself for layouts that keep no per-node struct
+    type Node;
+
+    /// Number of nodes; ids run from 0 to `node_count() - 1`
+    fn node_count(&self) -> usize;
+
+    /// The node at `id`; panics if `id` is out of range
+    fn node(&self, id: NodeId) -> &Self::Node;
+
+    /// Value of a node returned by `node`
+    fn node_value(node: &Self::Node) -> &Self::Value;
+
+    /// Out-neighbors of `id` in order; panics if `id` is out of range.
+    /// Traversals never pass such an id: they skip frontier entries and
+    /// neighbor ids at or past `node_count`, so a bad start yields nothing and
+    /// corrupt adjacency data loses edges, not the run.
+    fn neighbors(&self, id: NodeId) -> impl DoubleEndedIterator<Item = NodeId> + '_;
+
+    /// Value of the node at `id`; panics if `id` is out of range
+    fn value(&self, id: NodeId) -> &Self::Value {
+        Self::node_value(self.node(id))
+    }
+}
+
+impl<T> GraphBase for Graph<T> {
+    type Value = T;
+    type Node = Node<T>;
+
+    fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn node(&self, id: NodeId) -> &Node<T> {
+        &self.nodes[id.index()]
+    }
+
+    fn node_value(node: &Node<T>) -> &T {
+        &node.value
+    }
+
+    fn neighbors(&self, id: NodeId) -> impl DoubleEndedIterator<Item = NodeId> + '_ {
+        self.nodes[id.index()].neighbors()
+    }
+}
+
+impl<T> GraphBase for UnGraph<T> {
+    type Value = T;
+    type Node = Node<T>;
+
+    fn node_count(&self) -> usize {
+        self.len()
+    }
+
+    fn node(&self, id: NodeId) -> &Node<T> {
+        &self.as_graph()[id]
+    }
+
+    fn node_value(node: &Node<T>) -> &T {
+        &node.value
+    }
+
+    fn neighbors(&self, id: NodeId) -> impl DoubleEndedIterator<Item = NodeId> + '_ {
+        self.as_graph()[id].neighbors()
+    }
+}
+
+// Storage layouts are shared read-only across threads; keep them Send + Sync
+const _: () = {
+    const fn assert_send_sync<G: GraphBase + Send + Sync>() {}
+    assert_send_sync::<Graph>();
+    assert_send_sync::<UnGraph>();
+    assert_send_sync::<CsrGraph>();
+    assert_send_sync::<AdjMatrixGraph>();
+    #[cfg(all(feature = "mmap", target_endian = "little"))]
+    assert_send_sync::<crate::mmap::MmapGraph>();
+};
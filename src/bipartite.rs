@@ -0,0 +1,390 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::graph::{Graph, Node, NodeId};
+
+/// Side of a bipartite graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// Bipartite graph with separately indexed left and right nodes; edges are
+/// stored once as left -> right adjacency and read as undirected. Its node
+/// indices are plain `usize`s local to their side, so left 0 and right 0 are
+/// different nodes; they are not `NodeId`s of any `Graph`.
+#[derive(Debug, Clone, Default)]
+pub struct BipartiteGraph {
+    left: Vec<i32>,
+    right: Vec<i32>,
+    edges: Vec<Vec<usize>>,
+}
+
+/// One-mode projection: nodes of one side, joined in both directions when they
+/// share a neighbor, weighted by how many neighbors they share
+#[derive(Debug)]
+pub struct Projection {
+    pub graph: Graph,
+    pub weights: HashMap<(usize, usize), usize>,
+}
+
+impl Projection {
+    /// Number of shared neighbors between two projected nodes
+    pub fn weight(&self, from: usize, to: usize) -> usize {
+        self.weights.get(&(from, to)).copied().unwrap_or(0)
+    }
+}
+
+impl BipartiteGraph {
+    pub fn add_node(&mut self, side: Side, value: i32) -> usize {
+        match side {
+            Side::Left => {
+                self.left.push(value);
+                self.edges.push(Vec::new());
+                self.left.len() - 1
+            }
+            Side::Right => {
+                self.right.push(value);
+                self.right.len() - 1
+            }
+        }
+    }
+
+    /// Node values of one side
+    pub fn values(&self, side: Side) -> &[i32] {
+        match side {
+            Side::Left => &self.left,
+            Side::Right => &self.right,
+        }
+    }
+
+    /// Right-side neighbors of each left node
+    pub fn edges(&self) -> &[Vec<usize>] {
+        &self.edges
+    }
+
+    pub fn add_edge(&mut self, left: usize, right: usize) {
+        assert!(
+            left < self.left.len() && right < self.right.len(),
+            "edge endpoint out of range"
+        );
+        if !self.edges[left].contains(&right) {
+            self.edges[left].push(right);
+        }
+    }
+
+    /// Neighbors of every node on `side`
+    pub fn adjacency(&self, side: Side) -> Vec<Vec<usize>> {
+        match side {
+            Side::Left => self.edges.clone(),
+            Side::Right => {
+                let mut adjacency = vec![Vec::new(); self.right.len()];
+                for (l, rights) in self.edges.iter().enumerate() {
+                    for &r in rights {
+                        adjacency[r].push(l);
+                    }
+                }
+                adjacency
+            }
+        }
+    }
+
+    /// Maximum matching by Hopcroft-Karp, in O(E sqrt(V)): (left index,
+    /// right index) pairs sharing an edge, no node in two of them, as many as
+    /// possible. Both indices are side-local. Sorted by left node.
+    pub fn maximum_matching(&self) -> Vec<(usize, usize)> {
+        hopcroft_karp(&self.edges, self.right.len())
+            .into_iter()
+            .enumerate()
+            .filter_map(|(l, r)| r.map(|r| (l, r)))
+            .collect()
+    }
+
+    /// Project onto one side, counting shared neighbors on the other
+    pub fn project(&self, side: Side) -> Projection {
+        let (values, own, other) = match side {
+            Side::Left => (
+                &self.left,
+                self.adjacency(Side::Left),
+                self.adjacency(Side::Right),
+            ),
+            Side::Right => (
+                &self.right,
+                self.adjacency(Side::Right),
+                self.adjacency(Side::Left),
+            ),
+        };
+        let mut weights: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut neighbors: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); values.len()];
+        for (v, through) in own.iter().enumerate() {
+            for &middle in through {
+                for &w in other[middle].iter().filter(|&&w| w != v) {
+                    *weights.entry((v, w)).or_default() += 1;
+                    neighbors[v].insert(w);
+                }
+            }
+        }
+        let nodes = values
+            .iter()
+            .zip(neighbors)
+            .map(|(&value, neighbors)| Node {
+                value,
+                neighbors: neighbors.into_iter().collect(),
+            })
+            .collect();
+        Projection {
+            graph: Graph { nodes },
+            weights,
+        }
+    }
+
+    /// Plain graph with left nodes first, then right nodes at offset left.len(),
+    /// and every edge in both directions
+    pub fn to_graph(&self) -> Graph {
+        let offset = self.left.len();
+        let mut nodes: Vec<Node> = self
+            .left
+            .iter()
+            .zip(&self.edges)
+            .map(|(&value, rights)| Node {
+                value,
+                neighbors: rights.iter().map(|&r| offset + r).collect(),
+            })
+            .collect();
+        nodes.extend(
+            self.right
+                .iter()
+                .zip(self.adjacency(Side::Right))
+                .map(|(&value, lefts)| Node {
+                    value,
+                    neighbors: lefts,
+                }),
+        );
+        Graph { nodes }
+    }
+
+    /// Split a graph into sides by 2-coloring its undirected edges, returning
+    /// the bipartite graph and each node's (side, index); None if an odd cycle
+    /// (or a self loop) makes that impossible
+    pub fn from_graph(graph: &Graph) -> Option<(BipartiteGraph, Vec<(Side, usize)>)> {
+        let adjacency = graph.undirected_adjacency();
+        let side = graph.two_coloring(&adjacency)?;
+
+        let mut bipartite = BipartiteGraph::default();
+        let placement: Vec<(Side, usize)> = (0..adjacency.len())
+            .map(|v| (side[v], bipartite.add_node(side[v], graph.nodes[v].value)))
+            .collect();
+        for (v, neighbors) in adjacency.iter().enumerate() {
+            if let (Side::Left, l) = placement[v] {
+                for &w in neighbors {
+                    bipartite.add_edge(l, placement[w].1);
+                }
+            }
+        }
+        Some((bipartite, placement))
+    }
+}
+
+impl<T> Graph<T> {
+    /// Two-coloring of the edges read as undirected, found by BFS: the
+    /// (left, right) node sets, each sorted, with the smallest node of every
+    /// component on the left. None if an odd cycle or a self-loop makes the
+    /// graph non-bipartite.
+    pub fn is_bipartite(&self) -> Option<(Vec<NodeId>, Vec<NodeId>)> {
+        let side = self.two_coloring(&self.undirected_adjacency())?;
+        let (left, right): (Vec<usize>, Vec<usize>) =
+            (0..side.len()).partition(|&v| side[v] == Side::Left);
+        Some((
+            left.into_iter().map(NodeId::new).collect(),
+            right.into_iter().map(NodeId::new).collect(),
+        ))
+    }
+
+    /// Maximum matching of a bipartite graph, edges read as undirected: pairs
+    /// (left, right) for the partition `is_bipartite` finds, sorted by left
+    /// node. None if the graph is not bipartite. Unlike
+    /// `BipartiteGraph::maximum_matching`, both ends are ids of this graph.
+    pub fn maximum_bipartite_matching(&self) -> Option<Vec<(NodeId, NodeId)>> {
+        let adjacency = self.undirected_adjacency();
+        let side = self.two_coloring(&adjacency)?;
+        // Index each side separately, as Hopcroft-Karp wants
+        let mut position = vec![0; side.len()];
+        let (mut lefts, mut rights) = (Vec::new(), Vec::new());
+        for (v, &s) in side.iter().enumerate() {
+            let nodes = if s == Side::Left {
+                &mut lefts
+            } else {
+                &mut rights
+            };
+            position[v] = nodes.len();
+            nodes.push(v);
+        }
+        let edges: Vec<Vec<usize>> = lefts
+            .iter()
+            .map(|&v| adjacency[v].iter().map(|&w| position[w]).collect())
+            .collect();
+        let matching = hopcroft_karp(&edges, rights.len())
+            .into_iter()
+            .enumerate()
+            .filter_map(|(l, r)| r.map(|r| (NodeId::new(lefts[l]), NodeId::new(rights[r]))))
+            .collect();
+        Some(matching)
+    }
+
+    /// Side of every node in a BFS two-coloring of `adjacency`, the graph's
+    /// undirected adjacency; None if there is an odd cycle or a self-loop
+    fn two_coloring(&self, adjacency: &[Vec<usize>]) -> Option<Vec<Side>> {
+        if self
+            .nodes
+            .iter()
+            .enumerate()
+            .any(|(v, node)| node.neighbors.contains(&v))
+        {
+            return None;
+        }
+        let mut side: Vec<Option<Side>> = vec![None; adjacency.len()];
+        let mut queue = VecDeque::new();
+        for start in 0..adjacency.len() {
+            if side[start].is_some() {
+                continue;
+            }
+            side[start] = Some(Side::Left);
+            queue.push_back(start);
+            while let Some(v) = queue.pop_front() {
+                let flipped = if side[v] == Some(Side::Left) {
+                    Side::Right
+                } else {
+                    Side::Left
+                };
+                for &w in &adjacency[v] {
+                    match side[w] {
+                        None => {
+                            side[w] = Some(flipped);
+                            queue.push_back(w);
+                        }
+                        Some(s) if s != flipped => return None,
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+        side.into_iter().collect()
+    }
+}
+
+/// Marks an unmatched node, and an unreachable one in the BFS layers
+const FREE: usize = usize::MAX;
+
+/// Hopcroft-Karp over left -> right adjacency: the right node matched to each
+/// left node. Each phase layers the graph by BFS from the free left nodes,
+/// then augments along vertex-disjoint shortest paths found by DFS.
+fn hopcroft_karp(edges: &[Vec<usize>], right_len: usize) -> Vec<Option<usize>> {
+    let mut match_left = vec![FREE; edges.len()];
+    let mut match_right = vec![FREE; right_len];
+    let mut layer = vec![FREE; edges.len()];
+    let mut queue = VecDeque::new();
+    loop {
+        // BFS layers of left nodes, alternating free and matched edges
+        let mut found_free = false;
+        for (l, layer) in layer.iter_mut().enumerate() {
+            *layer = if match_left[l] == FREE { 0 } else { FREE };
+            if *layer == 0 {
+                queue.push_back(l);
+            }
+        }
+        while let Some(l) = queue.pop_front() {
+            for &r in &edges[l] {
+                let next = match_right[r];
+                if next == FREE {
+                    found_free = true;
+                } else if layer[next] == FREE {
+                    layer[next] = layer[l] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        if !found_free {
+            break;
+        }
+
+        // Augment from every free left node along the layers; `cursor` keeps
+        // each node's next edge to try, so no edge is scanned twice per phase
+        let mut cursor = vec![0; edges.len()];
+        for root in 0..edges.len() {
+            if match_left[root] != FREE {
+                continue;
+            }
+            let mut stack = vec![root];
+            while let Some(&l) = stack.last() {
+                let Some(&r) = edges[l].get(cursor[l]) else {
+                    // Dead end: drop the node from this phase's layers
+                    layer[l] = FREE;
+                    stack.pop();
+                    continue;
+                };
+                cursor[l] += 1;
+                let next = match_right[r];
+                if next == FREE {
+                    // Flip the path: each node on the stack takes the edge it is on
+                    for &l in &stack {
+                        let r = edges[l][cursor[l] - 1];
+                        match_left[l] = r;
+                        match_right[r] = l;
+                    }
+                    break;
+                }
+                if layer[next] == layer[l] + 1 {
+                    stack.push(next);
+                }
+            }
+        }
+    }
+    match_left
+        .into_iter()
+        .map(|r| (r != FREE).then_some(r))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bipartite(left: usize, right: usize, edges: &[(usize, usize)]) -> BipartiteGraph {
+        let mut graph = BipartiteGraph::default();
+        for value in 0..left {
+            graph.add_node(Side::Left, value as i32);
+        }
+        for value in 0..right {
+            graph.add_node(Side::Right, value as i32);
+        }
+        for &(l, r) in edges {
+            graph.add_edge(l, r);
+        }
+        graph
+    }
+
+    #[test]
+    fn hopcroft_karp_finds_maximum_matchings() {
+        // Perfect only once left 0 gives right 0 up to left 1
+        let edges = [(0, 0), (0, 1), (1, 0), (2, 1), (2, 2), (3, 2), (3, 3)];
+        let matching = bipartite(4, 4, &edges).maximum_matching();
+        assert_eq!(matching, vec![(0, 1), (1, 0), (2, 2), (3, 3)]);
+
+        // Three left nodes compete for right 0
+        let matching = bipartite(4, 2, &[(0, 0), (1, 0), (2, 0), (3, 1)]).maximum_matching();
+        assert_eq!(matching.len(), 2);
+        assert!(matching.contains(&(3, 1)));
+
+        let hexagon = Graph::cycle(6);
+        let matching = hexagon.maximum_bipartite_matching().unwrap();
+        assert_eq!(matching.len(), 3);
+        assert!(matching
+            .iter()
+            .all(|&pair| hexagon.edges().any(|edge| edge == pair)));
+        assert_eq!(
+            Graph::path(4).maximum_bipartite_matching().unwrap().len(),
+            2
+        );
+        assert_eq!(Graph::cycle(5).maximum_bipartite_matching(), None);
+    }
+}
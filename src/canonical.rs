@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+
+use crate::graph::Graph;
+use crate::isomorphism::EdgeSets;
+
+/// Canonical form of a graph. Isomorphic graphs with matching node values get
+/// equal `code`s, so the code (or its hash) deduplicates graphs up to isomorphism.
+#[derive(Debug, Clone)]
+pub struct CanonicalForm {
+    pub order: Vec<usize>, // Original node at each canonical position
+    pub code: Vec<i64>,    // Node count, values and adjacency in canonical order
+}
+
+impl CanonicalForm {
+    /// Stable 64-bit FNV-1a hash of the code, identical across runs and platforms
+    pub fn hash(&self) -> u64 {
+        let mut hash = 0xCBF2_9CE4_8422_2325u64;
+        for word in &self.code {
+            for byte in word.to_le_bytes() {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0000_0100_0000_01B3);
+            }
+        }
+        hash
+    }
+}
+
+impl Graph {
+    /// Canonical form by color refinement plus individualization, keeping the
+    /// smallest code over the search tree (a small-graph take on nauty).
+    /// Interchangeable twin nodes are branched on once, which keeps highly
+    /// symmetric graphs such as cliques and stars cheap.
+    pub fn canonical_form(&self) -> CanonicalForm {
+        let edges = EdgeSets::new(self);
+        let n = self.nodes.len();
+
+        // Initial colors rank nodes by value
+        let mut values: Vec<i32> = self.nodes.iter().map(|node| node.value).collect();
+        values.sort_unstable();
+        values.dedup();
+        let colors: Vec<usize> = self
+            .nodes
+            .iter()
+            .map(|node| values.binary_search(&node.value).unwrap())
+            .collect();
+
+        let mut best: Option<CanonicalForm> = None;
+        canonical_search(self, &edges, refine_colors(&edges, colors), &mut best);
+        best.unwrap_or(CanonicalForm {
+            order: Vec::new(),
+            code: vec![n as i64],
+        })
+    }
+
+    /// Hash of the canonical form
+    pub fn canonical_hash(&self) -> u64 {
+        self.canonical_form().hash()
+    }
+}
+
+/// Refine a coloring until every node of a color class sees the same multiset
+/// of colors among its out- and in-neighbors. New colors are ranked by
+/// (old color, neighbor colors), so the result does not depend on labeling.
+fn refine_colors(edges: &EdgeSets, mut colors: Vec<usize>) -> Vec<usize> {
+    let n = colors.len();
+    let mut class_count = colors.iter().collect::<HashSet<_>>().len();
+    loop {
+        let signatures: Vec<(usize, Vec<usize>, Vec<usize>)> = (0..n)
+            .map(|v| {
+                let mut out: Vec<usize> = edges.out[v].iter().map(|&w| colors[w]).collect();
+                let mut inc: Vec<usize> = edges.inc[v].iter().map(|&w| colors[w]).collect();
+                out.sort_unstable();
+                inc.sort_unstable();
+                (colors[v], out, inc)
+            })
+            .collect();
+        let mut ranked: Vec<&(usize, Vec<usize>, Vec<usize>)> = signatures.iter().collect();
+        ranked.sort();
+        ranked.dedup();
+        colors = signatures
+            .iter()
+            .map(|signature| ranked.binary_search(&signature).unwrap())
+            .collect();
+        if ranked.len() == class_count {
+            return colors;
+        }
+        class_count = ranked.len();
+    }
+}
+
+fn canonical_search(
+    graph: &Graph,
+    edges: &EdgeSets,
+    colors: Vec<usize>,
+    best: &mut Option<CanonicalForm>,
+) {
+    let n = colors.len();
+    let mut cells: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (v, &color) in colors.iter().enumerate() {
+        cells[color].push(v);
+    }
+
+    // Discrete coloring: read off the code and keep the smallest
+    let Some(cell) = cells.iter().find(|cell| cell.len() > 1) else {
+        let mut order = vec![0; n];
+        for (v, &color) in colors.iter().enumerate() {
+            order[color] = v;
+        }
+        let mut code = vec![n as i64];
+        code.extend(order.iter().map(|&v| i64::from(graph.nodes[v].value)));
+        for &v in &order {
+            let mut targets: Vec<i64> = edges.out[v].iter().map(|&w| colors[w] as i64).collect();
+            targets.sort_unstable();
+            code.push(targets.len() as i64);
+            code.extend(targets);
+        }
+        if best.as_ref().is_none_or(|best| code < best.code) {
+            *best = Some(CanonicalForm { order, code });
+        }
+        return;
+    };
+
+    // Individualize each member of the first non-singleton cell in turn
+    let twins = |u: usize, v: usize| {
+        let strip = |list: &[usize], other: usize| {
+            let mut list: Vec<usize> = list.iter().copied().filter(|&w| w != other).collect();
+            list.sort_unstable();
+            list
+        };
+        strip(&edges.out[u], v) == strip(&edges.out[v], u)
+            && strip(&edges.inc[u], v) == strip(&edges.inc[v], u)
+            && edges.has(u, u) == edges.has(v, v)
+            && edges.has(u, v) == edges.has(v, u)
+    };
+    let mut tried: Vec<usize> = Vec::new();
+    for &v in cell {
+        if tried.iter().any(|&u| twins(u, v)) {
+            continue;
+        }
+        tried.push(v);
+        let individualized = (0..n)
+            .map(|u| 2 * colors[u] + usize::from(colors[u] == colors[v] && u != v))
+            .collect();
+        canonical_search(graph, edges, refine_colors(edges, individualized), best);
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::VecDeque;
+
+use crate::graph::Graph;
+use crate::sampling::Rng;
+
+impl<T> Graph<T> {
+    /// Exact betweenness centrality by Brandes' algorithm over out-edges: for
+    /// every node, the sum over ordered pairs (s, t) of the fraction of shortest
+    /// s-t paths passing through it
+    pub fn betweenness_centrality(&self) -> Vec<f64> {
+        let mut centrality = vec![0.0; self.nodes.len()];
+        for source in 0..self.nodes.len() {
+            for (total, dependency) in centrality.iter_mut().zip(self.dependencies(source)) {
+                *total += dependency;
+            }
+        }
+        centrality
+    }
+
+    /// Brandes dependency of `source` on every node: the sum over targets t of
+    /// the fraction of shortest source-t paths through the node; 0 for the
+    /// source itself
+    pub(crate) fn dependencies(&self, source: usize) -> Vec<f64> {
+        let (order, predecessors, paths) = self.shortest_path_dag(source, None);
+        let mut dependency = vec![0.0; self.nodes.len()];
+        for &w in order.iter().rev() {
+            for &v in &predecessors[w] {
+                dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+            }
+        }
+        dependency[source] = 0.0;
+        dependency
+    }
+
+    /// Exact closeness centrality over out-edges, in the Wasserman–Faust form
+    /// that stays meaningful on disconnected graphs: a node reaching r other
+    /// nodes at total hop distance d scores (r / d) * (r / (n - 1)), and 0 if it
+    /// reaches none. On a strongly connected graph this is (n - 1) / d. One BFS
+    /// per node, O(V * (V + E)).
+    pub fn closeness_centrality(&self) -> Vec<f64> {
+        (0..self.nodes.len())
+            .map(|node| self.closeness(node))
+            .collect()
+    }
+
+    /// Closeness of one node; see `closeness_centrality`
+    pub(crate) fn closeness(&self, node: usize) -> f64 {
+        let n = self.nodes.len();
+        let mut distance = vec![usize::MAX; n];
+        distance[node] = 0;
+        let (mut reached, mut total) = (0usize, 0usize);
+        let mut queue = VecDeque::from([node]);
+        while let Some(v) = queue.pop_front() {
+            for &w in &self.nodes[v].neighbors {
+                if distance[w] == usize::MAX {
+                    distance[w] = distance[v] + 1;
+                    reached += 1;
+                    total += distance[w];
+                    queue.push_back(w);
+                }
+            }
+        }
+        if total == 0 {
+            return 0.0;
+        }
+        let reached = reached as f64;
+        reached / total as f64 * (reached / (n - 1) as f64)
+    }
+
+    /// BFS from `source` recording visit order, shortest-path predecessors and
+    /// path counts; with a `target`, stops once the target's level is complete
+    fn shortest_path_dag(
+        &self,
+        source: usize,
+        target: Option<usize>,
+    ) -> (Vec<usize>, Vec<Vec<usize>>, Vec<f64>) {
+        let n = self.nodes.len();
+        let mut distance = vec![usize::MAX; n];
+        let mut predecessors = vec![Vec::new(); n];
+        let mut paths = vec![0.0; n];
+        let mut order = Vec::new();
+        distance[source] = 0;
+        paths[source] = 1.0;
+        let mut queue = VecDeque::from([source]);
+        while let Some(v) = queue.pop_front() {
+            if target.is_some_and(|t| distance[t] < distance[v]) {
+                break;
+            }
+            order.push(v);
+            for &w in &self.nodes[v].neighbors {
+                if distance[w] == usize::MAX {
+                    distance[w] = distance[v] + 1;
+                    queue.push_back(w);
+                }
+                if distance[w] == distance[v] + 1 {
+                    paths[w] += paths[v];
+                    predecessors[w].push(v);
+                }
+            }
+        }
+        (order, predecessors, paths)
+    }
+
+    /// Upper bound on the vertex diameter (nodes on a longest shortest path):
+    /// twice the undirected eccentricity of one node per component, plus one
+    fn vertex_diameter_bound(&self) -> usize {
+        let adjacency = self.undirected_adjacency();
+        let mut depth = vec![usize::MAX; adjacency.len()];
+        let mut bound = 1;
+        for start in 0..adjacency.len() {
+            if depth[start] != usize::MAX {
+                continue;
+            }
+            depth[start] = 0;
+            let mut queue = VecDeque::from([start]);
+            while let Some(v) = queue.pop_front() {
+                bound = bound.max(2 * depth[v] + 1);
+                for &w in &adjacency[v] {
+                    if depth[w] == usize::MAX {
+                        depth[w] = depth[v] + 1;
+                        queue.push_back(w);
+                    }
+                }
+            }
+        }
+        bound
+    }
+
+    /// Betweenness estimated by Riondato–Kornaropoulos sampling: each sample
+    /// draws a random ordered pair and a uniformly random shortest path between
+    /// them. With probability at least 1 - delta every estimate is within
+    /// epsilon * n * (n - 1) of `betweenness_centrality`.
+    pub fn approximate_betweenness(&self, epsilon: f64, delta: f64, seed: u64) -> Vec<f64> {
+        let n = self.nodes.len();
+        let mut centrality = vec![0.0; n];
+        if n < 2 {
+            return centrality;
+        }
+        let diameter = self.vertex_diameter_bound().max(3);
+        let samples = (0.5 / (epsilon * epsilon)
+            * (((diameter - 2) as f64).log2().floor() + 1.0 + (1.0 / delta).ln()))
+        .ceil() as usize;
+
+        let mut rng = Rng::new(seed);
+        let weight = (n * (n - 1)) as f64 / samples as f64;
+        for _ in 0..samples {
+            let source = rng.below(n);
+            let target = (source + 1 + rng.below(n - 1)) % n;
+            let (_, predecessors, paths) = self.shortest_path_dag(source, Some(target));
+            if paths[target] == 0.0 {
+                continue;
+            }
+
+            // Walk back choosing each predecessor with probability proportional
+            // to its path count
+            let mut v = target;
+            loop {
+                let mut pick = rng.next_f64() * paths[v];
+                let mut previous = predecessors[v][0];
+                for &w in &predecessors[v] {
+                    previous = w;
+                    if pick < paths[w] {
+                        break;
+                    }
+                    pick -= paths[w];
+                }
+                if previous == source {
+                    break;
+                }
+                centrality[previous] += weight;
+                v = previous;
+            }
+        }
+        centrality
+    }
+}
+
+/// HyperLogLog counter with 2^b one-byte registers
+#[derive(Debug, Clone, PartialEq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    pub fn new(log2_registers: u32) -> HyperLogLog {
+        HyperLogLog {
+            registers: vec![0; 1 << log2_registers],
+        }
+    }
+
+    pub fn insert_hash(&mut self, hash: u64) {
+        let bits = self.registers.len().trailing_zeros();
+        let register = (hash >> (64 - bits)) as usize;
+        let rank = ((hash << bits) | (1 << (bits - 1))).leading_zeros() as u8 + 1;
+        self.registers[register] = self.registers[register].max(rank);
+    }
+
+    /// Register-wise max, returning whether anything changed
+    pub fn union(&mut self, other: &HyperLogLog) -> bool {
+        let mut changed = false;
+        for (a, &b) in self.registers.iter_mut().zip(&other.registers) {
+            if b > *a {
+                *a = b;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&r| 2f64.powi(-i32::from(r)))
+            .sum();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        }
+    }
+}
+
+/// Approximate distance statistics from `Graph::hyperball`
+#[derive(Debug, Clone)]
+pub struct HyperBall {
+    /// Estimated number of (source, target) pairs within distance t, for each t
+    pub neighborhood: Vec<f64>,
+    /// Per node: 1 / sum of distances to reachable nodes (0 if none)
+    pub closeness: Vec<f64>,
+    /// Per node: sum of 1 / distance over reachable nodes
+    pub harmonic: Vec<f64>,
+}
+
+impl HyperBall {
+    /// Smallest distance, linearly interpolated, within which the given fraction
+    /// of all reachable pairs lie; 0.9 gives the usual effective diameter
+    pub fn effective_diameter(&self, fraction: f64) -> f64 {
+        let total = *self.neighborhood.last().unwrap_or(&0.0);
+        let goal = fraction * total;
+        for t in 1..self.neighborhood.len() {
+            let (low, high) = (self.neighborhood[t - 1], self.neighborhood[t]);
+            if high >= goal {
+                return (t - 1) as f64
+                    + if high > low {
+                        (goal - low) / (high - low)
+                    } else {
+                        1.0
+                    };
+            }
+        }
+        0.0
+    }
+}
+
+impl<T> Graph<T> {
+    /// HyperBall: one HyperLogLog counter per node for the ball of nodes
+    /// reachable within t out-edges, grown by unioning successors' counters
+    /// until no counter changes. Relative error is about 1.04 / sqrt(2^b).
+    pub fn hyperball(&self, log2_registers: u32, seed: u64) -> HyperBall {
+        let n = self.nodes.len();
+        let log2_registers = log2_registers.clamp(4, 16);
+        let mut counters: Vec<HyperLogLog> = (0..n)
+            .map(|v| {
+                let mut counter = HyperLogLog::new(log2_registers);
+                counter.insert_hash(
+                    Rng::new(seed ^ (v as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)).next_u64(),
+                );
+                counter
+            })
+            .collect();
+        let mut sizes: Vec<f64> = counters.iter().map(HyperLogLog::estimate).collect();
+        let mut neighborhood = vec![sizes.iter().sum::<f64>()];
+        let (mut distance_sum, mut harmonic) = (vec![0.0; n], vec![0.0; n]);
+        for t in 1.. {
+            let mut next = counters.clone();
+            let mut changed = false;
+            for (v, node) in self.nodes.iter().enumerate() {
+                for &w in &node.neighbors {
+                    changed |= next[v].union(&counters[w]);
+                }
+            }
+            if !changed {
+                break;
+            }
+            for v in 0..n {
+                let size = next[v].estimate();
+                let reached = (size - sizes[v]).max(0.0);
+                distance_sum[v] += t as f64 * reached;
+                harmonic[v] += reached / t as f64;
+                sizes[v] = size;
+            }
+            neighborhood.push(sizes.iter().sum());
+            counters = next;
+        }
+        let closeness = distance_sum
+            .iter()
+            .map(|&sum| if sum > 0.0 { 1.0 / sum } else { 0.0 })
+            .collect();
+        HyperBall {
+            neighborhood,
+            closeness,
+            harmonic,
+        }
+    }
+}
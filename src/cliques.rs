@@ -0,0 +1,223 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::graph::{Graph, NodeId};
+use crate::union_find::UnionFind;
+
+impl<T> Graph<T> {
+    /// Degeneracy ordering of the undirected simple graph: repeatedly remove a
+    /// node of minimum remaining degree. Returns the order and the degeneracy.
+    pub fn degeneracy_order(&self) -> (Vec<NodeId>, usize) {
+        let (order, degeneracy) = self.degeneracy_indices();
+        (order.into_iter().map(NodeId::new).collect(), degeneracy)
+    }
+
+    pub(crate) fn degeneracy_indices(&self) -> (Vec<usize>, usize) {
+        let adjacency = self.undirected_adjacency();
+        let n = adjacency.len();
+        let mut degree: Vec<usize> = adjacency.iter().map(Vec::len).collect();
+        let mut buckets: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); n];
+        for v in 0..n {
+            buckets[degree[v]].insert(v);
+        }
+        let mut removed = vec![false; n];
+        let (mut order, mut degeneracy, mut low) = (Vec::with_capacity(n), 0, 0usize);
+        for _ in 0..n {
+            low = low.saturating_sub(1);
+            while buckets[low].is_empty() {
+                low += 1;
+            }
+            let v = buckets[low].pop_first().unwrap();
+            degeneracy = degeneracy.max(low);
+            removed[v] = true;
+            order.push(v);
+            for &w in &adjacency[v] {
+                if !removed[w] {
+                    buckets[degree[w]].remove(&w);
+                    degree[w] -= 1;
+                    buckets[degree[w]].insert(w);
+                }
+            }
+        }
+        (order, degeneracy)
+    }
+
+    /// Iterate over the maximal cliques with at least `min_size` nodes, reading
+    /// edges as undirected. Each clique is yielded once, sorted ascending.
+    pub fn maximal_cliques(&self, min_size: usize) -> MaximalCliques {
+        let adjacency: Vec<HashSet<usize>> = self
+            .undirected_adjacency()
+            .into_iter()
+            .map(|neighbors| neighbors.into_iter().collect())
+            .collect();
+        let (order, _) = self.degeneracy_indices();
+        MaximalCliques {
+            adjacency,
+            order,
+            next_root: 0,
+            stack: Vec::new(),
+            min_size,
+        }
+    }
+}
+
+/// One Bron–Kerbosch call: clique R, candidates P, excluded X, and the
+/// candidates still to branch on (P minus the pivot's neighbors)
+struct CliqueFrame {
+    clique: Vec<usize>,
+    candidates: Vec<usize>,
+    excluded: Vec<usize>,
+    branches: Vec<usize>,
+}
+
+/// Iterator returned by `Graph::maximal_cliques`: Bron–Kerbosch with Tomita
+/// pivoting, run once per node in degeneracy order with that node's later
+/// neighbors as candidates, so the work is bounded by the degeneracy.
+pub struct MaximalCliques {
+    adjacency: Vec<HashSet<usize>>,
+    order: Vec<usize>,
+    next_root: usize,
+    stack: Vec<CliqueFrame>,
+    min_size: usize,
+}
+
+impl MaximalCliques {
+    /// Frame for (R, P, X), or the finished clique if P and X are both empty.
+    /// Subtrees that cannot reach `min_size` are dropped.
+    fn frame(
+        &self,
+        clique: Vec<usize>,
+        candidates: Vec<usize>,
+        excluded: Vec<usize>,
+    ) -> Option<Result<CliqueFrame, Vec<usize>>> {
+        if clique.len() + candidates.len() < self.min_size {
+            return None;
+        }
+        if candidates.is_empty() {
+            return excluded.is_empty().then_some(Err(clique));
+        }
+        let pivot = candidates
+            .iter()
+            .chain(&excluded)
+            .max_by_key(|&&u| {
+                candidates
+                    .iter()
+                    .filter(|v| self.adjacency[u].contains(v))
+                    .count()
+            })
+            .copied()
+            .unwrap();
+        let branches = candidates
+            .iter()
+            .copied()
+            .filter(|v| !self.adjacency[pivot].contains(v))
+            .collect();
+        Some(Ok(CliqueFrame {
+            clique,
+            candidates,
+            excluded,
+            branches,
+        }))
+    }
+}
+
+impl Iterator for MaximalCliques {
+    type Item = Vec<NodeId>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next = match self.stack.last_mut() {
+                Some(frame) => match frame.branches.pop() {
+                    Some(v) => {
+                        let mut clique = frame.clique.clone();
+                        clique.push(v);
+                        let neighbors = &self.adjacency[v];
+                        let candidates = frame
+                            .candidates
+                            .iter()
+                            .copied()
+                            .filter(|w| neighbors.contains(w))
+                            .collect();
+                        let excluded = frame
+                            .excluded
+                            .iter()
+                            .copied()
+                            .filter(|w| neighbors.contains(w))
+                            .collect();
+                        frame.candidates.retain(|&w| w != v);
+                        frame.excluded.push(v);
+                        self.frame(clique, candidates, excluded)
+                    }
+                    None => {
+                        self.stack.pop();
+                        continue;
+                    }
+                },
+                None => {
+                    let &v = self.order.get(self.next_root)?;
+                    self.next_root += 1;
+                    let earlier: HashSet<usize> =
+                        self.order[..self.next_root - 1].iter().copied().collect();
+                    let (excluded, candidates) =
+                        self.adjacency[v].iter().partition(|w| earlier.contains(w));
+                    self.frame(vec![v], candidates, excluded)
+                }
+            };
+            match next {
+                Some(Ok(frame)) => self.stack.push(frame),
+                Some(Err(mut clique)) => {
+                    clique.sort_unstable();
+                    return Some(clique.into_iter().map(NodeId::new).collect());
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+impl<T> Graph<T> {
+    /// k-clique percolation communities: unions of k-cliques reachable from one
+    /// another through k-cliques sharing k - 1 nodes. Computed from the maximal
+    /// cliques of size >= k, two of which percolate when they share at least
+    /// k - 1 nodes. Communities may overlap; each is sorted, and they are
+    /// ordered by smallest member.
+    pub fn clique_percolation(&self, k: usize) -> Vec<Vec<NodeId>> {
+        let k = k.max(2);
+        let cliques: Vec<Vec<NodeId>> = self.maximal_cliques(k).collect();
+        let mut by_node: HashMap<NodeId, Vec<usize>> = HashMap::new();
+        for (index, clique) in cliques.iter().enumerate() {
+            for &v in clique {
+                by_node.entry(v).or_default().push(index);
+            }
+        }
+
+        // Union cliques that share k - 1 nodes, counting shared nodes per pair
+        let mut sets = UnionFind::new(cliques.len());
+        for (index, clique) in cliques.iter().enumerate() {
+            let mut shared: HashMap<usize, usize> = HashMap::new();
+            for v in clique {
+                for &other in &by_node[v] {
+                    if other > index {
+                        *shared.entry(other).or_default() += 1;
+                    }
+                }
+            }
+            for (other, count) in shared {
+                if count >= k - 1 {
+                    sets.union(index, other);
+                }
+            }
+        }
+
+        let mut communities: HashMap<usize, BTreeSet<NodeId>> = HashMap::new();
+        for (index, clique) in cliques.iter().enumerate() {
+            let community = sets.find(index);
+            communities.entry(community).or_default().extend(clique);
+        }
+        let mut communities: Vec<Vec<NodeId>> = communities
+            .into_values()
+            .map(|members| members.into_iter().collect())
+            .collect();
+        communities.sort_unstable();
+        communities
+    }
+}
@@ -0,0 +1,88 @@
+use crate::graph::{Graph, NodeId};
+use crate::undirected::UnGraph;
+
+/// Order in which `Graph::greedy_coloring` colors the nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColoringOrder {
+    /// Ascending node index
+    Natural,
+    /// Welsh–Powell: descending degree, ties by index, so the most constrained
+    /// nodes pick first; uses at most max over i of min(d_i + 1, i) colors, for
+    /// the i-th largest degree d_i
+    WelshPowell,
+}
+
+/// A proper node coloring: adjacent nodes never share a color. Colors are
+/// numbered from 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coloring {
+    colors: Vec<usize>,
+    color_count: usize,
+}
+
+impl Coloring {
+    /// Color of `node`; panics if it is out of range
+    pub fn color(&self, node: NodeId) -> usize {
+        self.colors[node.index()]
+    }
+
+    /// Color of every node, by index
+    pub fn colors(&self) -> &[usize] {
+        &self.colors
+    }
+
+    /// Number of distinct colors used
+    pub fn color_count(&self) -> usize {
+        self.color_count
+    }
+
+    /// Nodes of each color, by color then ascending index
+    pub fn classes(&self) -> Vec<Vec<NodeId>> {
+        let mut classes = vec![Vec::new(); self.color_count];
+        for (index, &color) in self.colors.iter().enumerate() {
+            classes[color].push(NodeId::new(index));
+        }
+        classes
+    }
+}
+
+impl<T> Graph<T> {
+    /// Greedy coloring, reading edges as undirected and ignoring self-loops:
+    /// each node in `order` takes the smallest color none of its colored
+    /// neighbors has. Not optimal in general (that is NP-hard), but at most
+    /// one more color than the maximum degree. O(V + E) after the ordering.
+    pub fn greedy_coloring(&self, order: ColoringOrder) -> Coloring {
+        let adjacency = self.undirected_adjacency();
+        let mut sequence: Vec<usize> = (0..adjacency.len()).collect();
+        if order == ColoringOrder::WelshPowell {
+            sequence.sort_by_key(|&v| std::cmp::Reverse(adjacency[v].len()));
+        }
+
+        let unset = usize::MAX;
+        let mut colors = vec![unset; adjacency.len()];
+        // taken[c] == v marks color c as used by a neighbor of v
+        let mut taken = vec![unset; adjacency.len() + 1];
+        let mut color_count = 0;
+        for v in sequence {
+            for &w in &adjacency[v] {
+                if colors[w] != unset {
+                    taken[colors[w]] = v;
+                }
+            }
+            let color = (0..).find(|&c| taken[c] != v).unwrap();
+            colors[v] = color;
+            color_count = color_count.max(color + 1);
+        }
+        Coloring {
+            colors,
+            color_count,
+        }
+    }
+}
+
+impl<T> UnGraph<T> {
+    /// Greedy coloring; see `Graph::greedy_coloring`
+    pub fn greedy_coloring(&self, order: ColoringOrder) -> Coloring {
+        self.as_graph().greedy_coloring(order)
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+
+use crate::graph::{Graph, NodeId};
+use crate::sampling::Rng;
+
+impl<T> Graph<T> {
+    /// Communities by asynchronous label propagation (Raghavan, Albert and
+    /// Kumara), reading edges as undirected: every node starts in a community
+    /// of its own, then in each pass, in a fresh random order, takes the label
+    /// most frequent among its neighbors, ties broken at random but keeping
+    /// its own label when that is among the most frequent. Stops after a pass
+    /// that changes nothing, or after `max_iters` passes. Near-linear per
+    /// pass; the result depends on `seed`. Community ids run from 0 in order
+    /// of each community's smallest node.
+    pub fn label_propagation_communities(&self, max_iters: usize, seed: u64) -> Vec<usize> {
+        let adjacency = self.undirected_adjacency();
+        let n = adjacency.len();
+        let mut rng = Rng::new(seed);
+        let mut labels: Vec<usize> = (0..n).collect();
+        let mut order: Vec<usize> = (0..n).collect();
+        // Neighbor count per label, reset through `seen` after each node
+        let mut counts = vec![0usize; n];
+        let mut seen = Vec::new();
+        let mut best = Vec::new();
+        for _ in 0..max_iters {
+            rng.shuffle(&mut order);
+            let mut changed = false;
+            for &v in &order {
+                for &w in &adjacency[v] {
+                    if counts[labels[w]] == 0 {
+                        seen.push(labels[w]);
+                    }
+                    counts[labels[w]] += 1;
+                }
+                let top = seen.iter().map(|&label| counts[label]).max();
+                if let Some(top) = top {
+                    best.clear();
+                    best.extend(seen.iter().copied().filter(|&label| counts[label] == top));
+                    if counts[labels[v]] < top {
+                        labels[v] = best[rng.below(best.len())];
+                        changed = true;
+                    }
+                }
+                for label in seen.drain(..) {
+                    counts[label] = 0;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        renumber(&labels)
+    }
+
+    /// Newman modularity of a split into communities (`communities[v]` is the
+    /// community of node v) at the given resolution, reading edges as
+    /// undirected: the sum over communities of (internal weight) / m minus
+    /// resolution * ((total degree) / 2m)^2, for total edge weight m. When
+    /// both a -> b and b -> a are listed, the pair counts once, with the larger
+    /// weight; self-loops are ignored. 0 for a graph without weight. Panics if
+    /// `communities` is shorter than the node count.
+    pub fn modularity<W>(&self, communities: &[usize], resolution: f64, weight: W) -> f64
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        let edges = self.community_weights(weight);
+        let total: f64 = edges.values().sum();
+        if total == 0.0 {
+            return 0.0;
+        }
+        let count = communities.iter().map(|&c| c + 1).max().unwrap_or(0);
+        let (mut internal, mut degree) = (vec![0.0; count], vec![0.0; count]);
+        for (&(a, b), &w) in &edges {
+            let (ca, cb) = (communities[a], communities[b]);
+            if ca == cb {
+                internal[ca] += w;
+            }
+            degree[ca] += w;
+            degree[cb] += w;
+        }
+        (0..count)
+            .map(|c| internal[c] / total - resolution * (degree[c] / (2.0 * total)).powi(2))
+            .sum()
+    }
+
+    /// Communities by the Louvain method (Blondel et al.), reading edges as
+    /// undirected with the same weighting as `modularity`. Each level moves
+    /// nodes one at a time, in index order, to the neighboring community with
+    /// the largest modularity gain until no move helps, then merges every
+    /// community into a single node and repeats on the smaller graph; it
+    /// stops when a level moves nothing. Higher `resolution` gives more,
+    /// smaller communities; 1 is standard modularity. Deterministic, and
+    /// near-linear in practice. Community ids run from 0 in order of each
+    /// community's smallest node.
+    pub fn louvain_communities<W>(&self, resolution: f64, weight: W) -> Vec<usize>
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        let n = self.nodes.len();
+        let mut level = LouvainLevel {
+            adjacency: vec![Vec::new(); n],
+            self_loops: vec![0.0; n],
+        };
+        let mut total = 0.0;
+        for ((a, b), w) in self.community_weights(weight) {
+            level.adjacency[a].push((b, w));
+            level.adjacency[b].push((a, w));
+            total += w;
+        }
+        let mut membership: Vec<usize> = (0..n).collect();
+        if total == 0.0 {
+            return membership;
+        }
+        while let Some(communities) = level.local_moves(total, resolution) {
+            let communities = renumber(&communities);
+            for community in &mut membership {
+                *community = communities[*community];
+            }
+            level = level.aggregate(&communities);
+        }
+        renumber(&membership)
+    }
+
+    /// Weight of each undirected pair (a, b) with a < b; see `modularity`
+    fn community_weights<W>(&self, weight: W) -> BTreeMap<(usize, usize), f64>
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        let mut edges = BTreeMap::new();
+        for (from, node) in self.nodes.iter().enumerate() {
+            for &to in node.neighbors.iter().filter(|&&to| to != from) {
+                let w = weight(NodeId::new(from), NodeId::new(to)) as f64;
+                let heaviest = edges.entry((from.min(to), from.max(to))).or_insert(w);
+                *heaviest = heaviest.max(w);
+            }
+        }
+        edges.retain(|_, w| *w > 0.0);
+        edges
+    }
+}
+
+/// Weighted undirected graph of one Louvain level, whose nodes are the
+/// communities of the level below
+struct LouvainLevel {
+    adjacency: Vec<Vec<(usize, f64)>>, // Each edge listed at both ends
+    self_loops: Vec<f64>,              // Weight inside each merged node
+}
+
+impl LouvainLevel {
+    /// Move nodes between communities while some move raises modularity;
+    /// the community of each node, or None if nothing moved
+    fn local_moves(&self, total: f64, resolution: f64) -> Option<Vec<usize>> {
+        let n = self.adjacency.len();
+        let degree: Vec<f64> = (0..n)
+            .map(|v| {
+                2.0 * self.self_loops[v] + self.adjacency[v].iter().map(|&(_, w)| w).sum::<f64>()
+            })
+            .collect();
+        let mut community: Vec<usize> = (0..n).collect();
+        let mut community_degree = degree.clone();
+        // Weight from the current node into each community, reset through `seen`
+        let mut links = vec![0.0; n];
+        let mut seen = Vec::new();
+        let mut moved = false;
+        loop {
+            let mut improved = false;
+            for v in 0..n {
+                for &(w, weight) in &self.adjacency[v] {
+                    if links[community[w]] == 0.0 {
+                        seen.push(community[w]);
+                    }
+                    links[community[w]] += weight;
+                }
+                let own = community[v];
+                community_degree[own] -= degree[v];
+                // Gain of joining c, up to terms shared by every choice
+                let gain = |c: usize, links: &[f64], community_degree: &[f64]| {
+                    links[c] / total
+                        - resolution * community_degree[c] * degree[v] / (2.0 * total * total)
+                };
+                let (mut best, mut best_gain) = (own, gain(own, &links, &community_degree));
+                for &c in &seen {
+                    let candidate = gain(c, &links, &community_degree);
+                    if candidate > best_gain + 1e-12 {
+                        (best, best_gain) = (c, candidate);
+                    }
+                }
+                community_degree[best] += degree[v];
+                if best != own {
+                    community[v] = best;
+                    improved = true;
+                }
+                for c in seen.drain(..) {
+                    links[c] = 0.0;
+                }
+            }
+            if !improved {
+                break;
+            }
+            moved = true;
+        }
+        moved.then_some(community)
+    }
+
+    /// The level above, one node per community (ids 0..count)
+    fn aggregate(&self, community: &[usize]) -> LouvainLevel {
+        let count = community.iter().map(|&c| c + 1).max().unwrap_or(0);
+        let mut self_loops = vec![0.0; count];
+        let mut merged: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); count];
+        for (v, neighbors) in self.adjacency.iter().enumerate() {
+            let c = community[v];
+            self_loops[c] += self.self_loops[v];
+            for &(w, weight) in neighbors {
+                let d = community[w];
+                if c == d {
+                    // Seen from both ends
+                    self_loops[c] += weight / 2.0;
+                } else {
+                    *merged[c].entry(d).or_insert(0.0) += weight;
+                }
+            }
+        }
+        LouvainLevel {
+            adjacency: merged
+                .into_iter()
+                .map(|m| m.into_iter().collect())
+                .collect(),
+            self_loops,
+        }
+    }
+}
+
+/// Relabel so ids run from 0 in order of first appearance
+fn renumber(labels: &[usize]) -> Vec<usize> {
+    let mut id = vec![usize::MAX; labels.len()];
+    let mut next = 0;
+    labels
+        .iter()
+        .map(|&label| {
+            if id[label] == usize::MAX {
+                id[label] = next;
+                next += 1;
+            }
+            id[label]
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+use crate::graph::{Graph, Node, NodeId};
+use crate::traversal::{BfsIterator, Traversal};
+
+/// The DAG of strongly connected components: node c stands for
+/// `components[c]` and has value c, with one edge c -> d when some edge of the
+/// original graph runs from component c to component d != c
+#[derive(Debug)]
+pub struct Condensation {
+    pub graph: Graph,
+    pub components: Vec<Vec<NodeId>>,
+    pub component_of: Vec<usize>, // Component index of each original node
+}
+
+impl<T> Graph<T> {
+    /// Strongly connected components by iterative Tarjan, each sorted by node
+    /// index. Components come in topological order: every edge between two
+    /// components runs from an earlier one to a later one.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        let n = self.nodes.len();
+        let unseen = usize::MAX;
+        let mut discovery = vec![unseen; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut timer = 0;
+        let mut components = Vec::new();
+
+        for root in 0..n {
+            if discovery[root] != unseen {
+                continue;
+            }
+            discovery[root] = timer;
+            low[root] = timer;
+            timer += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            let mut calls = vec![(root, 0)];
+
+            while let Some(&mut (v, ref mut next)) = calls.last_mut() {
+                if let Some(&w) = self.nodes[v].neighbors.get(*next) {
+                    *next += 1;
+                    if discovery[w] == unseen {
+                        discovery[w] = timer;
+                        low[w] = timer;
+                        timer += 1;
+                        stack.push(w);
+                        on_stack[w] = true;
+                        calls.push((w, 0));
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(discovery[w]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(u, _)) = calls.last() {
+                    low[u] = low[u].min(low[v]);
+                }
+                if low[v] == discovery[v] {
+                    // v is the root of its component: everything above it on the stack
+                    let mut component = Vec::new();
+                    while let Some(w) = stack.pop() {
+                        on_stack[w] = false;
+                        component.push(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component.into_iter().map(NodeId::new).collect());
+                }
+            }
+        }
+        // Tarjan finishes sink components first
+        components.reverse();
+        components
+    }
+
+    /// Collapse every strongly connected component into one node
+    pub fn condensation(&self) -> Condensation {
+        let components = self.strongly_connected_components();
+        let mut component_of = vec![0; self.nodes.len()];
+        for (c, members) in components.iter().enumerate() {
+            for member in members {
+                component_of[member.index()] = c;
+            }
+        }
+
+        let mut nodes: Vec<Node> = (0..components.len())
+            .map(|c| Node {
+                value: c as i32,
+                neighbors: Vec::new(),
+            })
+            .collect();
+        for (index, node) in self.nodes.iter().enumerate() {
+            let from = component_of[index];
+            for &neighbor_index in &node.neighbors {
+                let to = component_of[neighbor_index];
+                if to != from {
+                    nodes[from].neighbors.push(to);
+                }
+            }
+        }
+        for node in &mut nodes {
+            node.neighbors.sort_unstable();
+            node.neighbors.dedup();
+        }
+
+        Condensation {
+            graph: Graph { nodes },
+            components,
+            component_of,
+        }
+    }
+
+    /// Connected components with edges read as undirected, each sorted by node
+    /// index and numbered in order of their smallest node
+    pub fn connected_components(&self) -> Vec<Vec<NodeId>> {
+        let mut components: Vec<Vec<NodeId>> = Vec::new();
+        for (index, component) in self.weak_components().into_iter().enumerate() {
+            if component == components.len() {
+                components.push(Vec::new());
+            }
+            components[component].push(NodeId::new(index));
+        }
+        components
+    }
+
+    /// Whether `to` can be reached from `from` along directed edges; every node
+    /// reaches itself. The search stops as soon as `to` turns up.
+    pub fn is_reachable(&self, from: NodeId, to: NodeId) -> bool {
+        if self.check_node(from).is_err() || self.check_node(to).is_err() {
+            return false;
+        }
+        let mut bfs = BfsIterator::new(self, from);
+        std::iter::from_fn(|| bfs.next_index()).any(|index| index == to.index())
+    }
+}
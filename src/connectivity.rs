@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+
+use crate::error::GraphError;
+use crate::graph::{Graph, NodeId};
+
+/// Undirected connectivity of a graph whose edges arrive online, answering
+/// `connected(a, b)` in O(log V). It is a union-find by size without path
+/// compression, so every merge can be undone: `undo` takes back the last
+/// `add_edge`, and `rollback` returns to a `checkpoint`. Nodes stay when edges
+/// are undone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicConnectivity {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+    /// One entry per `add_edge`: the (child, root) pair it joined, if any
+    history: Vec<Option<(usize, usize)>>,
+}
+
+/// Point in a `DynamicConnectivity`'s history to `rollback` to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Checkpoint(usize);
+
+impl DynamicConnectivity {
+    /// `len` nodes without edges, each a component of its own
+    pub fn new(len: usize) -> Self {
+        DynamicConnectivity {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            components: len,
+            history: Vec::new(),
+        }
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of connected components
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Append a node without edges and return it
+    pub fn add_node(&mut self) -> NodeId {
+        let node = self.parent.len();
+        self.parent.push(node);
+        self.size.push(1);
+        self.components += 1;
+        NodeId::new(node)
+    }
+
+    /// Add an undirected edge, returning whether it joined two components
+    pub fn add_edge(&mut self, a: NodeId, b: NodeId) -> Result<bool, GraphError> {
+        self.check_node(a)?;
+        self.check_node(b)?;
+        let (root_a, root_b) = (self.find(a.index()), self.find(b.index()));
+        if root_a == root_b {
+            self.history.push(None);
+            return Ok(false);
+        }
+        // Hang the smaller tree under the larger, keeping trees O(log V) deep
+        let (child, root) = if self.size[root_a] < self.size[root_b] {
+            (root_a, root_b)
+        } else {
+            (root_b, root_a)
+        };
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        self.components -= 1;
+        self.history.push(Some((child, root)));
+        Ok(true)
+    }
+
+    /// Whether `a` and `b` are joined by the edges added so far; false if
+    /// either is out of range
+    pub fn connected(&self, a: NodeId, b: NodeId) -> bool {
+        a.index() < self.len()
+            && b.index() < self.len()
+            && self.find(a.index()) == self.find(b.index())
+    }
+
+    /// Representative of the component holding `node`
+    pub fn component(&self, node: NodeId) -> Result<NodeId, GraphError> {
+        self.check_node(node)?;
+        Ok(NodeId::new(self.find(node.index())))
+    }
+
+    /// The current point in history, for `rollback`
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.history.len())
+    }
+
+    /// Take back every edge added since `checkpoint`; a checkpoint from the
+    /// future, after an earlier rollback, does nothing
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        while self.history.len() > checkpoint.0 {
+            self.undo();
+        }
+    }
+
+    /// Take back the last `add_edge` still in effect, returning false if
+    /// there is none
+    pub fn undo(&mut self) -> bool {
+        let Some(merge) = self.history.pop() else {
+            return false;
+        };
+        if let Some((child, root)) = merge {
+            self.parent[child] = child;
+            self.size[root] -= self.size[child];
+            self.components += 1;
+        }
+        true
+    }
+
+    fn find(&self, mut node: usize) -> usize {
+        while self.parent[node] != node {
+            node = self.parent[node];
+        }
+        node
+    }
+
+    fn check_node(&self, node: NodeId) -> Result<(), GraphError> {
+        if node.index() < self.len() {
+            Ok(())
+        } else {
+            Err(GraphError::NodeOutOfRange {
+                node: node.index(),
+                len: self.len(),
+            })
+        }
+    }
+}
+
+impl<T> Graph<T> {
+    /// Connectivity of this graph, its edges read as undirected, to keep
+    /// adding edges to
+    pub fn dynamic_connectivity(&self) -> DynamicConnectivity {
+        let mut connectivity = DynamicConnectivity::new(self.nodes.len());
+        for (from, to) in self.edges() {
+            connectivity
+                .add_edge(from, to)
+                .expect("graph edges join nodes of the graph");
+        }
+        connectivity
+    }
+}
+
+/// One step of an offline dynamic connectivity run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// Add an undirected edge; parallel edges count separately
+    Add(NodeId, NodeId),
+    /// Remove one copy of an edge added earlier, either way round; ignored
+    /// if there is none
+    Remove(NodeId, NodeId),
+    /// Ask whether two nodes are connected at this point
+    Connected(NodeId, NodeId),
+}
+
+/// Answer every `Event::Connected` among `events` on `len` nodes, in order,
+/// when edges are removed as well as added. Each edge is alive for a span of
+/// events; the spans are laid over a segment tree on time, and a depth-first
+/// walk of the tree adds each edge on entering its nodes and rolls it back on
+/// leaving, so the whole run takes O(m log m log V) for m events.
+pub fn offline_connectivity(len: usize, events: &[Event]) -> Result<Vec<bool>, GraphError> {
+    let key = |a: NodeId, b: NodeId| (a.index().min(b.index()), a.index().max(b.index()));
+    let mut open: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    let mut spans = Vec::new();
+    for (time, event) in events.iter().enumerate() {
+        match *event {
+            Event::Add(a, b) => open.entry(key(a, b)).or_default().push(time),
+            Event::Remove(a, b) => {
+                if let Some(start) = open.get_mut(&key(a, b)).and_then(Vec::pop) {
+                    spans.push((start, time, key(a, b)));
+                }
+            }
+            Event::Connected(..) => {}
+        }
+    }
+    for (&edge, starts) in &open {
+        spans.extend(starts.iter().map(|&start| (start, events.len(), edge)));
+    }
+
+    let mut tree: Vec<Vec<(usize, usize)>> = vec![Vec::new(); 4 * events.len().max(1)];
+    for (start, end, edge) in spans {
+        insert_span(&mut tree, 1, 0, events.len(), start, end, edge);
+    }
+    let mut connectivity = DynamicConnectivity::new(len);
+    let mut answers = Vec::new();
+    if !events.is_empty() {
+        walk(
+            &tree,
+            1,
+            0,
+            events.len(),
+            events,
+            &mut connectivity,
+            &mut answers,
+        )?;
+    }
+    Ok(answers)
+}
+
+/// Record `edge` on the nodes of the segment tree covering start..end
+fn insert_span(
+    tree: &mut [Vec<(usize, usize)>],
+    node: usize,
+    low: usize,
+    high: usize,
+    start: usize,
+    end: usize,
+    edge: (usize, usize),
+) {
+    if end <= low || high <= start {
+        return;
+    }
+    if start <= low && high <= end {
+        tree[node].push(edge);
+        return;
+    }
+    let middle = (low + high) / 2;
+    insert_span(tree, 2 * node, low, middle, start, end, edge);
+    insert_span(tree, 2 * node + 1, middle, high, start, end, edge);
+}
+
+fn walk(
+    tree: &[Vec<(usize, usize)>],
+    node: usize,
+    low: usize,
+    high: usize,
+    events: &[Event],
+    connectivity: &mut DynamicConnectivity,
+    answers: &mut Vec<bool>,
+) -> Result<(), GraphError> {
+    let checkpoint = connectivity.checkpoint();
+    for &(a, b) in &tree[node] {
+        connectivity.add_edge(NodeId::new(a), NodeId::new(b))?;
+    }
+    if high - low == 1 {
+        if let Event::Connected(a, b) = events[low] {
+            connectivity.check_node(a)?;
+            connectivity.check_node(b)?;
+            answers.push(connectivity.connected(a, b));
+        }
+    } else {
+        let middle = (low + high) / 2;
+        walk(tree, 2 * node, low, middle, events, connectivity, answers)?;
+        walk(
+            tree,
+            2 * node + 1,
+            middle,
+            high,
+            events,
+            connectivity,
+            answers,
+        )?;
+    }
+    connectivity.rollback(checkpoint);
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+
+use crate::base::GraphBase;
+use crate::graph::{Graph, Node, NodeId};
+use crate::traversal::{BfsIterator, DfsIterator, NextSmallestIterator};
+
+/// Compressed sparse row graph: node values in one array and all adjacency in
+/// two flat ones, the neighbors of node v being `targets[offsets[v]..offsets[v + 1]]`.
+/// Immutable once built; traversals over it touch contiguous memory instead of
+/// a separate allocation per node. Node indices must fit in a u32.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrGraph<T = i32> {
+    pub(crate) values: Vec<T>,
+    pub(crate) offsets: Vec<usize>,
+    pub(crate) targets: Vec<u32>,
+}
+
+impl<T: Clone> CsrGraph<T> {
+    /// Copy of `graph` in CSR form, with neighbors in the same order
+    pub fn from_graph(graph: &Graph<T>) -> Self {
+        CsrGraph::build(
+            graph
+                .nodes
+                .iter()
+                .map(|node| (node.value.clone(), &node.neighbors)),
+        )
+    }
+
+    /// Back to an adjacency-list graph
+    pub fn to_graph(&self) -> Graph<T> {
+        let nodes = (0..self.len())
+            .map(|index| {
+                let neighbors = self
+                    .targets(index)
+                    .iter()
+                    .map(|&target| target as usize)
+                    .collect();
+                Node::new(self.values[index].clone(), neighbors)
+            })
+            .collect();
+        Graph { nodes }
+    }
+}
+
+impl<T> From<Graph<T>> for CsrGraph<T> {
+    fn from(graph: Graph<T>) -> Self {
+        let (values, neighbors): (Vec<T>, Vec<Vec<usize>>) = graph
+            .nodes
+            .into_iter()
+            .map(|node| (node.value, node.neighbors))
+            .unzip();
+        CsrGraph::build(values.into_iter().zip(&neighbors))
+    }
+}
+
+impl<T> CsrGraph<T> {
+    fn build<'n>(nodes: impl ExactSizeIterator<Item = (T, &'n Vec<usize>)>) -> Self {
+        let len = nodes.len();
+        assert!(u32::try_from(len).is_ok(), "too many nodes for a CsrGraph");
+        let mut values = Vec::with_capacity(len);
+        let mut offsets = Vec::with_capacity(len + 1);
+        let mut targets = Vec::new();
+        offsets.push(0);
+        for (value, neighbors) in nodes {
+            values.push(value);
+            targets.extend(neighbors.iter().map(|&neighbor| neighbor as u32));
+            offsets.push(targets.len());
+        }
+        CsrGraph {
+            values,
+            offsets,
+            targets,
+        }
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Number of edges, counting parallel edges
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn value(&self, id: NodeId) -> Option<&T> {
+        self.values.get(id.index())
+    }
+
+    /// Out-neighbors of `id` in order; panics if `id` is out of range
+    pub fn neighbors(
+        &self,
+        id: NodeId,
+    ) -> impl DoubleEndedIterator<Item = NodeId> + ExactSizeIterator + '_ {
+        self.targets(id.index())
+            .iter()
+            .map(|&target| NodeId::new(target as usize))
+    }
+
+    pub fn degree(&self, id: NodeId) -> usize {
+        self.offsets[id.index() + 1] - self.offsets[id.index()]
+    }
+
+    /// The `len + 1` offsets into the target array
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    fn targets(&self, index: usize) -> &[u32] {
+        &self.targets[self.offsets[index]..self.offsets[index + 1]]
+    }
+
+    /// Breadth-first traversal from `start`; the iterator yields node values
+    pub fn bfs(&self, start: NodeId) -> BfsIterator<'_, T, HashSet<usize>, Self> {
+        BfsIterator::new(self, start)
+    }
+
+    /// Depth-first traversal from `start`; the iterator yields node values
+    pub fn dfs(&self, start: NodeId) -> DfsIterator<'_, T, HashSet<usize>, Self> {
+        DfsIterator::new(self, start)
+    }
+
+    /// Smallest-value-first traversal from `start`; the iterator yields node values
+    pub fn next_smallest(
+        &self,
+        start: NodeId,
+    ) -> NextSmallestIterator<'_, T, HashSet<usize>, &T, Self>
+    where
+        T: Ord,
+    {
+        NextSmallestIterator::new(self, start)
+    }
+}
+
+impl<T: Clone> Graph<T> {
+    /// The graph in CSR form; see `CsrGraph`
+    pub fn to_csr_graph(&self) -> CsrGraph<T> {
+        CsrGraph::from_graph(self)
+    }
+}
+
+/// Nodes of a `CsrGraph` are just their values
+impl<T> GraphBase for CsrGraph<T> {
+    type Value = T;
+    type Node = T;
+
+    fn node_count(&self) -> usize {
+        self.values.len()
+    }
+
+    fn node(&self, id: NodeId) -> &T {
+        &self.values[id.index()]
+    }
+
+    fn node_value(node: &T) -> &T {
+        node
+    }
+
+    fn neighbors(&self, id: NodeId) -> impl DoubleEndedIterator<Item = NodeId> + '_ {
+        CsrGraph::neighbors(self, id)
+    }
+}
@@ -0,0 +1,261 @@
+use crate::graph::{Graph, NodeId};
+use crate::undirected::UnGraph;
+use crate::visit::Color;
+
+impl<T> Graph<T> {
+    /// Whether following edges in their listed direction can return to a node
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// A directed cycle as node ids in edge order, the closing edge running from
+    /// the last back to the first; a self-loop is a cycle of one node.
+    /// Found by DFS: the first back edge (to a node still on the DFS path) closes it.
+    pub fn find_cycle(&self) -> Option<Vec<NodeId>> {
+        let mut color = vec![Color::White; self.nodes.len()];
+        let mut path: Vec<(usize, usize)> = Vec::new(); // (node, next neighbor position)
+
+        for root in 0..self.nodes.len() {
+            if color[root] != Color::White {
+                continue;
+            }
+            color[root] = Color::Gray;
+            path.push((root, 0));
+
+            while let Some(top) = path.last_mut() {
+                let v = top.0;
+                let Some(&w) = self.nodes[v].neighbors.get(top.1) else {
+                    color[v] = Color::Black;
+                    path.pop();
+                    continue;
+                };
+                top.1 += 1;
+                match color[w] {
+                    Color::White => {
+                        color[w] = Color::Gray;
+                        path.push((w, 0));
+                    }
+                    // Back edge: w is on the path, so the path from w closes a cycle
+                    Color::Gray => {
+                        let at = path.iter().position(|&(u, _)| u == w).unwrap();
+                        return Some(path[at..].iter().map(|&(u, _)| NodeId::new(u)).collect());
+                    }
+                    // Forward or cross edge into a finished subtree
+                    Color::Black => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// Whether the graph has a cycle when edges are read as undirected
+    pub fn has_undirected_cycle(&self) -> bool {
+        self.find_undirected_cycle().is_some()
+    }
+
+    /// A cycle of the undirected reading of the graph, as node ids in order
+    /// around it. Edges are taken from `undirected_adjacency`, so a -> b listed
+    /// alongside b -> a is a single edge and self-loops are ignored: every cycle
+    /// found has at least three nodes.
+    pub fn find_undirected_cycle(&self) -> Option<Vec<NodeId>> {
+        let adjacency = self.undirected_adjacency();
+        let mut color = vec![Color::White; self.nodes.len()];
+        let mut path: Vec<(usize, usize)> = Vec::new(); // (node, next neighbor position)
+
+        for root in 0..self.nodes.len() {
+            if color[root] != Color::White {
+                continue;
+            }
+            color[root] = Color::Gray;
+            path.push((root, 0));
+
+            while let Some(top) = path.last_mut() {
+                let v = top.0;
+                let Some(&w) = adjacency[v].get(top.1) else {
+                    color[v] = Color::Black;
+                    path.pop();
+                    continue;
+                };
+                top.1 += 1;
+                // The tree edge back to the parent is the same undirected edge
+                let parent = path.len().checked_sub(2).map(|at| path[at].0);
+                if parent == Some(w) {
+                    continue;
+                }
+                match color[w] {
+                    Color::White => {
+                        color[w] = Color::Gray;
+                        path.push((w, 0));
+                    }
+                    Color::Gray => {
+                        let at = path.iter().position(|&(u, _)| u == w).unwrap();
+                        return Some(path[at..].iter().map(|&(u, _)| NodeId::new(u)).collect());
+                    }
+                    // Undirected DFS has no cross edges; this is a back edge
+                    // already seen from the descendant's side
+                    Color::Black => {}
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<T> Graph<T> {
+    /// A closed walk using every edge exactly once (Hierholzer's algorithm),
+    /// as node ids with the first repeated at the end; parallel edges and
+    /// self-loops each count. Exists when every node has equal in- and
+    /// out-degree and all edges are weakly connected. A graph without edges
+    /// gives the empty walk.
+    pub fn eulerian_circuit(&self) -> Option<Vec<NodeId>> {
+        let (out_degree, in_degree) = self.degrees();
+        if out_degree
+            .iter()
+            .zip(&in_degree)
+            .any(|(out, into)| out != into)
+        {
+            return None;
+        }
+        let Some(start) = out_degree.iter().position(|&degree| degree > 0) else {
+            return Some(Vec::new());
+        };
+        self.hierholzer(start)
+    }
+
+    /// A walk using every edge exactly once, as node ids: from the node with
+    /// one more out- than in-edge to the one with one more in- than out-edge,
+    /// or a circuit when all degrees balance (see `eulerian_circuit`). None if
+    /// degrees rule it out or the edges are not weakly connected.
+    pub fn eulerian_path(&self) -> Option<Vec<NodeId>> {
+        let (out_degree, in_degree) = self.degrees();
+        let mut start = None;
+        let mut surplus = 0;
+        for (v, (&out, &into)) in out_degree.iter().zip(&in_degree).enumerate() {
+            match out as isize - into as isize {
+                0 => {}
+                1 if start.is_none() => start = Some(v),
+                -1 => surplus += 1,
+                _ => return None,
+            }
+            if surplus > 1 {
+                return None;
+            }
+        }
+        match start {
+            Some(start) => self.hierholzer(start),
+            None => self.eulerian_circuit(),
+        }
+    }
+
+    /// Out- and in-degree of every node
+    fn degrees(&self) -> (Vec<usize>, Vec<usize>) {
+        let out_degree: Vec<usize> = self.nodes.iter().map(|node| node.neighbors.len()).collect();
+        let mut in_degree = vec![0; self.nodes.len()];
+        for node in &self.nodes {
+            for &w in &node.neighbors {
+                in_degree[w] += 1;
+            }
+        }
+        (out_degree, in_degree)
+    }
+
+    /// Hierholzer's walk from `start`, taking each node's edges in listed
+    /// order; None if it strands some edge, i.e. the edges are not connected
+    fn hierholzer(&self, start: usize) -> Option<Vec<NodeId>> {
+        let edges: usize = self.nodes.iter().map(|node| node.neighbors.len()).sum();
+        let mut next_edge = vec![0; self.nodes.len()];
+        let mut stack = vec![start];
+        let mut walk = Vec::with_capacity(edges + 1);
+        while let Some(&v) = stack.last() {
+            match self.nodes[v].neighbors.get(next_edge[v]) {
+                Some(&w) => {
+                    next_edge[v] += 1;
+                    stack.push(w);
+                }
+                // Every edge out of v is used: v is next on the walk, backwards
+                None => walk.push(NodeId::new(stack.pop().unwrap())),
+            }
+        }
+        walk.reverse();
+        (walk.len() == edges + 1).then_some(walk)
+    }
+}
+
+impl<T> UnGraph<T> {
+    /// A closed walk using every edge exactly once (Hierholzer's algorithm),
+    /// as node ids with the first repeated at the end. Exists when every
+    /// degree is even and all edges are connected; a self-loop adds 2 to its
+    /// node's degree. A graph without edges gives the empty walk.
+    pub fn eulerian_circuit(&self) -> Option<Vec<NodeId>> {
+        if self.odd_degree_nodes().next().is_some() {
+            return None;
+        }
+        let start = (0..self.len()).find(|&v| self.degree(NodeId::new(v)) > 0);
+        match start {
+            Some(start) => self.hierholzer(start),
+            None => Some(Vec::new()),
+        }
+    }
+
+    /// A walk using every edge exactly once, as node ids: between the two
+    /// odd-degree nodes, starting at the smaller, or a circuit when there are
+    /// none (see `eulerian_circuit`). None if more nodes have odd degree or the
+    /// edges are not connected.
+    pub fn eulerian_path(&self) -> Option<Vec<NodeId>> {
+        let odd: Vec<usize> = self.odd_degree_nodes().take(3).collect();
+        match odd[..] {
+            [] => self.eulerian_circuit(),
+            [start, _] => self.hierholzer(start),
+            _ => None,
+        }
+    }
+
+    /// Nodes of odd degree, counting a self-loop twice, in index order
+    fn odd_degree_nodes(&self) -> impl Iterator<Item = usize> + '_ {
+        let nodes = &self.as_graph().nodes;
+        (0..nodes.len()).filter(move |&v| {
+            let loops = nodes[v].neighbors.iter().filter(|&&w| w == v).count();
+            (nodes[v].neighbors.len() - loops) % 2 == 1
+        })
+    }
+
+    /// Hierholzer's walk from `start`, marking edges used from either end;
+    /// None if it strands some edge, i.e. the edges are not connected
+    fn hierholzer(&self, start: usize) -> Option<Vec<NodeId>> {
+        // Number each edge once and list it at both ends (a self-loop at one)
+        let mut incident = vec![Vec::new(); self.len()];
+        let mut edges = 0;
+        for (a, b) in self.edges() {
+            let (a, b) = (a.index(), b.index());
+            incident[a].push((b, edges));
+            if a != b {
+                incident[b].push((a, edges));
+            }
+            edges += 1;
+        }
+
+        let mut used = vec![false; edges];
+        let mut next_edge = vec![0; self.len()];
+        let mut stack = vec![start];
+        let mut walk = Vec::with_capacity(edges + 1);
+        while let Some(&v) = stack.last() {
+            // Skip edges already walked from their other end
+            while incident[v]
+                .get(next_edge[v])
+                .is_some_and(|&(_, edge)| used[edge])
+            {
+                next_edge[v] += 1;
+            }
+            match incident[v].get(next_edge[v]) {
+                Some(&(w, edge)) => {
+                    used[edge] = true;
+                    next_edge[v] += 1;
+                    stack.push(w);
+                }
+                None => walk.push(NodeId::new(stack.pop().unwrap())),
+            }
+        }
+        walk.reverse();
+        (walk.len() == edges + 1).then_some(walk)
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::error::ParseError;
+use crate::graph::{Graph, NodeId};
+
+/// Argument of a Datalog atom; variables are numbered per rule
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Term {
+    Variable(usize),
+    Constant(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Atom {
+    pub relation: String,
+    pub terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatalogRule {
+    pub head: Atom,
+    pub body: Vec<Atom>,
+    pub variables: usize,
+}
+
+/// Positive Datalog rules over the built-in relations `edge(x, y)` and `node(x)`
+#[derive(Debug, Clone)]
+pub struct DatalogProgram {
+    pub rules: Vec<DatalogRule>,
+}
+
+/// Tuples in insertion order with a per-column index, so each semi-naive round's
+/// delta is a contiguous range of positions
+#[derive(Default)]
+struct Relation {
+    pub tuples: Vec<Vec<usize>>,
+    pub set: HashSet<Vec<usize>>,
+    pub index: Vec<HashMap<usize, Vec<usize>>>,
+}
+
+impl Relation {
+    fn insert(&mut self, tuple: Vec<usize>) -> bool {
+        if self.set.contains(&tuple) {
+            return false;
+        }
+        self.index.resize_with(tuple.len(), HashMap::new);
+        for (column, &value) in tuple.iter().enumerate() {
+            self.index[column]
+                .entry(value)
+                .or_default()
+                .push(self.tuples.len());
+        }
+        self.set.insert(tuple.clone());
+        self.tuples.push(tuple);
+        true
+    }
+}
+
+impl DatalogProgram {
+    /// Parse rules like `reach(x,y) :- edge(x,y); reach(x,y) :- reach(x,z), edge(z,y)`.
+    /// Rules end with `;` or `.`; lowercase names are variables, numbers constants.
+    pub fn parse(source: &str) -> Result<DatalogProgram, ParseError> {
+        let mut rules = Vec::new();
+        let mut arities: HashMap<String, usize> =
+            HashMap::from([("edge".to_string(), 2), ("node".to_string(), 1)]);
+        let mut offset = 0;
+        for text in source.split([';', '.']) {
+            let start = offset + (text.len() - text.trim_start().len());
+            offset += text.len() + 1;
+            if text.trim().is_empty() {
+                continue;
+            }
+            let error = |message: String| ParseError {
+                position: start,
+                message,
+            };
+            let (head, body) = text
+                .split_once(":-")
+                .ok_or_else(|| error("expected ':-'".to_string()))?;
+            let mut variables = HashMap::new();
+            let head = Self::atom(head, &mut variables).map_err(error)?;
+            let mut atoms = Vec::new();
+            let mut rest = body.trim();
+            while !rest.is_empty() {
+                let close = rest
+                    .find(')')
+                    .ok_or_else(|| error("expected ')'".to_string()))?;
+                atoms.push(Self::atom(&rest[..=close], &mut variables).map_err(error)?);
+                rest = rest[close + 1..].trim_start();
+                rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+            }
+            if atoms.is_empty() {
+                return Err(error("rule body is empty".to_string()));
+            }
+            if head.relation == "edge" || head.relation == "node" {
+                return Err(error(format!(
+                    "cannot define built-in relation '{}'",
+                    head.relation
+                )));
+            }
+
+            // Range restriction: every head variable must occur in the body
+            let body_variables: HashSet<&Term> =
+                atoms.iter().flat_map(|atom| &atom.terms).collect();
+            if head
+                .terms
+                .iter()
+                .any(|term| matches!(term, Term::Variable(_)) && !body_variables.contains(term))
+            {
+                return Err(error("head variable missing from the body".to_string()));
+            }
+            for atom in std::iter::once(&head).chain(&atoms) {
+                let arity = *arities
+                    .entry(atom.relation.clone())
+                    .or_insert(atom.terms.len());
+                if arity != atom.terms.len() {
+                    return Err(error(format!(
+                        "'{}' used with {} arguments, expected {}",
+                        atom.relation,
+                        atom.terms.len(),
+                        arity
+                    )));
+                }
+            }
+            rules.push(DatalogRule {
+                head,
+                body: atoms,
+                variables: variables.len(),
+            });
+        }
+        Ok(DatalogProgram { rules })
+    }
+
+    fn atom(text: &str, variables: &mut HashMap<String, usize>) -> Result<Atom, String> {
+        let text = text.trim();
+        let (relation, args) = text
+            .strip_suffix(')')
+            .and_then(|t| t.split_once('('))
+            .ok_or_else(|| format!("malformed atom '{}'", text))?;
+        let relation = relation.trim();
+        if relation.is_empty() || !relation.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!("malformed relation name '{}'", relation));
+        }
+        let mut terms = Vec::new();
+        for arg in args.split(',').map(str::trim) {
+            if let Ok(constant) = arg.parse() {
+                terms.push(Term::Constant(constant));
+            } else if arg.starts_with(|c: char| c.is_lowercase() || c == '_')
+                && arg.chars().all(|c| c.is_alphanumeric() || c == '_')
+            {
+                let next = variables.len();
+                terms.push(Term::Variable(
+                    *variables.entry(arg.to_string()).or_insert(next),
+                ));
+            } else {
+                return Err(format!("malformed argument '{}'", arg));
+            }
+        }
+        Ok(Atom {
+            relation: relation.to_string(),
+            terms,
+        })
+    }
+}
+
+/// Tuple ranges visible to one body atom: positions in start..end
+type Ranges<'a> = dyn Fn(usize, &str) -> (usize, usize) + 'a;
+
+/// Enumerate bindings satisfying body[at..] and collect head tuples
+fn join_atoms(
+    relations: &HashMap<String, Relation>,
+    rule: &DatalogRule,
+    at: usize,
+    ranges: &Ranges,
+    binding: &mut Vec<Option<usize>>,
+    out: &mut Vec<Vec<usize>>,
+) {
+    let Some(atom) = rule.body.get(at) else {
+        let tuple = rule
+            .head
+            .terms
+            .iter()
+            .map(|term| match term {
+                Term::Variable(v) => binding[*v].unwrap(),
+                Term::Constant(c) => *c,
+            })
+            .collect();
+        out.push(tuple);
+        return;
+    };
+    let Some(relation) = relations.get(&atom.relation) else {
+        return;
+    };
+    let (start, end) = ranges(at, &atom.relation);
+    let value_of = |term: &Term, binding: &[Option<usize>]| match term {
+        Term::Variable(v) => binding[*v],
+        Term::Constant(c) => Some(*c),
+    };
+
+    // Use the index on the first bound column, else scan the range
+    let bound = atom
+        .terms
+        .iter()
+        .enumerate()
+        .find_map(|(column, term)| value_of(term, binding).map(|value| (column, value)));
+    let candidates: Vec<usize> = match bound {
+        Some((column, value)) => relation
+            .index
+            .get(column)
+            .and_then(|index| index.get(&value))
+            .map_or(Vec::new(), |positions| {
+                positions
+                    .iter()
+                    .copied()
+                    .filter(|&p| p >= start && p < end)
+                    .collect()
+            }),
+        None => (start..end).collect(),
+    };
+    for position in candidates {
+        let tuple = &relation.tuples[position];
+        let saved = binding.clone();
+        let consistent =
+            atom.terms
+                .iter()
+                .zip(tuple)
+                .all(|(term, &value)| match value_of(term, binding) {
+                    Some(existing) => existing == value,
+                    None => {
+                        if let Term::Variable(v) = term {
+                            binding[*v] = Some(value);
+                        }
+                        true
+                    }
+                });
+        if consistent {
+            join_atoms(relations, rule, at + 1, ranges, binding, out);
+        }
+        *binding = saved;
+    }
+}
+
+impl<T> Graph<T> {
+    /// Evaluate a Datalog program to its least fixpoint by semi-naive iteration:
+    /// after the first round each rule is re-run once per derived body atom with
+    /// that atom restricted to the previous round's new tuples. Returns the
+    /// derived relations as sorted tuple lists.
+    pub fn evaluate_datalog(&self, program: &DatalogProgram) -> HashMap<String, Vec<Vec<NodeId>>> {
+        let mut relations: HashMap<String, Relation> = HashMap::new();
+        let edges = relations.entry("edge".to_string()).or_default();
+        for (v, node) in self.nodes.iter().enumerate() {
+            for &w in &node.neighbors {
+                edges.insert(vec![v, w]);
+            }
+        }
+        let nodes = relations.entry("node".to_string()).or_default();
+        for v in 0..self.nodes.len() {
+            nodes.insert(vec![v]);
+        }
+        let derived: HashSet<&str> = program
+            .rules
+            .iter()
+            .map(|rule| rule.head.relation.as_str())
+            .collect();
+        for &name in &derived {
+            relations.entry(name.to_string()).or_default();
+        }
+
+        // Delta of each relation is delta_start..len at the start of the round
+        let mut delta_start: HashMap<String, usize> =
+            relations.keys().map(|name| (name.clone(), 0)).collect();
+        let mut first_round = true;
+        loop {
+            let lengths: HashMap<String, usize> = relations
+                .iter()
+                .map(|(name, r)| (name.clone(), r.tuples.len()))
+                .collect();
+            let mut produced: Vec<(String, Vec<usize>)> = Vec::new();
+            for rule in &program.rules {
+                let mut out = Vec::new();
+                let mut binding = vec![None; rule.variables];
+                if first_round {
+                    let ranges = |_: usize, name: &str| (0, lengths[name]);
+                    join_atoms(&relations, rule, 0, &ranges, &mut binding, &mut out);
+                } else {
+                    for (delta_at, atom) in rule.body.iter().enumerate() {
+                        let name = atom.relation.as_str();
+                        if !derived.contains(name) || delta_start[name] == lengths[name] {
+                            continue;
+                        }
+                        // Earlier atoms see only old tuples, so each new
+                        // derivation is found through exactly one delta position
+                        let ranges = |at: usize, name: &str| match at.cmp(&delta_at) {
+                            std::cmp::Ordering::Less => (0, delta_start[name]),
+                            std::cmp::Ordering::Equal => (delta_start[name], lengths[name]),
+                            std::cmp::Ordering::Greater => (0, lengths[name]),
+                        };
+                        join_atoms(&relations, rule, 0, &ranges, &mut binding, &mut out);
+                    }
+                }
+                produced.extend(
+                    out.into_iter()
+                        .map(|tuple| (rule.head.relation.clone(), tuple)),
+                );
+            }
+
+            delta_start = lengths;
+            let mut changed = false;
+            for (name, tuple) in produced {
+                changed |= relations.get_mut(&name).unwrap().insert(tuple);
+            }
+            first_round = false;
+            if !changed {
+                break;
+            }
+        }
+
+        relations
+            .into_iter()
+            .filter(|(name, _)| derived.contains(name.as_str()))
+            .map(|(name, relation)| {
+                let mut tuples = relation.tuples;
+                tuples.sort_unstable();
+                let tuples = tuples
+                    .into_iter()
+                    .map(|tuple| tuple.into_iter().map(NodeId::new).collect())
+                    .collect();
+                (name, tuples)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use crate::graph::Graph;
+
+/// Greedy rules for choosing the next node to eliminate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EliminationHeuristic {
+    MinDegree, // Fewest remaining neighbors
+    MinFill,   // Fewest edges added to make the neighbors a clique
+}
+
+/// Tree decomposition built from an elimination order. Bag i holds the i-th
+/// eliminated node and its neighbors at that time; a bag's parent always has a
+/// larger index, so the bags form a forest with one tree per component.
+#[derive(Debug, Clone)]
+pub struct TreeDecomposition {
+    pub bags: Vec<Vec<usize>>,
+    pub parent: Vec<Option<usize>>,
+    pub width: usize,
+}
+
+impl TreeDecomposition {
+    /// Bags without a parent
+    pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.bags.len()).filter(|&bag| self.parent[bag].is_none())
+    }
+
+    /// Run a dynamic program bottom-up over the bags. `combine` gets a bag's
+    /// index, its nodes and the results of its children; one result is
+    /// returned per root.
+    pub fn dynamic_program<S, F>(&self, mut combine: F) -> Vec<S>
+    where
+        F: FnMut(usize, &[usize], Vec<S>) -> S,
+    {
+        let mut pending: Vec<Vec<S>> = (0..self.bags.len()).map(|_| Vec::new()).collect();
+        let mut results = Vec::new();
+        for bag in 0..self.bags.len() {
+            let children = std::mem::take(&mut pending[bag]);
+            let result = combine(bag, &self.bags[bag], children);
+            match self.parent[bag] {
+                Some(parent) => pending[parent].push(result),
+                None => results.push(result),
+            }
+        }
+        results
+    }
+}
+
+impl<T> Graph<T> {
+    /// Tree decomposition from a greedy elimination order, reading edges as
+    /// undirected. The reported width is an upper bound on the treewidth.
+    pub fn tree_decomposition(&self, heuristic: EliminationHeuristic) -> TreeDecomposition {
+        let n = self.nodes.len();
+        let mut adjacency: Vec<HashSet<usize>> = vec![HashSet::new(); n];
+        for (a, b) in self.undirected_edge_indices() {
+            adjacency[a].insert(b);
+            adjacency[b].insert(a);
+        }
+
+        let fill_in = |adjacency: &[HashSet<usize>], v: usize| {
+            let neighbors: Vec<usize> = adjacency[v].iter().copied().collect();
+            let mut missing = 0;
+            for (i, &a) in neighbors.iter().enumerate() {
+                for &b in &neighbors[i + 1..] {
+                    if !adjacency[a].contains(&b) {
+                        missing += 1;
+                    }
+                }
+            }
+            missing
+        };
+
+        let mut eliminated = vec![false; n];
+        let mut position = vec![0; n];
+        let mut order = Vec::with_capacity(n);
+        let mut bags = Vec::with_capacity(n);
+        for step in 0..n {
+            let v = (0..n)
+                .filter(|&v| !eliminated[v])
+                .min_by_key(|&v| match heuristic {
+                    EliminationHeuristic::MinDegree => (adjacency[v].len(), 0),
+                    EliminationHeuristic::MinFill => (fill_in(&adjacency, v), adjacency[v].len()),
+                })
+                .unwrap();
+
+            let neighbors: Vec<usize> = adjacency[v].iter().copied().collect();
+            for &a in &neighbors {
+                adjacency[a].remove(&v);
+                for &b in &neighbors {
+                    if a != b {
+                        adjacency[a].insert(b);
+                    }
+                }
+            }
+            let mut bag = neighbors;
+            bag.push(v);
+            bag.sort_unstable();
+
+            eliminated[v] = true;
+            position[v] = step;
+            order.push(v);
+            bags.push(bag);
+        }
+
+        // Attach each bag to the bag of its earliest eliminated other member
+        let parent = (0..n)
+            .map(|step| {
+                bags[step]
+                    .iter()
+                    .filter(|&&u| u != order[step])
+                    .map(|&u| position[u])
+                    .min()
+            })
+            .collect();
+        let width = bags.iter().map(|bag| bag.len()).max().unwrap_or(1) - 1;
+        TreeDecomposition {
+            bags,
+            parent,
+            width,
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::graph::{Graph, NodeId};
+use crate::traversal::Traversal;
+
+/// Visit positions of one node in two traversals being compared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankDelta {
+    pub node: usize,
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+}
+
+impl RankDelta {
+    /// How many positions later the node is visited on the right than on the left
+    pub fn delta(&self) -> Option<i64> {
+        Some(self.right? as i64 - self.left? as i64)
+    }
+}
+
+/// Structured comparison of two visit orders
+#[derive(Debug, Clone)]
+pub struct TraversalDiff {
+    pub left: Vec<usize>,
+    pub right: Vec<usize>,
+    pub first_divergence: Option<usize>,
+    pub ranks: Vec<RankDelta>,
+}
+
+impl TraversalDiff {
+    pub fn new(left: Vec<usize>, right: Vec<usize>) -> TraversalDiff {
+        let common = left.iter().zip(&right).take_while(|(a, b)| a == b).count();
+        let first_divergence = (common < left.len().max(right.len())).then_some(common);
+
+        let mut positions: BTreeMap<usize, (Option<usize>, Option<usize>)> = BTreeMap::new();
+        for (position, &node) in left.iter().enumerate() {
+            positions.entry(node).or_default().0 = Some(position);
+        }
+        for (position, &node) in right.iter().enumerate() {
+            positions.entry(node).or_default().1 = Some(position);
+        }
+        let ranks = positions
+            .into_iter()
+            .map(|(node, (left, right))| RankDelta { node, left, right })
+            .collect();
+        TraversalDiff {
+            left,
+            right,
+            first_divergence,
+            ranks,
+        }
+    }
+
+    pub fn is_identical(&self) -> bool {
+        self.first_divergence.is_none()
+    }
+
+    /// Nodes whose position differs, including nodes visited by only one side
+    pub fn changed(&self) -> impl Iterator<Item = &RankDelta> + '_ {
+        self.ranks.iter().filter(|rank| rank.left != rank.right)
+    }
+
+    pub fn to_text(&self) -> String {
+        let join = |order: &[usize]| {
+            order
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let mut out = format!(
+            "left:  {}\nright: {}\n",
+            join(&self.left),
+            join(&self.right)
+        );
+        match self.first_divergence {
+            None => out += "identical\n",
+            Some(position) => {
+                let show = |order: &[usize]| {
+                    order
+                        .get(position)
+                        .map_or("end".to_string(), usize::to_string)
+                };
+                out += &format!(
+                    "first divergence at position {}: {} vs {}\n",
+                    position,
+                    show(&self.left),
+                    show(&self.right)
+                );
+            }
+        }
+        for rank in self.changed() {
+            let show =
+                |position: Option<usize>| position.map_or("-".to_string(), |p| p.to_string());
+            let delta = rank
+                .delta()
+                .map_or(String::new(), |d| format!(" ({:+})", d));
+            out += &format!(
+                "  node {}: {} -> {}{}\n",
+                rank.node,
+                show(rank.left),
+                show(rank.right),
+                delta
+            );
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let join = |order: &[usize]| {
+            order
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let show = |position: Option<usize>| position.map_or("null".to_string(), |p| p.to_string());
+        let ranks: Vec<String> = self
+            .ranks
+            .iter()
+            .map(|rank| {
+                let delta = rank.delta().map_or("null".to_string(), |d| d.to_string());
+                format!(
+                    "{{\"node\":{},\"left\":{},\"right\":{},\"delta\":{}}}",
+                    rank.node,
+                    show(rank.left),
+                    show(rank.right),
+                    delta
+                )
+            })
+            .collect();
+        format!(
+            "{{\"left\":[{}],\"right\":[{}],\"first_divergence\":{},\"ranks\":[{}]}}",
+            join(&self.left),
+            join(&self.right),
+            show(self.first_divergence),
+            ranks.join(",")
+        )
+    }
+}
+
+/// Run a traversal to completion, collecting the visited indices
+pub fn visit_order<'a, T: Traversal<'a>>(mut traversal: T) -> Vec<usize> {
+    std::iter::from_fn(|| traversal.next_index()).collect()
+}
+
+/// Run two traversals to completion and diff their visit orders
+pub fn diff_traversals<'a, 'b, A, B>(left: A, right: B) -> TraversalDiff
+where
+    A: Traversal<'a>,
+    B: Traversal<'b>,
+{
+    TraversalDiff::new(visit_order(left), visit_order(right))
+}
+
+/// Changes from one graph to another, matching nodes by index, as produced
+/// by `Graph::diff`. Edges are compared as multisets, so one of two parallel
+/// edges going away is a removal; the edges of an added or removed node are
+/// listed too. Every list is sorted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphDiff<T> {
+    pub added_nodes: Vec<(NodeId, T)>,
+    pub removed_nodes: Vec<(NodeId, T)>,
+    /// (node, old value, new value)
+    pub changed_values: Vec<(NodeId, T, T)>,
+    pub added_edges: Vec<(NodeId, NodeId)>,
+    pub removed_edges: Vec<(NodeId, NodeId)>,
+}
+
+impl<T> GraphDiff<T> {
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_values.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for GraphDiff<T> {
+    /// One line per change: `+ node 3: value`, `- node 3: value`,
+    /// `~ node 0: old -> new`, `+ edge 1 -> 2`, `- edge 1 -> 2`; `identical`
+    /// when there are none
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "identical");
+        }
+        for (node, value) in &self.added_nodes {
+            writeln!(f, "+ node {}: {}", node.index(), value)?;
+        }
+        for (node, value) in &self.removed_nodes {
+            writeln!(f, "- node {}: {}", node.index(), value)?;
+        }
+        for (node, old, new) in &self.changed_values {
+            writeln!(f, "~ node {}: {} -> {}", node.index(), old, new)?;
+        }
+        for (from, to) in &self.added_edges {
+            writeln!(f, "+ edge {} -> {}", from.index(), to.index())?;
+        }
+        for (from, to) in &self.removed_edges {
+            writeln!(f, "- edge {} -> {}", from.index(), to.index())?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Clone + PartialEq> Graph<T> {
+    /// What changed from `self` to `other`, node by node index; see `GraphDiff`
+    pub fn diff(&self, other: &Graph<T>) -> GraphDiff<T> {
+        let node_list = |graph: &Graph<T>, from: usize| {
+            graph.nodes[from..]
+                .iter()
+                .enumerate()
+                .map(|(offset, node)| (NodeId::new(from + offset), node.value.clone()))
+                .collect::<Vec<_>>()
+        };
+        let common = self.nodes.len().min(other.nodes.len());
+        let changed_values = (0..common)
+            .filter(|&index| self.nodes[index].value != other.nodes[index].value)
+            .map(|index| {
+                (
+                    NodeId::new(index),
+                    self.nodes[index].value.clone(),
+                    other.nodes[index].value.clone(),
+                )
+            })
+            .collect();
+
+        // Net count of each edge: positive if `other` has more copies
+        let mut edges: BTreeMap<(usize, usize), i64> = BTreeMap::new();
+        for (graph, sign) in [(self, -1), (other, 1)] {
+            for (from, node) in graph.nodes.iter().enumerate() {
+                for &to in &node.neighbors {
+                    *edges.entry((from, to)).or_insert(0) += sign;
+                }
+            }
+        }
+        let (mut added_edges, mut removed_edges) = (Vec::new(), Vec::new());
+        for ((from, to), count) in edges {
+            let list = if count > 0 {
+                &mut added_edges
+            } else {
+                &mut removed_edges
+            };
+            let edge = (NodeId::new(from), NodeId::new(to));
+            list.extend(std::iter::repeat_n(edge, count.unsigned_abs() as usize));
+        }
+
+        GraphDiff {
+            added_nodes: node_list(other, common),
+            removed_nodes: node_list(self, common),
+            changed_values,
+            added_edges,
+            removed_edges,
+        }
+    }
+}
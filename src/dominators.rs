@@ -0,0 +1,131 @@
+use crate::graph::{Graph, NodeId};
+use crate::visit::{dfs_visit, DfsVisitor};
+
+/// Immediate dominators of the nodes reachable from an entry node: d dominates
+/// v when every path from the entry to v passes through d. Each reachable node
+/// other than the entry has exactly one closest strict dominator, and those
+/// links form a tree rooted at the entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DominatorTree {
+    entry: usize,
+    idom: Vec<Option<usize>>, // None for the entry and unreachable nodes
+}
+
+impl DominatorTree {
+    pub fn entry(&self) -> NodeId {
+        NodeId::new(self.entry)
+    }
+
+    /// Whether `node` is reachable from the entry, the only nodes the tree covers
+    pub fn is_reachable(&self, node: NodeId) -> bool {
+        node.index() == self.entry || self.immediate_dominator(node).is_some()
+    }
+
+    /// The closest strict dominator of `node`; None for the entry and for
+    /// unreachable nodes
+    pub fn immediate_dominator(&self, node: NodeId) -> Option<NodeId> {
+        self.idom
+            .get(node.index())
+            .copied()
+            .flatten()
+            .map(NodeId::new)
+    }
+
+    /// All dominators of a reachable `node`, from the node itself up to the entry
+    pub fn dominators(&self, node: NodeId) -> Option<Vec<NodeId>> {
+        if !self.is_reachable(node) {
+            return None;
+        }
+        let mut chain = vec![node];
+        while let Some(parent) = self.immediate_dominator(*chain.last().unwrap()) {
+            chain.push(parent);
+        }
+        Some(chain)
+    }
+
+    /// Whether `dominator` dominates `node`; every reachable node dominates itself
+    pub fn dominates(&self, dominator: NodeId, node: NodeId) -> bool {
+        self.dominators(node)
+            .is_some_and(|chain| chain.contains(&dominator))
+    }
+}
+
+/// Records the DFS finishing order
+struct Postorder(Vec<usize>);
+
+impl DfsVisitor for Postorder {
+    fn finish_node(&mut self, node: NodeId) {
+        self.0.push(node.index());
+    }
+}
+
+impl<T> Graph<T> {
+    /// Dominator tree of the nodes reachable from `entry`, by the iterative
+    /// algorithm of Cooper, Harvey and Kennedy: immediate dominators are
+    /// refined in reverse DFS postorder, intersecting the dominator chains of
+    /// each node's predecessors, until nothing changes. Near-linear on
+    /// control-flow graphs, which settle in a few passes. Panics if `entry` is
+    /// out of range.
+    pub fn dominator_tree(&self, entry: NodeId) -> DominatorTree {
+        let n = self.nodes.len();
+        let mut postorder = Postorder(Vec::new());
+        dfs_visit(self, entry, &mut postorder);
+        let postorder = postorder.0;
+        let unreached = usize::MAX;
+        let mut rank = vec![unreached; n]; // Position in postorder
+        for (position, &v) in postorder.iter().enumerate() {
+            rank[v] = position;
+        }
+        let mut predecessors = vec![Vec::new(); n];
+        for &v in &postorder {
+            for &w in &self.nodes[v].neighbors {
+                predecessors[w].push(v);
+            }
+        }
+
+        let entry = entry.index();
+        let mut idom = vec![unreached; n];
+        idom[entry] = entry;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &v in postorder.iter().rev().filter(|&&v| v != entry) {
+                // Intersect over the predecessors processed so far
+                let mut new_idom = unreached;
+                for &p in &predecessors[v] {
+                    if idom[p] == unreached {
+                        continue;
+                    }
+                    new_idom = if new_idom == unreached {
+                        p
+                    } else {
+                        intersect(&idom, &rank, p, new_idom)
+                    };
+                }
+                if idom[v] != new_idom {
+                    idom[v] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        let idom = (0..n)
+            .map(|v| (v != entry && idom[v] != unreached).then_some(idom[v]))
+            .collect();
+        DominatorTree { entry, idom }
+    }
+}
+
+/// Nearest common ancestor of `a` and `b` in the dominator tree built so far,
+/// climbing from whichever finishes earlier in postorder
+fn intersect(idom: &[usize], rank: &[usize], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        while rank[a] < rank[b] {
+            a = idom[a];
+        }
+        while rank[b] < rank[a] {
+            b = idom[b];
+        }
+    }
+    a
+}
@@ -0,0 +1,624 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::Write;
+
+use crate::attributes::GraphAttributes;
+use crate::error::DotError;
+use crate::graph::{dedup_neighbors, Graph, Node, NodeId};
+use crate::undirected::UnGraph;
+
+/// Extra Graphviz attributes for a node
+pub type NodeAttributes<'a, T> = Box<dyn Fn(NodeId, &Node<T>) -> Vec<(String, String)> + 'a>;
+
+/// Extra Graphviz attributes for the edge from -> to
+pub type EdgeAttributes<'a> = Box<dyn Fn(NodeId, NodeId) -> Vec<(String, String)> + 'a>;
+
+/// Optional per-node and per-edge attribute callbacks for DOT export. Node
+/// attributes follow the default `label`, so returning a `label` replaces it.
+pub struct DotStyle<'a, T> {
+    node_attributes: Option<NodeAttributes<'a, T>>,
+    edge_attributes: Option<EdgeAttributes<'a>>,
+}
+
+impl<T> Default for DotStyle<'_, T> {
+    fn default() -> Self {
+        DotStyle {
+            node_attributes: None,
+            edge_attributes: None,
+        }
+    }
+}
+
+impl<'a, T> DotStyle<'a, T> {
+    /// No extra attributes: nodes get their value as label and edges nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_node_attributes<F>(mut self, attributes: F) -> Self
+    where
+        F: Fn(NodeId, &Node<T>) -> Vec<(String, String)> + 'a,
+    {
+        self.node_attributes = Some(Box::new(attributes));
+        self
+    }
+
+    pub fn with_edge_attributes<F>(mut self, attributes: F) -> Self
+    where
+        F: Fn(NodeId, NodeId) -> Vec<(String, String)> + 'a,
+    {
+        self.edge_attributes = Some(Box::new(attributes));
+        self
+    }
+
+    /// Node and edge attributes from `attributes`, replacing both callbacks
+    pub fn with_attributes(self, attributes: &'a GraphAttributes) -> Self {
+        self.with_node_attributes(move |id, _| owned(attributes.node_attrs(id)))
+            .with_edge_attributes(move |from, to| owned(attributes.edge_attrs(from, to)))
+    }
+}
+
+fn owned<'a>(pairs: impl Iterator<Item = (&'a str, &'a str)>) -> Vec<(String, String)> {
+    pairs
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Quote a DOT attribute value, escaping quotes and backslashes
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn attribute_list(attributes: &[(String, String)]) -> String {
+    let pairs: Vec<String> = attributes
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, quote(value)))
+        .collect();
+    pairs.join(", ")
+}
+
+/// Write a graph as DOT, one statement per line: nodes by index, then edges
+fn write_dot<T: Display, W: Write>(
+    graph: &Graph<T>,
+    edges: impl Iterator<Item = (usize, usize)>,
+    directed: bool,
+    style: &DotStyle<T>,
+    mut out: W,
+) -> std::io::Result<()> {
+    let (keyword, connector) = if directed {
+        ("digraph", "->")
+    } else {
+        ("graph", "--")
+    };
+    writeln!(out, "{} {{", keyword)?;
+    for (index, node) in graph.nodes.iter().enumerate() {
+        let mut attributes = vec![("label".to_string(), node.value.to_string())];
+        if let Some(node_attributes) = &style.node_attributes {
+            attributes.extend(node_attributes(NodeId::new(index), node));
+        }
+        writeln!(out, "    {} [{}];", index, attribute_list(&attributes))?;
+    }
+    for (from, to) in edges {
+        let attributes = match &style.edge_attributes {
+            Some(edge_attributes) => edge_attributes(NodeId::new(from), NodeId::new(to)),
+            None => Vec::new(),
+        };
+        if attributes.is_empty() {
+            writeln!(out, "    {} {} {};", from, connector, to)?;
+        } else {
+            let list = attribute_list(&attributes);
+            writeln!(out, "    {} {} {} [{}];", from, connector, to, list)?;
+        }
+    }
+    writeln!(out, "}}")
+}
+
+impl<T: Display> Graph<T> {
+    /// Graphviz DOT for the graph: a `digraph` with node indices as ids and
+    /// node values as labels
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotStyle::new())
+    }
+
+    /// Like `to_dot`, adding the attributes from `style`
+    pub fn to_dot_with(&self, style: &DotStyle<T>) -> String {
+        let mut out = Vec::new();
+        self.write_dot_with(style, &mut out)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(out).expect("DOT output is UTF-8")
+    }
+
+    /// Stream the DOT for the graph to `out`
+    pub fn write_dot<W: Write>(&self, out: W) -> std::io::Result<()> {
+        self.write_dot_with(&DotStyle::new(), out)
+    }
+
+    pub fn write_dot_with<W: Write>(&self, style: &DotStyle<T>, out: W) -> std::io::Result<()> {
+        let edges = self.edges().map(|(from, to)| (from.index(), to.index()));
+        write_dot(self, edges, true, style, out)
+    }
+}
+
+impl<T: Display> UnGraph<T> {
+    /// Graphviz DOT for the graph: a `graph` listing each edge once
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotStyle::new())
+    }
+
+    /// Like `to_dot`, adding the attributes from `style`
+    pub fn to_dot_with(&self, style: &DotStyle<T>) -> String {
+        let mut out = Vec::new();
+        self.write_dot_with(style, &mut out)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(out).expect("DOT output is UTF-8")
+    }
+
+    /// Stream the DOT for the graph to `out`
+    pub fn write_dot<W: Write>(&self, out: W) -> std::io::Result<()> {
+        self.write_dot_with(&DotStyle::new(), out)
+    }
+
+    pub fn write_dot_with<W: Write>(&self, style: &DotStyle<T>, out: W) -> std::io::Result<()> {
+        let edges = self.edges().map(|(a, b)| (a.index(), b.index()));
+        write_dot(self.as_graph(), edges, false, style, out)
+    }
+}
+
+/// A graph read from DOT: `ids[index]` is the DOT id of each node, in order of
+/// first mention, and each node's value is its `label` attribute, or its id
+/// when it has none. Edges of an undirected `graph` are stored both ways.
+/// `attributes` holds the other attributes of node and edge statements.
+#[derive(Debug, Clone)]
+pub struct DotGraph {
+    pub graph: Graph<String>,
+    pub ids: Vec<String>,
+    pub directed: bool,
+    pub attributes: GraphAttributes,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DotToken {
+    Id { text: String, quoted: bool },
+    Symbol(&'static str),
+}
+
+/// Character stream with the 1-based line and column of the next character
+struct DotLexer {
+    chars: Vec<char>,
+    next: usize,
+    line: usize,
+    column: usize,
+}
+
+impl DotLexer {
+    fn peek(&self, ahead: usize) -> Option<char> {
+        self.chars.get(self.next + ahead).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.next += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_line(&mut self) {
+        while self.peek(0).is_some_and(|c| c != '\n') {
+            self.bump();
+        }
+    }
+
+    /// Whether only whitespace precedes the next character on its line
+    fn at_line_start(&self) -> bool {
+        self.chars[..self.next]
+            .iter()
+            .rev()
+            .take_while(|&&c| c != '\n')
+            .all(|c| c.is_whitespace())
+    }
+
+    fn take_while<P: Fn(char) -> bool>(&mut self, predicate: P) -> String {
+        let mut text = String::new();
+        while let Some(c) = self.peek(0).filter(|&c| predicate(c)) {
+            text.push(c);
+            self.bump();
+        }
+        text
+    }
+
+    /// Body of a double-quoted string, after the opening quote
+    fn quoted(&mut self, line: usize, column: usize) -> Result<String, DotError> {
+        let mut text = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(text),
+                Some('\\') => match self.bump() {
+                    Some('"') => text.push('"'),
+                    Some('\\') => text.push('\\'),
+                    Some('n') => text.push('\n'),
+                    // A backslash before a newline continues the line
+                    Some('\n') => {}
+                    Some(c) => {
+                        text.push('\\');
+                        text.push(c);
+                    }
+                    None => break,
+                },
+                Some(c) => text.push(c),
+                None => break,
+            }
+        }
+        Err(DotError {
+            line,
+            column,
+            message: "unterminated string".to_string(),
+        })
+    }
+
+    fn tokenize(mut self) -> Result<Vec<(usize, usize, DotToken)>, DotError> {
+        const SYMBOLS: [&str; 10] = ["->", "--", "{", "}", "[", "]", ";", ",", "=", ":"];
+        let is_id_start = |c: char| c.is_alphabetic() || c == '_' || !c.is_ascii();
+        let mut tokens = Vec::new();
+        while let Some(c) = self.peek(0) {
+            let (line, column) = (self.line, self.column);
+            let next = self.peek(1);
+            if c.is_whitespace() {
+                self.bump();
+            } else if (c == '#' && self.at_line_start()) || (c == '/' && next == Some('/')) {
+                self.skip_line();
+            } else if c == '/' && next == Some('*') {
+                self.bump();
+                self.bump();
+                while !(self.peek(0) == Some('*') && self.peek(1) == Some('/')) {
+                    if self.bump().is_none() {
+                        return Err(DotError {
+                            line,
+                            column,
+                            message: "unterminated comment".to_string(),
+                        });
+                    }
+                }
+                self.bump();
+                self.bump();
+            } else if c == '"' {
+                self.bump();
+                let text = self.quoted(line, column)?;
+                tokens.push((line, column, DotToken::Id { text, quoted: true }));
+            } else if is_id_start(c) {
+                let text = self.take_while(|c| is_id_start(c) || c.is_ascii_digit());
+                tokens.push((
+                    line,
+                    column,
+                    DotToken::Id {
+                        text,
+                        quoted: false,
+                    },
+                ));
+            } else if c.is_ascii_digit()
+                || (c == '.' && next.is_some_and(|c| c.is_ascii_digit()))
+                || (c == '-' && next.is_some_and(|c| c.is_ascii_digit() || c == '.'))
+            {
+                let mut text = String::new();
+                if c == '-' {
+                    text.push('-');
+                    self.bump();
+                }
+                text += &self.take_while(|c| c.is_ascii_digit());
+                if self.peek(0) == Some('.') {
+                    self.bump();
+                    text.push('.');
+                    text += &self.take_while(|c| c.is_ascii_digit());
+                }
+                tokens.push((
+                    line,
+                    column,
+                    DotToken::Id {
+                        text,
+                        quoted: false,
+                    },
+                ));
+            } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| {
+                let mut expected = symbol.chars();
+                expected.next() == Some(c) && expected.next().is_none_or(|e| next == Some(e))
+            }) {
+                for _ in 0..symbol.len() {
+                    self.bump();
+                }
+                tokens.push((line, column, DotToken::Symbol(symbol)));
+            } else {
+                return Err(DotError {
+                    line,
+                    column,
+                    message: format!("unexpected character '{}'", c),
+                });
+            }
+        }
+        Ok(tokens)
+    }
+}
+
+struct DotParser {
+    tokens: Vec<(usize, usize, DotToken)>,
+    next: usize,
+    end: (usize, usize), // Line and column just past the input
+    directed: bool,
+    ids: Vec<String>,
+    indices: HashMap<String, usize>,
+    labels: Vec<Option<String>>,
+    edges: Vec<(usize, usize)>,
+    attributes: GraphAttributes,
+}
+
+impl DotParser {
+    fn error(&self, message: &str) -> DotError {
+        let (line, column) = self
+            .tokens
+            .get(self.next)
+            .map_or(self.end, |&(line, column, _)| (line, column));
+        DotError {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<&DotToken> {
+        self.tokens.get(self.next).map(|(_, _, token)| token)
+    }
+
+    fn peek_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(DotToken::Symbol(s)) if *s == symbol)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = self.peek_symbol(symbol);
+        self.next += usize::from(found);
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), DotError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", symbol)))
+        }
+    }
+
+    /// Whether the next token is the (unquoted, case-insensitive) keyword
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(
+            self.peek(),
+            Some(DotToken::Id { text, quoted: false }) if text.eq_ignore_ascii_case(keyword)
+        )
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek_keyword(keyword);
+        self.next += usize::from(found);
+        found
+    }
+
+    fn expect_id(&mut self, what: &str) -> Result<String, DotError> {
+        match self.peek() {
+            Some(DotToken::Id { text, .. }) => {
+                let text = text.clone();
+                self.next += 1;
+                Ok(text)
+            }
+            _ => Err(self.error(&format!("expected {}", what))),
+        }
+    }
+
+    fn node_index(&mut self, id: String) -> usize {
+        if let Some(&index) = self.indices.get(&id) {
+            return index;
+        }
+        self.indices.insert(id.clone(), self.ids.len());
+        self.ids.push(id);
+        self.labels.push(None);
+        self.ids.len() - 1
+    }
+
+    /// Node id of a node or edge statement; ports are not supported
+    fn node_id(&mut self) -> Result<usize, DotError> {
+        let id = self.expect_id("a node id")?;
+        if self.peek_symbol(":") {
+            return Err(self.error("ports are not supported"));
+        }
+        Ok(self.node_index(id))
+    }
+
+    /// Zero or more `[name = value, ...]` lists
+    fn attributes(&mut self) -> Result<Vec<(String, String)>, DotError> {
+        let mut attributes = Vec::new();
+        while self.eat_symbol("[") {
+            while !self.eat_symbol("]") {
+                let name = self.expect_id("an attribute name or ']'")?;
+                self.expect_symbol("=")?;
+                let value = self.expect_id("an attribute value")?;
+                attributes.push((name, value));
+                if !self.eat_symbol(",") {
+                    self.eat_symbol(";");
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    fn statement(&mut self) -> Result<(), DotError> {
+        if self.peek_keyword("subgraph") || self.peek_symbol("{") {
+            return Err(self.error("subgraphs are not supported"));
+        }
+        // Default attributes for the graph, nodes or edges are skipped
+        if ["graph", "node", "edge"]
+            .iter()
+            .any(|k| self.peek_keyword(k))
+        {
+            self.next += 1;
+            if !self.peek_symbol("[") {
+                return Err(self.error("expected '['"));
+            }
+            self.attributes()?;
+            return Ok(());
+        }
+        // A graph attribute `name = value` is skipped too
+        if matches!(
+            self.tokens.get(self.next + 1),
+            Some((_, _, DotToken::Symbol("=")))
+        ) {
+            self.next += 2;
+            self.expect_id("an attribute value")?;
+            return Ok(());
+        }
+
+        let (connector, wrong) = if self.directed {
+            ("->", "--")
+        } else {
+            ("--", "->")
+        };
+        let mut from = self.node_id()?;
+        let chain_start = self.edges.len();
+        loop {
+            if self.peek_symbol(wrong) {
+                let kind = if self.directed { "digraph" } else { "graph" };
+                return Err(self.error(&format!("'{}' in a {}; use '{}'", wrong, kind, connector)));
+            }
+            if !self.eat_symbol(connector) {
+                break;
+            }
+            let to = self.node_id()?;
+            self.edges.push((from, to));
+            from = to;
+        }
+        let attributes = self.attributes()?;
+        if self.edges.len() == chain_start {
+            let node = NodeId::new(from);
+            for (name, value) in &attributes {
+                if name == "label" {
+                    self.labels[from] = Some(value.clone());
+                } else {
+                    self.attributes.set_node_attr(node, name, value);
+                }
+            }
+        }
+        // Every edge of a chain gets the list; undirected edges both ways
+        for &(from, to) in &self.edges[chain_start..] {
+            let (from, to) = (NodeId::new(from), NodeId::new(to));
+            for (name, value) in &attributes {
+                self.attributes.set_edge_attr(from, to, name, value);
+                if !self.directed {
+                    self.attributes.set_edge_attr(to, from, name, value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DotGraph {
+    /// Parse a subset of the DOT language: an optionally `strict` `graph` or
+    /// `digraph` with node statements, edge statements (including chains like
+    /// `a -> b -> c`) and attribute lists. Node `label`s become values and
+    /// the other node and edge attributes go to `attributes`; default
+    /// attribute statements and graph attributes are accepted and ignored.
+    /// Subgraphs, ports and HTML strings are rejected.
+    /// A strict graph keeps one copy of each edge.
+    pub fn parse(source: &str) -> Result<DotGraph, DotError> {
+        let lexer = DotLexer {
+            chars: source.chars().collect(),
+            next: 0,
+            line: 1,
+            column: 1,
+        };
+        let end = source
+            .lines()
+            .enumerate()
+            .last()
+            .map_or((1, 1), |(number, line)| {
+                (number + 1, line.chars().count() + 1)
+            });
+        let mut parser = DotParser {
+            tokens: lexer.tokenize()?,
+            next: 0,
+            end,
+            directed: false,
+            ids: Vec::new(),
+            indices: HashMap::new(),
+            labels: Vec::new(),
+            edges: Vec::new(),
+            attributes: GraphAttributes::new(),
+        };
+
+        let strict = parser.eat_keyword("strict");
+        parser.directed = if parser.eat_keyword("digraph") {
+            true
+        } else if parser.eat_keyword("graph") {
+            false
+        } else {
+            return Err(parser.error("expected 'graph' or 'digraph'"));
+        };
+        if !parser.peek_symbol("{") {
+            parser.expect_id("a graph name or '{'")?;
+        }
+        parser.expect_symbol("{")?;
+        while !parser.eat_symbol("}") {
+            if parser.peek().is_none() {
+                return Err(parser.error("expected '}'"));
+            }
+            parser.statement()?;
+            parser.eat_symbol(";");
+        }
+        if parser.peek().is_some() {
+            return Err(parser.error("unexpected input after the graph"));
+        }
+
+        let mut nodes: Vec<Node<String>> = parser
+            .ids
+            .iter()
+            .zip(parser.labels)
+            .map(|(id, label)| Node::new(label.unwrap_or_else(|| id.clone()), Vec::new()))
+            .collect();
+        for &(from, to) in &parser.edges {
+            nodes[from].neighbors.push(to);
+            if !parser.directed && from != to {
+                nodes[to].neighbors.push(from);
+            }
+        }
+        if strict {
+            for node in &mut nodes {
+                dedup_neighbors(&mut node.neighbors);
+            }
+        }
+        Ok(DotGraph {
+            graph: Graph { nodes },
+            ids: parser.ids,
+            directed: parser.directed,
+            attributes: parser.attributes,
+        })
+    }
+}
+
+impl Graph<String> {
+    /// Parse DOT into a graph valued by node labels; see `DotGraph::parse`
+    pub fn from_dot(source: &str) -> Result<Graph<String>, DotError> {
+        DotGraph::parse(source).map(|dot| dot.graph)
+    }
+}
@@ -0,0 +1,197 @@
+use crate::graph::NodeId;
+
+/// Error from one of the small text parsers, with the byte offset where it was detected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.position)
+    }
+}
+
+/// Error from the DOT parser, with the 1-based line and column where it was detected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for DotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Error from the GraphML parser, with the 1-based line and column where it was detected
+#[cfg(feature = "graphml")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphMlError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+#[cfg(feature = "graphml")]
+impl std::fmt::Display for GraphMlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Error from building or querying a graph: an invalid node index, an edge
+/// the graph does not accept, a cycle where a DAG is needed, or input that
+/// does not parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphError {
+    NodeOutOfRange {
+        node: usize,
+        len: usize,
+    },
+    DanglingNeighbor {
+        node: usize,
+        neighbor: usize,
+    },
+    /// A `StableGraph` handle whose node has been removed
+    RemovedNode {
+        node: usize,
+    },
+    /// A self-loop that a `PolicyGraph` does not allow
+    SelfLoop {
+        node: usize,
+    },
+    /// A second from -> to edge that a `PolicyGraph` does not allow
+    ParallelEdge {
+        from: usize,
+        to: usize,
+    },
+    /// A directed cycle, in edge order as `Graph::find_cycle` gives it, in a
+    /// graph that must be acyclic
+    CycleDetected {
+        cycle: Vec<NodeId>,
+    },
+    /// Text that is not a valid graph
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for GraphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphError::NodeOutOfRange { node, len } => {
+                write!(f, "node {} out of range for a graph of {} nodes", node, len)
+            }
+            GraphError::DanglingNeighbor { node, neighbor } => {
+                write!(f, "node {} lists missing neighbor {}", node, neighbor)
+            }
+            GraphError::RemovedNode { node } => {
+                write!(f, "node {} has been removed", node)
+            }
+            GraphError::SelfLoop { node } => {
+                write!(f, "self-loop at node {} is not allowed", node)
+            }
+            GraphError::ParallelEdge { from, to } => {
+                write!(f, "edge {} -> {} already exists", from, to)
+            }
+            GraphError::CycleDetected { cycle } => {
+                write!(f, "graph has a cycle through ")?;
+                for (position, node) in cycle.iter().enumerate() {
+                    if position > 0 {
+                        write!(f, " -> ")?;
+                    }
+                    write!(f, "{}", node)?;
+                }
+                Ok(())
+            }
+            GraphError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseError> for GraphError {
+    fn from(error: ParseError) -> Self {
+        GraphError::Parse(error)
+    }
+}
+
+/// Something `Graph::validate_all` found wrong with a graph's adjacency lists.
+/// Only dangling neighbors break algorithms; self-loops and parallel edges are
+/// legal but often unintended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphDefect {
+    /// `node` lists `neighbor`, which is not a node of the graph
+    DanglingNeighbor { node: usize, neighbor: usize },
+    /// `node` lists itself as a neighbor
+    SelfLoop { node: usize },
+    /// from -> to is listed `copies` times, more than once
+    DuplicateEdge {
+        from: usize,
+        to: usize,
+        copies: usize,
+    },
+}
+
+impl std::fmt::Display for GraphDefect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphDefect::DanglingNeighbor { node, neighbor } => {
+                write!(f, "node {} lists missing neighbor {}", node, neighbor)
+            }
+            GraphDefect::SelfLoop { node } => write!(f, "node {} has a self-loop", node),
+            GraphDefect::DuplicateEdge { from, to, copies } => {
+                write!(f, "edge {} -> {} is listed {} times", from, to, copies)
+            }
+        }
+    }
+}
+
+/// Error from a shortest path search that allows negative weights: a cycle
+/// of negative total weight is reachable from the start, so distances through
+/// it are unbounded. `cycle` lists its nodes in edge order, each one having an
+/// edge to the next and the last one an edge back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeCycle {
+    pub cycle: Vec<NodeId>,
+}
+
+impl std::fmt::Display for NegativeCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "negative-weight cycle ")?;
+        for node in &self.cycle {
+            write!(f, "{} -> ", node)?;
+        }
+        match self.cycle.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Error from loading a graph file
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    Gzip(String),
+    Parse { line: usize, message: String },
+    Format(String),
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "{}", error),
+            LoadError::Gzip(message) => write!(f, "invalid gzip data: {}", message),
+            LoadError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            LoadError::Format(message) => write!(f, "invalid file: {}", message),
+        }
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(error: std::io::Error) -> Self {
+        LoadError::Io(error)
+    }
+}
@@ -0,0 +1,196 @@
+use crate::error::LoadError;
+use crate::graph::Graph;
+
+/// On-disk CSR layout: the magic bytes, node count n and edge count m as u64,
+/// n + 1 u64 offsets into the target array, then m u32 targets (all little endian)
+pub const CSR_MAGIC: &[u8; 8] = b"GTCSR1\0\0";
+
+impl<T> Graph<T> {
+    /// Write the graph in the on-disk CSR layout read by `CsrFile`
+    pub fn write_csr<W: std::io::Write>(&self, out: W) -> std::io::Result<()> {
+        use std::io::Write;
+        if self.nodes.len() > u32::MAX as usize {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "CSR files hold at most 2^32 - 1 nodes",
+            ));
+        }
+        let mut out = std::io::BufWriter::new(out);
+        let edges: usize = self.nodes.iter().map(|node| node.neighbors.len()).sum();
+        out.write_all(CSR_MAGIC)?;
+        out.write_all(&(self.nodes.len() as u64).to_le_bytes())?;
+        out.write_all(&(edges as u64).to_le_bytes())?;
+        let mut offset = 0u64;
+        out.write_all(&offset.to_le_bytes())?;
+        for node in &self.nodes {
+            offset += node.neighbors.len() as u64;
+            out.write_all(&offset.to_le_bytes())?;
+        }
+        for node in &self.nodes {
+            for &w in &node.neighbors {
+                out.write_all(&(w as u32).to_le_bytes())?;
+            }
+        }
+        out.flush()
+    }
+}
+
+/// Read-only handle on a CSR file that fetches adjacency lists on demand
+pub struct CsrFile {
+    reader: std::io::BufReader<std::fs::File>,
+    position: u64,
+    nodes: usize,
+    edges: u64,
+}
+
+impl CsrFile {
+    const HEADER: u64 = 24;
+
+    pub fn open(path: &std::path::Path) -> Result<CsrFile, LoadError> {
+        use std::io::Read;
+        let mut reader = std::io::BufReader::with_capacity(1 << 16, std::fs::File::open(path)?);
+        let mut header = [0u8; 24];
+        reader.read_exact(&mut header)?;
+        if &header[..8] != CSR_MAGIC {
+            return Err(LoadError::Format("not a CSR file".to_string()));
+        }
+        let word = |i: usize| u64::from_le_bytes(header[i..i + 8].try_into().unwrap());
+        let (nodes, edges) = (word(8), word(16));
+        let expected = Self::HEADER + 8 * (nodes + 1) + 4 * edges;
+        if reader.get_ref().metadata()?.len() != expected {
+            return Err(LoadError::Format(
+                "CSR file has the wrong length".to_string(),
+            ));
+        }
+        Ok(CsrFile {
+            reader,
+            position: Self::HEADER,
+            nodes: nodes as usize,
+            edges,
+        })
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes
+    }
+
+    pub fn edge_count(&self) -> u64 {
+        self.edges
+    }
+
+    /// Seek relative to the current position so nearby reads reuse the buffer
+    fn read_at(&mut self, offset: u64, buffer: &mut [u8]) -> std::io::Result<()> {
+        use std::io::Read;
+        self.reader
+            .seek_relative(offset as i64 - self.position as i64)?;
+        self.reader.read_exact(buffer)?;
+        self.position = offset + buffer.len() as u64;
+        Ok(())
+    }
+
+    /// Replace `out` with the out-neighbors of `node`
+    pub fn neighbors(&mut self, node: usize, out: &mut Vec<u32>) -> Result<(), LoadError> {
+        let mut range = [0u8; 16];
+        self.read_at(Self::HEADER + 8 * node as u64, &mut range)?;
+        let start = u64::from_le_bytes(range[..8].try_into().unwrap());
+        let end = u64::from_le_bytes(range[8..].try_into().unwrap());
+        if start > end || end > self.edges {
+            return Err(LoadError::Format(format!(
+                "corrupt offsets for node {}",
+                node
+            )));
+        }
+        let mut bytes = vec![0u8; 4 * (end - start) as usize];
+        let targets = Self::HEADER + 8 * (self.nodes as u64 + 1);
+        self.read_at(targets + 4 * start, &mut bytes)?;
+        out.clear();
+        for chunk in bytes.chunks_exact(4) {
+            let target = u32::from_le_bytes(chunk.try_into().unwrap());
+            if target as usize >= self.nodes {
+                return Err(LoadError::Format(format!(
+                    "edge target {} out of range",
+                    target
+                )));
+            }
+            out.push(target);
+        }
+        Ok(())
+    }
+
+    /// Semi-external BFS from `start`. Only the visited bitmap lives in memory:
+    /// each level's frontier is spilled to a file in `scratch`, and is then read
+    /// back `batch` nodes at a time and expanded in sorted order, so adjacency
+    /// reads sweep forward through the file. Calls `visit(node, depth)` for
+    /// every reached node and returns how many were reached.
+    pub fn bfs<F>(
+        &mut self,
+        start: usize,
+        batch: usize,
+        scratch: &std::path::Path,
+        mut visit: F,
+    ) -> Result<usize, LoadError>
+    where
+        F: FnMut(usize, usize),
+    {
+        use std::io::{Read, Write};
+        if start >= self.nodes {
+            return Err(LoadError::Format(format!(
+                "start node {} out of range",
+                start
+            )));
+        }
+        let mut visited = vec![0u64; self.nodes.div_ceil(64)];
+        let frontier_path =
+            |depth: usize| scratch.join(format!("bfs-{}-{}.frontier", std::process::id(), depth));
+        let write_frontier =
+            |depth: usize| std::fs::File::create(frontier_path(depth)).map(std::io::BufWriter::new);
+
+        visited[start / 64] |= 1 << (start % 64);
+        visit(start, 0);
+        let mut reached = 1;
+        let mut next = write_frontier(0)?;
+        next.write_all(&(start as u32).to_le_bytes())?;
+        next.flush()?;
+        drop(next);
+
+        let mut neighbors = Vec::new();
+        for depth in 0.. {
+            let path = frontier_path(depth);
+            let mut frontier = std::io::BufReader::new(std::fs::File::open(&path)?);
+            let mut next = write_frontier(depth + 1)?;
+            let mut found = 0;
+            loop {
+                let mut ids = Vec::with_capacity(batch.max(1));
+                let mut word = [0u8; 4];
+                while ids.len() < batch.max(1) && frontier.read_exact(&mut word).is_ok() {
+                    ids.push(u32::from_le_bytes(word) as usize);
+                }
+                if ids.is_empty() {
+                    break;
+                }
+                ids.sort_unstable();
+                for v in ids {
+                    self.neighbors(v, &mut neighbors)?;
+                    for &w in &neighbors {
+                        let (word, bit) = (w as usize / 64, 1u64 << (w % 64));
+                        if visited[word] & bit == 0 {
+                            visited[word] |= bit;
+                            visit(w as usize, depth + 1);
+                            next.write_all(&w.to_le_bytes())?;
+                            found += 1;
+                        }
+                    }
+                }
+            }
+            next.flush()?;
+            drop(next);
+            std::fs::remove_file(&path)?;
+            reached += found;
+            if found == 0 {
+                std::fs::remove_file(frontier_path(depth + 1))?;
+                break;
+            }
+        }
+        Ok(reached)
+    }
+}
@@ -0,0 +1,480 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use crate::graph::{Graph, Node, NodeId};
+use crate::traversal::{BfsIterator, Traversal};
+
+/// Dinic's max-flow over integer capacities
+pub(crate) struct Dinic {
+    adjacency: Vec<Vec<usize>>,
+    to: Vec<usize>,
+    capacity: Vec<i64>,
+}
+
+impl Dinic {
+    pub fn new(n: usize) -> Dinic {
+        Dinic {
+            adjacency: vec![Vec::new(); n],
+            to: Vec::new(),
+            capacity: Vec::new(),
+        }
+    }
+
+    /// Add an arc with its residual twin at the next index
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: i64, reverse_capacity: i64) {
+        for (a, b, c) in [(from, to, capacity), (to, from, reverse_capacity)] {
+            self.adjacency[a].push(self.to.len());
+            self.to.push(b);
+            self.capacity.push(c);
+        }
+    }
+
+    pub fn levels(&self, source: usize) -> Vec<usize> {
+        let mut level = vec![usize::MAX; self.adjacency.len()];
+        level[source] = 0;
+        let mut queue = VecDeque::from([source]);
+        while let Some(v) = queue.pop_front() {
+            for &arc in &self.adjacency[v] {
+                let w = self.to[arc];
+                if self.capacity[arc] > 0 && level[w] == usize::MAX {
+                    level[w] = level[v] + 1;
+                    queue.push_back(w);
+                }
+            }
+        }
+        level
+    }
+
+    pub fn augment(
+        &mut self,
+        v: usize,
+        sink: usize,
+        limit: i64,
+        level: &[usize],
+        next: &mut [usize],
+    ) -> i64 {
+        if v == sink {
+            return limit;
+        }
+        while next[v] < self.adjacency[v].len() {
+            let arc = self.adjacency[v][next[v]];
+            let w = self.to[arc];
+            if self.capacity[arc] > 0 && level[w] == level[v] + 1 {
+                let pushed = self.augment(w, sink, limit.min(self.capacity[arc]), level, next);
+                if pushed > 0 {
+                    self.capacity[arc] -= pushed;
+                    self.capacity[arc ^ 1] += pushed;
+                    return pushed;
+                }
+            }
+            next[v] += 1;
+        }
+        0
+    }
+
+    pub fn max_flow(&mut self, source: usize, sink: usize) -> i64 {
+        let mut flow = 0;
+        loop {
+            let level = self.levels(source);
+            if level[sink] == usize::MAX {
+                return flow;
+            }
+            let mut next = vec![0; self.adjacency.len()];
+            loop {
+                let pushed = self.augment(source, sink, i64::MAX, &level, &mut next);
+                if pushed == 0 {
+                    break;
+                }
+                flow += pushed;
+            }
+        }
+    }
+
+    /// Nodes reachable from the source in the residual graph: the source side
+    /// of a minimum cut once `max_flow` has run
+    pub fn source_side(&self, source: usize) -> Vec<bool> {
+        self.levels(source)
+            .into_iter()
+            .map(|level| level != usize::MAX)
+            .collect()
+    }
+}
+
+/// Flow network over integer capacities. Every arc added gets a reverse twin
+/// at the neighboring id (arc ^ 1) with zero capacity, and pushing flow on an
+/// arc cancels the same amount on its twin, so the residual graph is always
+/// consistent.
+#[derive(Debug, Clone, Default)]
+pub struct FlowNetwork {
+    out: Vec<Vec<usize>>,
+    heads: Vec<usize>,
+    capacity: Vec<i64>,
+    flow: Vec<i64>,
+}
+
+/// Residual graph snapshot as an ordinary Graph (node values are indices), so
+/// the usual iterators and path searches run on it; `arcs[v][k]` is the
+/// network arc behind the edge `graph.nodes[v].neighbors[k]`
+#[derive(Debug, Clone)]
+pub struct ResidualGraph {
+    pub graph: Graph,
+    pub arcs: Vec<Vec<usize>>,
+}
+
+impl FlowNetwork {
+    pub fn new(nodes: usize) -> Self {
+        Self {
+            out: vec![Vec::new(); nodes],
+            ..Self::default()
+        }
+    }
+
+    /// Network with an arc per edge of `graph`, in adjacency order, so the
+    /// k-th edge overall becomes arc 2k
+    pub fn from_graph<T, C>(graph: &Graph<T>, capacity: C) -> Self
+    where
+        C: Fn(NodeId, NodeId) -> i64,
+    {
+        let mut network = FlowNetwork::new(graph.nodes.len());
+        for (from, node) in graph.nodes.iter().enumerate() {
+            for &to in &node.neighbors {
+                let (from, to) = (NodeId::new(from), NodeId::new(to));
+                network.add_edge(from, to, capacity(from, to));
+            }
+        }
+        network
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.out.len()
+    }
+
+    /// Add an arc and its reverse twin, returning the forward arc's id
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, capacity: i64) -> usize {
+        let arc = self.heads.len();
+        let (from, to) = (from.index(), to.index());
+        for (tail, head, capacity) in [(from, to, capacity), (to, from, 0)] {
+            self.out[tail].push(self.heads.len());
+            self.heads.push(head);
+            self.capacity.push(capacity);
+            self.flow.push(0);
+        }
+        arc
+    }
+
+    pub fn tail(&self, arc: usize) -> NodeId {
+        NodeId::new(self.heads[arc ^ 1])
+    }
+
+    pub fn head(&self, arc: usize) -> NodeId {
+        NodeId::new(self.heads[arc])
+    }
+
+    pub fn flow(&self, arc: usize) -> i64 {
+        self.flow[arc]
+    }
+
+    pub fn residual(&self, arc: usize) -> i64 {
+        self.capacity[arc] - self.flow[arc]
+    }
+
+    /// Push flow along an arc, cancelling it on the twin
+    pub fn push(&mut self, arc: usize, amount: i64) {
+        debug_assert!(
+            amount <= self.residual(arc),
+            "push exceeds residual capacity"
+        );
+        self.flow[arc] += amount;
+        self.flow[arc ^ 1] -= amount;
+    }
+
+    /// Net flow leaving `node`
+    pub fn outflow(&self, node: NodeId) -> i64 {
+        self.out[node.index()]
+            .iter()
+            .map(|&arc| self.flow[arc])
+            .sum()
+    }
+
+    pub fn residual_graph(&self) -> ResidualGraph {
+        let arcs: Vec<Vec<usize>> = self
+            .out
+            .iter()
+            .map(|arcs| {
+                arcs.iter()
+                    .copied()
+                    .filter(|&arc| self.residual(arc) > 0)
+                    .collect()
+            })
+            .collect();
+        let nodes = arcs
+            .iter()
+            .enumerate()
+            .map(|(v, arcs)| Node {
+                value: v as i32,
+                neighbors: arcs.iter().map(|&arc| self.heads[arc]).collect(),
+            })
+            .collect();
+        ResidualGraph {
+            graph: Graph { nodes },
+            arcs,
+        }
+    }
+
+    /// Push the bottleneck amount along a node path of the residual graph,
+    /// using the widest residual arc between each consecutive pair. Returns the
+    /// amount pushed, 0 if some step has no residual arc.
+    pub fn augment(&mut self, path: &[NodeId]) -> i64 {
+        let steps: Option<Vec<usize>> = path
+            .windows(2)
+            .map(|pair| {
+                self.out[pair[0].index()]
+                    .iter()
+                    .copied()
+                    .filter(|&arc| self.heads[arc] == pair[1].index() && self.residual(arc) > 0)
+                    .max_by_key(|&arc| self.residual(arc))
+            })
+            .collect();
+        let Some(steps) = steps else {
+            return 0;
+        };
+        let amount = steps
+            .iter()
+            .map(|&arc| self.residual(arc))
+            .min()
+            .unwrap_or(0);
+        for arc in steps {
+            self.push(arc, amount);
+        }
+        amount
+    }
+
+    /// Edmonds–Karp from the pieces above: augment along BFS-shortest residual
+    /// paths until the sink is unreachable. Returns the added flow value.
+    pub fn max_flow(&mut self, source: NodeId, sink: NodeId) -> i64 {
+        let mut total = 0;
+        if source == sink {
+            return total;
+        }
+        loop {
+            let residual = self.residual_graph();
+            let Some(path) = residual.graph.zero_one_bfs(source, |_, _| 1).path_to(sink) else {
+                break;
+            };
+            total += self.augment(&path);
+        }
+        total
+    }
+
+    /// Source side of a minimum cut once the flow is maximum: the nodes a BFS
+    /// of the residual graph reaches from the source
+    pub fn min_cut(&self, source: NodeId) -> Vec<NodeId> {
+        let residual = self.residual_graph();
+        let mut side: Vec<NodeId> = BfsIterator::new(&residual.graph, source)
+            .with_ids()
+            .map(|(id, _)| id)
+            .collect();
+        side.sort_unstable();
+        side
+    }
+
+    /// Arcs of a minimum cut once the flow is maximum: the arcs with positive
+    /// capacity from the `min_cut` side to the rest, all saturated. Their
+    /// capacities add up to the flow value.
+    pub fn min_cut_edges(&self, source: NodeId) -> Vec<usize> {
+        let mut on_source_side = vec![false; self.node_count()];
+        for node in self.min_cut(source) {
+            on_source_side[node.index()] = true;
+        }
+        // Forward arcs have even ids; their twins never carry capacity
+        (0..self.heads.len())
+            .step_by(2)
+            .filter(|&arc| {
+                self.capacity[arc] > 0
+                    && on_source_side[self.heads[arc ^ 1]]
+                    && !on_source_side[self.heads[arc]]
+            })
+            .collect()
+    }
+}
+
+/// Global minimum cut found by `Graph::min_cut`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalMinCut {
+    /// Total weight of the edges between the two sides
+    pub weight: u64,
+    /// The side without node 0, sorted; every other node is on the other side
+    pub side: Vec<NodeId>,
+}
+
+impl<T> Graph<T> {
+    /// Global minimum cut with every edge, read as undirected, weighing 1: the
+    /// fewest edges whose removal disconnects the graph. See
+    /// `min_cut_weighted`.
+    pub fn min_cut(&self) -> Option<GlobalMinCut> {
+        self.min_cut_weighted(|_, _| 1)
+    }
+
+    /// Global minimum cut (Stoer-Wagner) of the graph read as undirected:
+    /// the split of the nodes into two non-empty sides with the least total
+    /// edge weight between them. Edges are merged as in `undirected_edges`,
+    /// so `weight(a, b)` is asked once per adjacent pair, with a < b. A
+    /// disconnected graph has a cut of weight 0. None with fewer than two
+    /// nodes. Runs in O(V * E log V).
+    pub fn min_cut_weighted<W>(&self, weight: W) -> Option<GlobalMinCut>
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        let len = self.nodes.len();
+        if len < 2 {
+            return None;
+        }
+        // Weights between the current super-nodes, each a set of merged nodes
+        let mut adjacency: Vec<HashMap<usize, u64>> = vec![HashMap::new(); len];
+        for (a, b) in self.undirected_edge_indices() {
+            let w = weight(NodeId::new(a), NodeId::new(b));
+            adjacency[a].insert(b, w);
+            adjacency[b].insert(a, w);
+        }
+        let mut members: Vec<Vec<usize>> = (0..len).map(|node| vec![node]).collect();
+        let mut active: Vec<usize> = (0..len).collect();
+        let mut key = vec![0u64; len];
+        let mut added = vec![false; len];
+        let mut best: Option<(u64, Vec<usize>)> = None;
+
+        while active.len() > 1 {
+            // Maximum adjacency order: repeatedly add the super-node most
+            // tightly connected to those added so far, ties to the smallest
+            for &node in &active {
+                key[node] = 0;
+                added[node] = false;
+            }
+            let mut heap: BinaryHeap<(u64, Reverse<usize>)> =
+                active.iter().map(|&node| (0, Reverse(node))).collect();
+            let (mut previous, mut last) = (active[0], active[0]);
+            while let Some((node_key, Reverse(node))) = heap.pop() {
+                // Skip entries superseded by a larger key
+                if added[node] || node_key != key[node] {
+                    continue;
+                }
+                added[node] = true;
+                (previous, last) = (last, node);
+                for (&neighbor, &w) in &adjacency[node] {
+                    if !added[neighbor] {
+                        key[neighbor] += w;
+                        heap.push((key[neighbor], Reverse(neighbor)));
+                    }
+                }
+            }
+
+            // The last super-node alone against the rest is this phase's cut
+            if best.as_ref().is_none_or(|(weight, _)| key[last] < *weight) {
+                best = Some((key[last], members[last].clone()));
+            }
+
+            // Merge the last super-node into the one added before it
+            for (neighbor, w) in std::mem::take(&mut adjacency[last]) {
+                adjacency[neighbor].remove(&last);
+                if neighbor != previous {
+                    *adjacency[previous].entry(neighbor).or_insert(0) += w;
+                    *adjacency[neighbor].entry(previous).or_insert(0) += w;
+                }
+            }
+            let merged = std::mem::take(&mut members[last]);
+            members[previous].extend(merged);
+            active.retain(|&node| node != last);
+        }
+
+        let (weight, mut side) = best?;
+        if side.contains(&0) {
+            let mut on_side = vec![false; len];
+            for &node in &side {
+                on_side[node] = true;
+            }
+            side = (0..len).filter(|&node| !on_side[node]).collect();
+        }
+        side.sort_unstable();
+        Some(GlobalMinCut {
+            weight,
+            side: side.into_iter().map(NodeId::new).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_flow_and_min_cut_match_the_textbook_network() {
+        // Cormen et al., figure 26.1: s = 0, t = 5, maximum flow 23
+        let capacities = HashMap::from([
+            ((0, 1), 16),
+            ((0, 2), 13),
+            ((2, 1), 4),
+            ((1, 3), 12),
+            ((3, 2), 9),
+            ((2, 4), 14),
+            ((4, 3), 7),
+            ((3, 5), 20),
+            ((4, 5), 4),
+        ]);
+        let mut graph = Graph::from_nodes((0..6).map(|v| Node::new(v, vec![])).collect());
+        for &(from, to) in capacities.keys() {
+            graph.add_edge(NodeId::new(from), NodeId::new(to)).unwrap();
+        }
+        let mut network =
+            FlowNetwork::from_graph(&graph, |from, to| capacities[&(from.index(), to.index())]);
+        let (source, sink) = (NodeId::new(0), NodeId::new(5));
+        assert_eq!(network.max_flow(source, sink), 23);
+        assert_eq!(network.outflow(source), 23);
+        assert_eq!(network.max_flow(source, sink), 0);
+
+        let side: Vec<usize> = network.min_cut(source).iter().map(|v| v.index()).collect();
+        assert_eq!(side, vec![0, 1, 2, 4]);
+        let mut cut: Vec<(usize, usize)> = network
+            .min_cut_edges(source)
+            .into_iter()
+            .map(|arc| (network.tail(arc).index(), network.head(arc).index()))
+            .collect();
+        cut.sort_unstable();
+        assert_eq!(cut, vec![(1, 3), (4, 3), (4, 5)]);
+    }
+
+    #[test]
+    fn stoer_wagner_finds_the_papers_minimum_cut() {
+        // Stoer and Wagner's example, renumbered from 0: the cut {2, 3, 6, 7}
+        // weighs 4
+        let weights = HashMap::from([
+            ((0, 1), 2),
+            ((0, 4), 3),
+            ((1, 2), 3),
+            ((1, 4), 2),
+            ((1, 5), 2),
+            ((2, 3), 4),
+            ((2, 6), 2),
+            ((3, 6), 2),
+            ((3, 7), 2),
+            ((4, 5), 3),
+            ((5, 6), 1),
+            ((6, 7), 3),
+        ]);
+        let graph = Graph::from_undirected_edges(8, weights.keys().copied());
+        let cut = graph
+            .min_cut_weighted(|a, b| weights[&(a.index(), b.index())])
+            .unwrap();
+        assert_eq!(cut.weight, 4);
+        let side: Vec<usize> = cut.side.iter().map(|v| v.index()).collect();
+        assert_eq!(side, vec![2, 3, 6, 7]);
+
+        // Two triangles joined by a bridge split at the bridge
+        let bridged = Graph::from_undirected_edges(
+            6,
+            [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)],
+        );
+        let cut = bridged.min_cut().unwrap();
+        assert_eq!(cut.weight, 1);
+        assert_eq!(cut.side, [3, 4, 5].map(NodeId::new));
+        assert_eq!(Graph::path(1).min_cut(), None);
+    }
+}
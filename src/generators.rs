@@ -0,0 +1,142 @@
+//! Seeded random graph models. Every generator returns an undirected simple
+//! graph stored with each edge in both directions, nodes valued by index and
+//! neighbor lists sorted, and the same seed always gives the same graph.
+
+use std::collections::BTreeSet;
+
+use crate::graph::Graph;
+use crate::sampling::Rng;
+
+/// Erdős–Rényi G(n, p): each of the n(n - 1)/2 possible edges is present
+/// independently with probability `p`. Skips geometrically over absent edges
+/// (Batagelj and Brandes), so it runs in O(n + m) rather than O(n^2).
+pub fn erdos_renyi(n: usize, p: f64, seed: u64) -> Graph {
+    if p >= 1.0 {
+        return Graph::complete(n);
+    }
+    let mut edges = Vec::new();
+    if p > 0.0 {
+        let mut rng = Rng::new(seed);
+        let log_absent = (1.0 - p).ln();
+        // Candidate (v, w) with w < v, walked in row-major order
+        let (mut v, mut w) = (1, -1i64);
+        while v < n {
+            let skip = ((1.0 - rng.next_f64()).ln() / log_absent).floor() as i64;
+            w += 1 + skip;
+            while w >= v as i64 && v < n {
+                w -= v as i64;
+                v += 1;
+            }
+            if v < n {
+                edges.push((w as usize, v));
+            }
+        }
+    }
+    Graph::from_undirected_edges(n, edges)
+}
+
+/// Barabási–Albert preferential attachment: starting from `m` isolated nodes,
+/// each new node links to `m` distinct existing nodes chosen with probability
+/// proportional to their degree, giving a power-law degree tail. Panics unless
+/// 1 <= m < n.
+pub fn barabasi_albert(n: usize, m: usize, seed: u64) -> Graph {
+    assert!(m >= 1 && m < n, "barabasi_albert needs 1 <= m < n");
+    let mut rng = Rng::new(seed);
+    let mut edges = Vec::with_capacity((n - m) * m);
+    // Every node once per incident edge, so a uniform pick is degree-weighted
+    let mut endpoints: Vec<usize> = Vec::with_capacity(2 * (n - m) * m);
+    let mut targets: Vec<usize> = (0..m).collect();
+    for source in m..n {
+        for &target in &targets {
+            edges.push((target, source));
+            endpoints.extend([target, source]);
+        }
+        let mut chosen = BTreeSet::new();
+        while chosen.len() < m {
+            chosen.insert(endpoints[rng.below(endpoints.len())]);
+        }
+        targets = chosen.into_iter().collect();
+    }
+    Graph::from_undirected_edges(n, edges)
+}
+
+/// Random `d`-regular graph, every node with exactly `d` neighbors, by
+/// pairing edge stubs at random and retrying the unusable pairs (Steger and
+/// Wormald); nearly uniform for small `d`. Panics unless n * d is even and
+/// d < n.
+pub fn random_regular(n: usize, d: usize, seed: u64) -> Graph {
+    assert!(
+        (n * d).is_multiple_of(2) && (d < n || d == 0),
+        "random_regular needs n * d even and d < n"
+    );
+    let mut rng = Rng::new(seed);
+    loop {
+        if let Some(edges) = try_pairing(n, d, &mut rng) {
+            return Graph::from_undirected_edges(n, edges);
+        }
+    }
+}
+
+/// One attempt at `random_regular`: None if the stubs left over can no
+/// longer be paired without a self-loop or a repeated edge
+fn try_pairing(n: usize, d: usize, rng: &mut Rng) -> Option<BTreeSet<(usize, usize)>> {
+    let mut edges = BTreeSet::new();
+    let mut stubs: Vec<usize> = (0..n).flat_map(|v| std::iter::repeat_n(v, d)).collect();
+    while !stubs.is_empty() {
+        rng.shuffle(&mut stubs);
+        let mut left = Vec::new();
+        for pair in stubs.chunks_exact(2) {
+            let (a, b) = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+            if a == b || !edges.insert((a, b)) {
+                left.extend([a, b]);
+            }
+        }
+        let mut nodes = left.clone();
+        nodes.sort_unstable();
+        nodes.dedup();
+        let pairable = nodes
+            .iter()
+            .enumerate()
+            .any(|(i, &a)| nodes[i + 1..].iter().any(|&b| !edges.contains(&(a, b))));
+        if !left.is_empty() && !pairable {
+            return None;
+        }
+        stubs = left;
+    }
+    Some(edges)
+}
+
+/// Watts–Strogatz small world: a ring where each node links to its `k / 2`
+/// nearest neighbors on either side, after which each ring edge has its far
+/// end moved with probability `beta` to a uniformly random node that is
+/// neither the near end nor already its neighbor (left in place if no such
+/// node exists). Panics unless `k` is even and k < n.
+pub fn watts_strogatz(n: usize, k: usize, beta: f64, seed: u64) -> Graph {
+    assert!(
+        k.is_multiple_of(2) && k < n,
+        "watts_strogatz needs k even and k < n"
+    );
+    let mut rng = Rng::new(seed);
+    let key = |a: usize, b: usize| (a.min(b), a.max(b));
+    let mut edges: BTreeSet<(usize, usize)> = (0..n)
+        .flat_map(|v| (1..=k / 2).map(move |j| key(v, (v + j) % n)))
+        .collect();
+    let mut degree = vec![k; n];
+    for j in 1..=k / 2 {
+        for v in 0..n {
+            if rng.next_f64() >= beta || degree[v] >= n - 1 {
+                continue;
+            }
+            let mut w = rng.below(n);
+            while w == v || edges.contains(&key(v, w)) {
+                w = rng.below(n);
+            }
+            let old = (v + j) % n;
+            edges.remove(&key(v, old));
+            edges.insert(key(v, w));
+            degree[old] -= 1;
+            degree[w] += 1;
+        }
+    }
+    Graph::from_undirected_edges(n, edges)
+}
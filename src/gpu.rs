@@ -0,0 +1,367 @@
+use wgpu::util::DeviceExt;
+
+use crate::analysis::{power_iteration, PageRankStop};
+use crate::backend::{Adjacency, ComputeBackend, CpuBackend};
+
+/// Threads per workgroup in both kernels
+const WORKGROUP: u32 = 64;
+
+/// Workgroups per row of a dispatch; larger grids wrap into more rows
+const ROW: u32 = 65_535;
+
+const FRONTIER_SHADER: &str = r#"
+struct Params { count: u32, depth: u32 }
+
+@group(0) @binding(0) var<storage, read> offsets: array<u32>;
+@group(0) @binding(1) var<storage, read> targets: array<u32>;
+@group(0) @binding(2) var<storage, read> frontier: array<u32>;
+@group(0) @binding(3) var<storage, read_write> levels: array<atomic<u32>>;
+@group(0) @binding(4) var<storage, read_write> next: array<u32>;
+@group(0) @binding(5) var<storage, read_write> next_len: atomic<u32>;
+@group(0) @binding(6) var<uniform> params: Params;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let i = id.x + id.y * groups.x * 64u;
+    if (i >= params.count) {
+        return;
+    }
+    let v = frontier[i];
+    for (var e = offsets[v]; e < offsets[v + 1u]; e += 1u) {
+        let w = targets[e];
+        // The weak exchange may fail spuriously, so retry while w is unclaimed
+        loop {
+            let result = atomicCompareExchangeWeak(&levels[w], 0xffffffffu, params.depth);
+            if (result.exchanged) {
+                next[atomicAdd(&next_len, 1u)] = w;
+                break;
+            }
+            if (result.old_value != 0xffffffffu) {
+                break;
+            }
+        }
+    }
+}
+"#;
+
+const PAGERANK_SHADER: &str = r#"
+struct Params { count: u32, damping: f32, base: f32 }
+
+@group(0) @binding(0) var<storage, read> offsets: array<u32>;
+@group(0) @binding(1) var<storage, read> sources: array<u32>;
+@group(0) @binding(2) var<storage, read> out_degree: array<u32>;
+@group(0) @binding(3) var<storage, read> rank: array<f32>;
+@group(0) @binding(4) var<storage, read_write> next: array<f32>;
+@group(0) @binding(5) var<uniform> params: Params;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let w = id.x + id.y * groups.x * 64u;
+    if (w >= params.count) {
+        return;
+    }
+    var sum = 0.0;
+    for (var e = offsets[w]; e < offsets[w + 1u]; e += 1u) {
+        let v = sources[e];
+        sum += rank[v] / f32(out_degree[v]);
+    }
+    next[w] = params.base + params.damping * sum;
+}
+"#;
+
+/// `ComputeBackend` running both kernels as wgpu compute shaders, on any
+/// adapter wgpu finds: Vulkan, Metal, DX12 or OpenGL. BFS claims nodes with
+/// atomic compare-exchange, so each level's frontier comes back in no fixed
+/// order, though the levels are exact. PageRank pulls rank along reversed
+/// edges in f32, as WGSL has no f64, so scores agree with `CpuBackend` only
+/// to f32 precision. Graphs whose arrays exceed the device's storage buffer
+/// limit, or with 2^32 edges or more, run on `CpuBackend` instead.
+pub struct GpuBackend {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    info: wgpu::AdapterInfo,
+    frontier: wgpu::ComputePipeline,
+    pagerank: wgpu::ComputePipeline,
+}
+
+impl GpuBackend {
+    /// Open the default adapter; None if there is none or it cannot give a
+    /// device
+    pub fn new() -> Option<Self> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle());
+        let adapter =
+            pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+                .ok()?;
+        let descriptor = wgpu::DeviceDescriptor {
+            label: Some("graph_traversal"),
+            required_limits: adapter.limits(),
+            ..Default::default()
+        };
+        let (device, queue) = pollster::block_on(adapter.request_device(&descriptor)).ok()?;
+        let frontier = pipeline(&device, "bfs frontier", FRONTIER_SHADER);
+        let pagerank = pipeline(&device, "pagerank step", PAGERANK_SHADER);
+        Some(GpuBackend {
+            device,
+            queue,
+            info: adapter.get_info(),
+            frontier,
+            pagerank,
+        })
+    }
+
+    /// Name of the adapter in use
+    pub fn adapter_name(&self) -> &str {
+        &self.info.name
+    }
+
+    /// Whether the adapter is a software rasterizer such as llvmpipe, which
+    /// `default_backend` passes over for `CpuBackend`
+    pub fn is_software(&self) -> bool {
+        self.info.device_type == wgpu::DeviceType::Cpu
+    }
+
+    /// Whether arrays of `words` 32-bit values fit in one storage binding
+    fn fits(&self, words: usize) -> bool {
+        let limits = self.device.limits();
+        let bytes = 4 * words.max(1) as u64;
+        bytes <= limits.max_storage_buffer_binding_size && bytes <= limits.max_buffer_size
+    }
+
+    fn storage(&self, label: &str, words: &[u32], extra: wgpu::BufferUsages) -> wgpu::Buffer {
+        // Empty bindings are invalid, so pad to one word
+        let mut bytes: Vec<u8> = words.iter().flat_map(|word| word.to_ne_bytes()).collect();
+        bytes.resize(bytes.len().max(4), 0);
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: &bytes,
+                usage: wgpu::BufferUsages::STORAGE | extra,
+            })
+    }
+
+    fn uniform(&self, label: &str, words: [u32; 4]) -> wgpu::Buffer {
+        let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_ne_bytes()).collect();
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: &bytes,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            })
+    }
+
+    fn bind(&self, pipeline: &wgpu::ComputePipeline, buffers: &[&wgpu::Buffer]) -> wgpu::BindGroup {
+        let entries: Vec<wgpu::BindGroupEntry> = buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect();
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        })
+    }
+
+    /// Record one dispatch of `pipeline` over `count` threads
+    fn dispatch(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::ComputePipeline,
+        bind_group: &wgpu::BindGroup,
+        count: u32,
+    ) {
+        let groups = count.div_ceil(WORKGROUP);
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.dispatch_workgroups(groups.min(ROW), groups.div_ceil(ROW), 1);
+    }
+
+    /// Submit `encoder`, then copy `len` words of `buffer` back to the host
+    fn submit_and_read(
+        &self,
+        mut encoder: wgpu::CommandEncoder,
+        buffer: &wgpu::Buffer,
+        len: usize,
+    ) -> Vec<[u8; 4]> {
+        let size = 4 * len.max(1) as u64;
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, size);
+        self.queue.submit([encoder.finish()]);
+        staging.map_async(wgpu::MapMode::Read, .., |result| {
+            result.expect("mapping a readback buffer");
+        });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .expect("waiting for the GPU");
+        let view = staging
+            .get_mapped_range(..)
+            .expect("mapped readback buffer");
+        view.chunks_exact(4)
+            .take(len)
+            .map(|word| word.try_into().unwrap())
+            .collect()
+    }
+
+    fn encoder(&self) -> wgpu::CommandEncoder {
+        self.device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default())
+    }
+}
+
+fn pipeline(device: &wgpu::Device, label: &str, source: &str) -> wgpu::ComputePipeline {
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some(label),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+    device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some(label),
+        layout: None,
+        module: &module,
+        entry_point: Some("main"),
+        compilation_options: Default::default(),
+        cache: None,
+    })
+}
+
+/// Offsets as u32, if every one fits
+fn narrow(offsets: &[usize]) -> Option<Vec<u32>> {
+    offsets
+        .iter()
+        .map(|&offset| u32::try_from(offset).ok())
+        .collect()
+}
+
+impl ComputeBackend for GpuBackend {
+    fn name(&self) -> &'static str {
+        "gpu"
+    }
+
+    fn bfs_levels(&self, graph: Adjacency<'_>, start: usize) -> Vec<Option<u32>> {
+        let n = graph.len();
+        let offsets = match narrow(graph.offsets()) {
+            Some(offsets) if self.fits(graph.targets().len()) && self.fits(n + 1) => offsets,
+            _ => return CpuBackend.bfs_levels(graph, start),
+        };
+        let copy = wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST;
+        let offsets = self.storage("offsets", &offsets, wgpu::BufferUsages::empty());
+        let targets = self.storage("targets", graph.targets(), wgpu::BufferUsages::empty());
+        let mut levels = vec![u32::MAX; n];
+        levels[start] = 0;
+        let levels = self.storage("levels", &levels, copy);
+        // Each node joins at most one frontier, so n words hold any of them
+        let mut frontiers = [
+            self.storage("frontier a", &vec![0; n], copy),
+            self.storage("frontier b", &vec![0; n], copy),
+        ];
+        self.queue
+            .write_buffer(&frontiers[0], 0, &(start as u32).to_ne_bytes());
+        let next_len = self.storage("next len", &[0], copy);
+        let params = self.uniform("params", [1, 1, 0, 0]);
+
+        let mut count = 1;
+        let mut depth = 1u32;
+        while count > 0 {
+            self.queue.write_buffer(&next_len, 0, &0u32.to_ne_bytes());
+            let words = [count, depth];
+            let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_ne_bytes()).collect();
+            self.queue.write_buffer(&params, 0, &bytes);
+            let bind_group = self.bind(
+                &self.frontier,
+                &[
+                    &offsets,
+                    &targets,
+                    &frontiers[0],
+                    &levels,
+                    &frontiers[1],
+                    &next_len,
+                    &params,
+                ],
+            );
+            let mut encoder = self.encoder();
+            self.dispatch(&mut encoder, &self.frontier, &bind_group, count);
+            count = u32::from_ne_bytes(self.submit_and_read(encoder, &next_len, 1)[0]);
+            frontiers.swap(0, 1);
+            depth += 1;
+        }
+        self.submit_and_read(self.encoder(), &levels, n)
+            .into_iter()
+            .map(|word| Some(u32::from_ne_bytes(word)).filter(|&level| level != u32::MAX))
+            .collect()
+    }
+
+    fn pagerank(&self, graph: Adjacency<'_>, damping: f64, stop: PageRankStop) -> Vec<f64> {
+        let n = graph.len();
+        if narrow(graph.offsets()).is_none()
+            || !self.fits(graph.targets().len())
+            || !self.fits(n + 1)
+        {
+            return CpuBackend.pagerank(graph, damping, stop);
+        }
+        // Reverse the edges, so that each thread sums into its own node
+        let mut in_offsets = vec![0u32; n + 1];
+        for &w in graph.targets() {
+            in_offsets[w as usize + 1] += 1;
+        }
+        for v in 0..n {
+            in_offsets[v + 1] += in_offsets[v];
+        }
+        let mut fill = in_offsets.clone();
+        let mut sources = vec![0u32; graph.targets().len()];
+        for v in 0..n {
+            for &w in graph.neighbors(v) {
+                sources[fill[w as usize] as usize] = v as u32;
+                fill[w as usize] += 1;
+            }
+        }
+        let out_degree: Vec<u32> = (0..n).map(|v| graph.neighbors(v).len() as u32).collect();
+
+        let copy = wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST;
+        let offsets = self.storage("in offsets", &in_offsets, wgpu::BufferUsages::empty());
+        let sources = self.storage("sources", &sources, wgpu::BufferUsages::empty());
+        let degrees = self.storage("out degree", &out_degree, wgpu::BufferUsages::empty());
+        let uniform = (1.0 / n as f32).to_bits();
+        let mut ranks = [
+            self.storage("rank a", &vec![uniform; n], copy),
+            self.storage("rank b", &vec![uniform; n], copy),
+        ];
+        let params = self.uniform("params", [n as u32, 0, 0, 0]);
+
+        power_iteration(n, stop, |rank, next| {
+            // The dangling share comes from the host copy of the scores
+            let dangling: f64 = (0..n)
+                .filter(|&v| out_degree[v] == 0)
+                .map(|v| rank[v])
+                .sum();
+            let base = (1.0 - damping + damping * dangling) / n as f64;
+            let words = [
+                n as u32,
+                (damping as f32).to_bits(),
+                (base as f32).to_bits(),
+            ];
+            let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_ne_bytes()).collect();
+            self.queue.write_buffer(&params, 0, &bytes);
+            let bind_group = self.bind(
+                &self.pagerank,
+                &[&offsets, &sources, &degrees, &ranks[0], &ranks[1], &params],
+            );
+            let mut encoder = self.encoder();
+            self.dispatch(&mut encoder, &self.pagerank, &bind_group, n as u32);
+            for (score, word) in next
+                .iter_mut()
+                .zip(self.submit_and_read(encoder, &ranks[1], n))
+            {
+                *score = f64::from(f32::from_ne_bytes(word));
+            }
+            ranks.swap(0, 1);
+        })
+    }
+}
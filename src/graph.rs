@@ -0,0 +1,635 @@
+use std::collections::HashSet;
+
+use crate::error::{GraphDefect, GraphError};
+use crate::traversal::{TopoSortIterator, Traversal};
+
+/// Index of a node in a `Graph`, as handed out by `add_node` and the traversals.
+/// Convert explicitly from and to a raw index with `NodeId::new` / `index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub fn new(index: usize) -> Self {
+        NodeId(index)
+    }
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl From<usize> for NodeId {
+    fn from(index: usize) -> Self {
+        NodeId(index)
+    }
+}
+
+impl From<NodeId> for usize {
+    fn from(id: NodeId) -> Self {
+        id.0
+    }
+}
+
+impl std::fmt::Display for NodeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A graph node: a value plus the indices of its neighbors. The value type
+/// defaults to `i32`, which is what most of the algorithms in this crate use.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Node<T = i32> {
+    pub(crate) value: T,
+    pub(crate) neighbors: Vec<usize>, // List of indices representing neighbors
+}
+
+/// A directed graph stored as per-node adjacency lists
+///
+/// It is a multigraph: parallel edges and self-loops are kept as added.
+/// Traversals visit each node once however many edges lead to it, while edge
+/// listings and degrees count every copy. `validate_all` reports both,
+/// `dedup_edges` and `remove_self_loops` strip them, and `PolicyGraph`
+/// rejects them as they are added.
+///
+/// Read-only use is thread-safe: `Graph<T>` is `Send + Sync` whenever `T` is,
+/// and traversals only borrow it immutably, so any number of them can run at
+/// once on a shared `&Graph` from different threads (under `std::thread::scope`,
+/// or `par_multi_source_bfs` with the `rayon` feature). The traversal iterators
+/// themselves are not `Send`, since `prune_if` predicates and keys are held in
+/// `Rc`: create each one on the thread that drives it. Mutation takes
+/// `&mut Graph`, which the borrow checker keeps apart from every traversal.
+#[derive(Debug, Clone)]
+pub struct Graph<T = i32> {
+    pub(crate) nodes: Vec<Node<T>>,
+}
+
+/// `Graph` under the name that pairs it with `UnGraph`
+pub type DiGraph<T = i32> = Graph<T>;
+
+impl<T> Node<T> {
+    /// Node with a value and the raw indices of its neighbors, which
+    /// `Graph::from_nodes` checks
+    pub fn new(value: T, neighbors: Vec<usize>) -> Self {
+        Node { value, neighbors }
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Neighbors in insertion order
+    pub fn neighbors(&self) -> impl DoubleEndedIterator<Item = NodeId> + ExactSizeIterator + '_ {
+        self.neighbors.iter().map(|&index| NodeId(index))
+    }
+}
+
+impl<T> Graph<T> {
+    /// Empty graph
+    pub fn new() -> Self {
+        Graph { nodes: Vec::new() }
+    }
+
+    /// Graph over existing nodes. Panics if a neighbor index is out of
+    /// range; `try_from_nodes` returns the error instead.
+    pub fn from_nodes(nodes: Vec<Node<T>>) -> Self {
+        match Self::try_from_nodes(nodes) {
+            Ok(graph) => graph,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Graph over existing nodes, or `DanglingNeighbor` for the first node
+    /// listing a neighbor index out of range
+    pub fn try_from_nodes(nodes: Vec<Node<T>>) -> Result<Self, GraphError> {
+        let graph = Graph { nodes };
+        graph.validate()?;
+        Ok(graph)
+    }
+
+    /// Append a node without neighbors and return its id
+    pub fn add_node(&mut self, value: T) -> NodeId {
+        self.nodes.push(Node::new(value, Vec::new()));
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Add a directed edge from -> to
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), GraphError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+        self.nodes[from.0].neighbors.push(to.0);
+        Ok(())
+    }
+
+    /// Remove one edge from -> to, the first listed, returning whether there
+    /// was one. Node ids are unaffected; `StableGraph` also removes nodes.
+    pub fn remove_edge(&mut self, from: NodeId, to: NodeId) -> Result<bool, GraphError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+        let neighbors = &mut self.nodes[from.0].neighbors;
+        match neighbors.iter().position(|&neighbor| neighbor == to.0) {
+            Some(position) => {
+                neighbors.remove(position);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Add the edge from -> to at its sorted place in the neighbors of `from`,
+    /// after any copies already there, so lists kept sorted stay sorted
+    pub fn add_edge_sorted(&mut self, from: NodeId, to: NodeId) -> Result<(), GraphError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+        let neighbors = &mut self.nodes[from.0].neighbors;
+        let position = neighbors.partition_point(|&neighbor| neighbor <= to.0);
+        neighbors.insert(position, to.0);
+        Ok(())
+    }
+
+    /// Sort every neighbor list by index, which makes traversal orders
+    /// independent of the order edges were added in
+    pub fn sort_neighbors(&mut self) {
+        for node in &mut self.nodes {
+            node.neighbors.sort_unstable();
+        }
+    }
+
+    /// Add an edge in both directions; a self-loop is recorded once
+    pub fn add_undirected_edge(&mut self, a: NodeId, b: NodeId) -> Result<(), GraphError> {
+        self.add_edge(a, b)?;
+        if a != b {
+            self.nodes[b.0].neighbors.push(a.0);
+        }
+        Ok(())
+    }
+
+    /// Ok if `node` is a node of this graph
+    pub fn check_node(&self, node: NodeId) -> Result<(), GraphError> {
+        if node.0 < self.nodes.len() {
+            Ok(())
+        } else {
+            Err(GraphError::NodeOutOfRange {
+                node: node.0,
+                len: self.nodes.len(),
+            })
+        }
+    }
+
+    /// Ok if every neighbor index names a node of this graph; `validate_all`
+    /// also reports self-loops and parallel edges
+    pub fn validate(&self) -> Result<(), GraphError> {
+        for (node, entry) in self.nodes.iter().enumerate() {
+            if let Some(&neighbor) = entry.neighbors.iter().find(|&&n| n >= self.nodes.len()) {
+                return Err(GraphError::DanglingNeighbor { node, neighbor });
+            }
+        }
+        Ok(())
+    }
+
+    /// Every defect of the adjacency lists, not just the first dangling
+    /// neighbor `validate` stops at: node by node in index order, each
+    /// problem neighbor once, by neighbor index
+    pub fn validate_all(&self) -> Result<(), Vec<GraphDefect>> {
+        let mut defects = Vec::new();
+        let mut sorted = Vec::new();
+        for (node, entry) in self.nodes.iter().enumerate() {
+            sorted.clone_from(&entry.neighbors);
+            sorted.sort_unstable();
+            for run in sorted.chunk_by(|a, b| a == b) {
+                let neighbor = run[0];
+                if neighbor >= self.nodes.len() {
+                    defects.push(GraphDefect::DanglingNeighbor { node, neighbor });
+                    continue;
+                }
+                if neighbor == node {
+                    defects.push(GraphDefect::SelfLoop { node });
+                }
+                if run.len() > 1 {
+                    defects.push(GraphDefect::DuplicateEdge {
+                        from: node,
+                        to: neighbor,
+                        copies: run.len(),
+                    });
+                }
+            }
+        }
+        if defects.is_empty() {
+            Ok(())
+        } else {
+            Err(defects)
+        }
+    }
+
+    /// Keep one copy of each parallel edge, the first listed, returning how
+    /// many were removed
+    pub fn dedup_edges(&mut self) -> usize {
+        self.retain_neighbors(|_, _| true, true)
+    }
+
+    /// Remove every self-loop, returning how many there were
+    pub fn remove_self_loops(&mut self) -> usize {
+        self.retain_neighbors(|node, neighbor| node != neighbor, false)
+    }
+
+    /// Remove neighbor entries that name no node, returning how many there were
+    pub fn remove_dangling_neighbors(&mut self) -> usize {
+        let len = self.nodes.len();
+        self.retain_neighbors(|_, neighbor| neighbor < len, false)
+    }
+
+    fn retain_neighbors<F: Fn(usize, usize) -> bool>(&mut self, keep: F, dedup: bool) -> usize {
+        let mut removed = 0;
+        for (node, entry) in self.nodes.iter_mut().enumerate() {
+            let before = entry.neighbors.len();
+            entry.neighbors.retain(|&neighbor| keep(node, neighbor));
+            if dedup {
+                dedup_neighbors(&mut entry.neighbors);
+            }
+            removed += before - entry.neighbors.len();
+        }
+        removed
+    }
+
+    /// Ids of all nodes, in index order
+    pub fn node_ids(&self) -> impl DoubleEndedIterator<Item = NodeId> + ExactSizeIterator {
+        (0..self.nodes.len()).map(NodeId)
+    }
+
+    pub fn nodes(&self) -> &[Node<T>] {
+        &self.nodes
+    }
+
+    /// Ids of all nodes ordered by `compare` on the nodes, equal nodes by
+    /// index, for heuristics that process nodes in a global order rather
+    /// than traversal order
+    pub fn nodes_sorted_by<F>(
+        &self,
+        mut compare: F,
+    ) -> impl DoubleEndedIterator<Item = NodeId> + ExactSizeIterator
+    where
+        F: FnMut(&Node<T>, &Node<T>) -> std::cmp::Ordering,
+    {
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by(|&a, &b| compare(&self.nodes[a], &self.nodes[b]));
+        order.into_iter().map(NodeId)
+    }
+
+    /// Ids of all nodes by ascending out-degree, parallel edges and
+    /// self-loops counted, equal degrees by index; `.rev()` gives the largest
+    /// first, as greedy coloring wants
+    pub fn nodes_by_degree(&self) -> impl DoubleEndedIterator<Item = NodeId> + ExactSizeIterator {
+        self.nodes_sorted_by(|a, b| a.neighbors.len().cmp(&b.neighbors.len()))
+    }
+
+    /// Every edge as (from, to): by source index, each source's in neighbor
+    /// order, every parallel copy listed
+    pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        self.node_ids().flat_map(move |from| self.edges_of(from))
+    }
+
+    /// Out-edges of `node` as (node, neighbor), in neighbor order; panics if
+    /// `node` is out of range
+    pub fn edges_of(&self, node: NodeId) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        self.nodes[node.0]
+            .neighbors
+            .iter()
+            .map(move |&to| (node, NodeId(to)))
+    }
+
+    /// Whether from -> to is an edge; false if either node is out of range
+    pub fn has_edge(&self, from: NodeId, to: NodeId) -> bool {
+        to.0 < self.nodes.len()
+            && self
+                .nodes
+                .get(from.0)
+                .is_some_and(|node| node.neighbors.contains(&to.0))
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&Node<T>> {
+        self.nodes.get(id.0)
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl<T> std::ops::Index<NodeId> for Graph<T> {
+    type Output = Node<T>;
+
+    fn index(&self, id: NodeId) -> &Node<T> {
+        &self.nodes[id.0]
+    }
+}
+
+impl<T> Default for Graph<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> Graph<T> {
+    /// Ids of all nodes by ascending value, equal values by index
+    pub fn nodes_by_value(&self) -> impl DoubleEndedIterator<Item = NodeId> + ExactSizeIterator {
+        self.nodes_sorted_by(|a, b| a.value.cmp(&b.value))
+    }
+
+    /// Sort every neighbor list by the neighbors' values, equal values by index
+    pub fn sort_neighbors_by_value(&mut self) {
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by(|&a, &b| self.nodes[a].value.cmp(&self.nodes[b].value));
+        let mut rank = vec![0; order.len()];
+        for (position, &node) in order.iter().enumerate() {
+            rank[node] = position;
+        }
+        for node in &mut self.nodes {
+            node.neighbors
+                .sort_unstable_by_key(|&neighbor| rank[neighbor]);
+        }
+    }
+}
+
+/// Remove repeated entries from a neighbor list, keeping the first occurrence
+pub(crate) fn dedup_neighbors(neighbors: &mut Vec<usize>) {
+    let mut seen = HashSet::new();
+    neighbors.retain(|&neighbor| seen.insert(neighbor));
+}
+
+impl Graph {
+    /// Complete graph K_n with edges in both directions; values are indices
+    pub fn complete(n: usize) -> Graph {
+        let nodes = (0..n)
+            .map(|v| Node {
+                value: v as i32,
+                neighbors: (0..n).filter(|&w| w != v).collect(),
+            })
+            .collect();
+        Graph { nodes }
+    }
+
+    /// Complete bipartite graph K_{a,b}: nodes 0..a on one side, a..a+b on the other
+    pub fn complete_bipartite(a: usize, b: usize) -> Graph {
+        let nodes = (0..a + b)
+            .map(|v| Node {
+                value: v as i32,
+                neighbors: if v < a {
+                    (a..a + b).collect()
+                } else {
+                    (0..a).collect()
+                },
+            })
+            .collect();
+        Graph { nodes }
+    }
+
+    /// Path 0 - 1 - ... - (n - 1)
+    pub fn path(n: usize) -> Graph {
+        Graph::from_undirected_edges(n, (1..n).map(|v| (v - 1, v)))
+    }
+
+    /// Cycle 0 - 1 - ... - (n - 1) - 0; below 3 nodes, the path on n nodes
+    pub fn cycle(n: usize) -> Graph {
+        let closing = (n >= 3).then(|| (0, n - 1));
+        Graph::from_undirected_edges(n, (1..n).map(|v| (v - 1, v)).chain(closing))
+    }
+
+    /// Star on n nodes: hub 0 joined to each of 1..n
+    pub fn star(n: usize) -> Graph {
+        Graph::from_undirected_edges(n, (1..n).map(|v| (0, v)))
+    }
+
+    /// `rows` x `cols` grid; the node at row r, column c is r * cols + c, joined
+    /// to the nodes beside, above and below it
+    pub fn grid(rows: usize, cols: usize) -> Graph {
+        let horizontal = (0..rows * cols)
+            .filter(|v| (v + 1) % cols != 0)
+            .map(|v| (v, v + 1));
+        let vertical = (0..rows.saturating_sub(1) * cols).map(|v| (v, v + cols));
+        Graph::from_undirected_edges(rows * cols, horizontal.chain(vertical))
+    }
+
+    /// Complete binary tree of the given depth, 2^(depth + 1) - 1 nodes in
+    /// heap order: the children of v are 2v + 1 and 2v + 2, and 0 is the root.
+    /// Panics unless depth < usize::BITS - 1, so that the node count fits.
+    pub fn binary_tree(depth: u32) -> Graph {
+        let n = depth
+            .checked_add(1)
+            .filter(|&bits| bits < usize::BITS)
+            .map(|bits| (1usize << bits) - 1)
+            .expect("binary tree depth must be below usize::BITS - 1");
+        Graph::from_undirected_edges(n, (1..n).map(|v| ((v - 1) / 2, v)))
+    }
+
+    /// Backend of `graph! { 0: [1, 2], ... }`: every node 0..n listed once,
+    /// in any order, valued by index; panics naming the first mistake
+    #[doc(hidden)]
+    pub fn from_macro_lists(lists: &[(usize, &[usize])]) -> Graph {
+        let len = lists.len();
+        let mut neighbors: Vec<Option<Vec<usize>>> = vec![None; len];
+        for &(node, list) in lists {
+            assert!(
+                node < len,
+                "graph!: node {} out of range for {} listed nodes",
+                node,
+                len
+            );
+            assert!(
+                neighbors[node].is_none(),
+                "graph!: node {} listed twice",
+                node
+            );
+            if let Some(&neighbor) = list.iter().find(|&&neighbor| neighbor >= len) {
+                panic!("graph!: node {} lists missing node {}", node, neighbor);
+            }
+            neighbors[node] = Some(list.to_vec());
+        }
+        let nodes = neighbors
+            .into_iter()
+            .enumerate()
+            .map(|(v, list)| Node {
+                value: v as i32,
+                neighbors: list.expect("every slot filled by a distinct node"),
+            })
+            .collect();
+        Graph { nodes }
+    }
+
+    /// Nodes 0..n valued by index, with each edge stored in both directions
+    /// and neighbor lists sorted
+    pub(crate) fn from_undirected_edges(
+        n: usize,
+        edges: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Graph {
+        let mut neighbors = vec![Vec::new(); n];
+        for (a, b) in edges {
+            neighbors[a].push(b);
+            neighbors[b].push(a);
+        }
+        let nodes = neighbors
+            .into_iter()
+            .enumerate()
+            .map(|(v, mut list)| {
+                list.sort_unstable();
+                Node {
+                    value: v as i32,
+                    neighbors: list,
+                }
+            })
+            .collect();
+        Graph { nodes }
+    }
+}
+
+impl Graph<&'static str> {
+    /// Backend of `graph! { a - b, b -> c }`: (from, directed, to) triples
+    /// over nodes named by identifier, numbered in order of first mention
+    #[doc(hidden)]
+    pub fn from_macro_names(edges: &[(&'static str, bool, &'static str)]) -> Self {
+        let mut graph = Graph::new();
+        let mut ids = std::collections::HashMap::new();
+        for &(from, directed, to) in edges {
+            let from = *ids.entry(from).or_insert_with(|| graph.add_node(from));
+            let to = *ids.entry(to).or_insert_with(|| graph.add_node(to));
+            if directed {
+                graph.add_edge(from, to)
+            } else {
+                graph.add_undirected_edge(from, to)
+            }
+            .expect("both ends were just added");
+        }
+        graph
+    }
+}
+
+impl<T> Graph<T> {
+    /// Kahn's topological order as raw indices, smallest ready index first;
+    /// None if there is a cycle
+    pub(crate) fn topological_indices(&self) -> Option<Vec<usize>> {
+        let mut topo = TopoSortIterator::new(self);
+        let order: Vec<usize> = std::iter::from_fn(|| topo.next_index()).collect();
+        (!topo.has_cycle()).then_some(order)
+    }
+
+    /// Kahn's topological order, smallest ready index first, or a cycle when
+    /// there is one
+    pub fn try_topological_order(&self) -> Result<Vec<NodeId>, GraphError> {
+        match self.topological_indices() {
+            Some(order) => Ok(order.into_iter().map(NodeId).collect()),
+            None => Err(GraphError::CycleDetected {
+                cycle: self
+                    .find_cycle()
+                    .expect("a graph without an order has a cycle"),
+            }),
+        }
+    }
+}
+
+/// A graph serializes as the bare array of its nodes, each `{"value": ...,
+/// "neighbors": [...]}` with neighbors as node indices. Deserializing checks
+/// every neighbor index.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Graph<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.nodes.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Graph<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let graph = Graph {
+            nodes: Vec::<Node<T>>::deserialize(deserializer)?,
+        };
+        graph.validate().map_err(serde::de::Error::custom)?;
+        Ok(graph)
+    }
+}
+
+/// Graph literal, checked as it is built. Either every node 0..n with its
+/// neighbor list, valued by index:
+///
+/// ```
+/// use graph_traversal::graph;
+///
+/// let graph = graph! { 0: [1, 2], 1: [0], 2: [] };
+/// assert_eq!(graph.edge_count(), 3);
+/// ```
+///
+/// or edges between nodes named by identifier, `a - b` undirected and
+/// `a -> b` directed, giving a `Graph<&str>` valued by name with nodes in
+/// order of first mention:
+///
+/// ```
+/// use graph_traversal::graph;
+///
+/// let graph = graph! { a - b, b -> c };
+/// assert_eq!(graph.len(), 3);
+/// assert_eq!(graph.edge_count(), 3);
+/// ```
+///
+/// Panics if a list names a node that is missing or listed twice.
+#[macro_export]
+macro_rules! graph {
+    (@directed -) => {
+        false
+    };
+    (@directed ->) => {
+        true
+    };
+    () => {
+        $crate::Graph::<i32>::new()
+    };
+    ($($node:literal : [$($neighbor:expr),* $(,)?]),+ $(,)?) => {
+        $crate::Graph::from_macro_lists(&[$(($node, {
+            let neighbors: &[usize] = &[$($neighbor),*];
+            neighbors
+        })),+])
+    };
+    ($($from:ident $op:tt $to:ident),+ $(,)?) => {
+        $crate::Graph::from_macro_names(&[$((
+            stringify!($from),
+            $crate::graph!(@directed $op),
+            stringify!($to),
+        )),+])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generators_handle_degenerate_sizes() {
+        assert_eq!(Graph::cycle(0).len(), 0);
+        assert_eq!(Graph::cycle(1).edge_count(), 0);
+        assert_eq!(Graph::cycle(2).edge_count(), 2);
+        assert_eq!(Graph::binary_tree(0).len(), 1);
+        assert!(std::panic::catch_unwind(|| Graph::binary_tree(64)).is_err());
+    }
+
+    #[test]
+    fn try_from_nodes_reports_dangling_neighbors() {
+        let nodes = || vec![Node::new(0, vec![1]), Node::new(1, vec![0, 2])];
+        assert_eq!(
+            Graph::try_from_nodes(nodes()).unwrap_err(),
+            GraphError::DanglingNeighbor {
+                node: 1,
+                neighbor: 2
+            }
+        );
+        assert!(std::panic::catch_unwind(|| Graph::from_nodes(nodes())).is_err());
+        let graph = Graph::try_from_nodes(vec![Node::new(0, vec![1]), Node::new(1, vec![])]);
+        assert_eq!(graph.unwrap().edge_count(), 1);
+    }
+}
@@ -0,0 +1,796 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::Write;
+
+use crate::attributes::GraphAttributes;
+use crate::error::GraphMlError;
+use crate::graph::{Graph, Node, NodeId};
+use crate::undirected::UnGraph;
+use crate::xml::escape;
+
+const GRAPHML_NAMESPACE: &str = "http://graphml.graphdrawing.org/xmlns";
+
+/// Write a graph as GraphML: nodes `n0, n1, ...` by index, each with its value
+/// under the `value` data key, then edges. Each attribute name gets a string
+/// key `d0, d1, ...`, node attributes first; a node attribute named `value`
+/// would clash with the values and is left out.
+fn write_graphml<T: Display, W: Write>(
+    graph: &Graph<T>,
+    edges: impl Iterator<Item = (usize, usize)>,
+    directed: bool,
+    attributes: &GraphAttributes,
+    mut out: W,
+) -> std::io::Result<()> {
+    let edgedefault = if directed { "directed" } else { "undirected" };
+    let node_keys: Vec<&str> = attributes
+        .node_attr_names()
+        .into_iter()
+        .filter(|&name| name != "value")
+        .collect();
+    let edge_keys = attributes.edge_attr_names();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(out, r#"<graphml xmlns="{}">"#, GRAPHML_NAMESPACE)?;
+    writeln!(
+        out,
+        r#"  <key id="value" for="node" attr.name="value" attr.type="string"/>"#
+    )?;
+    let domains = node_keys.iter().map(|name| ("node", name));
+    let domains = domains.chain(edge_keys.iter().map(|name| ("edge", name)));
+    for (id, (domain, name)) in domains.enumerate() {
+        writeln!(
+            out,
+            r#"  <key id="d{}" for="{}" attr.name="{}" attr.type="string"/>"#,
+            id,
+            domain,
+            escape(name)
+        )?;
+    }
+    writeln!(out, r#"  <graph id="G" edgedefault="{}">"#, edgedefault)?;
+    for (index, node) in graph.nodes.iter().enumerate() {
+        let value = escape(&node.value.to_string());
+        let data = key_data(&node_keys, 0, attributes.node_attrs(NodeId::new(index)));
+        writeln!(
+            out,
+            r#"    <node id="n{}"><data key="value">{}</data>{}</node>"#,
+            index, value, data
+        )?;
+    }
+    for (from, to) in edges {
+        let pairs = attributes.edge_attrs(NodeId::new(from), NodeId::new(to));
+        let data = key_data(&edge_keys, node_keys.len(), pairs);
+        if data.is_empty() {
+            writeln!(out, r#"    <edge source="n{}" target="n{}"/>"#, from, to)?;
+        } else {
+            writeln!(
+                out,
+                r#"    <edge source="n{}" target="n{}">{}</edge>"#,
+                from, to, data
+            )?;
+        }
+    }
+    writeln!(out, "  </graph>")?;
+    writeln!(out, "</graphml>")
+}
+
+/// `<data>` elements for the attributes whose names are among `keys`, the
+/// key at position i being `d<first + i>`
+fn key_data<'a>(
+    keys: &[&str],
+    first: usize,
+    attributes: impl Iterator<Item = (&'a str, &'a str)>,
+) -> String {
+    let mut data = String::new();
+    for (name, value) in attributes {
+        if let Ok(position) = keys.binary_search(&name) {
+            let key = first + position;
+            data += &format!(r#"<data key="d{}">{}</data>"#, key, escape(value));
+        }
+    }
+    data
+}
+
+impl<T: Display> Graph<T> {
+    /// GraphML for the graph, readable by Gephi and yEd: a directed graph with
+    /// nodes `n<index>` whose values are stored as strings under the `value` key
+    pub fn to_graphml(&self) -> String {
+        self.to_graphml_with(&GraphAttributes::new())
+    }
+
+    /// Like `to_graphml`, adding node and edge data from `attributes`
+    pub fn to_graphml_with(&self, attributes: &GraphAttributes) -> String {
+        let mut out = Vec::new();
+        self.write_graphml_with(attributes, &mut out)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(out).expect("GraphML output is UTF-8")
+    }
+
+    /// Stream the GraphML for the graph to `out`
+    pub fn write_graphml<W: Write>(&self, out: W) -> std::io::Result<()> {
+        self.write_graphml_with(&GraphAttributes::new(), out)
+    }
+
+    pub fn write_graphml_with<W: Write>(
+        &self,
+        attributes: &GraphAttributes,
+        out: W,
+    ) -> std::io::Result<()> {
+        let edges = self.edges().map(|(from, to)| (from.index(), to.index()));
+        write_graphml(self, edges, true, attributes, out)
+    }
+}
+
+impl<T: Display> UnGraph<T> {
+    /// GraphML for the graph: an undirected graph listing each edge once
+    pub fn to_graphml(&self) -> String {
+        self.to_graphml_with(&GraphAttributes::new())
+    }
+
+    /// Like `to_graphml`, adding node and edge data from `attributes`; each
+    /// edge takes the attributes of the orientation `edges` lists
+    pub fn to_graphml_with(&self, attributes: &GraphAttributes) -> String {
+        let mut out = Vec::new();
+        self.write_graphml_with(attributes, &mut out)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(out).expect("GraphML output is UTF-8")
+    }
+
+    /// Stream the GraphML for the graph to `out`
+    pub fn write_graphml<W: Write>(&self, out: W) -> std::io::Result<()> {
+        self.write_graphml_with(&GraphAttributes::new(), out)
+    }
+
+    pub fn write_graphml_with<W: Write>(
+        &self,
+        attributes: &GraphAttributes,
+        out: W,
+    ) -> std::io::Result<()> {
+        let edges = self.edges().map(|(a, b)| (a.index(), b.index()));
+        write_graphml(self.as_graph(), edges, false, attributes, out)
+    }
+}
+
+/// A graph read from GraphML: `ids[index]` is the GraphML id of each node, in
+/// document order. Each node's value is its data for the node key named
+/// `value`, else the one named `label`, falling back to the key's default and
+/// then to the node id. Undirected edges are stored both ways, and `directed`
+/// is the graph's `edgedefault`. The other node data and all edge data, key
+/// defaults included, are in `attributes` under each key's `attr.name` (or
+/// its id when it has none).
+#[derive(Debug, Clone)]
+pub struct GraphMlGraph {
+    pub graph: Graph<String>,
+    pub ids: Vec<String>,
+    pub directed: bool,
+    pub attributes: GraphAttributes,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum XmlToken {
+    Start {
+        name: String,
+        attributes: Vec<(String, String)>,
+        empty: bool,
+    },
+    End(String),
+    Text(String),
+}
+
+/// Character stream with the 1-based line and column of the next character
+struct XmlLexer {
+    chars: Vec<char>,
+    next: usize,
+    line: usize,
+    column: usize,
+}
+
+impl XmlLexer {
+    fn peek(&self, ahead: usize) -> Option<char> {
+        self.chars.get(self.next + ahead).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.next += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, message: &str) -> GraphMlError {
+        GraphMlError {
+            line: self.line,
+            column: self.column,
+            message: message.to_string(),
+        }
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        text.chars()
+            .enumerate()
+            .all(|(i, c)| self.peek(i) == Some(c))
+    }
+
+    /// Consume input up to and including `end`, returning what came before it
+    fn until(&mut self, end: &str, what: &str) -> Result<String, GraphMlError> {
+        let mut skipped = String::new();
+        while !self.starts_with(end) {
+            skipped.push(
+                self.bump()
+                    .ok_or_else(|| self.error(&format!("unterminated {}", what)))?,
+            );
+        }
+        for _ in end.chars() {
+            self.bump();
+        }
+        Ok(skipped)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek(0).is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn name(&mut self) -> Result<String, GraphMlError> {
+        let mut name = String::new();
+        while let Some(c) = self.peek(0) {
+            if c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<' | '"' | '\'') {
+                break;
+            }
+            name.push(c);
+            self.bump();
+        }
+        if name.is_empty() {
+            return Err(self.error("expected a name"));
+        }
+        Ok(name)
+    }
+
+    /// Decode the entity after a `&`: the five predefined ones and `&#...;`
+    fn entity(&mut self) -> Result<char, GraphMlError> {
+        let (line, column) = (self.line, self.column);
+        let name = self.until(";", "entity")?;
+        let c = match name.as_str() {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => name
+                    .strip_prefix('#')
+                    .and_then(|decimal| decimal.parse().ok())
+                    .and_then(char::from_u32),
+            },
+        };
+        c.ok_or(GraphMlError {
+            line,
+            column,
+            message: format!("unknown entity '&{};'", name),
+        })
+    }
+
+    fn attribute_value(&mut self) -> Result<String, GraphMlError> {
+        let quote = match self.peek(0) {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => return Err(self.error("expected a quoted attribute value")),
+        };
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some(c) if c == quote => return Ok(value),
+                Some('&') => value.push(self.entity()?),
+                Some('<') => return Err(self.error("'<' in an attribute value")),
+                Some(c) => value.push(c),
+                None => return Err(self.error("unterminated attribute value")),
+            }
+        }
+    }
+
+    /// The tag after a `<` that starts an element
+    fn start_tag(&mut self) -> Result<XmlToken, GraphMlError> {
+        let name = self.name()?;
+        let mut attributes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let empty = match self.peek(0) {
+                Some('>') => false,
+                Some('/') if self.peek(1) == Some('>') => {
+                    self.bump();
+                    true
+                }
+                Some(_) => {
+                    let attribute = self.name()?;
+                    self.skip_whitespace();
+                    if self.bump() != Some('=') {
+                        return Err(self.error("expected '=' after an attribute name"));
+                    }
+                    self.skip_whitespace();
+                    attributes.push((attribute, self.attribute_value()?));
+                    continue;
+                }
+                None => return Err(self.error("unterminated tag")),
+            };
+            self.bump();
+            return Ok(XmlToken::Start {
+                name,
+                attributes,
+                empty,
+            });
+        }
+    }
+
+    /// Tags and text with their positions. Declarations, processing
+    /// instructions and comments are dropped; CDATA sections become text.
+    fn tokenize(mut self) -> Result<Vec<(usize, usize, XmlToken)>, GraphMlError> {
+        let mut tokens = Vec::new();
+        let mut text = String::new();
+        let mut text_start = (self.line, self.column);
+        while let Some(c) = self.peek(0) {
+            if c != '<' {
+                if text.is_empty() {
+                    text_start = (self.line, self.column);
+                }
+                self.bump();
+                if c == '&' {
+                    text.push(self.entity()?);
+                } else {
+                    text.push(c);
+                }
+                continue;
+            }
+            if self.starts_with("<![CDATA[") {
+                if text.is_empty() {
+                    text_start = (self.line, self.column);
+                }
+                self.until("<![CDATA[", "CDATA section")?;
+                text.push_str(&self.until("]]>", "CDATA section")?);
+                continue;
+            }
+            if !text.is_empty() {
+                let (line, column) = text_start;
+                tokens.push((line, column, XmlToken::Text(std::mem::take(&mut text))));
+            }
+            let (line, column) = (self.line, self.column);
+            if self.starts_with("<!--") {
+                self.until("-->", "comment")?;
+            } else if self.starts_with("<?") {
+                self.until("?>", "processing instruction")?;
+            } else if self.starts_with("<!") {
+                // A DOCTYPE; internal subsets are not supported
+                self.until(">", "declaration")?;
+            } else if self.starts_with("</") {
+                self.bump();
+                self.bump();
+                let name = self.name()?;
+                self.skip_whitespace();
+                if self.bump() != Some('>') {
+                    return Err(self.error("expected '>'"));
+                }
+                tokens.push((line, column, XmlToken::End(name)));
+            } else {
+                self.bump();
+                tokens.push((line, column, self.start_tag()?));
+            }
+        }
+        if !text.is_empty() {
+            let (line, column) = text_start;
+            tokens.push((line, column, XmlToken::Text(text)));
+        }
+        Ok(tokens)
+    }
+}
+
+/// Element name, attributes, and whether the element has content
+type StartTag = (String, Vec<(String, String)>, bool);
+
+/// A `<key>` declared for nodes or edges
+#[derive(Clone)]
+struct DataKey {
+    name: Option<String>,
+    default: Option<String>,
+}
+
+/// An `<edge>` as read, resolved once every node is known
+struct EdgeElement {
+    position: usize, // Token of the start tag
+    source: String,
+    target: String,
+    directed: bool,
+    data: HashMap<String, String>,
+}
+
+struct GraphMlParser {
+    tokens: Vec<(usize, usize, XmlToken)>,
+    next: usize,
+    end: (usize, usize), // Line and column just past the input
+    node_keys: HashMap<String, DataKey>,
+    edge_keys: HashMap<String, DataKey>,
+    directed: bool,
+    ids: Vec<String>,
+    indices: HashMap<String, usize>,
+    data: Vec<HashMap<String, String>>, // Node data by key id
+    edges: Vec<EdgeElement>,
+}
+
+impl GraphMlParser {
+    fn error(&self, message: &str) -> GraphMlError {
+        let (line, column) = self
+            .tokens
+            .get(self.next)
+            .map_or(self.end, |&(line, column, _)| (line, column));
+        GraphMlError {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    /// The next token that is not whitespace-only text, left unconsumed
+    fn peek(&mut self) -> Option<&XmlToken> {
+        while let Some((_, _, XmlToken::Text(text))) = self.tokens.get(self.next) {
+            if !text.trim().is_empty() {
+                break;
+            }
+            self.next += 1;
+        }
+        self.tokens.get(self.next).map(|(_, _, token)| token)
+    }
+
+    /// Consume the start tag of the next element, returning its name,
+    /// attributes and whether it has content to read up to its end tag
+    fn start(&mut self) -> Result<StartTag, GraphMlError> {
+        match self.peek() {
+            Some(XmlToken::Start {
+                name,
+                attributes,
+                empty,
+            }) => {
+                let start = (name.clone(), attributes.clone(), !empty);
+                self.next += 1;
+                Ok(start)
+            }
+            Some(XmlToken::End(_)) => Err(self.error("unexpected end tag")),
+            Some(XmlToken::Text(_)) => Err(self.error("unexpected text")),
+            None => Err(self.error("expected an element")),
+        }
+    }
+
+    /// Whether the next token ends the element `name`, consuming it if so
+    fn eat_end(&mut self, name: &str) -> Result<bool, GraphMlError> {
+        match self.peek() {
+            Some(XmlToken::End(end)) if end == name => {
+                self.next += 1;
+                Ok(true)
+            }
+            Some(XmlToken::End(_)) => Err(self.error(&format!("expected '</{}>'", name))),
+            None => Err(self.error(&format!("expected '</{}>'", name))),
+            _ => Ok(false),
+        }
+    }
+
+    /// All text inside the element `name`, whose start tag has been consumed,
+    /// through its end tag
+    fn text_content(&mut self, name: &str) -> Result<String, GraphMlError> {
+        let mut text = String::new();
+        let mut open = vec![name.to_string()];
+        while let Some(top) = open.last() {
+            match self.tokens.get(self.next).map(|(_, _, token)| token) {
+                Some(XmlToken::Text(part)) => text.push_str(part),
+                Some(XmlToken::Start {
+                    name, empty: false, ..
+                }) => open.push(name.clone()),
+                Some(XmlToken::Start { .. }) => {}
+                Some(XmlToken::End(end)) if end == top => {
+                    open.pop();
+                }
+                Some(XmlToken::End(_)) | None => {
+                    return Err(self.error(&format!("expected '</{}>'", top)));
+                }
+            }
+            self.next += 1;
+        }
+        Ok(text)
+    }
+
+    /// Skip the rest of the element `name`, whose start tag has been consumed
+    fn skip(&mut self, name: &str) -> Result<(), GraphMlError> {
+        self.text_content(name).map(drop)
+    }
+
+    fn key(&mut self, attributes: &[(String, String)], open: bool) -> Result<(), GraphMlError> {
+        let id = attribute(attributes, "id").ok_or_else(|| self.error("key without an id"))?;
+        let domain = attribute(attributes, "for").unwrap_or("all");
+        let mut key = DataKey {
+            name: attribute(attributes, "attr.name").map(str::to_string),
+            default: None,
+        };
+        if open {
+            while !self.eat_end("key")? {
+                let (name, _, open) = self.start()?;
+                if name == "default" && open {
+                    key.default = Some(self.text_content(&name)?);
+                } else if open {
+                    self.skip(&name)?;
+                }
+            }
+        }
+        match domain {
+            "node" => {
+                self.node_keys.insert(id.to_string(), key);
+            }
+            "edge" => {
+                self.edge_keys.insert(id.to_string(), key);
+            }
+            "all" => {
+                self.node_keys.insert(id.to_string(), key.clone());
+                self.edge_keys.insert(id.to_string(), key);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn node(&mut self, attributes: &[(String, String)], open: bool) -> Result<(), GraphMlError> {
+        let id = attribute(attributes, "id").ok_or_else(|| self.error("node without an id"))?;
+        if self.indices.contains_key(id) {
+            return Err(self.error(&format!("duplicate node id '{}'", id)));
+        }
+        self.indices.insert(id.to_string(), self.ids.len());
+        self.ids.push(id.to_string());
+        let data = if open {
+            self.data_children("node")?
+        } else {
+            HashMap::new()
+        };
+        self.data.push(data);
+        Ok(())
+    }
+
+    /// The `<data>` of the node or edge element `element`, by key id, through
+    /// its end tag
+    fn data_children(&mut self, element: &str) -> Result<HashMap<String, String>, GraphMlError> {
+        let mut data = HashMap::new();
+        while !self.eat_end(element)? {
+            let (name, attributes, open) = self.start()?;
+            match name.as_str() {
+                "graph" => return Err(self.error("nested graphs are not supported")),
+                "port" => return Err(self.error("ports are not supported")),
+                "data" => {
+                    let key = attribute(&attributes, "key")
+                        .ok_or_else(|| self.error("data without a key"))?
+                        .to_string();
+                    let value = if open {
+                        self.text_content(&name)?
+                    } else {
+                        String::new()
+                    };
+                    data.insert(key, value);
+                }
+                _ if open => self.skip(&name)?,
+                _ => {}
+            }
+        }
+        Ok(data)
+    }
+
+    fn edge(&mut self, attributes: &[(String, String)], open: bool) -> Result<(), GraphMlError> {
+        let endpoint = |parser: &Self, name: &str| {
+            attribute(attributes, name)
+                .map(str::to_string)
+                .ok_or_else(|| parser.error(&format!("edge without a {}", name)))
+        };
+        let source = endpoint(self, "source")?;
+        let target = endpoint(self, "target")?;
+        let directed = match attribute(attributes, "directed") {
+            Some("true") => true,
+            Some("false") => false,
+            Some(_) => return Err(self.error("edge 'directed' must be true or false")),
+            None => self.directed,
+        };
+        let position = self.next - 1;
+        let data = if open {
+            self.data_children("edge")?
+        } else {
+            HashMap::new()
+        };
+        self.edges.push(EdgeElement {
+            position,
+            source,
+            target,
+            directed,
+            data,
+        });
+        Ok(())
+    }
+
+    fn graph(&mut self, attributes: &[(String, String)], open: bool) -> Result<(), GraphMlError> {
+        self.directed = match attribute(attributes, "edgedefault") {
+            Some("directed") | None => true,
+            Some("undirected") => false,
+            Some(_) => return Err(self.error("edgedefault must be directed or undirected")),
+        };
+        if !open {
+            return Ok(());
+        }
+        while !self.eat_end("graph")? {
+            let (name, attributes, open) = self.start()?;
+            match name.as_str() {
+                "node" => self.node(&attributes, open)?,
+                "edge" => self.edge(&attributes, open)?,
+                "hyperedge" => return Err(self.error("hyperedges are not supported")),
+                _ if open => self.skip(&name)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Data of one element as (attribute name, value), with the defaults of the
+/// keys it lacks, leaving out the key `skip`
+fn named_data<'a>(
+    keys: &'a HashMap<String, DataKey>,
+    data: &'a HashMap<String, String>,
+    skip: Option<&str>,
+) -> Vec<(&'a str, &'a str)> {
+    let name = |id: &'a str| {
+        keys.get(id)
+            .and_then(|key| key.name.as_deref())
+            .unwrap_or(id)
+    };
+    let mut named: Vec<(&str, &str)> = data
+        .iter()
+        .filter(|(id, _)| Some(id.as_str()) != skip)
+        .map(|(id, value)| (name(id), value.as_str()))
+        .collect();
+    for (id, key) in keys {
+        if let Some(default) = &key.default {
+            if !data.contains_key(id) && Some(id.as_str()) != skip {
+                named.push((name(id), default));
+            }
+        }
+    }
+    named
+}
+
+fn attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(attribute, _)| attribute == name)
+        .map(|(_, value)| value.as_str())
+}
+
+impl GraphMlGraph {
+    /// Parse a GraphML document holding one graph. Keys, nodes, edges and
+    /// their data are read; other elements, such as yEd's graphics, are
+    /// skipped.
+    /// Edges may override the graph's `edgedefault` with `directed`. Nested
+    /// graphs, ports and hyperedges are rejected.
+    pub fn parse(source: &str) -> Result<GraphMlGraph, GraphMlError> {
+        let lexer = XmlLexer {
+            chars: source.chars().collect(),
+            next: 0,
+            line: 1,
+            column: 1,
+        };
+        let end = source
+            .lines()
+            .enumerate()
+            .last()
+            .map_or((1, 1), |(number, line)| {
+                (number + 1, line.chars().count() + 1)
+            });
+        let mut parser = GraphMlParser {
+            tokens: lexer.tokenize()?,
+            next: 0,
+            end,
+            node_keys: HashMap::new(),
+            edge_keys: HashMap::new(),
+            directed: true,
+            ids: Vec::new(),
+            indices: HashMap::new(),
+            data: Vec::new(),
+            edges: Vec::new(),
+        };
+
+        let (root, _, open) = parser.start()?;
+        if root != "graphml" {
+            parser.next -= 1;
+            return Err(parser.error("expected a 'graphml' element"));
+        }
+        let mut found_graph = false;
+        if open {
+            while !parser.eat_end("graphml")? {
+                let (name, attributes, open) = parser.start()?;
+                match name.as_str() {
+                    "key" => parser.key(&attributes, open)?,
+                    "graph" if found_graph => {
+                        parser.next -= 1;
+                        return Err(parser.error("only one graph per document is supported"));
+                    }
+                    "graph" => {
+                        parser.graph(&attributes, open)?;
+                        found_graph = true;
+                    }
+                    _ if open => parser.skip(&name)?,
+                    _ => {}
+                }
+            }
+        }
+        if parser.peek().is_some() {
+            return Err(parser.error("unexpected input after the document"));
+        }
+        if !found_graph {
+            return Err(parser.error("expected a 'graph' element"));
+        }
+
+        // The key holding node values: named `value`, else `label`
+        let value_key = ["value", "label"].iter().find_map(|wanted| {
+            parser
+                .node_keys
+                .iter()
+                .find(|(_, key)| key.name.as_deref() == Some(*wanted))
+        });
+        let mut nodes: Vec<Node<String>> = parser
+            .ids
+            .iter()
+            .zip(&parser.data)
+            .map(|(id, data)| {
+                let value = value_key
+                    .and_then(|(key_id, key)| data.get(key_id).or(key.default.as_ref()).cloned());
+                Node::new(value.unwrap_or_else(|| id.clone()), Vec::new())
+            })
+            .collect();
+        let mut attributes = GraphAttributes::new();
+        let value_id = value_key.map(|(id, _)| id.as_str());
+        for (index, data) in parser.data.iter().enumerate() {
+            for (name, value) in named_data(&parser.node_keys, data, value_id) {
+                attributes.set_node_attr(NodeId::new(index), name, value);
+            }
+        }
+        for edge in &parser.edges {
+            let index = |id: &str| {
+                parser.indices.get(id).copied().ok_or_else(|| {
+                    let (line, column, _) = parser.tokens[edge.position];
+                    GraphMlError {
+                        line,
+                        column,
+                        message: format!("edge to undeclared node '{}'", id),
+                    }
+                })
+            };
+            let (from, to) = (index(&edge.source)?, index(&edge.target)?);
+            nodes[from].neighbors.push(to);
+            if !edge.directed && from != to {
+                nodes[to].neighbors.push(from);
+            }
+            let (a, b) = (NodeId::new(from), NodeId::new(to));
+            for (name, value) in named_data(&parser.edge_keys, &edge.data, None) {
+                attributes.set_edge_attr(a, b, name, value);
+                if !edge.directed {
+                    attributes.set_edge_attr(b, a, name, value);
+                }
+            }
+        }
+        Ok(GraphMlGraph {
+            graph: Graph { nodes },
+            ids: parser.ids,
+            directed: parser.directed,
+            attributes,
+        })
+    }
+}
+
+impl Graph<String> {
+    /// Parse GraphML into a graph valued by node data; see `GraphMlGraph::parse`
+    pub fn from_graphml(source: &str) -> Result<Graph<String>, GraphMlError> {
+        GraphMlGraph::parse(source).map(|graphml| graphml.graph)
+    }
+}
@@ -0,0 +1,236 @@
+use crate::error::ParseError;
+use crate::graph::{Graph, Node, NodeId};
+use crate::implicit::ImplicitGraph;
+
+/// A cell of a `Grid` as (row, column), row 0 at the top
+pub type Cell = (usize, usize);
+
+/// Which cells count as adjacent on a `Grid`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Up, left, right and down
+    Four,
+    /// Also the four diagonals, but never squeezing between two walls that
+    /// touch at a corner
+    Eight,
+}
+
+/// Rectangular maze or map of cells, each a wall or open with a cost to
+/// enter it, seen as a graph with an edge between adjacent open cells. BFS
+/// flood fill and shortest paths run over it directly; `to_graph` gives the
+/// core `Graph` for everything else.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    rows: usize,
+    cols: usize,
+    /// Entry cost by row-major cell index; None for a wall
+    costs: Vec<Option<u64>>,
+    connectivity: Connectivity,
+}
+
+impl Grid {
+    /// `rows` x `cols` open cells, each costing 1, with 4-connectivity
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Grid {
+            rows,
+            cols,
+            costs: vec![Some(1); rows * cols],
+            connectivity: Connectivity::Four,
+        }
+    }
+
+    /// Parse a picture of the grid, one line per row: `#` is a wall, `.` or a
+    /// space an open cell costing 1, and a digit 1-9 an open cell with that
+    /// cost. Lines shorter than the longest are padded with walls.
+    pub fn parse(text: &str) -> Result<Grid, ParseError> {
+        // Each line with its byte offset in `text`, so error positions stay
+        // right whether lines end in LF or CRLF
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for raw in text.split_inclusive('\n') {
+            let line = raw
+                .strip_suffix("\r\n")
+                .or_else(|| raw.strip_suffix('\n'))
+                .unwrap_or(raw);
+            lines.push((offset, line));
+            offset += raw.len();
+        }
+        let cols = lines
+            .iter()
+            .map(|(_, line)| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut grid = Grid::new(lines.len(), cols);
+        for (row, &(offset, line)) in lines.iter().enumerate() {
+            for (col, (position, c)) in line.char_indices().enumerate() {
+                let cost = match c {
+                    '#' => None,
+                    '.' | ' ' => Some(1),
+                    '1'..='9' => c.to_digit(10).map(u64::from),
+                    _ => {
+                        return Err(ParseError {
+                            position: offset + position,
+                            message: format!("unexpected '{}' in grid", c),
+                        })
+                    }
+                };
+                grid.costs[row * cols + col] = cost;
+            }
+            for col in line.chars().count()..cols {
+                grid.costs[row * cols + col] = None;
+            }
+        }
+        Ok(grid)
+    }
+
+    /// Use `connectivity` for neighbors and paths
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Whether `cell` is inside the grid and not a wall
+    pub fn is_open(&self, cell: Cell) -> bool {
+        self.cost(cell).is_some()
+    }
+
+    /// Cost of entering `cell`; None for walls and cells outside the grid
+    pub fn cost(&self, cell: Cell) -> Option<u64> {
+        let (row, col) = cell;
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        self.costs[row * self.cols + col]
+    }
+
+    /// Make `cell` a wall, or open it with entry cost `cost`. Panics if the
+    /// cell is outside the grid.
+    pub fn set_cost(&mut self, cell: Cell, cost: Option<u64>) {
+        let (row, col) = cell;
+        assert!(row < self.rows && col < self.cols, "cell outside the grid");
+        self.costs[row * self.cols + col] = cost;
+    }
+
+    /// Open cells adjacent to `cell`, row by row from the top left
+    pub fn neighbors(&self, cell: Cell) -> impl Iterator<Item = Cell> + '_ {
+        let (row, col) = cell;
+        let open = self.is_open(cell);
+        let diagonal = self.connectivity == Connectivity::Eight;
+        [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ]
+        .into_iter()
+        .filter(move |&(dr, dc)| (dr == 0 || dc == 0) || diagonal)
+        .filter_map(move |(dr, dc)| {
+            let next = (row.checked_add_signed(dr)?, col.checked_add_signed(dc)?);
+            // A diagonal step needs one of the two cells beside it to be open
+            let squeezed =
+                dr != 0 && dc != 0 && !self.is_open((next.0, col)) && !self.is_open((row, next.1));
+            (open && self.is_open(next) && !squeezed).then_some(next)
+        })
+    }
+
+    /// Open cells reachable from `start`, in BFS order from it; empty if
+    /// `start` is a wall
+    pub fn flood_fill(&self, start: Cell) -> Vec<Cell> {
+        if !self.is_open(start) {
+            return Vec::new();
+        }
+        ImplicitGraph::new(|&cell: &Cell| self.neighbors(cell))
+            .bfs(start)
+            .collect()
+    }
+
+    /// Path with the fewest steps from `start` to `goal`, both included,
+    /// ignoring cell costs; None if either is a wall or `goal` is unreachable
+    pub fn shortest_path(&self, start: Cell, goal: Cell) -> Option<Vec<Cell>> {
+        if !self.is_open(start) {
+            return None;
+        }
+        let graph = ImplicitGraph::new(|&cell: &Cell| self.neighbors(cell));
+        let mut bfs = graph.bfs(start);
+        bfs.by_ref().find(|&cell| cell == goal)?;
+        bfs.path_to(&goal)
+    }
+
+    /// Cheapest path from `start` to `goal` by A*, each step costing the cell
+    /// it enters, as (total cost, cells); None if either is a wall or `goal`
+    /// is unreachable
+    pub fn cheapest_path(&self, start: Cell, goal: Cell) -> Option<(u64, Vec<Cell>)> {
+        if !self.is_open(start) || !self.is_open(goal) {
+            return None;
+        }
+        // Every remaining step costs at least the cheapest open cell
+        let cheapest = self.costs.iter().flatten().copied().min().unwrap_or(0);
+        let heuristic = |node: NodeId| {
+            let (row, col) = self.cell(node);
+            let (rows, cols) = (row.abs_diff(goal.0) as u64, col.abs_diff(goal.1) as u64);
+            let steps = match self.connectivity {
+                Connectivity::Four => rows + cols,
+                Connectivity::Eight => rows.max(cols),
+            };
+            steps * cheapest
+        };
+        let graph = self.to_graph();
+        let weight = |_, to: NodeId| self.cost(self.cell(to)).unwrap_or(u64::MAX);
+        let (cost, path) = graph.astar(self.node(start), self.node(goal), weight, heuristic)?;
+        Some((cost, path.into_iter().map(|node| self.cell(node)).collect()))
+    }
+
+    /// Node of `cell` in `to_graph`: row * cols + column
+    pub fn node(&self, cell: Cell) -> NodeId {
+        NodeId::new(cell.0 * self.cols + cell.1)
+    }
+
+    /// Cell of a `to_graph` node
+    pub fn cell(&self, node: NodeId) -> Cell {
+        (node.index() / self.cols, node.index() % self.cols)
+    }
+
+    /// One node per cell, numbered as `node` gives and valued by index, with
+    /// an edge each way between adjacent open cells. Walls are nodes without
+    /// edges, so indices stay row-major; weigh edges by
+    /// `grid.cost(grid.cell(to))` for the costs.
+    pub fn to_graph(&self) -> Graph {
+        let nodes = (0..self.rows * self.cols)
+            .map(|index| {
+                let cell = self.cell(NodeId::new(index));
+                let neighbors = self
+                    .neighbors(cell)
+                    .map(|next| self.node(next).index())
+                    .collect();
+                Node::new(index as i32, neighbors)
+            })
+            .collect();
+        Graph::from_nodes(nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_parse_errors_point_into_crlf_text() {
+        let text = "..#\r\n.x.\r\n";
+        let error = Grid::parse(text).unwrap_err();
+        assert_eq!(&text[error.position..error.position + 1], "x");
+        let grid = Grid::parse("..#\r\n.1.\r\n").unwrap();
+        assert_eq!((grid.rows(), grid.cols()), (2, 3));
+    }
+}
@@ -0,0 +1,261 @@
+/// LSB-first bit reader over a DEFLATE stream
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    buffer: u64,
+    available: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn bits(&mut self, count: u32) -> Result<u32, String> {
+        while self.available < count {
+            let byte = *self
+                .data
+                .get(self.position)
+                .ok_or("unexpected end of stream")?;
+            self.buffer |= u64::from(byte) << self.available;
+            self.position += 1;
+            self.available += 8;
+        }
+        let value = (self.buffer & ((1 << count) - 1)) as u32;
+        self.buffer >>= count;
+        self.available -= count;
+        Ok(value)
+    }
+
+    /// Drop the rest of the current byte, returning the next unread byte offset
+    fn align(&mut self) -> usize {
+        self.position -= (self.available / 8) as usize;
+        self.buffer = 0;
+        self.available = 0;
+        self.position
+    }
+}
+
+/// Canonical Huffman code given by per-symbol code lengths
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Huffman {
+        let mut counts = [0u16; 16];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+        let mut offsets = [0u16; 16];
+        for length in 1..15 {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Huffman { counts, symbols }
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, String> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for length in 1..16 {
+            code |= reader.bits(1)? as i32;
+            let count = i32::from(self.counts[length]);
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid Huffman code".to_string())
+    }
+}
+
+/// Decompress a raw DEFLATE stream, returning the data and the bytes consumed
+fn inflate(data: &[u8]) -> Result<(Vec<u8>, usize), String> {
+    const LENGTH_BASE: [u16; 29] = [
+        3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115,
+        131, 163, 195, 227, 258,
+    ];
+    const LENGTH_EXTRA: [u8; 29] = [
+        0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+    ];
+    const DISTANCE_BASE: [u16; 30] = [
+        1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+        2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+    ];
+    const DISTANCE_EXTRA: [u8; 30] = [
+        0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12,
+        13, 13,
+    ];
+    const CODE_LENGTH_ORDER: [usize; 19] = [
+        16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+    ];
+
+    let mut reader = BitReader {
+        data,
+        position: 0,
+        buffer: 0,
+        available: 0,
+    };
+    let mut out = Vec::new();
+    loop {
+        let last = reader.bits(1)? == 1;
+        let (literals, distances) = match reader.bits(2)? {
+            0 => {
+                let start = reader.align();
+                let header = data.get(start..start + 4).ok_or("truncated stored block")?;
+                let len = usize::from(u16::from_le_bytes([header[0], header[1]]));
+                if u16::from_le_bytes([header[2], header[3]]) != !(len as u16) {
+                    return Err("stored block length mismatch".to_string());
+                }
+                out.extend_from_slice(
+                    data.get(start + 4..start + 4 + len)
+                        .ok_or("truncated stored block")?,
+                );
+                reader.position = start + 4 + len;
+                if last {
+                    break;
+                }
+                continue;
+            }
+            1 => {
+                let mut lengths = [8u8; 288];
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+            }
+            2 => {
+                let literal_count = reader.bits(5)? as usize + 257;
+                let distance_count = reader.bits(5)? as usize + 1;
+                let code_length_count = reader.bits(4)? as usize + 4;
+                let mut code_lengths = [0u8; 19];
+                for &symbol in &CODE_LENGTH_ORDER[..code_length_count] {
+                    code_lengths[symbol] = reader.bits(3)? as u8;
+                }
+                let code_length_code = Huffman::new(&code_lengths);
+                let mut lengths = Vec::with_capacity(literal_count + distance_count);
+                while lengths.len() < literal_count + distance_count {
+                    let symbol = code_length_code.decode(&mut reader)?;
+                    let (value, repeat) = match symbol {
+                        0..=15 => (symbol as u8, 1),
+                        16 => (
+                            *lengths.last().ok_or("repeat with no previous length")?,
+                            3 + reader.bits(2)?,
+                        ),
+                        17 => (0, 3 + reader.bits(3)?),
+                        _ => (0, 11 + reader.bits(7)?),
+                    };
+                    lengths.extend(std::iter::repeat_n(value, repeat as usize));
+                }
+                if lengths.len() > literal_count + distance_count {
+                    return Err("code lengths overrun".to_string());
+                }
+                (
+                    Huffman::new(&lengths[..literal_count]),
+                    Huffman::new(&lengths[literal_count..]),
+                )
+            }
+            _ => return Err("invalid block type".to_string()),
+        };
+
+        loop {
+            let symbol = literals.decode(&mut reader)? as usize;
+            if symbol < 256 {
+                out.push(symbol as u8);
+                continue;
+            }
+            if symbol == 256 {
+                break;
+            }
+            let code = symbol - 257;
+            if code >= 29 {
+                return Err("invalid length code".to_string());
+            }
+            let length = usize::from(LENGTH_BASE[code])
+                + reader.bits(u32::from(LENGTH_EXTRA[code]))? as usize;
+            let code = distances.decode(&mut reader)? as usize;
+            if code >= 30 {
+                return Err("invalid distance code".to_string());
+            }
+            let distance = usize::from(DISTANCE_BASE[code])
+                + reader.bits(u32::from(DISTANCE_EXTRA[code]))? as usize;
+            if distance > out.len() {
+                return Err("distance before start of output".to_string());
+            }
+            let start = out.len() - distance;
+            for i in 0..length {
+                out.push(out[start + i]);
+            }
+        }
+        if last {
+            break;
+        }
+    }
+    Ok((out, reader.align()))
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (i, entry) in table.iter_mut().enumerate() {
+        let mut c = i as u32;
+        for _ in 0..8 {
+            c = if c & 1 == 1 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+        }
+        *entry = c;
+    }
+    !data.iter().fold(!0u32, |c, &byte| {
+        table[((c ^ u32::from(byte)) & 0xFF) as usize] ^ (c >> 8)
+    })
+}
+
+/// Decompress gzip data, including files made of several concatenated members
+pub(crate) fn gunzip(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        if rest.len() < 18 || rest[0] != 0x1F || rest[1] != 0x8B || rest[2] != 8 {
+            return Err("missing gzip header".to_string());
+        }
+        let flags = rest[3];
+        let mut position = 10;
+        if flags & 4 != 0 {
+            let extra = rest.get(position..position + 2).ok_or("truncated header")?;
+            position += 2 + usize::from(u16::from_le_bytes([extra[0], extra[1]]));
+        }
+        for flag in [8, 16] {
+            if flags & flag != 0 {
+                let end = rest
+                    .get(position..)
+                    .and_then(|r| r.iter().position(|&b| b == 0));
+                position += end.ok_or("truncated header")? + 1;
+            }
+        }
+        if flags & 2 != 0 {
+            position += 2;
+        }
+        let (member, used) = inflate(rest.get(position..).ok_or("truncated header")?)?;
+        let trailer = rest
+            .get(position + used..position + used + 8)
+            .ok_or("truncated trailer")?;
+        if u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]) != crc32(&member) {
+            return Err("CRC mismatch".to_string());
+        }
+        if u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]])
+            != member.len() as u32
+        {
+            return Err("length mismatch".to_string());
+        }
+        out.extend(member);
+        rest = &rest[position + used + 8..];
+    }
+    Ok(out)
+}
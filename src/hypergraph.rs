@@ -0,0 +1,100 @@
+use std::collections::BTreeSet;
+
+use crate::graph::{Graph, Node, NodeId};
+
+/// Hypergraph: valued nodes and hyperedges that each join a set of nodes
+#[derive(Debug, Clone, Default)]
+pub struct Hypergraph {
+    values: Vec<i32>,
+    edges: Vec<Vec<NodeId>>,
+}
+
+impl Hypergraph {
+    pub fn new(values: Vec<i32>) -> Self {
+        Self {
+            values,
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn add_node(&mut self, value: i32) -> NodeId {
+        self.values.push(value);
+        NodeId::new(self.values.len() - 1)
+    }
+
+    /// Add a hyperedge over `members` (deduplicated, sorted) and return its index
+    pub fn add_edge(&mut self, members: &[NodeId]) -> usize {
+        let mut members = members.to_vec();
+        members.sort_unstable();
+        members.dedup();
+        assert!(
+            members.iter().all(|v| v.index() < self.values.len()),
+            "hyperedge member out of range"
+        );
+        self.edges.push(members);
+        self.edges.len() - 1
+    }
+
+    pub fn values(&self) -> &[i32] {
+        &self.values
+    }
+
+    /// Member lists of the hyperedges, each sorted
+    pub fn edges(&self) -> &[Vec<NodeId>] {
+        &self.edges
+    }
+
+    /// Indices of the hyperedges containing each node
+    pub fn incidence(&self) -> Vec<Vec<usize>> {
+        let mut incidence = vec![Vec::new(); self.values.len()];
+        for (e, members) in self.edges.iter().enumerate() {
+            for v in members {
+                incidence[v.index()].push(e);
+            }
+        }
+        incidence
+    }
+
+    /// Graph joining every two nodes that share a hyperedge, with edges in both
+    /// directions and parallel edges collapsed. Co-membership in several
+    /// hyperedges is lost; `star_expansion` keeps it.
+    pub fn clique_expansion(&self) -> Graph {
+        let mut neighbors: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); self.values.len()];
+        for members in &self.edges {
+            for &a in members {
+                neighbors[a.index()].extend(members.iter().filter(|&&b| b != a).map(|b| b.index()));
+            }
+        }
+        let nodes = self
+            .values
+            .iter()
+            .zip(neighbors)
+            .map(|(&value, neighbors)| Node {
+                value,
+                neighbors: neighbors.into_iter().collect(),
+            })
+            .collect();
+        Graph { nodes }
+    }
+
+    /// Bipartite incidence graph: nodes 0..n are the hypergraph's nodes and node
+    /// n + e stands for hyperedge e (its value is its index), with edges in
+    /// both directions between each hyperedge and its members
+    pub fn star_expansion(&self) -> Graph {
+        let n = self.values.len();
+        let mut nodes: Vec<Node> = self
+            .values
+            .iter()
+            .zip(self.incidence())
+            .map(|(&value, edges)| Node {
+                value,
+                neighbors: edges.into_iter().map(|e| n + e).collect(),
+            })
+            .collect();
+        nodes.extend(self.edges.iter().enumerate().map(|(e, members)| Node {
+            value: (n + e) as i32,
+            neighbors: members.iter().map(|v| v.index()).collect(),
+        }));
+        Graph { nodes }
+    }
+}
@@ -0,0 +1,663 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::traversal::KeyOrder;
+
+/// A graph given by a successor function instead of stored nodes, for state
+/// spaces generated on the fly (puzzle positions, game trees). States are
+/// identified by value, so traversals track visited states in a hash map
+/// rather than by index, and can rebuild the path to any state they reached.
+/// Such spaces are often infinite, so every traversal can be bounded with
+/// `max_nodes` and `max_depth`, and best-first also with `fuel`.
+pub struct ImplicitGraph<N, F> {
+    successors: F,
+    states: PhantomData<fn(&N)>,
+}
+
+impl<N, F, I> ImplicitGraph<N, F>
+where
+    N: Hash + Eq + Clone,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    /// Graph whose edges run from each state to `successors(state)`, in order
+    pub fn new(successors: F) -> Self {
+        ImplicitGraph {
+            successors,
+            states: PhantomData,
+        }
+    }
+
+    pub fn successors(&self, state: &N) -> I {
+        (self.successors)(state)
+    }
+
+    /// Breadth-first traversal from `start`, in the order `BfsIterator` visits
+    pub fn bfs(&self, start: N) -> ImplicitBfs<'_, N, F> {
+        ImplicitBfs {
+            graph: self,
+            queue: VecDeque::from([(start, NO_PARENT, 0)]),
+            discovered: Discovered::default(),
+            depth: 0,
+            max_depth: None,
+            max_nodes: None,
+        }
+    }
+
+    /// Depth-first traversal from `start`, in the order `DfsIterator` visits
+    pub fn dfs(&self, start: N) -> ImplicitDfs<'_, N, F> {
+        ImplicitDfs {
+            graph: self,
+            stack: vec![(start, NO_PARENT, 0)],
+            discovered: Discovered::default(),
+            depth: 0,
+            max_depth: None,
+            max_nodes: None,
+        }
+    }
+
+    /// Best-first traversal from `start`: always yields the frontier state
+    /// whose key comes first in `order`, ties going to the state pushed first
+    pub fn best_first<K, P>(
+        &self,
+        start: N,
+        key: P,
+        order: KeyOrder,
+    ) -> ImplicitBestFirst<'_, N, F, K, P>
+    where
+        K: Ord,
+        P: Fn(&N) -> K,
+    {
+        let mut best_first = ImplicitBestFirst {
+            graph: self,
+            heap: BinaryHeap::new(),
+            pushed: 0,
+            key,
+            order,
+            discovered: Discovered::default(),
+            depth: 0,
+            max_depth: None,
+            max_nodes: None,
+            fuel: None,
+        };
+        best_first.push(start, NO_PARENT, 0);
+        best_first
+    }
+
+    /// Beam search from `start`: best-first search that keeps only the
+    /// `width` best states of each level. The start is level 0; each next
+    /// level is the unvisited successors of the level's states, ranked by
+    /// key in `order` (ties to the state reached first) and cut to `width`.
+    /// States are yielded level by level, best first within each. Width 1
+    /// is greedy hill climbing, and a width above any level's size is a BFS
+    /// that yields each level in key order. Only the successors of `width`
+    /// states are held at a time, besides the record of states yielded.
+    /// Panics if `width` is 0.
+    pub fn beam_search<K, P>(
+        &self,
+        start: N,
+        width: usize,
+        key: P,
+        order: KeyOrder,
+    ) -> BeamSearchIterator<'_, N, F, K, P>
+    where
+        K: Ord,
+        P: Fn(&N) -> K,
+    {
+        assert!(width > 0, "beam width must be positive");
+        BeamSearchIterator {
+            graph: self,
+            width,
+            key,
+            order,
+            beam: VecDeque::from([(start, NO_PARENT)]),
+            candidates: Vec::new(),
+            candidate_states: HashSet::new(),
+            discovered: Discovered::default(),
+            depth: 0,
+            max_depth: None,
+            max_nodes: None,
+            fuel: None,
+        }
+    }
+}
+
+/// Parent rank of a start state
+const NO_PARENT: usize = usize::MAX;
+
+/// Use up one unit of an optional budget
+fn spend(fuel: &mut Option<usize>) {
+    if let Some(units) = fuel {
+        *units -= 1;
+    }
+}
+
+/// Yielded states in order, each with the rank of the state it was reached from
+struct Discovered<N> {
+    states: Vec<N>,
+    ranks: HashMap<N, usize>,
+    parents: Vec<usize>,
+}
+
+impl<N> Default for Discovered<N> {
+    fn default() -> Self {
+        Discovered {
+            states: Vec::new(),
+            ranks: HashMap::new(),
+            parents: Vec::new(),
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone> Discovered<N> {
+    /// Record `state` as yielded, unless it already was
+    fn insert(&mut self, state: &N, parent: usize) -> bool {
+        if self.ranks.contains_key(state) {
+            return false;
+        }
+        self.ranks.insert(state.clone(), self.states.len());
+        self.states.push(state.clone());
+        self.parents.push(parent);
+        true
+    }
+
+    fn contains(&self, state: &N) -> bool {
+        self.ranks.contains_key(state)
+    }
+
+    /// Whether `max_nodes` states have been yielded
+    fn full(&self, max_nodes: Option<usize>) -> bool {
+        max_nodes.is_some_and(|max_nodes| self.states.len() >= max_nodes)
+    }
+
+    fn parent_of_last(&self) -> Option<&N> {
+        let &parent = self.parents.last()?;
+        self.states.get(parent)
+    }
+
+    fn path_to(&self, state: &N) -> Option<Vec<N>> {
+        let mut rank = *self.ranks.get(state)?;
+        let mut path = vec![self.states[rank].clone()];
+        while self.parents[rank] != NO_PARENT {
+            rank = self.parents[rank];
+            path.push(self.states[rank].clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first traversal of an `ImplicitGraph`, yielding states
+pub struct ImplicitBfs<'g, N, F> {
+    graph: &'g ImplicitGraph<N, F>,
+    queue: VecDeque<(N, usize, usize)>, // (state, parent rank, depth)
+    discovered: Discovered<N>,
+    depth: usize,
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+}
+
+impl<N, F, I> ImplicitBfs<'_, N, F>
+where
+    N: Hash + Eq + Clone,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    /// Stop `max_depth` steps from the start: deeper states are never yielded
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// End after yielding `max_nodes` states
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Steps from the start to the state last yielded
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The state the last yielded one was reached from, or None for the start
+    pub fn parent(&self) -> Option<&N> {
+        self.discovered.parent_of_last()
+    }
+
+    /// Whether `state` has been yielded
+    pub fn visited(&self, state: &N) -> bool {
+        self.discovered.contains(state)
+    }
+
+    /// States from the start to an already yielded `state`, both included;
+    /// for BFS this is a shortest path
+    pub fn path_to(&self, state: &N) -> Option<Vec<N>> {
+        self.discovered.path_to(state)
+    }
+}
+
+impl<N, F, I> Iterator for ImplicitBfs<'_, N, F>
+where
+    N: Hash + Eq + Clone,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        if self.discovered.full(self.max_nodes) {
+            return None;
+        }
+        while let Some((state, parent, depth)) = self.queue.pop_front() {
+            // If the state has already been visited, skip it
+            if !self.discovered.insert(&state, parent) {
+                continue;
+            }
+
+            // Add all unvisited successors to the queue, unless they would be too deep
+            if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                let rank = self.discovered.states.len() - 1;
+                for successor in self.graph.successors(&state) {
+                    if !self.discovered.contains(&successor) {
+                        self.queue.push_back((successor, rank, depth + 1));
+                    }
+                }
+            }
+
+            self.depth = depth;
+            return Some(state);
+        }
+        None
+    }
+}
+
+/// Depth-first traversal of an `ImplicitGraph`, yielding states
+pub struct ImplicitDfs<'g, N, F> {
+    graph: &'g ImplicitGraph<N, F>,
+    stack: Vec<(N, usize, usize)>, // (state, parent rank, depth)
+    discovered: Discovered<N>,
+    depth: usize,
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+}
+
+impl<N, F, I> ImplicitDfs<'_, N, F>
+where
+    N: Hash + Eq + Clone,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    /// Never go more than `max_depth` steps deep along tree paths. As with
+    /// `DfsIterator::with_depth_limit`, a state first reached too deep is
+    /// not yielded even if a shallower path to it comes later.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// End after yielding `max_nodes` states
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Length of the tree path from the start to the state last yielded
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The state the last yielded one was reached from, or None for the start
+    pub fn parent(&self) -> Option<&N> {
+        self.discovered.parent_of_last()
+    }
+
+    /// Whether `state` has been yielded
+    pub fn visited(&self, state: &N) -> bool {
+        self.discovered.contains(state)
+    }
+
+    /// States along the DFS tree from the start to an already yielded `state`
+    pub fn path_to(&self, state: &N) -> Option<Vec<N>> {
+        self.discovered.path_to(state)
+    }
+}
+
+impl<N, F, I> Iterator for ImplicitDfs<'_, N, F>
+where
+    N: Hash + Eq + Clone,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        if self.discovered.full(self.max_nodes) {
+            return None;
+        }
+        while let Some((state, parent, depth)) = self.stack.pop() {
+            // If the state has already been visited, skip it
+            if !self.discovered.insert(&state, parent) {
+                continue;
+            }
+
+            // Add all unvisited successors to the stack (in reverse order)
+            if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                let rank = self.discovered.states.len() - 1;
+                let successors: Vec<N> = self.graph.successors(&state).into_iter().collect();
+                for successor in successors.into_iter().rev() {
+                    if !self.discovered.contains(&successor) {
+                        self.stack.push((successor, rank, depth + 1));
+                    }
+                }
+            }
+            self.depth = depth;
+            return Some(state);
+        }
+        None
+    }
+}
+
+/// Heap entry ordered by key in the traversal's direction, then push order
+struct Frontier<N, K> {
+    key: K,
+    order: KeyOrder,
+    pushed: usize,
+    state: N,
+    parent: usize,
+    depth: usize,
+}
+
+impl<N, K: Ord> Ord for Frontier<N, K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let by_key = match self.order {
+            KeyOrder::Ascending => self.key.cmp(&other.key),
+            KeyOrder::Descending => other.key.cmp(&self.key),
+        };
+        by_key.then(self.pushed.cmp(&other.pushed))
+    }
+}
+
+impl<N, K: Ord> PartialOrd for Frontier<N, K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, K: Ord> PartialEq for Frontier<N, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, K: Ord> Eq for Frontier<N, K> {}
+
+/// Best-first traversal of an `ImplicitGraph`, yielding states
+pub struct ImplicitBestFirst<'g, N, F, K, P> {
+    graph: &'g ImplicitGraph<N, F>,
+    heap: BinaryHeap<Reverse<Frontier<N, K>>>,
+    pushed: usize,
+    key: P,
+    order: KeyOrder,
+    discovered: Discovered<N>,
+    depth: usize,
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+    fuel: Option<usize>,
+}
+
+impl<N, F, I, K, P> ImplicitBestFirst<'_, N, F, K, P>
+where
+    N: Hash + Eq + Clone,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = N>,
+    K: Ord,
+    P: Fn(&N) -> K,
+{
+    fn push(&mut self, state: N, parent: usize, depth: usize) {
+        self.heap.push(Reverse(Frontier {
+            key: (self.key)(&state),
+            order: self.order,
+            pushed: self.pushed,
+            state,
+            parent,
+            depth,
+        }));
+        self.pushed += 1;
+    }
+
+    /// Never expand states `max_depth` steps from the start
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// End after yielding `max_nodes` states
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Spend at most `units` of work: one per state yielded and one per
+    /// successor examined, as in `NextSmallestIterator::fuel`
+    pub fn fuel(mut self, units: usize) -> Self {
+        self.fuel = Some(units);
+        self
+    }
+
+    /// Units of fuel left, if a budget was set
+    pub fn remaining_fuel(&self) -> Option<usize> {
+        self.fuel
+    }
+
+    /// Length of the tree path from the start to the state last yielded
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The state the last yielded one was reached from, or None for the start
+    pub fn parent(&self) -> Option<&N> {
+        self.discovered.parent_of_last()
+    }
+
+    /// Whether `state` has been yielded
+    pub fn visited(&self, state: &N) -> bool {
+        self.discovered.contains(state)
+    }
+
+    /// States along the traversal tree from the start to an already yielded `state`
+    pub fn path_to(&self, state: &N) -> Option<Vec<N>> {
+        self.discovered.path_to(state)
+    }
+}
+
+impl<N, F, I, K, P> Iterator for ImplicitBestFirst<'_, N, F, K, P>
+where
+    N: Hash + Eq + Clone,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = N>,
+    K: Ord,
+    P: Fn(&N) -> K,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        if self.fuel == Some(0) || self.discovered.full(self.max_nodes) {
+            return None;
+        }
+        while let Some(Reverse(entry)) = self.heap.pop() {
+            // Lazy deletion: skip entries for states popped before
+            if !self.discovered.insert(&entry.state, entry.parent) {
+                continue;
+            }
+            spend(&mut self.fuel);
+
+            if self
+                .max_depth
+                .is_none_or(|max_depth| entry.depth < max_depth)
+            {
+                let rank = self.discovered.states.len() - 1;
+                for successor in self.graph.successors(&entry.state) {
+                    if self.fuel == Some(0) {
+                        break;
+                    }
+                    spend(&mut self.fuel);
+                    if !self.discovered.contains(&successor) {
+                        self.push(successor, rank, entry.depth + 1);
+                    }
+                }
+            }
+            self.depth = entry.depth;
+            return Some(entry.state);
+        }
+        None
+    }
+}
+
+/// Beam search over an `ImplicitGraph`, yielding states; see
+/// `ImplicitGraph::beam_search`
+pub struct BeamSearchIterator<'g, N, F, K, P> {
+    graph: &'g ImplicitGraph<N, F>,
+    width: usize,
+    key: P,
+    order: KeyOrder,
+    /// States of the current level still to yield, best first, with the
+    /// rank of their parent
+    beam: VecDeque<(N, usize)>,
+    /// Successors found so far for the next level, in the order reached
+    candidates: Vec<Frontier<N, K>>,
+    candidate_states: HashSet<N>,
+    discovered: Discovered<N>,
+    /// Level of the state last yielded
+    depth: usize,
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+    fuel: Option<usize>,
+}
+
+impl<N, F, I, K, P> BeamSearchIterator<'_, N, F, K, P>
+where
+    N: Hash + Eq + Clone,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = N>,
+    K: Ord,
+    P: Fn(&N) -> K,
+{
+    /// Never expand states `max_depth` levels from the start
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// End after yielding `max_nodes` states
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Spend at most `units` of work: one per state yielded and one per
+    /// successor examined, as in `NextSmallestIterator::fuel`
+    pub fn fuel(mut self, units: usize) -> Self {
+        self.fuel = Some(units);
+        self
+    }
+
+    /// Units of fuel left, if a budget was set
+    pub fn remaining_fuel(&self) -> Option<usize> {
+        self.fuel
+    }
+
+    /// Level of the state last yielded: its steps from the start
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The state the last yielded one was reached from, or None for the start
+    pub fn parent(&self) -> Option<&N> {
+        self.discovered.parent_of_last()
+    }
+
+    /// Whether `state` has been yielded
+    pub fn visited(&self, state: &N) -> bool {
+        self.discovered.contains(state)
+    }
+
+    /// States along the search tree from the start to an already yielded `state`
+    pub fn path_to(&self, state: &N) -> Option<Vec<N>> {
+        self.discovered.path_to(state)
+    }
+
+    /// Replace the exhausted beam with the best `width` candidates
+    fn advance_level(&mut self) {
+        let mut candidates = std::mem::take(&mut self.candidates);
+        self.candidate_states.clear();
+        candidates.sort_unstable();
+        self.beam = candidates
+            .into_iter()
+            .take(self.width)
+            .map(|entry| (entry.state, entry.parent))
+            .collect();
+        self.depth += 1;
+    }
+}
+
+impl<N, F, I, K, P> Iterator for BeamSearchIterator<'_, N, F, K, P>
+where
+    N: Hash + Eq + Clone,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = N>,
+    K: Ord,
+    P: Fn(&N) -> K,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        if self.fuel == Some(0) || self.discovered.full(self.max_nodes) {
+            return None;
+        }
+        loop {
+            if self.beam.is_empty() {
+                if self.candidates.is_empty() {
+                    return None;
+                }
+                self.advance_level();
+            }
+            let (state, parent) = self.beam.pop_front().expect("the beam is not empty");
+            // A state can reach the next level's candidates before it is
+            // yielded on its own
+            if !self.discovered.insert(&state, parent) {
+                continue;
+            }
+            spend(&mut self.fuel);
+
+            if self
+                .max_depth
+                .is_none_or(|max_depth| self.depth < max_depth)
+            {
+                let rank = self.discovered.states.len() - 1;
+                for successor in self.graph.successors(&state) {
+                    if self.fuel == Some(0) {
+                        break;
+                    }
+                    spend(&mut self.fuel);
+                    if self.discovered.contains(&successor)
+                        || !self.candidate_states.insert(successor.clone())
+                    {
+                        continue;
+                    }
+                    self.candidates.push(Frontier {
+                        key: (self.key)(&successor),
+                        order: self.order,
+                        pushed: self.candidates.len(),
+                        state: successor,
+                        parent: rank,
+                        depth: self.depth + 1,
+                    });
+                }
+            }
+            return Some(state);
+        }
+    }
+}
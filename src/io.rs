@@ -0,0 +1,491 @@
+use std::collections::HashMap;
+
+use crate::error::LoadError;
+use crate::graph::{Graph, Node, NodeId};
+use crate::gzip::gunzip;
+use crate::paths::EdgeWeights;
+
+impl Graph {
+    /// Parse an edge list as `read_edge_list` does with the default options
+    /// (`source target [weight]` per line, `#` or `%` comments, ids compacted
+    /// to indices in order of first appearance and kept as values), optionally
+    /// adding every edge in both directions
+    pub fn from_edge_list(text: &str, undirected: bool) -> Result<Graph, LoadError> {
+        let options = EdgeListOptions {
+            undirected,
+            ..EdgeListOptions::default()
+        };
+        Graph::read_edge_list(text.as_bytes(), &options).map(|list| list.graph)
+    }
+}
+
+/// How `read_edge_list` maps the ids in an edge list to node indices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeListIds {
+    /// Any non-negative ids, given indices in order of first appearance
+    Compact,
+    /// Ids are the node indices; every index up to the largest id gets a node
+    ZeroBased,
+    /// Ids are the node indices plus one
+    OneBased,
+}
+
+/// Options for reading and writing `src dst [weight]` edge lists
+#[derive(Debug, Clone)]
+pub struct EdgeListOptions {
+    /// Lines starting with one of these (after leading whitespace) are skipped
+    pub comment_prefixes: Vec<String>,
+    pub ids: EdgeListIds,
+    /// Add every edge in both directions when reading, and write each pair once
+    pub undirected: bool,
+    /// Sort each neighbor list by index after reading, so traversal orders do
+    /// not depend on the order of lines in the file
+    pub sort_neighbors: bool,
+    /// Most nodes a `ZeroBased` or `OneBased` file may imply, so that one
+    /// stray large id cannot allocate billions of nodes; a larger id is a
+    /// parse error
+    pub max_nodes: usize,
+}
+
+impl Default for EdgeListOptions {
+    /// `#` and `%` comments, compact ids, directed edges, neighbors in file
+    /// order, and at most 2^27 nodes
+    fn default() -> Self {
+        EdgeListOptions {
+            comment_prefixes: vec!["#".to_string(), "%".to_string()],
+            ids: EdgeListIds::Compact,
+            undirected: false,
+            sort_neighbors: false,
+            max_nodes: 1 << 27,
+        }
+    }
+}
+
+/// A graph read by `read_edge_list`: `ids[index]` is the original id of each
+/// node, which is also its value, and `weights` holds the weight column
+#[derive(Debug, Clone)]
+pub struct EdgeList {
+    pub graph: Graph,
+    pub ids: Vec<u64>,
+    pub weights: EdgeWeights,
+}
+
+impl Graph {
+    /// Read an edge list with one `src dst [weight]` edge per line, fields
+    /// separated by tabs, commas or spaces, as in SNAP and most CSV/TSV graph
+    /// datasets. Weights are non-negative integers; edges without one weigh 1.
+    pub fn read_edge_list<R: std::io::BufRead>(
+        reader: R,
+        options: &EdgeListOptions,
+    ) -> Result<EdgeList, LoadError> {
+        let mut indices: HashMap<u64, usize> = HashMap::new();
+        let mut ids = Vec::new();
+        let mut edges = Vec::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            let comment = options
+                .comment_prefixes
+                .iter()
+                .any(|prefix| line.starts_with(prefix.as_str()));
+            if line.is_empty() || comment {
+                continue;
+            }
+            let error = |message: &str| LoadError::Parse {
+                line: number + 1,
+                message: message.to_string(),
+            };
+            let mut fields = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|field| !field.is_empty());
+            let mut endpoint = || -> Result<usize, LoadError> {
+                let id: u64 = fields
+                    .next()
+                    .ok_or_else(|| error("expected two node ids"))?
+                    .parse()
+                    .map_err(|_| error("node ids must be non-negative integers"))?;
+                i32::try_from(id).map_err(|_| error("node id does not fit in a node value"))?;
+                let index = match options.ids {
+                    EdgeListIds::Compact => {
+                        return Ok(*indices.entry(id).or_insert_with(|| {
+                            ids.push(id);
+                            ids.len() - 1
+                        }))
+                    }
+                    EdgeListIds::ZeroBased => id as usize,
+                    EdgeListIds::OneBased => match id {
+                        0 => return Err(error("node ids are 1-based")),
+                        id => id as usize - 1,
+                    },
+                };
+                if index >= options.max_nodes {
+                    return Err(error("node id exceeds the maximum node count"));
+                }
+                Ok(index)
+            };
+            let source = endpoint()?;
+            let target = endpoint()?;
+            let weight = match fields.next() {
+                Some(field) => Some(
+                    field
+                        .parse::<u64>()
+                        .map_err(|_| error("weights must be non-negative integers"))?,
+                ),
+                None => None,
+            };
+            if fields.next().is_some() {
+                return Err(error("expected at most three fields"));
+            }
+            edges.push((source, target, weight));
+        }
+
+        if options.ids != EdgeListIds::Compact {
+            let len = edges
+                .iter()
+                .map(|&(s, t, _)| s.max(t) + 1)
+                .max()
+                .unwrap_or(0);
+            let base = u64::from(options.ids == EdgeListIds::OneBased);
+            ids = (0..len as u64).map(|index| index + base).collect();
+        }
+        let mut graph = Graph {
+            nodes: ids
+                .iter()
+                .map(|&id| Node {
+                    value: id as i32,
+                    neighbors: Vec::new(),
+                })
+                .collect(),
+        };
+        let mut weights = EdgeWeights::default();
+        for (source, target, weight) in edges {
+            graph.nodes[source].neighbors.push(target);
+            if options.undirected && source != target {
+                graph.nodes[target].neighbors.push(source);
+            }
+            if let Some(weight) = weight {
+                weights.set(NodeId::new(source), NodeId::new(target), weight);
+                if options.undirected {
+                    weights.set(NodeId::new(target), NodeId::new(source), weight);
+                }
+            }
+        }
+        if options.sort_neighbors {
+            graph.sort_neighbors();
+        }
+        Ok(EdgeList {
+            graph,
+            ids,
+            weights,
+        })
+    }
+
+    /// Write one `src<TAB>dst[<TAB>weight]` line per edge, with node indices as
+    /// ids (plus one for `OneBased`). Undirected output lists each pair with
+    /// src <= dst only, so it suits graphs that store both directions.
+    pub fn write_edge_list<W: std::io::Write>(
+        &self,
+        out: W,
+        options: &EdgeListOptions,
+        weights: Option<&EdgeWeights>,
+    ) -> std::io::Result<()> {
+        use std::io::Write;
+        let mut out = std::io::BufWriter::new(out);
+        let base = usize::from(options.ids == EdgeListIds::OneBased);
+        for (from, node) in self.nodes.iter().enumerate() {
+            for &to in &node.neighbors {
+                if options.undirected && to < from {
+                    continue;
+                }
+                write!(out, "{}\t{}", from + base, to + base)?;
+                if let Some(weights) = weights {
+                    write!(out, "\t{}", weights.get(NodeId::new(from), NodeId::new(to)))?;
+                }
+                writeln!(out)?;
+            }
+        }
+        out.flush()
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: serde::de::DeserializeOwned> Graph<T> {
+    /// Parse the JSON layout of the `serde` feature (see the README), checking
+    /// every neighbor index
+    pub fn from_json(text: &str) -> Result<Graph<T>, LoadError> {
+        serde_json::from_str(text).map_err(|e| LoadError::Format(e.to_string()))
+    }
+}
+
+/// Read a file, transparently decompressing it if it starts with the gzip magic
+pub fn read_graph_file(path: &str) -> Result<Vec<u8>, LoadError> {
+    let data = std::fs::read(path)?;
+    if data.starts_with(&[0x1F, 0x8B]) {
+        gunzip(&data).map_err(LoadError::Gzip)
+    } else {
+        Ok(data)
+    }
+}
+
+/// A SNAP dataset with its sparse node ids relabeled to dense indices;
+/// `ids[index]` is the original id and each node's value is its index
+#[derive(Debug, Clone)]
+pub struct SnapDataset {
+    pub graph: Graph,
+    pub ids: Vec<u64>,
+}
+
+impl SnapDataset {
+    /// Parse SNAP edge-list bytes: `#` comment lines and whitespace-separated
+    /// id pairs. Undirected datasets get both directions, deduplicated, since
+    /// SNAP files list undirected edges either once or twice.
+    pub fn parse(data: &[u8], undirected: bool) -> Result<SnapDataset, LoadError> {
+        let mut indices: HashMap<u64, usize> = HashMap::new();
+        let mut ids = Vec::new();
+        let mut adjacency: Vec<Vec<usize>> = Vec::new();
+        for (number, line) in data.split(|&b| b == b'\n').enumerate() {
+            let error = |message: &str| LoadError::Parse {
+                line: number + 1,
+                message: message.to_string(),
+            };
+            let mut fields = line
+                .split(|b| b.is_ascii_whitespace())
+                .filter(|field| !field.is_empty());
+            let Some(first) = fields.next() else {
+                continue;
+            };
+            if first[0] == b'#' {
+                continue;
+            }
+            let mut endpoint = |field: Option<&[u8]>| -> Result<usize, LoadError> {
+                let field = field.ok_or_else(|| error("expected two node ids"))?;
+                let mut id: u64 = 0;
+                for &digit in field {
+                    if !digit.is_ascii_digit() {
+                        return Err(error("node ids must be non-negative integers"));
+                    }
+                    id = id
+                        .checked_mul(10)
+                        .and_then(|id| id.checked_add(u64::from(digit - b'0')))
+                        .ok_or_else(|| error("node id out of range"))?;
+                }
+                Ok(*indices.entry(id).or_insert_with(|| {
+                    ids.push(id);
+                    adjacency.push(Vec::new());
+                    ids.len() - 1
+                }))
+            };
+            let source = endpoint(Some(first))?;
+            let target = endpoint(fields.next())?;
+            adjacency[source].push(target);
+            if undirected {
+                adjacency[target].push(source);
+            }
+        }
+        if undirected {
+            for neighbors in &mut adjacency {
+                neighbors.sort_unstable();
+                neighbors.dedup();
+            }
+        }
+        let nodes = adjacency
+            .into_iter()
+            .enumerate()
+            .map(|(index, neighbors)| Node {
+                value: index as i32,
+                neighbors,
+            })
+            .collect();
+        Ok(SnapDataset {
+            graph: Graph { nodes },
+            ids,
+        })
+    }
+
+    /// Load a SNAP dataset from a plain or gzip-compressed file
+    pub fn load(path: &str, undirected: bool) -> Result<SnapDataset, LoadError> {
+        SnapDataset::parse(&read_graph_file(path)?, undirected)
+    }
+}
+
+/// Binary graph layout: the magic bytes, a u32 format version, node count n and
+/// edge count m as u64, n i32 node values, n + 1 u64 offsets into the target
+/// array, then m u32 targets (all little endian)
+pub const BINARY_MAGIC: &[u8; 8] = b"GTGRAPH\0";
+
+/// Version written by `write_binary`; `read_binary` rejects any other
+pub const BINARY_VERSION: u32 = 1;
+
+impl Graph {
+    /// Write the graph in the binary layout read by `read_binary`
+    pub fn write_binary<W: std::io::Write>(&self, out: W) -> std::io::Result<()> {
+        use std::io::Write;
+        if self.nodes.len() > u32::MAX as usize {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "binary graphs hold at most 2^32 - 1 nodes",
+            ));
+        }
+        let mut out = std::io::BufWriter::new(out);
+        let edges: usize = self.nodes.iter().map(|node| node.neighbors.len()).sum();
+        out.write_all(BINARY_MAGIC)?;
+        out.write_all(&BINARY_VERSION.to_le_bytes())?;
+        out.write_all(&(self.nodes.len() as u64).to_le_bytes())?;
+        out.write_all(&(edges as u64).to_le_bytes())?;
+        for node in &self.nodes {
+            out.write_all(&node.value.to_le_bytes())?;
+        }
+        let mut offset = 0u64;
+        out.write_all(&offset.to_le_bytes())?;
+        for node in &self.nodes {
+            offset += node.neighbors.len() as u64;
+            out.write_all(&offset.to_le_bytes())?;
+        }
+        for node in &self.nodes {
+            for &w in &node.neighbors {
+                out.write_all(&(w as u32).to_le_bytes())?;
+            }
+        }
+        out.flush()
+    }
+
+    /// Read a graph written by `write_binary`, checking the magic bytes, the
+    /// version, the section lengths and every offset and target
+    pub fn read_binary<R: std::io::Read>(mut reader: R) -> Result<Graph, LoadError> {
+        use std::io::Read;
+        let mut header = [0u8; 28];
+        reader.read_exact(&mut header)?;
+        if &header[..8] != BINARY_MAGIC {
+            return Err(LoadError::Format("not a binary graph file".to_string()));
+        }
+        let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if version != BINARY_VERSION {
+            return Err(LoadError::Format(format!(
+                "unsupported binary graph version {}",
+                version
+            )));
+        }
+        let word = |i: usize| u64::from_le_bytes(header[i..i + 8].try_into().unwrap());
+        let (nodes, edges) = (word(12), word(20));
+        if nodes > u64::from(u32::MAX) || edges > u64::MAX / 8 {
+            return Err(LoadError::Format("binary graph is too large".to_string()));
+        }
+
+        // Grow the buffer as data arrives rather than trusting the header's counts
+        let mut section = |len: u64| -> Result<Vec<u8>, LoadError> {
+            let mut bytes = Vec::new();
+            reader.by_ref().take(len).read_to_end(&mut bytes)?;
+            if bytes.len() as u64 != len {
+                return Err(LoadError::Format("binary graph is truncated".to_string()));
+            }
+            Ok(bytes)
+        };
+        let values = section(4 * nodes)?;
+        let offsets = section(8 * (nodes + 1))?;
+        let targets = section(4 * edges)?;
+
+        let offset = |i: usize| u64::from_le_bytes(offsets[8 * i..8 * i + 8].try_into().unwrap());
+        let mut graph = Graph::new();
+        for (index, value) in values.chunks_exact(4).enumerate() {
+            let (start, end) = (offset(index), offset(index + 1));
+            if start > end || end > edges || (index == 0 && start != 0) {
+                return Err(LoadError::Format(format!(
+                    "corrupt offsets for node {}",
+                    index
+                )));
+            }
+            let neighbors = targets[4 * start as usize..4 * end as usize]
+                .chunks_exact(4)
+                .map(|target| u32::from_le_bytes(target.try_into().unwrap()) as usize)
+                .collect();
+            graph.nodes.push(Node {
+                value: i32::from_le_bytes(value.try_into().unwrap()),
+                neighbors,
+            });
+        }
+        if offset(nodes as usize) != edges {
+            return Err(LoadError::Format(
+                "offsets do not cover every edge".to_string(),
+            ));
+        }
+        graph
+            .validate()
+            .map_err(|error| LoadError::Format(error.to_string()))?;
+        Ok(graph)
+    }
+}
+
+/// Edges between `bulk_from_edges` progress reports
+const BULK_PROGRESS_INTERVAL: usize = 1 << 20;
+
+/// Progress of a `bulk_from_edges_with_progress` load, reported every 2^20
+/// edges and at the end of each pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkProgress {
+    /// First pass: edges counted so far
+    Counting { edges: usize },
+    /// Second pass: edges placed so far, out of `total`
+    Filling { edges: usize, total: usize },
+}
+
+impl Graph {
+    /// Build a graph from `(from, to)` index pairs in two passes: the first
+    /// counts degrees, the second fills neighbor lists allocated at their final
+    /// size, so nothing is reallocated while loading. The iterator is cloned
+    /// and run twice, so it should be cheap to replay, such as one over parsed
+    /// pairs in memory; both runs must yield the same edges. Nodes are `0..=max index`, valued by index; neighbor
+    /// order is edge order, as adding the edges one by one would give.
+    pub fn bulk_from_edges<I>(edges: I) -> Graph
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+        I::IntoIter: Clone,
+    {
+        Graph::bulk_from_edges_with_progress(edges, |_| {})
+    }
+
+    /// Like `bulk_from_edges`, calling `progress` as the passes advance
+    pub fn bulk_from_edges_with_progress<I, F>(edges: I, mut progress: F) -> Graph
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+        I::IntoIter: Clone,
+        F: FnMut(BulkProgress),
+    {
+        let edges = edges.into_iter();
+        let mut degrees: Vec<usize> = Vec::new();
+        let mut total = 0;
+        for (from, to) in edges.clone() {
+            let len = from.max(to) + 1;
+            if degrees.len() < len {
+                degrees.resize(len, 0);
+            }
+            degrees[from] += 1;
+            total += 1;
+            if total % BULK_PROGRESS_INTERVAL == 0 {
+                progress(BulkProgress::Counting { edges: total });
+            }
+        }
+        progress(BulkProgress::Counting { edges: total });
+
+        let mut nodes: Vec<Node> = degrees
+            .iter()
+            .enumerate()
+            .map(|(index, &degree)| Node::new(index as i32, Vec::with_capacity(degree)))
+            .collect();
+        let mut placed = 0;
+        for (from, to) in edges.take(total) {
+            nodes[from].neighbors.push(to);
+            placed += 1;
+            if placed % BULK_PROGRESS_INTERVAL == 0 {
+                progress(BulkProgress::Filling {
+                    edges: placed,
+                    total,
+                });
+            }
+        }
+        progress(BulkProgress::Filling {
+            edges: placed,
+            total,
+        });
+        Graph { nodes }
+    }
+}
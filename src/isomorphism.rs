@@ -0,0 +1,530 @@
+use std::collections::HashSet;
+
+use crate::graph::{Graph, Node, NodeId};
+
+/// Whether a VF2 match must preserve non-edges too (isomorphism) or only the
+/// pattern's edges (subgraph monomorphism)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    Isomorphism,
+    Subgraph,
+}
+
+/// Deduplicated directed adjacency used by the matchers
+pub(crate) struct EdgeSets {
+    pub out: Vec<Vec<usize>>,
+    pub inc: Vec<Vec<usize>>,
+    pub edges: HashSet<(usize, usize)>,
+}
+
+impl EdgeSets {
+    pub(crate) fn new<T>(graph: &Graph<T>) -> Self {
+        let mut out = vec![Vec::new(); graph.nodes.len()];
+        let mut inc = vec![Vec::new(); graph.nodes.len()];
+        let mut edges = HashSet::new();
+        for (index, node) in graph.nodes.iter().enumerate() {
+            for &neighbor in &node.neighbors {
+                if edges.insert((index, neighbor)) {
+                    out[index].push(neighbor);
+                    inc[neighbor].push(index);
+                }
+            }
+        }
+        Self { out, inc, edges }
+    }
+
+    pub(crate) fn has(&self, from: usize, to: usize) -> bool {
+        self.edges.contains(&(from, to))
+    }
+}
+
+/// VF2-style state-space search mapping the nodes of `pattern` into `target`.
+/// Pattern nodes are matched in a connectivity-first order (as in VF2++) and
+/// candidates come from the images of already matched neighbors, which keeps
+/// the search tree small on sparse graphs.
+struct Vf2<'a> {
+    pub pattern: &'a Graph,
+    pub target: &'a Graph,
+    pub mode: MatchMode,
+    pub node_match: &'a dyn Fn(&Node, &Node) -> bool,
+    pub edge_match: &'a dyn Fn((usize, usize), (usize, usize)) -> bool,
+    pub pattern_edges: EdgeSets,
+    pub target_edges: EdgeSets,
+    pub order: Vec<usize>,
+    pub mapping: Vec<Option<usize>>, // pattern node -> target node
+    pub reverse: Vec<Option<usize>>, // target node -> pattern node
+}
+
+impl<'a> Vf2<'a> {
+    fn new(
+        pattern: &'a Graph,
+        target: &'a Graph,
+        mode: MatchMode,
+        node_match: &'a dyn Fn(&Node, &Node) -> bool,
+        edge_match: &'a dyn Fn((usize, usize), (usize, usize)) -> bool,
+    ) -> Self {
+        let pattern_edges = EdgeSets::new(pattern);
+        let target_edges = EdgeSets::new(target);
+        let order = Self::matching_order(&pattern_edges);
+        Self {
+            pattern,
+            target,
+            mode,
+            node_match,
+            edge_match,
+            pattern_edges,
+            target_edges,
+            order,
+            mapping: vec![None; pattern.nodes.len()],
+            reverse: vec![None; target.nodes.len()],
+        }
+    }
+
+    /// Repeatedly pick the node with the most links to already ordered nodes,
+    /// breaking ties by total degree
+    fn matching_order(edges: &EdgeSets) -> Vec<usize> {
+        let n = edges.out.len();
+        let degree = |index: usize| edges.out[index].len() + edges.inc[index].len();
+        let mut links = vec![0usize; n];
+        let mut ordered = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        for _ in 0..n {
+            let next = (0..n)
+                .filter(|&index| !ordered[index])
+                .max_by_key(|&index| (links[index], degree(index), std::cmp::Reverse(index)))
+                .unwrap();
+            ordered[next] = true;
+            order.push(next);
+            for &neighbor in edges.out[next].iter().chain(&edges.inc[next]) {
+                links[neighbor] += 1;
+            }
+        }
+        order
+    }
+
+    fn candidates(&self, p: usize) -> Vec<usize> {
+        let mapped_out = self.pattern_edges.out[p]
+            .iter()
+            .find_map(|&q| self.mapping[q]);
+        if let Some(image) = mapped_out {
+            return self.target_edges.inc[image].clone();
+        }
+        let mapped_in = self.pattern_edges.inc[p]
+            .iter()
+            .find_map(|&q| self.mapping[q]);
+        if let Some(image) = mapped_in {
+            return self.target_edges.out[image].clone();
+        }
+        (0..self.target.nodes.len()).collect()
+    }
+
+    fn feasible(&self, p: usize, t: usize) -> bool {
+        if self.reverse[t].is_some()
+            || !(self.node_match)(&self.pattern.nodes[p], &self.target.nodes[t])
+        {
+            return false;
+        }
+        let (pe, te) = (&self.pattern_edges, &self.target_edges);
+        let iso = self.mode == MatchMode::Isomorphism;
+        let degrees_ok = if iso {
+            pe.out[p].len() == te.out[t].len() && pe.inc[p].len() == te.inc[t].len()
+        } else {
+            pe.out[p].len() <= te.out[t].len() && pe.inc[p].len() <= te.inc[t].len()
+        };
+        if !degrees_ok {
+            return false;
+        }
+
+        // Self-loops
+        let (pattern_loop, target_loop) = (pe.has(p, p), te.has(t, t));
+        if (iso && pattern_loop != target_loop) || (pattern_loop && !target_loop) {
+            return false;
+        }
+        if pattern_loop && !(self.edge_match)((p, p), (t, t)) {
+            return false;
+        }
+
+        // Every edge to an already matched pattern node must exist in the target
+        let mut mapped_out = 0;
+        for &q in &pe.out[p] {
+            if let (true, Some(image)) = (q != p, self.mapping[q]) {
+                if !te.has(t, image) || !(self.edge_match)((p, q), (t, image)) {
+                    return false;
+                }
+                mapped_out += 1;
+            }
+        }
+        let mut mapped_in = 0;
+        for &q in &pe.inc[p] {
+            if let (true, Some(image)) = (q != p, self.mapping[q]) {
+                if !te.has(image, t) || !(self.edge_match)((q, p), (image, t)) {
+                    return false;
+                }
+                mapped_in += 1;
+            }
+        }
+
+        // For isomorphism the target may not have extra edges to matched nodes
+        if iso {
+            let count = |list: &[usize]| {
+                list.iter()
+                    .filter(|&&u| u != t && self.reverse[u].is_some())
+                    .count()
+            };
+            if count(&te.out[t]) != mapped_out || count(&te.inc[t]) != mapped_in {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Depth-first search over partial mappings; `visit` gets each complete
+    /// mapping and returns true to stop the search
+    fn search(&mut self, depth: usize, visit: &mut dyn FnMut(&[usize]) -> bool) -> bool {
+        if depth == self.order.len() {
+            let mapping: Vec<usize> = self.mapping.iter().map(|image| image.unwrap()).collect();
+            return visit(&mapping);
+        }
+        let p = self.order[depth];
+        for t in self.candidates(p) {
+            if !self.feasible(p, t) {
+                continue;
+            }
+            self.mapping[p] = Some(t);
+            self.reverse[t] = Some(p);
+            let stop = self.search(depth + 1, visit);
+            self.mapping[p] = None;
+            self.reverse[t] = None;
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Test whether two graphs are isomorphic, returning the node mapping from `g1`
+/// to `g2` when they are: entry i is the node of `g2` matched to node i of
+/// `g1`. Edges are compared as directed adjacency entries.
+pub fn is_isomorphic(g1: &Graph, g2: &Graph) -> Option<Vec<NodeId>> {
+    is_isomorphic_with(g1, g2, |_, _| true, |_, _| true)
+}
+
+/// Isomorphism test that also requires `node_match` on matched nodes and
+/// `edge_match` on matched edges, given as (from, to) pairs in each graph
+pub fn is_isomorphic_with<N, E>(
+    g1: &Graph,
+    g2: &Graph,
+    node_match: N,
+    edge_match: E,
+) -> Option<Vec<NodeId>>
+where
+    N: Fn(&Node, &Node) -> bool,
+    E: Fn((NodeId, NodeId), (NodeId, NodeId)) -> bool,
+{
+    if g1.nodes.len() != g2.nodes.len() {
+        return None;
+    }
+    let edge_match = by_ids(&edge_match);
+    let mut vf2 = Vf2::new(g1, g2, MatchMode::Isomorphism, &node_match, &edge_match);
+    if vf2.pattern_edges.edges.len() != vf2.target_edges.edges.len() {
+        return None;
+    }
+    let mut found = None;
+    vf2.search(0, &mut |mapping| {
+        found = Some(node_ids(mapping));
+        true
+    });
+    found
+}
+
+/// Enumerate embeddings of `pattern` into `target`: injective node mappings
+/// under which every pattern edge is a target edge. Each match lists the
+/// target node of every pattern node; at most `max_matches` are returned.
+pub fn find_subgraph_matches(
+    pattern: &Graph,
+    target: &Graph,
+    max_matches: usize,
+) -> Vec<Vec<NodeId>> {
+    find_subgraph_matches_with(pattern, target, max_matches, |_, _| true, |_, _| true)
+}
+
+/// Subgraph matching with node and edge compatibility predicates
+pub fn find_subgraph_matches_with<N, E>(
+    pattern: &Graph,
+    target: &Graph,
+    max_matches: usize,
+    node_match: N,
+    edge_match: E,
+) -> Vec<Vec<NodeId>>
+where
+    N: Fn(&Node, &Node) -> bool,
+    E: Fn((NodeId, NodeId), (NodeId, NodeId)) -> bool,
+{
+    let mut matches = Vec::new();
+    if max_matches == 0 || pattern.nodes.len() > target.nodes.len() {
+        return matches;
+    }
+    let edge_match = by_ids(&edge_match);
+    let mut vf2 = Vf2::new(
+        pattern,
+        target,
+        MatchMode::Subgraph,
+        &node_match,
+        &edge_match,
+    );
+    vf2.search(0, &mut |mapping| {
+        matches.push(node_ids(mapping));
+        matches.len() == max_matches
+    });
+    matches
+}
+
+impl Graph {
+    /// Method form of `is_isomorphic`: the node mapping from `self` to
+    /// `other`, if the two are isomorphic
+    pub fn is_isomorphic_to(&self, other: &Graph) -> Option<Vec<NodeId>> {
+        is_isomorphic(self, other)
+    }
+
+    /// Method form of `find_subgraph_matches`, for finding a small motif in
+    /// `self`: up to `max_matches` mappings, each giving the node of `self`
+    /// matched to every node of `pattern`
+    pub fn subgraph_matches(&self, pattern: &Graph, max_matches: usize) -> Vec<Vec<NodeId>> {
+        find_subgraph_matches(pattern, self, max_matches)
+    }
+}
+
+/// `edge_match` over the index pairs the search works with
+fn by_ids<E>(edge_match: &E) -> impl Fn((usize, usize), (usize, usize)) -> bool + '_
+where
+    E: Fn((NodeId, NodeId), (NodeId, NodeId)) -> bool,
+{
+    move |(p, q), (t, u)| {
+        edge_match(
+            (NodeId::new(p), NodeId::new(q)),
+            (NodeId::new(t), NodeId::new(u)),
+        )
+    }
+}
+
+fn node_ids(mapping: &[usize]) -> Vec<NodeId> {
+    mapping.iter().copied().map(NodeId::new).collect()
+}
+
+/// Relation between two nodes as seen from the first: bit 0 for a -> b,
+/// bit 1 for b -> a
+fn edge_relation(edges: &EdgeSets, a: usize, b: usize) -> u8 {
+    u8::from(edges.has(a, b)) | u8::from(edges.has(b, a)) << 1
+}
+
+/// Search state for the maximum common induced subgraph. Unmatched nodes are
+/// kept in label classes: every node of a class has the same relation to every
+/// matched node, so only nodes from the same class of each graph can extend
+/// the mapping (the McSplit formulation).
+struct CommonSubgraphSearch {
+    pub left: EdgeSets,
+    pub right: EdgeSets,
+    pub best: Vec<(usize, usize)>,
+}
+
+type LabelClasses = Vec<(Vec<usize>, Vec<usize>)>;
+
+impl CommonSubgraphSearch {
+    fn new(g1: &Graph, g2: &Graph) -> (Self, LabelClasses) {
+        let left = EdgeSets::new(g1);
+        let right = EdgeSets::new(g2);
+        // Initial classes separate nodes with and without a self-loop
+        let classes = [false, true]
+            .iter()
+            .map(|&has_loop| {
+                let pick = |edges: &EdgeSets| {
+                    (0..edges.out.len())
+                        .filter(|&index| edges.has(index, index) == has_loop)
+                        .collect::<Vec<_>>()
+                };
+                (pick(&left), pick(&right))
+            })
+            .filter(|(l, r)| !l.is_empty() && !r.is_empty())
+            .collect();
+        let search = Self {
+            left,
+            right,
+            best: Vec::new(),
+        };
+        (search, classes)
+    }
+
+    fn bound(classes: &LabelClasses) -> usize {
+        classes.iter().map(|(l, r)| l.len().min(r.len())).sum()
+    }
+
+    /// Split every class by relation to the newly matched pair (v, w)
+    fn refine(&self, classes: &LabelClasses, v: usize, w: usize) -> LabelClasses {
+        let mut refined = Vec::new();
+        for (l, r) in classes {
+            for relation in 0..4u8 {
+                let l2: Vec<usize> = l
+                    .iter()
+                    .copied()
+                    .filter(|&x| x != v && edge_relation(&self.left, v, x) == relation)
+                    .collect();
+                let r2: Vec<usize> = r
+                    .iter()
+                    .copied()
+                    .filter(|&y| y != w && edge_relation(&self.right, w, y) == relation)
+                    .collect();
+                if !l2.is_empty() && !r2.is_empty() {
+                    refined.push((l2, r2));
+                }
+            }
+        }
+        refined
+    }
+
+    /// Pick the class with the fewest options and its highest-degree left node
+    fn select(&self, classes: &LabelClasses) -> (usize, usize) {
+        let class = (0..classes.len())
+            .min_by_key(|&c| classes[c].0.len().max(classes[c].1.len()))
+            .unwrap();
+        let v = *classes[class]
+            .0
+            .iter()
+            .max_by_key(|&&x| self.left.out[x].len() + self.left.inc[x].len())
+            .unwrap();
+        (class, v)
+    }
+
+    fn exact(&mut self, classes: LabelClasses, current: &mut Vec<(usize, usize)>) {
+        if current.len() > self.best.len() {
+            self.best = current.clone();
+        }
+        if classes.is_empty() || current.len() + Self::bound(&classes) <= self.best.len() {
+            return;
+        }
+        let (class, v) = self.select(&classes);
+        for &w in &classes[class].1.clone() {
+            let refined = self.refine(&classes, v, w);
+            current.push((v, w));
+            self.exact(refined, current);
+            current.pop();
+        }
+
+        // Also try leaving v unmatched
+        let mut without_v = classes;
+        without_v[class].0.retain(|&x| x != v);
+        if without_v[class].0.is_empty() {
+            without_v.remove(class);
+        }
+        self.exact(without_v, current);
+    }
+
+    /// Greedy descent: take the pair that keeps the largest bound, never backtrack
+    fn greedy(&self, mut classes: LabelClasses) -> Vec<(usize, usize)> {
+        let mut mapping = Vec::new();
+        while !classes.is_empty() {
+            let (class, v) = self.select(&classes);
+            let (w, refined) = classes[class]
+                .1
+                .iter()
+                .map(|&w| (w, self.refine(&classes, v, w)))
+                .max_by_key(|(_, refined)| Self::bound(refined))
+                .unwrap();
+            mapping.push((v, w));
+            classes = refined;
+        }
+        mapping
+    }
+}
+
+/// Exact maximum common induced subgraph of two (small) graphs, as pairs
+/// (node in g1, node in g2). Runs a branch and bound search that is exponential
+/// in the worst case; use `approximate_common_subgraph` for larger inputs.
+pub fn maximum_common_subgraph(g1: &Graph, g2: &Graph) -> Vec<(NodeId, NodeId)> {
+    let (mut search, classes) = CommonSubgraphSearch::new(g1, g2);
+    search.exact(classes, &mut Vec::new());
+    pair_ids(search.best)
+}
+
+/// Common induced subgraph found greedily, in polynomial time
+pub fn approximate_common_subgraph(g1: &Graph, g2: &Graph) -> Vec<(NodeId, NodeId)> {
+    let (search, classes) = CommonSubgraphSearch::new(g1, g2);
+    pair_ids(search.greedy(classes))
+}
+
+/// Sorted mapping pairs as node ids
+fn pair_ids(mut mapping: Vec<(usize, usize)>) -> Vec<(NodeId, NodeId)> {
+    mapping.sort_unstable();
+    mapping
+        .into_iter()
+        .map(|(a, b)| (NodeId::new(a), NodeId::new(b)))
+        .collect()
+}
+
+/// Structural similarity in [0, 1]: common subgraph size over the larger graph's size
+pub fn common_subgraph_similarity(g1: &Graph, g2: &Graph, mapping: &[(NodeId, NodeId)]) -> f64 {
+    let larger = g1.nodes.len().max(g2.nodes.len());
+    if larger == 0 {
+        return 1.0;
+    }
+    mapping.len() as f64 / larger as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph;
+
+    /// Whether `mapping` carries every edge of `g1` onto an edge of `g2`
+    fn preserves_edges(g1: &Graph, g2: &Graph, mapping: &[NodeId]) -> bool {
+        g1.edges().all(|(a, b)| {
+            g2.edges()
+                .any(|edge| edge == (mapping[a.index()], mapping[b.index()]))
+        })
+    }
+
+    #[test]
+    fn isomorphism_finds_relabelings_and_rejects_lookalikes() {
+        let hexagon = Graph::cycle(6);
+        let relabeled =
+            Graph::from_undirected_edges(6, [(3, 0), (0, 5), (5, 1), (1, 4), (4, 2), (2, 3)]);
+        let mapping = is_isomorphic(&hexagon, &relabeled).unwrap();
+        assert!(preserves_edges(&hexagon, &relabeled, &mapping));
+
+        // Same size and degrees, but two components
+        let triangles =
+            Graph::from_undirected_edges(6, [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)]);
+        assert_eq!(is_isomorphic(&hexagon, &triangles), None);
+
+        // Same shape read as undirected, different directions
+        let chain = graph! { 0: [1], 1: [2], 2: [] };
+        let fork = graph! { 0: [], 1: [0, 2], 2: [] };
+        assert_eq!(is_isomorphic(&chain, &fork), None);
+
+        // Matching values pins the path to the identity, not its reversal
+        let path = Graph::path(3);
+        let same_value = |a: &Node, b: &Node| a.value == b.value;
+        let mapping = is_isomorphic_with(&path, &path, same_value, |_, _| true);
+        assert_eq!(mapping, Some(path.node_ids().collect()));
+    }
+
+    #[test]
+    fn subgraph_matching_counts_embeddings_and_honors_the_limit() {
+        let triangle = Graph::cycle(3);
+        let k4 = Graph::complete(4);
+        // Every ordered choice of three distinct nodes of K4
+        let matches = find_subgraph_matches(&triangle, &k4, usize::MAX);
+        assert_eq!(matches.len(), 24);
+        assert!(matches
+            .iter()
+            .all(|mapping| preserves_edges(&triangle, &k4, mapping)));
+        assert_eq!(k4.subgraph_matches(&triangle, 5).len(), 5);
+        assert!(find_subgraph_matches(&triangle, &Graph::cycle(5), usize::MAX).is_empty());
+
+        // A 3-node path in a 4-star must put its middle on the hub
+        let star = Graph::star(4);
+        let matches = star.subgraph_matches(&Graph::path(3), usize::MAX);
+        assert_eq!(matches.len(), 6);
+        assert!(matches.iter().all(|mapping| mapping[1] == NodeId::new(0)));
+    }
+}
@@ -0,0 +1,296 @@
+use std::fmt::{Display, Write as _};
+
+use crate::graph::{Graph, NodeId};
+use crate::sampling::Rng;
+use crate::xml::escape;
+
+/// Radius of a node's circle in `Layout::to_svg`, in SVG user units
+const NODE_RADIUS: f64 = 12.0;
+
+/// Pull of each node towards the middle of the layout, per unit of distance
+const GRAVITY: f64 = 1.0;
+
+/// Categorical fills for `Layout::color_by_components` (Tableau 10), reused
+/// in turn past ten components
+const PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+
+/// Options for `Graph::layout_with`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutOptions {
+    /// Width of the area node centers are kept in, in SVG user units
+    pub width: f64,
+    pub height: f64,
+    /// Rounds of force updates; the step size cools to zero over them
+    pub iterations: usize,
+    /// Seed for the random starting positions, so a layout is reproducible
+    pub seed: u64,
+}
+
+impl Default for LayoutOptions {
+    /// A 600 x 600 area, 300 rounds, seed 1
+    fn default() -> Self {
+        LayoutOptions {
+            width: 600.0,
+            height: 600.0,
+            iterations: 300,
+            seed: 1,
+        }
+    }
+}
+
+/// Node fills for `Layout::to_svg`
+#[derive(Debug, Clone, PartialEq)]
+enum NodeColors {
+    Uniform,
+    /// Position in a visit order and the order's length; None if unvisited
+    Rank(Vec<Option<usize>>, usize),
+    /// Component of each node; None if no component lists it
+    Component(Vec<Option<usize>>),
+}
+
+/// Node positions from `Graph::layout`, with what `to_svg` needs to draw the
+/// graph: its edges, node labels and node colors
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    positions: Vec<(f64, f64)>,
+    labels: Vec<String>,
+    edges: Vec<(usize, usize)>,
+    width: f64,
+    height: f64,
+    colors: NodeColors,
+}
+
+impl<T: Display> Graph<T> {
+    /// Fruchterman-Reingold force-directed layout with the default
+    /// `LayoutOptions`; see `layout_with`
+    pub fn layout(&self) -> Layout {
+        self.layout_with(&LayoutOptions::default())
+    }
+
+    /// Fruchterman-Reingold force-directed layout: every pair of nodes repels
+    /// and every edge, read as undirected, pulls its ends together, from
+    /// seeded random starting positions. The result is scaled, keeping its
+    /// aspect ratio, and centered to fill the options' area. Each round costs
+    /// O(V^2 + E), which suits the small graphs a picture is useful for.
+    pub fn layout_with(&self, options: &LayoutOptions) -> Layout {
+        let len = self.nodes.len();
+        let mut rng = Rng::new(options.seed);
+        // Laid out around the unit square, then fitted to the area
+        let mut positions: Vec<(f64, f64)> =
+            (0..len).map(|_| (rng.next_f64(), rng.next_f64())).collect();
+        let springs = self.undirected_edge_indices();
+
+        // Ideal edge length: the side of each node's share of the square
+        let k = 1.0 / (len.max(1) as f64).sqrt();
+        let start_temperature = 0.1;
+        for round in 0..options.iterations {
+            let mut moves = vec![(0.0, 0.0); len];
+            for u in 0..len {
+                for v in u + 1..len {
+                    let (dx, dy, distance) = offset(positions[u], positions[v]);
+                    let force = k * k / distance;
+                    moves[u].0 += dx / distance * force;
+                    moves[u].1 += dy / distance * force;
+                    moves[v].0 -= dx / distance * force;
+                    moves[v].1 -= dy / distance * force;
+                }
+            }
+            for &(u, v) in &springs {
+                let (dx, dy, distance) = offset(positions[u], positions[v]);
+                let force = distance * distance / k;
+                moves[u].0 -= dx / distance * force;
+                moves[u].1 -= dy / distance * force;
+                moves[v].0 += dx / distance * force;
+                moves[v].1 += dy / distance * force;
+            }
+            // Gravity towards the middle holds disconnected parts together
+            for (position, movement) in positions.iter().zip(&mut moves) {
+                movement.0 -= (position.0 - 0.5) * GRAVITY;
+                movement.1 -= (position.1 - 0.5) * GRAVITY;
+            }
+
+            // Move each node along its net force, at most `temperature` far
+            let temperature = start_temperature * (1.0 - round as f64 / options.iterations as f64);
+            for (position, (mx, my)) in positions.iter_mut().zip(moves) {
+                let length = (mx * mx + my * my).sqrt();
+                if length > 0.0 {
+                    let step = length.min(temperature) / length;
+                    position.0 += mx * step;
+                    position.1 += my * step;
+                }
+            }
+        }
+        fit(&mut positions, options.width, options.height);
+
+        let mut edges: Vec<(usize, usize)> = self
+            .edges()
+            .map(|(from, to)| (from.index(), to.index()))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        Layout {
+            positions,
+            labels: self
+                .nodes
+                .iter()
+                .map(|node| node.value.to_string())
+                .collect(),
+            edges,
+            width: options.width,
+            height: options.height,
+            colors: NodeColors::Uniform,
+        }
+    }
+}
+
+/// Scale and translate `positions` into a `width` x `height` box, equally on
+/// both axes and centered; a single node lands in the middle
+fn fit(positions: &mut [(f64, f64)], width: f64, height: f64) {
+    let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(x, y) in positions.iter() {
+        (min_x, min_y) = (min_x.min(x), min_y.min(y));
+        (max_x, max_y) = (max_x.max(x), max_y.max(y));
+    }
+    let (span_x, span_y) = (max_x - min_x, max_y - min_y);
+    let scale = match (span_x > 0.0, span_y > 0.0) {
+        (false, false) => 0.0,
+        _ => (width / span_x).min(height / span_y),
+    };
+    let (offset_x, offset_y) = (
+        (width - span_x * scale) / 2.0,
+        (height - span_y * scale) / 2.0,
+    );
+    for position in positions.iter_mut() {
+        position.0 = offset_x + (position.0 - min_x) * scale;
+        position.1 = offset_y + (position.1 - min_y) * scale;
+    }
+}
+
+/// (dx, dy, distance) from `b` to `a`, the distance kept away from zero so
+/// that coincident nodes do not divide by it
+fn offset(a: (f64, f64), b: (f64, f64)) -> (f64, f64, f64) {
+    let (dx, dy) = (a.0 - b.0, a.1 - b.1);
+    (dx, dy, (dx * dx + dy * dy).sqrt().max(0.01))
+}
+
+impl Layout {
+    /// Center of each node by index, within the layout's width and height
+    pub fn positions(&self) -> &[(f64, f64)] {
+        &self.positions
+    }
+
+    /// Shade nodes by their place in `order`, such as a traversal's
+    /// `tree().order()`: blue for the first through to red for the last, and
+    /// grey for nodes not in it
+    pub fn color_by_order(mut self, order: &[NodeId]) -> Self {
+        let mut ranks = vec![None; self.positions.len()];
+        for (rank, node) in order.iter().enumerate() {
+            if let Some(slot) = ranks.get_mut(node.index()) {
+                slot.get_or_insert(rank);
+            }
+        }
+        self.colors = NodeColors::Rank(ranks, order.len());
+        self
+    }
+
+    /// Fill each of `components`, such as `connected_components()` or
+    /// `strongly_connected_components()`, with its own color
+    pub fn color_by_components(mut self, components: &[Vec<NodeId>]) -> Self {
+        let mut of = vec![None; self.positions.len()];
+        for (component, nodes) in components.iter().enumerate() {
+            for node in nodes {
+                if let Some(slot) = of.get_mut(node.index()) {
+                    *slot = Some(component);
+                }
+            }
+        }
+        self.colors = NodeColors::Component(of);
+        self
+    }
+
+    fn fill(&self, node: usize) -> String {
+        match &self.colors {
+            NodeColors::Uniform => "#9ecae1".to_string(),
+            NodeColors::Rank(ranks, len) => match ranks[node] {
+                Some(rank) => {
+                    let fraction = rank as f64 / len.saturating_sub(1).max(1) as f64;
+                    format!("hsl({:.0},70%,60%)", 240.0 * (1.0 - fraction))
+                }
+                None => "#dddddd".to_string(),
+            },
+            NodeColors::Component(of) => match of[node] {
+                Some(component) => PALETTE[component % PALETTE.len()].to_string(),
+                None => "#dddddd".to_string(),
+            },
+        }
+    }
+
+    /// Standalone SVG picture: an arrow per edge (parallel edges drawn once,
+    /// self-loops as a small circle), and a labelled circle per node whose
+    /// tooltip gives its index
+    pub fn to_svg(&self) -> String {
+        let margin = 2.0 * NODE_RADIUS;
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="{x:.1} {x:.1} {w:.1} {h:.1}">"#,
+            x = -margin,
+            w = self.width + 2.0 * margin,
+            h = self.height + 2.0 * margin,
+        );
+        svg += concat!(
+            "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" ",
+            "markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">",
+            "<path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"#555\"/></marker></defs>\n",
+        );
+        svg += "<g stroke=\"#555\" stroke-width=\"1.5\" fill=\"none\">\n";
+        for &(from, to) in &self.edges {
+            let (x1, y1) = self.positions[from];
+            if from == to {
+                let _ = writeln!(
+                    svg,
+                    r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}"/>"#,
+                    x1,
+                    y1 - NODE_RADIUS,
+                    NODE_RADIUS * 0.7
+                );
+                continue;
+            }
+            // End the line at the target's rim, so the arrowhead shows
+            let (dx, dy, distance) = offset(self.positions[to], self.positions[from]);
+            let shorten = (distance - NODE_RADIUS).max(0.0) / distance;
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" marker-end="url(#arrow)"/>"#,
+                x1,
+                y1,
+                x1 + dx * shorten,
+                y1 + dy * shorten
+            );
+        }
+        svg += "</g>\n";
+        svg += "<g font-family=\"sans-serif\" font-size=\"11\" text-anchor=\"middle\" dominant-baseline=\"central\">\n";
+        for (node, &(x, y)) in self.positions.iter().enumerate() {
+            let label = escape(&self.labels[node]);
+            let _ = writeln!(
+                svg,
+                r##"<g><title>node {}: {}</title><circle cx="{:.1}" cy="{:.1}" r="{}" fill="{}" stroke="#333"/><text x="{:.1}" y="{:.1}">{}</text></g>"##,
+                node,
+                label,
+                x,
+                y,
+                NODE_RADIUS,
+                self.fill(node),
+                x,
+                y,
+                label
+            );
+        }
+        svg += "</g>\n</svg>\n";
+        svg
+    }
+}
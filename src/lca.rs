@@ -0,0 +1,114 @@
+use crate::graph::{Graph, NodeId};
+use crate::traversal::{BfsIterator, Traversal, TraversalTree};
+
+/// Lowest common ancestor queries on a rooted tree, by binary lifting: each
+/// node stores its 2^k-th ancestor for every k, so a query climbs O(log n)
+/// levels. Built from a `TraversalTree`, whose parent links give the tree; a
+/// forest from a whole-graph traversal works too, nodes in different trees
+/// having no common ancestor.
+#[derive(Debug, Clone)]
+pub struct LcaIndex {
+    depth: Vec<Option<usize>>,  // None for nodes outside the tree
+    root: Vec<usize>,           // Root of each node's tree
+    ancestors: Vec<Vec<usize>>, // ancestors[k][v]: 2^k-th ancestor, or the root
+}
+
+impl LcaIndex {
+    /// Index the tree traced by a traversal, in O(n log n)
+    pub fn from_tree(tree: &TraversalTree) -> Self {
+        let len = tree
+            .order()
+            .iter()
+            .map(|node| node.index() + 1)
+            .max()
+            .unwrap_or(0);
+        let mut depth = vec![None; len];
+        let mut root = vec![0; len];
+        let mut parent: Vec<usize> = (0..len).collect();
+        // Parents are visited before their children
+        for &node in tree.order() {
+            let v = node.index();
+            match tree.parent_of(node) {
+                Some(p) => {
+                    let p = p.index();
+                    parent[v] = p;
+                    depth[v] = depth[p].map(|d| d + 1);
+                    root[v] = root[p];
+                }
+                None => {
+                    depth[v] = Some(0);
+                    root[v] = v;
+                }
+            }
+        }
+
+        let mut ancestors = vec![parent];
+        let height = depth.iter().flatten().copied().max().unwrap_or(0);
+        while 1 << ancestors.len() <= height {
+            let last = ancestors.last().unwrap();
+            let next = (0..len).map(|v| last[last[v]]).collect();
+            ancestors.push(next);
+        }
+        LcaIndex {
+            depth,
+            root,
+            ancestors,
+        }
+    }
+
+    /// Edges from the root of its tree down to `node`; None outside the tree
+    pub fn depth(&self, node: NodeId) -> Option<usize> {
+        self.depth.get(node.index()).copied().flatten()
+    }
+
+    /// Deepest node that is an ancestor of both `u` and `v`, each node counting
+    /// as its own ancestor; None if either is outside the tree or they lie in
+    /// different trees of a forest
+    pub fn lca(&self, u: NodeId, v: NodeId) -> Option<NodeId> {
+        let (depth_u, depth_v) = (self.depth(u)?, self.depth(v)?);
+        let (mut u, mut v) = (u.index(), v.index());
+        if self.root[u] != self.root[v] {
+            return None;
+        }
+        if depth_u < depth_v {
+            std::mem::swap(&mut u, &mut v);
+        }
+        u = self.ancestor(u, depth_u.abs_diff(depth_v));
+        if u == v {
+            return Some(NodeId::new(u));
+        }
+        // Climb both while their ancestors differ; they end just below the LCA
+        for level in self.ancestors.iter().rev() {
+            if level[u] != level[v] {
+                u = level[u];
+                v = level[v];
+            }
+        }
+        Some(NodeId::new(self.ancestors[0][u]))
+    }
+
+    /// Number of tree edges between `u` and `v`, through their LCA
+    pub fn distance(&self, u: NodeId, v: NodeId) -> Option<usize> {
+        let lca = self.lca(u, v)?;
+        Some(self.depth(u)? + self.depth(v)? - 2 * self.depth(lca)?)
+    }
+
+    /// The ancestor `steps` levels above `v`
+    fn ancestor(&self, mut v: usize, steps: usize) -> usize {
+        for (k, level) in self.ancestors.iter().enumerate() {
+            if steps >> k & 1 == 1 {
+                v = level[v];
+            }
+        }
+        v
+    }
+}
+
+impl<T> Graph<T> {
+    /// LCA index of the BFS tree from `root`, which is the graph itself when
+    /// the graph is a tree with edges pointing away from `root`; panics if
+    /// `root` is out of range
+    pub fn lca_index(&self, root: NodeId) -> LcaIndex {
+        LcaIndex::from_tree(&BfsIterator::new(self, root).tree())
+    }
+}
@@ -0,0 +1,113 @@
+//! This crate implements BFS, DFS, and NextSmallest node iteration on a Graph.
+//! The iteration starts from a given node and then tries to visit all the nodes
+//! using the specific order.
+//!
+//! NextSmallest always visits the next smallest item among the current seen nodes.
+//!
+//! The remaining modules build graph algorithms on top of the same adjacency-list
+//! `Graph`: transformations, matching, decompositions, queries, loaders and more.
+
+pub mod analysis;
+#[cfg(feature = "async")]
+pub mod async_traversal;
+pub mod attributes;
+pub mod backend;
+pub mod base;
+pub mod bipartite;
+pub mod canonical;
+pub mod centrality;
+pub mod cliques;
+pub mod coloring;
+pub mod community;
+pub mod components;
+pub mod connectivity;
+pub mod csr;
+pub mod cycles;
+pub mod datalog;
+pub mod decomposition;
+pub mod dense;
+pub mod diff;
+pub mod dominators;
+pub mod dot;
+pub mod error;
+pub mod external;
+pub mod flow;
+pub mod generators;
+#[cfg(feature = "gpu")]
+mod gpu;
+pub mod graph;
+#[cfg(feature = "graphml")]
+pub mod graphml;
+pub mod grid;
+mod gzip;
+pub mod hypergraph;
+pub mod implicit;
+pub mod io;
+pub mod isomorphism;
+pub mod layout;
+pub mod lca;
+pub mod matrix;
+pub mod metrics;
+pub mod minors;
+#[cfg(all(feature = "mmap", target_endian = "little"))]
+pub mod mmap;
+pub mod optimization;
+pub mod ordering;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod partition;
+pub mod path_query;
+pub mod paths;
+#[cfg(feature = "petgraph")]
+mod petgraph_interop;
+pub mod planarity;
+pub mod policy;
+#[cfg(test)]
+mod proptests;
+pub mod query;
+pub mod reach;
+pub mod render;
+pub mod rewrite;
+pub mod sampling;
+pub mod schedule;
+pub mod set_ops;
+pub mod spanning;
+#[cfg(feature = "linalg")]
+pub mod spectral;
+pub mod stable;
+pub mod stats;
+pub mod temporal;
+pub mod transform;
+pub mod traversal;
+pub mod uncertain;
+pub mod undirected;
+pub mod union_find;
+pub mod visit;
+pub mod visited;
+pub mod walks;
+pub mod workspace;
+mod xml;
+
+#[cfg(feature = "async")]
+pub use async_traversal::{AsyncNeighbors, AsyncTraversal};
+pub use attributes::GraphAttributes;
+pub use base::GraphBase;
+pub use csr::CsrGraph;
+#[cfg(feature = "graphml")]
+pub use error::GraphMlError;
+pub use error::{DotError, GraphDefect, GraphError, LoadError, NegativeCycle, ParseError};
+pub use graph::{DiGraph, Graph, Node, NodeId};
+pub use implicit::{BeamSearchIterator, ImplicitGraph};
+pub use matrix::AdjMatrixGraph;
+pub use metrics::TraversalMetrics;
+pub use paths::{DijkstraIterator, EdgeWeights};
+pub use policy::{EdgePolicy, PolicyGraph};
+pub use stable::{StableGraph, StableId};
+pub use traversal::{
+    BestFirstIterator, BfsIterator, DfsIterator, IddfsIterator, KeyOrder, NextSmallestIterator,
+    TopoSortIterator, Traversal, TraversalState, TraversalTree, UniformCostIterator,
+};
+pub use undirected::UnGraph;
+pub use union_find::UnionFind;
+pub use visited::{BitVisited, VisitedSet};
+pub use workspace::{bfs_iter_with, dfs_iter_with, TraversalWorkspace};
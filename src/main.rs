@@ -0,0 +1,568 @@
+use std::cmp::Reverse;
+
+use graph_traversal::diff::{visit_order, TraversalDiff};
+use graph_traversal::graph::{Graph, Node, NodeId};
+use graph_traversal::io::read_graph_file;
+use graph_traversal::ordering::NodeOrdering;
+use graph_traversal::traversal::{BfsIterator, DfsIterator, NextSmallestIterator, Traversal};
+
+// Command-line subcommands, dispatched from `main`
+
+// `analyze <edge-list> [--json] [--undirected] [--top K]`
+fn run_analyze(args: &[String]) -> Result<(), String> {
+    let usage = "usage: graph_traversal analyze <edge-list> [--json] [--undirected] [--top K]";
+    let mut path = None;
+    let (mut json, mut undirected, mut top) = (false, false, 10);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--undirected" => undirected = true,
+            "--top" => {
+                top = args
+                    .next()
+                    .and_then(|k| k.parse().ok())
+                    .ok_or_else(|| "--top expects a number".to_string())?
+            }
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => return Err(usage.to_string()),
+        }
+    }
+    let path = path.ok_or_else(|| usage.to_string())?;
+    let graph = load_cli_graph(path, undirected)?;
+    let report = graph.analyze(top);
+    if json {
+        println!("{}", report.to_json());
+    } else {
+        print!("{}", report.to_text());
+    }
+    Ok(())
+}
+
+// Read a plain or gzip-compressed edge list given on the command line
+fn load_cli_graph(path: &str, undirected: bool) -> Result<Graph, String> {
+    let text = read_cli_text(path)?;
+    Graph::from_edge_list(&text, undirected).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn read_cli_text(path: &str) -> Result<String, String> {
+    let data = read_graph_file(path).map_err(|e| format!("{}: {}", path, e))?;
+    String::from_utf8(data).map_err(|_| format!("{}: not valid UTF-8", path))
+}
+
+// Read a graph in `format` (`edges`, `dot` or `json`), by default the one its
+// extension names (`.dot`/`.gv`, `.json`, anything else an edge list), before
+// any `.gz`. DOT nodes take their label as value if it is an integer and their
+// index otherwise; `undirected` applies to edge lists only.
+fn load_query_graph(path: &str, format: Option<&str>, undirected: bool) -> Result<Graph, String> {
+    let extension = path
+        .strip_suffix(".gz")
+        .unwrap_or(path)
+        .rsplit_once('.')
+        .map(|(_, extension)| extension);
+    let format = format.unwrap_or(match extension {
+        Some("dot" | "gv") => "dot",
+        Some("json") => "json",
+        _ => "edges",
+    });
+    if undirected && format != "edges" {
+        return Err("--undirected only applies to edge lists".to_string());
+    }
+    match format {
+        "edges" => load_cli_graph(path, undirected),
+        "dot" => {
+            let graph =
+                Graph::from_dot(&read_cli_text(path)?).map_err(|e| format!("{}: {}", path, e))?;
+            let nodes = graph.nodes().iter().enumerate().map(|(index, node)| {
+                Node::new(
+                    node.value().parse().unwrap_or(index as i32),
+                    node.neighbors().map(NodeId::index).collect(),
+                )
+            });
+            Ok(Graph::from_nodes(nodes.collect()))
+        }
+        #[cfg(feature = "json")]
+        "json" => Graph::from_json(&read_cli_text(path)?).map_err(|e| format!("{}: {}", path, e)),
+        #[cfg(not(feature = "json"))]
+        "json" => Err("reading JSON needs the `json` feature".to_string()),
+        _ => Err(format!(
+            "unknown format '{}'; expected edges, dot or json",
+            format
+        )),
+    }
+}
+
+// Arguments of the query subcommands: a graph file, `--format F`,
+// `--undirected` and `--json`, plus the subcommand's own `--name N` numbers
+// and `--name` flags
+struct QueryArgs<'a> {
+    path: &'a str,
+    format: Option<&'a str>,
+    undirected: bool,
+    json: bool,
+    numbers: Vec<(&'a str, usize)>,
+    flags: Vec<&'a str>,
+}
+
+impl<'a> QueryArgs<'a> {
+    fn parse(
+        args: &'a [String],
+        usage: &str,
+        numbers: &[&str],
+        flags: &[&str],
+    ) -> Result<Self, String> {
+        let mut query = QueryArgs {
+            path: "",
+            format: None,
+            undirected: false,
+            json: false,
+            numbers: Vec::new(),
+            flags: Vec::new(),
+        };
+        let mut path = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => query.json = true,
+                "--undirected" => query.undirected = true,
+                "--format" => {
+                    query.format = Some(
+                        args.next()
+                            .ok_or_else(|| "--format expects edges, dot or json".to_string())?,
+                    )
+                }
+                name if numbers.contains(&name) => {
+                    let number = args
+                        .next()
+                        .and_then(|k| k.parse().ok())
+                        .ok_or_else(|| format!("{} expects a number", name))?;
+                    query.numbers.push((name, number));
+                }
+                name if flags.contains(&name) => query.flags.push(name),
+                _ if path.is_none() && !arg.starts_with("--") => path = Some(arg.as_str()),
+                _ => return Err(usage.to_string()),
+            }
+        }
+        query.path = path.ok_or_else(|| usage.to_string())?;
+        Ok(query)
+    }
+
+    // The last value given for `--name`
+    fn number(&self, name: &str) -> Option<usize> {
+        self.numbers
+            .iter()
+            .rev()
+            .find(|(given, _)| *given == name)
+            .map(|&(_, number)| number)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+
+    fn load(&self) -> Result<Graph, String> {
+        load_query_graph(self.path, self.format, self.undirected)
+    }
+}
+
+// Node `index` of `graph`, or an error naming the range
+fn cli_node(graph: &Graph, index: usize) -> Result<NodeId, String> {
+    let id = NodeId::new(index);
+    graph.check_node(id).map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+fn join_indices(indices: impl Iterator<Item = usize>, separator: &str) -> String {
+    indices
+        .map(|index| index.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+// `bfs|dfs <graph> [--start N] [--max-depth D] [--max-nodes K] [--format F]
+// [--undirected] [--json]`
+fn run_visit(args: &[String], depth_first: bool) -> Result<(), String> {
+    let (command, usage) = if depth_first {
+        ("dfs", "usage: graph_traversal dfs <graph> [--start N] [--max-depth D] [--max-nodes K] [--format F] [--undirected] [--json]")
+    } else {
+        ("bfs", "usage: graph_traversal bfs <graph> [--start N] [--max-depth D] [--max-nodes K] [--format F] [--undirected] [--json]")
+    };
+    let query = QueryArgs::parse(args, usage, &["--start", "--max-depth", "--max-nodes"], &[])?;
+    let graph = query.load()?;
+    let start = cli_node(&graph, query.number("--start").unwrap_or(0))?;
+    let (max_depth, max_nodes) = (query.number("--max-depth"), query.number("--max-nodes"));
+    let order = if depth_first {
+        let mut dfs = DfsIterator::new(&graph, start);
+        if let Some(max_depth) = max_depth {
+            dfs = dfs.max_depth(max_depth);
+        }
+        if let Some(max_nodes) = max_nodes {
+            dfs = dfs.max_nodes(max_nodes);
+        }
+        visit_order(dfs)
+    } else {
+        let mut bfs = BfsIterator::new(&graph, start);
+        if let Some(max_depth) = max_depth {
+            bfs = bfs.max_depth(max_depth);
+        }
+        if let Some(max_nodes) = max_nodes {
+            bfs = bfs.max_nodes(max_nodes);
+        }
+        visit_order(bfs)
+    };
+    if query.json {
+        println!(
+            "{{\"traversal\":\"{}\",\"start\":{},\"order\":[{}]}}",
+            command,
+            start.index(),
+            join_indices(order.into_iter(), ",")
+        );
+    } else {
+        println!("{}", join_indices(order.into_iter(), " "));
+    }
+    Ok(())
+}
+
+// `shortest-path <graph> --from A --to B [--format F] [--undirected] [--json]`:
+// a path with the fewest edges
+fn run_shortest_path(args: &[String]) -> Result<(), String> {
+    let usage = "usage: graph_traversal shortest-path <graph> --from A --to B [--format F] [--undirected] [--json]";
+    let query = QueryArgs::parse(args, usage, &["--from", "--to"], &[])?;
+    let (from, to) = match (query.number("--from"), query.number("--to")) {
+        (Some(from), Some(to)) => (from, to),
+        _ => return Err(usage.to_string()),
+    };
+    let graph = query.load()?;
+    let path = graph.shortest_path(cli_node(&graph, from)?, cli_node(&graph, to)?);
+    let path = path.map(|path| path.into_iter().map(NodeId::index));
+    match (query.json, path) {
+        (true, Some(path)) => {
+            let path: Vec<usize> = path.collect();
+            println!(
+                "{{\"from\":{},\"to\":{},\"hops\":{},\"path\":[{}]}}",
+                from,
+                to,
+                path.len() - 1,
+                join_indices(path.into_iter(), ",")
+            );
+        }
+        (true, None) => println!(
+            "{{\"from\":{},\"to\":{},\"hops\":null,\"path\":null}}",
+            from, to
+        ),
+        (false, Some(path)) => println!("{}", join_indices(path, " -> ")),
+        (false, None) => println!("no path from {} to {}", from, to),
+    }
+    Ok(())
+}
+
+// `components <graph> [--strong] [--format F] [--undirected] [--json]`: weakly
+// (or strongly) connected components, one per line
+fn run_components(args: &[String]) -> Result<(), String> {
+    let usage =
+        "usage: graph_traversal components <graph> [--strong] [--format F] [--undirected] [--json]";
+    let query = QueryArgs::parse(args, usage, &[], &["--strong"])?;
+    let graph = query.load()?;
+    let components = if query.flag("--strong") {
+        graph.strongly_connected_components()
+    } else {
+        graph.connected_components()
+    };
+    let components = components
+        .into_iter()
+        .map(|component| component.into_iter().map(NodeId::index));
+    if query.json {
+        let components: Vec<String> = components
+            .map(|component| format!("[{}]", join_indices(component, ",")))
+            .collect();
+        println!("{{\"components\":[{}]}}", components.join(","));
+    } else {
+        for component in components {
+            println!("{}", join_indices(component, " "));
+        }
+    }
+    Ok(())
+}
+
+// `stats <graph> [--format F] [--undirected] [--json]`: size, degree and
+// density summary
+fn run_stats(args: &[String]) -> Result<(), String> {
+    let usage = "usage: graph_traversal stats <graph> [--format F] [--undirected] [--json]";
+    let query = QueryArgs::parse(args, usage, &[], &[])?;
+    let graph = query.load()?;
+    let distribution = graph.degree_distribution();
+    if query.json {
+        let histogram: Vec<String> = distribution
+            .iter()
+            .map(|(degree, count)| format!("{{\"degree\":{},\"count\":{}}}", degree, count))
+            .collect();
+        println!(
+            "{{\"nodes\":{},\"edges\":{},\"average_degree\":{},\"density\":{},\"degree_distribution\":[{}]}}",
+            graph.len(),
+            graph.edge_count(),
+            graph.average_degree(),
+            graph.density(),
+            histogram.join(",")
+        );
+    } else {
+        println!("nodes:          {}", graph.len());
+        println!("edges:          {}", graph.edge_count());
+        println!("average degree: {:.3}", graph.average_degree());
+        println!("density:        {:.6}", graph.density());
+        println!("out-degree distribution:");
+        for (degree, count) in distribution {
+            println!("  {:>6} {}", degree, count);
+        }
+    }
+    Ok(())
+}
+
+// Visit order for a traversal spec: `bfs`, `dfs`, `smallest` or `largest`
+// (by node value), optionally suffixed with `:reversed` to reverse every
+// neighbor list first
+fn run_traversal_spec(graph: &Graph, spec: &str, start: NodeId) -> Result<Vec<usize>, String> {
+    let (kind, reversed) = match spec.strip_suffix(":reversed") {
+        Some(kind) => (kind, true),
+        None => (spec, false),
+    };
+    let graph = if reversed {
+        Graph::from_nodes(
+            graph
+                .nodes()
+                .iter()
+                .map(|node| {
+                    Node::new(
+                        *node.value(),
+                        node.neighbors().rev().map(NodeId::index).collect(),
+                    )
+                })
+                .collect(),
+        )
+    } else {
+        graph.clone()
+    };
+    let order = match kind {
+        "bfs" => visit_order(BfsIterator::new(&graph, start)),
+        "dfs" => visit_order(DfsIterator::new(&graph, start)),
+        "smallest" => visit_order(NextSmallestIterator::new(&graph, start)),
+        "largest" => visit_order(
+            graph
+                .map(|node| Reverse(*node.value()))
+                .next_smallest(start),
+        ),
+        _ => {
+            return Err(format!(
+                "unknown traversal '{}'; expected bfs, dfs, smallest or largest",
+                spec
+            ))
+        }
+    };
+    Ok(order)
+}
+
+// `diff <edge-list> <left> <right> [--start N] [--undirected] [--json]`
+fn run_diff(args: &[String]) -> Result<(), String> {
+    let usage = "usage: graph_traversal diff <edge-list> <left> <right> [--start N] [--undirected] [--json]";
+    let mut positional = Vec::new();
+    let (mut json, mut undirected, mut start) = (false, false, 0);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--undirected" => undirected = true,
+            "--start" => {
+                start = args
+                    .next()
+                    .and_then(|k| k.parse().ok())
+                    .ok_or_else(|| "--start expects a node index".to_string())?
+            }
+            _ if !arg.starts_with("--") => positional.push(arg.as_str()),
+            _ => return Err(usage.to_string()),
+        }
+    }
+    let [path, left, right] = positional[..] else {
+        return Err(usage.to_string());
+    };
+    let graph = load_cli_graph(path, undirected)?;
+    if start >= graph.len() {
+        return Err(format!("start node {} is out of range", start));
+    }
+    let diff = TraversalDiff::new(
+        run_traversal_spec(&graph, left, NodeId::new(start))?,
+        run_traversal_spec(&graph, right, NodeId::new(start))?,
+    );
+    if json {
+        println!("{}", diff.to_json());
+    } else {
+        print!("{}", diff.to_text());
+    }
+    Ok(())
+}
+
+// `reorder-bench <edge-list> [--undirected] [--rounds N]`: time full BFS and
+// PageRank under each locality ordering
+fn run_reorder_bench(args: &[String]) -> Result<(), String> {
+    let usage = "usage: graph_traversal reorder-bench <edge-list> [--undirected] [--rounds N]";
+    let mut path = None;
+    let (mut undirected, mut rounds) = (false, 5);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--undirected" => undirected = true,
+            "--rounds" => {
+                rounds = args
+                    .next()
+                    .and_then(|k| k.parse().ok())
+                    .filter(|&k| k > 0)
+                    .ok_or_else(|| "--rounds expects a positive number".to_string())?
+            }
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => return Err(usage.to_string()),
+        }
+    }
+    let graph = load_cli_graph(path.ok_or_else(|| usage.to_string())?, undirected)?;
+    let orderings = [
+        ("original", None),
+        ("degree", Some(NodeOrdering::Degree)),
+        ("bfs", Some(NodeOrdering::Bfs)),
+        ("gorder", Some(NodeOrdering::Gorder { window: 5 })),
+        ("rcm", Some(NodeOrdering::ReverseCuthillMcKee)),
+    ];
+    println!(
+        "{:<10} {:>12} {:>10} {:>14} {:>10}",
+        "ordering", "reorder ms", "log gap", "bfs ms/round", "pagerank ms/round"
+    );
+    for (name, ordering) in orderings {
+        let started = std::time::Instant::now();
+        let order: Vec<NodeId> = match ordering {
+            Some(ordering) => graph.locality_order(ordering),
+            None => graph.node_ids().collect(),
+        };
+        let reordered = graph.permuted(&order);
+        let reorder_ms = started.elapsed().as_secs_f64() * 1e3;
+
+        // BFS from every unvisited node so the whole graph is touched
+        let started = std::time::Instant::now();
+        for _ in 0..rounds {
+            let mut visited = vec![false; reordered.len()];
+            for start in 0..reordered.len() {
+                if !visited[start] {
+                    let mut bfs =
+                        BfsIterator::with_visited(&reordered, NodeId::new(start), visited);
+                    while bfs.next_index().is_some() {}
+                    visited = bfs.into_visited();
+                }
+            }
+        }
+        let bfs_ms = started.elapsed().as_secs_f64() * 1e3 / rounds as f64;
+
+        let started = std::time::Instant::now();
+        for _ in 0..rounds {
+            reordered.pagerank(0.85, 20);
+        }
+        let pagerank_ms = started.elapsed().as_secs_f64() * 1e3 / rounds as f64;
+        println!(
+            "{:<10} {:>12.1} {:>10.3} {:>14.2} {:>10.2}",
+            name,
+            reorder_ms,
+            reordered.mean_log_gap(),
+            bfs_ms,
+            pagerank_ms
+        );
+    }
+    Ok(())
+}
+
+fn demo() {
+    // Create a simple graph
+    let graph = graph_traversal::graph! {
+        0: [1, 2],
+        1: [0, 5, 4],
+        2: [0, 3],
+        3: [1],
+        4: [1, 2],
+        5: [1, 2],
+    };
+
+    print!("{}", graph.render_ascii(NodeId::new(0)));
+
+    // Create a BFS iterator starting from node 0
+    let bfs_iter = BfsIterator::new(&graph, NodeId::new(0));
+
+    // Iterate over the graph using BFS
+    println!("BFS");
+    for node in bfs_iter {
+        println!("Visited node with value: {}", node.value());
+    }
+
+    // Create a DFS iterator starting from node 0
+    let dfs_iter = DfsIterator::new(&graph, NodeId::new(0));
+
+    // Iterate over the graph using BFS
+    println!("DFS");
+    for node in dfs_iter {
+        println!("Visited node with value: {}", node.value());
+    }
+
+    // Iterate over the graph using DFS, recording node indices
+    println!("DFS with ids");
+    for (index, node) in DfsIterator::new(&graph, NodeId::new(0)).with_ids() {
+        println!("Visited node {} with value: {}", index, node.value());
+    }
+
+    // Create a NextSmallest iterator starting from node 0
+    let ns_iter = NextSmallestIterator::new(&graph, NodeId::new(0));
+
+    // Iterate over the graph using BFS
+    println!("NextSmallest");
+    for node in ns_iter {
+        println!("Visited node with value: {}", node.value());
+    }
+
+    // Visit the largest values first by projecting each value to its negation
+    println!("NextSmallest on negated values");
+    for node in graph
+        .map(|node| -node.value())
+        .next_smallest(NodeId::new(0))
+    {
+        println!("Visited node with value: {}", node.value());
+    }
+
+    // Fork a BFS after its first step and continue both copies independently
+    let mut bfs_iter = BfsIterator::new(&graph, NodeId::new(0));
+    bfs_iter.next();
+    let fork = bfs_iter.clone();
+    println!("Forked BFS");
+    println!(
+        "Both forks agree: {}",
+        bfs_iter
+            .map(|node| node.value())
+            .eq(fork.map(|node| node.value()))
+    );
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        None => {
+            demo();
+            Ok(())
+        }
+        Some("analyze") => run_analyze(&args[1..]),
+        Some("diff") => run_diff(&args[1..]),
+        Some("reorder-bench") => run_reorder_bench(&args[1..]),
+        Some("bfs") => run_visit(&args[1..], false),
+        Some("dfs") => run_visit(&args[1..], true),
+        Some("shortest-path") => run_shortest_path(&args[1..]),
+        Some("components") => run_components(&args[1..]),
+        Some("stats") => run_stats(&args[1..]),
+        Some(command) => Err(format!(
+            "unknown command '{}'; expected 'analyze', 'diff', 'reorder-bench', 'bfs', 'dfs', 'shortest-path', 'components' or 'stats'",
+            command
+        )),
+    };
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        std::process::exit(2);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::base::GraphBase;
+use crate::error::GraphError;
+use crate::graph::{Graph, Node, NodeId};
+
+/// Dense graph stored as an n x n bit matrix, one row of u64 words per node:
+/// bit `to` of row `from` is set when from -> to is an edge. `has_edge` is a
+/// single bit test and complement and transpose work on whole words, at the
+/// cost of n^2 / 8 bytes however few edges there are, so it suits small dense
+/// graphs. Parallel edges collapse into one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdjMatrixGraph<T = i32> {
+    values: Vec<T>,
+    words_per_row: usize,
+    bits: Vec<u64>,
+}
+
+impl<T> AdjMatrixGraph<T> {
+    /// One node per value, without edges
+    pub fn from_values(values: Vec<T>) -> Self {
+        let words_per_row = values.len().div_ceil(64);
+        AdjMatrixGraph {
+            bits: vec![0; values.len() * words_per_row],
+            words_per_row,
+            values,
+        }
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn value(&self, id: NodeId) -> Option<&T> {
+        self.values.get(id.index())
+    }
+
+    fn row(&self, index: usize) -> &[u64] {
+        &self.bits[index * self.words_per_row..(index + 1) * self.words_per_row]
+    }
+
+    fn check_node(&self, node: NodeId) -> Result<(), GraphError> {
+        if node.index() < self.len() {
+            Ok(())
+        } else {
+            Err(GraphError::NodeOutOfRange {
+                node: node.index(),
+                len: self.len(),
+            })
+        }
+    }
+
+    pub(crate) fn row_mut(&mut self, index: usize) -> &mut [u64] {
+        &mut self.bits[index * self.words_per_row..(index + 1) * self.words_per_row]
+    }
+
+    /// Word index and mask of the bit for from -> to
+    fn bit(&self, from: usize, to: usize) -> (usize, u64) {
+        (from * self.words_per_row + to / 64, 1 << (to % 64))
+    }
+
+    /// Add the edge from -> to; adding an existing edge changes nothing
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), GraphError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+        let (word, mask) = self.bit(from.index(), to.index());
+        self.bits[word] |= mask;
+        Ok(())
+    }
+
+    /// Remove the edge from -> to, returning whether it was there
+    pub fn remove_edge(&mut self, from: NodeId, to: NodeId) -> Result<bool, GraphError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+        let (word, mask) = self.bit(from.index(), to.index());
+        let present = self.bits[word] & mask != 0;
+        self.bits[word] &= !mask;
+        Ok(present)
+    }
+
+    /// Whether from -> to is an edge; false if either node is out of range
+    pub fn has_edge(&self, from: NodeId, to: NodeId) -> bool {
+        if from.index() >= self.len() || to.index() >= self.len() {
+            return false;
+        }
+        let (word, mask) = self.bit(from.index(), to.index());
+        self.bits[word] & mask != 0
+    }
+
+    /// Number of edges
+    pub fn edge_count(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Out-degree of `id`; panics if `id` is out of range
+    pub fn degree(&self, id: NodeId) -> usize {
+        self.row(id.index())
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Out-neighbors of `id` in index order; panics if `id` is out of range
+    pub fn neighbors(&self, id: NodeId) -> MatrixNeighbors<'_> {
+        assert!(id.index() < self.len(), "node index out of range");
+        MatrixNeighbors {
+            row: self.row(id.index()),
+            front: 0,
+            back: self.len(),
+        }
+    }
+}
+
+impl<T: Clone> AdjMatrixGraph<T> {
+    /// Copy of `graph` as a bit matrix; each neighbor list comes back in index
+    /// order without duplicates
+    pub fn from_graph(graph: &Graph<T>) -> Self {
+        let mut matrix = AdjMatrixGraph::from_values(
+            graph.nodes.iter().map(|node| node.value.clone()).collect(),
+        );
+        for (from, node) in graph.nodes.iter().enumerate() {
+            for &to in &node.neighbors {
+                let (word, mask) = matrix.bit(from, to);
+                matrix.bits[word] |= mask;
+            }
+        }
+        matrix
+    }
+
+    /// Back to an adjacency-list graph, neighbors in index order
+    pub fn to_graph(&self) -> Graph<T> {
+        let nodes = (0..self.len())
+            .map(|index| {
+                let neighbors = self
+                    .neighbors(NodeId::new(index))
+                    .map(|neighbor| neighbor.index())
+                    .collect();
+                Node::new(self.values[index].clone(), neighbors)
+            })
+            .collect();
+        Graph { nodes }
+    }
+
+    /// The complement: i -> j is an edge exactly when it is not one here, with
+    /// no self-loops, as `Graph::complement`. Flips whole words at a time.
+    pub fn complement(&self) -> Self {
+        let mut complement = self.clone();
+        let n = self.len();
+        let tail = n % 64;
+        for from in 0..n {
+            let row = &mut complement.bits[from * self.words_per_row..][..self.words_per_row];
+            for word in row.iter_mut() {
+                *word = !*word;
+            }
+            // Clear the padding past the last node and the diagonal
+            if tail != 0 {
+                row[self.words_per_row - 1] &= (1 << tail) - 1;
+            }
+            row[from / 64] &= !(1 << (from % 64));
+        }
+        complement
+    }
+
+    /// The transpose: every edge a -> b becomes b -> a, in time proportional to
+    /// the matrix words plus the edges
+    pub fn transpose(&self) -> Self {
+        let mut transposed = AdjMatrixGraph::from_values(self.values.clone());
+        for from in 0..self.len() {
+            for to in self.neighbors(NodeId::new(from)) {
+                let (word, mask) = transposed.bit(to.index(), from);
+                transposed.bits[word] |= mask;
+            }
+        }
+        transposed
+    }
+}
+
+impl<T: Clone> Graph<T> {
+    /// The graph as a bit matrix; see `AdjMatrixGraph`
+    pub fn to_adj_matrix(&self) -> AdjMatrixGraph<T> {
+        AdjMatrixGraph::from_graph(self)
+    }
+}
+
+/// Set bits of one matrix row as node ids, from either end
+#[derive(Debug, Clone)]
+pub struct MatrixNeighbors<'a> {
+    row: &'a [u64],
+    front: usize, // Next bit to look at from the front
+    back: usize,  // One past the next bit to look at from the back
+}
+
+impl Iterator for MatrixNeighbors<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        while self.front < self.back {
+            let word_index = self.front / 64;
+            let word = self.row[word_index] & (u64::MAX << (self.front % 64));
+            if word == 0 {
+                self.front = (word_index + 1) * 64;
+                continue;
+            }
+            let bit = word_index * 64 + word.trailing_zeros() as usize;
+            if bit >= self.back {
+                break;
+            }
+            self.front = bit + 1;
+            return Some(NodeId::new(bit));
+        }
+        self.front = self.back;
+        None
+    }
+}
+
+impl DoubleEndedIterator for MatrixNeighbors<'_> {
+    fn next_back(&mut self) -> Option<NodeId> {
+        while self.front < self.back {
+            let last = self.back - 1;
+            let word_index = last / 64;
+            let word = self.row[word_index] & (u64::MAX >> (63 - last % 64));
+            if word == 0 {
+                self.back = word_index * 64;
+                continue;
+            }
+            let bit = word_index * 64 + 63 - word.leading_zeros() as usize;
+            if bit < self.front {
+                break;
+            }
+            self.back = bit;
+            return Some(NodeId::new(bit));
+        }
+        self.back = self.front;
+        None
+    }
+}
+
+/// Nodes of an `AdjMatrixGraph` are just their values
+impl<T> GraphBase for AdjMatrixGraph<T> {
+    type Value = T;
+    type Node = T;
+
+    fn node_count(&self) -> usize {
+        self.values.len()
+    }
+
+    fn node(&self, id: NodeId) -> &T {
+        &self.values[id.index()]
+    }
+
+    fn node_value(node: &T) -> &T {
+        node
+    }
+
+    fn neighbors(&self, id: NodeId) -> impl DoubleEndedIterator<Item = NodeId> + '_ {
+        AdjMatrixGraph::neighbors(self, id)
+    }
+}
@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+use crate::base::GraphBase;
+use crate::graph::NodeId;
+use crate::traversal::Traversal;
+
+/// What a traversal did, as gathered by `Traversal::instrumented`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TraversalMetrics {
+    /// Nodes yielded
+    pub nodes_visited: usize,
+    /// Out-edges of the yielded nodes, which the traversal looks at to
+    /// expand them. Each counts once: a depth or fuel limit may stop the
+    /// traversal looking at some, and IDDFS looks again in every pass.
+    pub edges_examined: usize,
+    /// Largest number of pending entries (queue, stack or heap) seen at the
+    /// start or after a step, stale lazy-deletion entries included
+    pub max_frontier: usize,
+    /// Time spent inside the traversal, not counting the caller's time
+    /// between steps
+    pub elapsed: Duration,
+}
+
+/// Adapter returned by `Traversal::instrumented`: the same traversal, also
+/// keeping `TraversalMetrics`, which stay readable after it is exhausted
+pub struct Instrumented<'a, I: Traversal<'a>> {
+    graph: &'a I::Graph,
+    inner: I,
+    metrics: TraversalMetrics,
+}
+
+impl<'a, I: Traversal<'a> + Clone> Clone for Instrumented<'a, I> {
+    fn clone(&self) -> Self {
+        Instrumented {
+            graph: self.graph,
+            inner: self.inner.clone(),
+            metrics: self.metrics,
+        }
+    }
+}
+
+impl<'a, I: Traversal<'a>> Instrumented<'a, I> {
+    pub(crate) fn new(inner: I) -> Self {
+        let metrics = TraversalMetrics {
+            max_frontier: inner.frontier_len(),
+            ..TraversalMetrics::default()
+        };
+        Instrumented {
+            graph: inner.graph(),
+            inner,
+            metrics,
+        }
+    }
+
+    /// Metrics of the steps taken so far
+    pub fn metrics(&self) -> TraversalMetrics {
+        self.metrics
+    }
+
+    /// The wrapped traversal, e.g. for its own accessors
+    pub fn get_ref(&self) -> &I {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<'a, I: Traversal<'a>> Traversal<'a> for Instrumented<'a, I> {
+    type Value = I::Value;
+    type Graph = I::Graph;
+
+    fn graph(&self) -> &'a I::Graph {
+        self.graph
+    }
+
+    fn parent(&self) -> Option<usize> {
+        self.inner.parent()
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.inner.frontier_len()
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        let started = Instant::now();
+        let next = self.inner.next_index();
+        if let Some(index) = next {
+            self.metrics.nodes_visited += 1;
+            self.metrics.edges_examined += self.graph.neighbors(NodeId::new(index)).count();
+        }
+        self.metrics.max_frontier = self.metrics.max_frontier.max(self.inner.frontier_len());
+        self.metrics.elapsed += started.elapsed();
+        next
+    }
+}
+
+impl<'a, I: Traversal<'a>> Iterator for Instrumented<'a, I> {
+    type Item = &'a <I::Graph as GraphBase>::Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+        self.next_index()
+            .map(|index| graph.node(NodeId::new(index)))
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::graph::{Graph, Node};
+use crate::isomorphism::find_subgraph_matches;
+
+/// Witness that a graph contains a minor: `branch_sets[v]` is the connected set
+/// of input nodes contracted onto node v of the minor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinorModel {
+    pub branch_sets: Vec<Vec<usize>>,
+}
+
+/// Simple undirected graph with each node's original members, used while
+/// searching contractions
+#[derive(Clone)]
+struct ContractionState {
+    adjacency: Vec<BTreeSet<usize>>,
+    members: Vec<Vec<usize>>,
+}
+
+impl ContractionState {
+    fn edge_count(&self) -> usize {
+        self.adjacency.iter().map(BTreeSet::len).sum::<usize>() / 2
+    }
+
+    fn to_graph(&self) -> Graph {
+        let nodes = self
+            .adjacency
+            .iter()
+            .map(|neighbors| Node {
+                value: 0,
+                neighbors: neighbors.iter().copied().collect(),
+            })
+            .collect();
+        Graph { nodes }
+    }
+
+    /// Merge node b into node a, shifting indices above b down by one
+    fn contract(&self, a: usize, b: usize) -> ContractionState {
+        let shift = |v: usize| if v > b { v - 1 } else { v };
+        let mut state = self.clone();
+        let moved = std::mem::take(&mut state.adjacency[b]);
+        let members = std::mem::take(&mut state.members[b]);
+        state.members[a].extend(members);
+        for w in moved {
+            state.adjacency[w].remove(&b);
+            if w != a {
+                state.adjacency[w].insert(a);
+                state.adjacency[a].insert(w);
+            }
+        }
+        state.adjacency.remove(b);
+        state.members.remove(b);
+        for neighbors in &mut state.adjacency {
+            *neighbors = neighbors.iter().map(|&v| shift(v)).collect();
+        }
+        state
+    }
+
+    /// Drop nodes without edges, which can only serve isolated minor nodes
+    fn without_isolated(&self) -> ContractionState {
+        let keep: Vec<usize> = (0..self.adjacency.len())
+            .filter(|&v| !self.adjacency[v].is_empty())
+            .collect();
+        let position: HashMap<usize, usize> =
+            keep.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        ContractionState {
+            adjacency: keep
+                .iter()
+                .map(|&v| self.adjacency[v].iter().map(|w| position[w]).collect())
+                .collect(),
+            members: keep.iter().map(|&v| self.members[v].clone()).collect(),
+        }
+    }
+}
+
+/// Depth-first search over edge contractions for a state that contains the
+/// minor as a subgraph, skipping states isomorphic to ones already explored
+fn search_minor(
+    state: ContractionState,
+    minor: &Graph,
+    minor_edges: usize,
+    seen: &mut HashSet<Vec<i64>>,
+) -> Option<MinorModel> {
+    let n = state.adjacency.len();
+    if n < minor.nodes.len() || state.edge_count() < minor_edges {
+        return None;
+    }
+    let graph = state.to_graph();
+    if !seen.insert(graph.canonical_form().code) {
+        return None;
+    }
+    if let Some(mapping) = find_subgraph_matches(minor, &graph, 1).pop() {
+        let branch_sets = mapping
+            .into_iter()
+            .map(|v| {
+                let mut members = state.members[v.index()].clone();
+                members.sort_unstable();
+                members
+            })
+            .collect();
+        return Some(MinorModel { branch_sets });
+    }
+    if n == minor.nodes.len() {
+        return None;
+    }
+    for a in 0..n {
+        for &b in state.adjacency[a].range(a + 1..) {
+            if let Some(model) = search_minor(state.contract(a, b), minor, minor_edges, seen) {
+                return Some(model);
+            }
+        }
+    }
+    None
+}
+
+impl<T> Graph<T> {
+    /// Find a model of `minor` in this graph, reading both as undirected simple
+    /// graphs. A graph has H as a minor exactly when some contraction of it has
+    /// H as a subgraph, so this searches contractions with isomorphism-based
+    /// memoization; it is exponential and meant for small minors and inputs.
+    /// Planar inputs are rejected at once for non-planar minors like K5 or K3,3.
+    pub fn minor_model(&self, minor: &Graph) -> Option<MinorModel> {
+        if self.is_planar() && !minor.is_planar() {
+            return None;
+        }
+        let symmetric = |graph: &Graph| {
+            let adjacency = graph.undirected_adjacency();
+            let nodes = adjacency
+                .into_iter()
+                .map(|neighbors| Node {
+                    value: 0,
+                    neighbors,
+                })
+                .collect();
+            Graph { nodes }
+        };
+        let minor = symmetric(minor);
+        let minor_edges = minor.undirected_edge_indices().len();
+        let mut state = ContractionState {
+            adjacency: self
+                .undirected_adjacency()
+                .into_iter()
+                .map(|neighbors| neighbors.into_iter().collect())
+                .collect(),
+            members: (0..self.nodes.len()).map(|v| vec![v]).collect(),
+        };
+        if minor.nodes.iter().all(|node| !node.neighbors.is_empty()) {
+            state = state.without_isolated();
+        }
+        search_minor(state, &minor, minor_edges, &mut HashSet::new())
+    }
+
+    pub fn has_minor(&self, minor: &Graph) -> bool {
+        self.minor_model(minor).is_some()
+    }
+}
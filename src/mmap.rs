@@ -0,0 +1,175 @@
+use memmap2::{Advice, Mmap};
+
+use crate::base::GraphBase;
+use crate::error::LoadError;
+use crate::graph::NodeId;
+use crate::io::{BINARY_MAGIC, BINARY_VERSION};
+use crate::traversal::{BfsIterator, DfsIterator};
+use crate::visited::BitVisited;
+
+/// Read-only graph over a file in the `write_binary` layout, memory-mapped
+/// rather than loaded: the operating system pages adjacency in as traversals
+/// touch it and can evict it again, so BFS runs over graphs larger than RAM
+/// with only the visited set in memory. Implements `GraphBase`, yielding node
+/// values as `CsrGraph` does. Little-endian targets only, since node values
+/// are borrowed straight from the file.
+///
+/// The file must not be changed or truncated while it is mapped.
+pub struct MmapGraph {
+    map: Mmap,
+    nodes: usize,
+    edges: usize,
+}
+
+impl MmapGraph {
+    const HEADER: usize = 28;
+
+    /// Map `path`, checking the magic bytes, the version, the file length,
+    /// and in one sequential pass every offset and target, so that later
+    /// accesses cannot go out of bounds
+    pub fn open(path: &std::path::Path) -> Result<MmapGraph, LoadError> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the mapping is only read, and the caller keeps the file
+        // unchanged while it is mapped, as documented on the type
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < Self::HEADER || &map[..8] != BINARY_MAGIC {
+            return Err(LoadError::Format("not a binary graph file".to_string()));
+        }
+        let version = u32::from_le_bytes(map[8..12].try_into().unwrap());
+        if version != BINARY_VERSION {
+            return Err(LoadError::Format(format!(
+                "unsupported binary graph version {}",
+                version
+            )));
+        }
+        let word = |i: usize| u64::from_le_bytes(map[i..i + 8].try_into().unwrap());
+        let (nodes, edges) = (word(12), word(20));
+        let expected = (nodes <= u64::from(u32::MAX))
+            .then(|| {
+                (Self::HEADER as u64)
+                    .checked_add(4 * nodes + 8 * (nodes + 1))?
+                    .checked_add(edges.checked_mul(4)?)
+            })
+            .flatten();
+        if expected != Some(map.len() as u64) {
+            return Err(LoadError::Format(
+                "binary graph has the wrong length".to_string(),
+            ));
+        }
+        let graph = MmapGraph {
+            map,
+            nodes: nodes as usize,
+            edges: edges as usize,
+        };
+        graph.check()?;
+        // Traversals jump around the file; don't read ahead on every fault
+        let _ = graph.map.advise(Advice::Random);
+        Ok(graph)
+    }
+
+    fn check(&self) -> Result<(), LoadError> {
+        let mut previous = 0;
+        for index in 0..=self.nodes {
+            let offset = self.offset(index);
+            if offset < previous || offset > self.edges || (index == 0 && offset != 0) {
+                return Err(LoadError::Format(format!(
+                    "corrupt offsets for node {}",
+                    index.saturating_sub(1)
+                )));
+            }
+            previous = offset;
+        }
+        if previous != self.edges {
+            return Err(LoadError::Format(
+                "offsets do not cover every edge".to_string(),
+            ));
+        }
+        let targets = &self.map[self.targets_start()..];
+        for target in targets.chunks_exact(4) {
+            let target = u32::from_le_bytes(target.try_into().unwrap());
+            if target as usize >= self.nodes {
+                return Err(LoadError::Format(format!(
+                    "edge target {} out of range",
+                    target
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.nodes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes == 0
+    }
+
+    /// Number of directed edges
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    /// Node values, borrowed from the mapping
+    pub fn values(&self) -> &[i32] {
+        let bytes = &self.map[Self::HEADER..Self::HEADER + 4 * self.nodes];
+        // SAFETY: any 4 bytes are a valid i32, and the check below rejects a
+        // misaligned section; the page-aligned mapping puts it at offset 28
+        let (prefix, values, _) = unsafe { bytes.align_to::<i32>() };
+        assert!(prefix.is_empty(), "binary graph values are misaligned");
+        values
+    }
+
+    /// Out-neighbors of `id` in order; panics if `id` is out of range
+    pub fn neighbors(&self, id: NodeId) -> impl DoubleEndedIterator<Item = NodeId> + '_ {
+        assert!(id.index() < self.nodes, "node out of range");
+        let (start, end) = (self.offset(id.index()), self.offset(id.index() + 1));
+        let targets = self.targets_start();
+        self.map[targets + 4 * start..targets + 4 * end]
+            .chunks_exact(4)
+            .map(|target| NodeId::new(u32::from_le_bytes(target.try_into().unwrap()) as usize))
+    }
+
+    /// Breadth-first traversal from `start` with a `BitVisited` set, one bit
+    /// per node; the iterator yields node values
+    pub fn bfs(&self, start: NodeId) -> BfsIterator<'_, i32, BitVisited, Self> {
+        BfsIterator::with_visited(self, start, BitVisited::with_len(self.nodes))
+    }
+
+    /// Depth-first traversal from `start` with a `BitVisited` set; the
+    /// iterator yields node values
+    pub fn dfs(&self, start: NodeId) -> DfsIterator<'_, i32, BitVisited, Self> {
+        DfsIterator::with_visited(self, start, BitVisited::with_len(self.nodes))
+    }
+
+    fn offset(&self, index: usize) -> usize {
+        let at = Self::HEADER + 4 * self.nodes + 8 * index;
+        u64::from_le_bytes(self.map[at..at + 8].try_into().unwrap()) as usize
+    }
+
+    fn targets_start(&self) -> usize {
+        Self::HEADER + 4 * self.nodes + 8 * (self.nodes + 1)
+    }
+}
+
+impl GraphBase for MmapGraph {
+    type Value = i32;
+    type Node = i32;
+
+    fn node_count(&self) -> usize {
+        self.nodes
+    }
+
+    fn node(&self, id: NodeId) -> &i32 {
+        &self.values()[id.index()]
+    }
+
+    fn node_value(node: &i32) -> &i32 {
+        node
+    }
+
+    fn neighbors(&self, id: NodeId) -> impl DoubleEndedIterator<Item = NodeId> + '_ {
+        MmapGraph::neighbors(self, id)
+    }
+}
@@ -0,0 +1,153 @@
+use crate::graph::{Graph, NodeId};
+
+/// Closed tour through every node of a complete graph, from the TSP
+/// heuristics: `order` lists each node once, starting with the start node,
+/// and the tour returns from the last node to the first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tour {
+    pub order: Vec<NodeId>,
+    /// Total distance, the closing edge included
+    pub length: u64,
+}
+
+impl<T> Graph<T> {
+    /// A directed path through every node exactly once, trying each start
+    /// in index order; see `hamiltonian_path_from`
+    pub fn hamiltonian_path(&self) -> Option<Vec<NodeId>> {
+        (0..self.nodes.len()).find_map(|start| self.hamiltonian_path_from(NodeId::new(start)))
+    }
+
+    /// A directed path from `start` through every node exactly once, by
+    /// backtracking. Neighbors with the fewest unvisited onward neighbors are
+    /// tried first (Warnsdorff's rule), which finds paths quickly when there
+    /// are many, but the search is exponential in the worst case, so it is
+    /// for small graphs. None if there is no such path or `start` is out of
+    /// range.
+    pub fn hamiltonian_path_from(&self, start: NodeId) -> Option<Vec<NodeId>> {
+        self.check_node(start).ok()?;
+        let mut visited = vec![false; self.nodes.len()];
+        let mut path = vec![start.index()];
+        visited[start.index()] = true;
+        self.extend_hamiltonian(&mut path, &mut visited)
+            .then(|| path.into_iter().map(NodeId::new).collect())
+    }
+
+    /// Extend `path` to cover every node, leaving it complete on success and
+    /// unchanged on failure
+    fn extend_hamiltonian(&self, path: &mut Vec<usize>, visited: &mut [bool]) -> bool {
+        if path.len() == self.nodes.len() {
+            return true;
+        }
+        let last = *path.last().expect("the path holds the start");
+        let onward = |node: usize| {
+            self.nodes[node]
+                .neighbors
+                .iter()
+                .filter(|&&neighbor| !visited[neighbor])
+                .count()
+        };
+        let mut candidates: Vec<(usize, usize)> = self.nodes[last]
+            .neighbors
+            .iter()
+            .filter(|&&neighbor| !visited[neighbor])
+            .map(|&neighbor| (onward(neighbor), neighbor))
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        for (_, next) in candidates {
+            visited[next] = true;
+            path.push(next);
+            if self.extend_hamiltonian(path, visited) {
+                return true;
+            }
+            path.pop();
+            visited[next] = false;
+        }
+        false
+    }
+}
+
+/// Closed tour of the complete graph on nodes `0..len` by the nearest
+/// neighbor heuristic: from `start`, always move to the closest unvisited
+/// node, ties to the smaller index. Panics if `start` is out of range.
+pub fn nearest_neighbor_tour<D>(len: usize, start: NodeId, distance: D) -> Tour
+where
+    D: Fn(NodeId, NodeId) -> u64,
+{
+    assert!(start.index() < len, "start node out of range");
+    let mut visited = vec![false; len];
+    let mut order = vec![start];
+    visited[start.index()] = true;
+    while order.len() < len {
+        let last = *order.last().expect("the tour holds the start");
+        let next = (0..len)
+            .filter(|&node| !visited[node])
+            .min_by_key(|&node| (distance(last, NodeId::new(node)), node))
+            .expect("an unvisited node remains");
+        visited[next] = true;
+        order.push(NodeId::new(next));
+    }
+    let length = tour_length(&order, &distance);
+    Tour { order, length }
+}
+
+/// Improve `tour` by 2-opt moves until none helps: reverse a stretch of the
+/// tour whenever swapping its two end edges for the two across shortens it.
+/// Distances should be symmetric, since a reversed stretch is walked the other
+/// way round. The start node stays first.
+pub fn two_opt<D>(tour: Tour, distance: D) -> Tour
+where
+    D: Fn(NodeId, NodeId) -> u64,
+{
+    let mut order = tour.order;
+    let len = order.len();
+    let d = |a: NodeId, b: NodeId| distance(a, b) as i128;
+    let mut improved = true;
+    while improved {
+        improved = false;
+        // Edges (order[i], order[i + 1]) and (order[j], order[j + 1]), the
+        // latter wrapping round to the start
+        for i in 0..len.saturating_sub(2) {
+            for j in i + 2..len {
+                let (a, b) = (order[i], order[i + 1]);
+                let (c, e) = (order[j], order[(j + 1) % len]);
+                if e == a {
+                    continue;
+                }
+                if d(a, c) + d(b, e) < d(a, b) + d(c, e) {
+                    order[i + 1..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+    let length = tour_length(&order, &distance);
+    Tour { order, length }
+}
+
+/// Short closed tour of the complete graph on nodes `0..len` with symmetric
+/// `distance`: a nearest neighbor tour from node 0, improved by `two_opt`.
+/// None for an empty graph.
+pub fn tsp_tour<D>(len: usize, distance: D) -> Option<Tour>
+where
+    D: Fn(NodeId, NodeId) -> u64,
+{
+    if len == 0 {
+        return None;
+    }
+    let tour = nearest_neighbor_tour(len, NodeId::new(0), &distance);
+    Some(two_opt(tour, &distance))
+}
+
+fn tour_length<D>(order: &[NodeId], distance: &D) -> u64
+where
+    D: Fn(NodeId, NodeId) -> u64,
+{
+    let closing = order.last().copied().zip(order.first().copied());
+    order
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .chain(closing.filter(|_| order.len() > 1))
+        .map(|(from, to)| distance(from, to))
+        .sum()
+}
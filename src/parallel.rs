@@ -0,0 +1,224 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+
+use crate::base::GraphBase;
+use crate::csr::CsrGraph;
+use crate::graph::{Graph, NodeId};
+use crate::paths::ShortestPaths;
+use crate::stats::extremes;
+use crate::traversal::Traversal;
+use crate::walks::{WalkOptions, Walker};
+use crate::workspace::{bfs_iter_with, TraversalWorkspace};
+
+/// Marks nodes not reached yet, in the distance and claim arrays
+const UNSEEN: usize = usize::MAX;
+
+/// Level-synchronous parallel BFS from `start`: each level's frontier is
+/// expanded across the rayon thread pool, and the next frontier is gathered
+/// before any node of it is expanded. Distances are hop counts. Parents are
+/// the ones `BfsIterator` would record (the earliest-visited node of the
+/// previous level with an edge to the node), so the result does not depend on
+/// thread scheduling. Panics if `start` is out of range.
+pub fn par_bfs<G: GraphBase + Sync>(graph: &G, start: NodeId) -> ShortestPaths {
+    let len = graph.node_count();
+    assert!(start.index() < len, "node index out of range");
+    let distances: Vec<AtomicUsize> = (0..len).map(|_| AtomicUsize::new(UNSEEN)).collect();
+    // Smallest frontier position with an edge to each node, during one level
+    let claims: Vec<AtomicUsize> = (0..len).map(|_| AtomicUsize::new(UNSEEN)).collect();
+    let mut parents = vec![None; len];
+
+    distances[start.index()].store(0, Ordering::Relaxed);
+    let mut frontier = vec![start.index()];
+    let mut level = 0;
+    while !frontier.is_empty() {
+        // Every edge into an unseen node, in frontier order then neighbor order;
+        // each target remembers the first frontier node reaching it
+        let candidates: Vec<(usize, usize)> = frontier
+            .par_iter()
+            .enumerate()
+            .flat_map_iter(|(position, &node_index)| {
+                let (distances, claims) = (&distances, &claims);
+                graph
+                    .neighbors(NodeId::new(node_index))
+                    .map(|neighbor| neighbor.index())
+                    .filter(move |&neighbor| distances[neighbor].load(Ordering::Relaxed) == UNSEEN)
+                    .map(move |neighbor| {
+                        claims[neighbor].fetch_min(position, Ordering::Relaxed);
+                        (neighbor, position)
+                    })
+            })
+            .collect();
+
+        // Keep each target once, from its claiming frontier node; the order of
+        // the survivors is the order a sequential BFS would queue them in
+        level += 1;
+        let next: Vec<(usize, usize)> = candidates
+            .into_par_iter()
+            .filter(|&(neighbor, position)| {
+                claims[neighbor].load(Ordering::Relaxed) == position
+                    && distances[neighbor]
+                        .compare_exchange(UNSEEN, level, Ordering::Relaxed, Ordering::Relaxed)
+                        .is_ok()
+            })
+            .collect();
+
+        for &(neighbor, position) in &next {
+            parents[neighbor] = Some(frontier[position]);
+        }
+        frontier = next.into_iter().map(|(neighbor, _)| neighbor).collect();
+    }
+
+    let distances = distances
+        .into_iter()
+        .map(|distance| {
+            let distance = distance.into_inner();
+            (distance != UNSEEN).then_some(distance as u64)
+        })
+        .collect();
+    ShortestPaths {
+        start: start.index(),
+        distances,
+        predecessors: parents,
+    }
+}
+
+/// One independent BFS per source, run concurrently across the rayon thread
+/// pool; result `i` holds the hop distances and BFS parents from `sources[i]`,
+/// exactly as `par_bfs` or `BfsIterator` would give them. This is not a single
+/// BFS seeded with every source (that is `BfsIterator::from_sources`). Each
+/// worker thread reuses one `TraversalWorkspace` for all the sources it takes.
+/// Panics if a source is out of range.
+pub fn par_multi_source_bfs<G: GraphBase + Sync>(
+    graph: &G,
+    sources: &[NodeId],
+) -> Vec<ShortestPaths> {
+    let len = graph.node_count();
+    assert!(
+        sources.iter().all(|source| source.index() < len),
+        "node index out of range"
+    );
+    sources
+        .par_iter()
+        .map_init(
+            || TraversalWorkspace::with_capacity(len),
+            |workspace, &source| {
+                let mut paths = ShortestPaths::new(source.index(), len);
+                let mut bfs = bfs_iter_with(graph, source, workspace);
+                while let Some(index) = bfs.next_index() {
+                    paths.distances[index] = Some(bfs.depth() as u64);
+                    paths.predecessors[index] = bfs.parent();
+                }
+                paths
+            },
+        )
+        .collect()
+}
+
+/// `Graph::biased_walks` with the walks spread across the rayon thread pool;
+/// every walk has its own seed, so the result is identical to the sequential
+/// one whatever the scheduling
+pub fn par_biased_walks<T, W>(
+    graph: &Graph<T>,
+    walks_per_node: usize,
+    options: &WalkOptions,
+    weight: W,
+    seed: u64,
+) -> Vec<Vec<NodeId>>
+where
+    T: Sync,
+    W: Fn(NodeId, NodeId) -> u64 + Sync,
+{
+    let walker = Walker::new(graph, options, weight);
+    (0..walks_per_node * graph.len())
+        .into_par_iter()
+        .map(|position| walker.walk_at(position, seed))
+        .collect()
+}
+
+impl<T: Sync> Graph<T> {
+    /// Hop distances and BFS parents from `start` for every node, computed in
+    /// parallel one level at a time; see `parallel::par_bfs`
+    pub fn par_bfs(&self, start: NodeId) -> ShortestPaths {
+        par_bfs(self, start)
+    }
+
+    /// One BFS per source, run concurrently; see `parallel::par_multi_source_bfs`
+    pub fn par_multi_source_bfs(&self, sources: &[NodeId]) -> Vec<ShortestPaths> {
+        par_multi_source_bfs(self, sources)
+    }
+
+    /// Biased walks from every node, generated concurrently; see
+    /// `parallel::par_biased_walks`
+    pub fn par_biased_walks<W>(
+        &self,
+        walks_per_node: usize,
+        options: &WalkOptions,
+        weight: W,
+        seed: u64,
+    ) -> Vec<Vec<NodeId>>
+    where
+        W: Fn(NodeId, NodeId) -> u64 + Sync,
+    {
+        par_biased_walks(self, walks_per_node, options, weight, seed)
+    }
+
+    /// `betweenness_centrality` with the per-source Brandes passes spread
+    /// across the rayon thread pool; equal to the sequential result up to
+    /// floating-point summation order
+    pub fn par_betweenness_centrality(&self) -> Vec<f64> {
+        let n = self.nodes.len();
+        (0..n)
+            .into_par_iter()
+            .map(|source| self.dependencies(source))
+            .reduce(
+                || vec![0.0; n],
+                |mut total, dependency| {
+                    for (sum, value) in total.iter_mut().zip(dependency) {
+                        *sum += value;
+                    }
+                    total
+                },
+            )
+    }
+
+    /// `closeness_centrality` with one BFS per node run concurrently; the
+    /// result is identical to the sequential one
+    pub fn par_closeness_centrality(&self) -> Vec<f64> {
+        (0..self.nodes.len())
+            .into_par_iter()
+            .map(|node| self.closeness(node))
+            .collect()
+    }
+
+    /// `eccentricities` with one BFS per node run concurrently
+    pub fn par_eccentricities(&self) -> Vec<Option<usize>> {
+        (0..self.nodes.len())
+            .into_par_iter()
+            .map(|node| self.eccentricity(NodeId::new(node)))
+            .collect()
+    }
+
+    /// `center` from `par_eccentricities`
+    pub fn par_center(&self) -> Vec<NodeId> {
+        extremes(&self.par_eccentricities(), false)
+    }
+
+    /// `periphery` from `par_eccentricities`
+    pub fn par_periphery(&self) -> Vec<NodeId> {
+        extremes(&self.par_eccentricities(), true)
+    }
+}
+
+impl<T: Sync> CsrGraph<T> {
+    /// Hop distances and BFS parents from `start` for every node, computed in
+    /// parallel one level at a time; see `parallel::par_bfs`
+    pub fn par_bfs(&self, start: NodeId) -> ShortestPaths {
+        par_bfs(self, start)
+    }
+
+    /// One BFS per source, run concurrently; see `parallel::par_multi_source_bfs`
+    pub fn par_multi_source_bfs(&self, sources: &[NodeId]) -> Vec<ShortestPaths> {
+        par_multi_source_bfs(self, sources)
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+
+use crate::graph::{Graph, NodeId};
+use crate::sampling::Rng;
+
+/// A split of the nodes into numbered parts, with cut metrics computed from
+/// the undirected edges crossing between parts
+#[derive(Debug, Clone)]
+pub struct Partition {
+    pub parts: Vec<usize>, // Part of each node
+    pub part_count: usize,
+    pub cut_size: usize,     // Edges with endpoints in different parts
+    pub ratio_cut: f64,      // Sum over parts of cut(P) / |P|
+    pub normalized_cut: f64, // Sum over parts of cut(P) / vol(P)
+}
+
+impl Partition {
+    /// Renumber parts in order of first appearance and compute the metrics
+    pub fn new<T>(graph: &Graph<T>, parts: Vec<usize>) -> Self {
+        let mut renumber = HashMap::new();
+        let parts: Vec<usize> = parts
+            .into_iter()
+            .map(|part| {
+                let next = renumber.len();
+                *renumber.entry(part).or_insert(next)
+            })
+            .collect();
+        let part_count = renumber.len();
+
+        let mut sizes = vec![0usize; part_count];
+        let mut volumes = vec![0usize; part_count];
+        let mut cuts = vec![0usize; part_count];
+        for &part in &parts {
+            sizes[part] += 1;
+        }
+        let mut cut_size = 0;
+        for (a, b) in graph.undirected_edge_indices() {
+            volumes[parts[a]] += 1;
+            volumes[parts[b]] += 1;
+            if parts[a] != parts[b] {
+                cut_size += 1;
+                cuts[parts[a]] += 1;
+                cuts[parts[b]] += 1;
+            }
+        }
+        let ratio_cut = (0..part_count)
+            .map(|p| cuts[p] as f64 / sizes[p] as f64)
+            .sum();
+        let normalized_cut = (0..part_count)
+            .filter(|&p| volumes[p] > 0)
+            .map(|p| cuts[p] as f64 / volumes[p] as f64)
+            .sum();
+        Self {
+            parts,
+            part_count,
+            cut_size,
+            ratio_cut,
+            normalized_cut,
+        }
+    }
+
+    /// Nodes assigned to `part`
+    pub fn members(&self, part: usize) -> Vec<NodeId> {
+        (0..self.parts.len())
+            .filter(|&v| self.parts[v] == part)
+            .map(NodeId::new)
+            .collect()
+    }
+
+    /// Largest part size over the ideal size; 1.0 is perfectly balanced
+    pub fn balance(&self) -> f64 {
+        if self.parts.is_empty() {
+            return 1.0;
+        }
+        let mut sizes = vec![0usize; self.part_count];
+        for &part in &self.parts {
+            sizes[part] += 1;
+        }
+        let ideal = self.parts.len() as f64 / self.part_count as f64;
+        *sizes.iter().max().unwrap() as f64 / ideal
+    }
+}
+
+/// One level of the multilevel partitioner: node weights count merged original
+/// nodes and edge weights count merged original edges
+struct CoarseLevel {
+    node_weights: Vec<u64>,
+    adjacency: Vec<Vec<(usize, u64)>>,
+}
+
+impl CoarseLevel {
+    fn from_graph<T>(graph: &Graph<T>) -> Self {
+        Self {
+            node_weights: vec![1; graph.nodes.len()],
+            adjacency: graph
+                .undirected_adjacency()
+                .into_iter()
+                .map(|neighbors| neighbors.into_iter().map(|w| (w, 1)).collect())
+                .collect(),
+        }
+    }
+
+    /// Heavy-edge matching: pair each node with the unmatched neighbor it shares
+    /// the heaviest edge with. Returns the coarser level and the coarse node of
+    /// every node here.
+    fn coarsen(&self, rng: &mut Rng) -> (CoarseLevel, Vec<usize>) {
+        let n = self.node_weights.len();
+        let mut order: Vec<usize> = (0..n).collect();
+        rng.shuffle(&mut order);
+        let mut coarse = vec![usize::MAX; n];
+        let mut count = 0;
+        for &v in &order {
+            if coarse[v] != usize::MAX {
+                continue;
+            }
+            let mate = self.adjacency[v]
+                .iter()
+                .filter(|&&(w, _)| coarse[w] == usize::MAX && w != v)
+                .max_by_key(|&&(_, weight)| weight)
+                .map(|&(w, _)| w);
+            coarse[v] = count;
+            if let Some(w) = mate {
+                coarse[w] = count;
+            }
+            count += 1;
+        }
+
+        let mut node_weights = vec![0; count];
+        let mut edges: Vec<HashMap<usize, u64>> = vec![HashMap::new(); count];
+        for v in 0..n {
+            node_weights[coarse[v]] += self.node_weights[v];
+            for &(w, weight) in &self.adjacency[v] {
+                if coarse[v] != coarse[w] {
+                    *edges[coarse[v]].entry(coarse[w]).or_insert(0) += weight;
+                }
+            }
+        }
+        let adjacency = edges
+            .into_iter()
+            .map(|edges| {
+                let mut edges: Vec<(usize, u64)> = edges.into_iter().collect();
+                edges.sort_unstable();
+                edges
+            })
+            .collect();
+        (
+            CoarseLevel {
+                node_weights,
+                adjacency,
+            },
+            coarse,
+        )
+    }
+
+    /// Greedy graph growing: fill parts one at a time, always adding the
+    /// frontier node most strongly connected to the growing part
+    fn initial_partition(&self, k: usize, rng: &mut Rng) -> Vec<usize> {
+        let n = self.node_weights.len();
+        let total: u64 = self.node_weights.iter().sum();
+        let mut parts = vec![usize::MAX; n];
+        for part in 0..k.saturating_sub(1) {
+            let target = total / k as u64;
+            let mut weight = 0;
+            let mut connection: HashMap<usize, u64> = HashMap::new();
+            while weight < target {
+                let next = connection
+                    .iter()
+                    .filter(|&(&v, _)| parts[v] == usize::MAX)
+                    .max_by_key(|&(&v, &c)| (c, std::cmp::Reverse(v)))
+                    .map(|(&v, _)| v)
+                    .or_else(|| {
+                        // Start (or restart) growing from a random free node
+                        let free: Vec<usize> = (0..n).filter(|&v| parts[v] == usize::MAX).collect();
+                        (!free.is_empty()).then(|| free[rng.below(free.len())])
+                    });
+                let Some(v) = next else { break };
+                parts[v] = part;
+                weight += self.node_weights[v];
+                connection.remove(&v);
+                for &(w, edge_weight) in &self.adjacency[v] {
+                    if parts[w] == usize::MAX {
+                        *connection.entry(w).or_insert(0) += edge_weight;
+                    }
+                }
+            }
+        }
+        for part in parts.iter_mut().filter(|part| **part == usize::MAX) {
+            *part = k - 1;
+        }
+        parts
+    }
+
+    /// Total weight of edges between different parts
+    fn cut_weight(&self, parts: &[usize]) -> u64 {
+        let doubled: u64 = (0..parts.len())
+            .flat_map(|v| {
+                self.adjacency[v]
+                    .iter()
+                    .map(move |&(w, weight)| (v, w, weight))
+            })
+            .filter(|&(v, w, _)| parts[v] != parts[w])
+            .map(|(_, _, weight)| weight)
+            .sum();
+        doubled / 2
+    }
+
+    /// Greedy boundary refinement: move nodes to the neighboring part they are
+    /// most connected to while that reduces the cut and respects `max_weight`
+    fn refine(&self, parts: &mut [usize], k: usize, max_weight: u64, rng: &mut Rng) {
+        let n = self.node_weights.len();
+        let mut part_weights = vec![0u64; k];
+        let mut part_sizes = vec![0usize; k];
+        for v in 0..n {
+            part_weights[parts[v]] += self.node_weights[v];
+            part_sizes[parts[v]] += 1;
+        }
+        let mut order: Vec<usize> = (0..n).collect();
+        for _ in 0..10 {
+            rng.shuffle(&mut order);
+            let mut moved = false;
+            for &v in &order {
+                let current = parts[v];
+                if part_sizes[current] == 1 {
+                    continue;
+                }
+                let mut connection = vec![0u64; k];
+                for &(w, weight) in &self.adjacency[v] {
+                    connection[parts[w]] += weight;
+                }
+                let best = (0..k)
+                    .filter(|&p| p != current)
+                    .filter(|&p| part_weights[p] + self.node_weights[v] <= max_weight)
+                    .max_by_key(|&p| (connection[p], std::cmp::Reverse(part_weights[p])));
+                // Take strict improvements, and zero-gain moves that even out part weights
+                let improves = |p: usize| {
+                    connection[p] > connection[current]
+                        || (connection[p] == connection[current]
+                            && connection[p] > 0
+                            && part_weights[p] + self.node_weights[v] < part_weights[current])
+                };
+                if let Some(p) = best.filter(|&p| improves(p)) {
+                    parts[v] = p;
+                    part_weights[current] -= self.node_weights[v];
+                    part_weights[p] += self.node_weights[v];
+                    part_sizes[current] -= 1;
+                    part_sizes[p] += 1;
+                    moved = true;
+                }
+            }
+            if !moved {
+                break;
+            }
+        }
+    }
+}
+
+impl<T> Graph<T> {
+    /// Multilevel k-way partition in the style of METIS: coarsen by heavy-edge
+    /// matching, split the coarsest graph by greedy growing, then project back
+    /// while refining the boundary. Parts may exceed the ideal size by the
+    /// `imbalance` fraction (e.g. 0.03); the returned partition reports the
+    /// achieved cut size and balance.
+    pub fn multilevel_partition(&self, k: usize, imbalance: f64, seed: u64) -> Partition {
+        let n = self.nodes.len();
+        let k = k.clamp(1, n.max(1));
+        let mut rng = Rng::new(seed);
+        let max_weight = ((n as f64 / k as f64) * (1.0 + imbalance)).ceil() as u64;
+
+        let mut levels = vec![CoarseLevel::from_graph(self)];
+        let mut mappings = Vec::new();
+        loop {
+            let current = levels.last().unwrap();
+            let size = current.node_weights.len();
+            if size <= (20 * k).max(k + 1) {
+                break;
+            }
+            let (coarser, mapping) = current.coarsen(&mut rng);
+            if coarser.node_weights.len() * 10 > size * 9 {
+                break;
+            }
+            levels.push(coarser);
+            mappings.push(mapping);
+        }
+
+        // Keep the best of a few randomized initial partitions of the coarsest level
+        let coarsest = levels.last().unwrap();
+        let mut parts = Vec::new();
+        let mut best_cut = u64::MAX;
+        for _ in 0..8 {
+            let mut candidate = coarsest.initial_partition(k, &mut rng);
+            coarsest.refine(&mut candidate, k, max_weight, &mut rng);
+            let cut = coarsest.cut_weight(&candidate);
+            if cut < best_cut {
+                best_cut = cut;
+                parts = candidate;
+            }
+        }
+
+        for (level, mapping) in levels.iter().rev().skip(1).zip(mappings.iter().rev()) {
+            parts = mapping.iter().map(|&coarse| parts[coarse]).collect();
+            level.refine(&mut parts, k, max_weight, &mut rng);
+        }
+        Partition::new(self, parts)
+    }
+}
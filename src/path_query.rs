@@ -0,0 +1,237 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::error::ParseError;
+use crate::graph::{Graph, NodeId};
+
+/// Labels attached to edges, keyed by (from, to). Parallel edges share a label.
+#[derive(Debug, Clone, Default)]
+pub struct EdgeLabels {
+    labels: HashMap<(NodeId, NodeId), String>,
+}
+
+impl EdgeLabels {
+    pub fn set(&mut self, from: NodeId, to: NodeId, label: &str) {
+        self.labels.insert((from, to), label.to_string());
+    }
+
+    pub fn get(&self, from: NodeId, to: NodeId) -> Option<&str> {
+        self.labels.get(&(from, to)).map(String::as_str)
+    }
+}
+
+/// Transition of the label automaton
+#[derive(Debug, Clone)]
+enum LabelMatch {
+    Epsilon,
+    Label(String),
+    Any,
+}
+
+/// Thompson automaton over edge labels; state 0 is the start
+#[derive(Debug, Clone)]
+struct LabelAutomaton {
+    transitions: Vec<Vec<(LabelMatch, usize)>>,
+    accept: usize,
+}
+
+/// Recursive descent parser for path expressions:
+///   alternation   := sequence ('|' sequence)*
+///   sequence      := repetition ('/' repetition)*
+///   repetition    := atom ('*' | '+' | '?')*
+///   atom          := label | '.' | '(' alternation ')'
+/// where `.` matches any label. Each rule returns an (entry, exit) state pair.
+struct PathExprParser<'s> {
+    source: &'s str,
+    position: usize,
+    transitions: Vec<Vec<(LabelMatch, usize)>>,
+}
+
+impl<'s> PathExprParser<'s> {
+    fn state(&mut self) -> usize {
+        self.transitions.push(Vec::new());
+        self.transitions.len() - 1
+    }
+
+    fn link(&mut self, from: usize, to: usize, label: LabelMatch) {
+        self.transitions[from].push((label, to));
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.source[self.position..];
+        let trimmed = rest.trim_start();
+        self.position += rest.len() - trimmed.len();
+        trimmed.chars().next()
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn alternation(&mut self) -> Result<(usize, usize), ParseError> {
+        let (entry, exit) = (self.state(), self.state());
+        loop {
+            let (a, b) = self.sequence()?;
+            self.link(entry, a, LabelMatch::Epsilon);
+            self.link(b, exit, LabelMatch::Epsilon);
+            if self.peek() != Some('|') {
+                return Ok((entry, exit));
+            }
+            self.position += 1;
+        }
+    }
+
+    fn sequence(&mut self) -> Result<(usize, usize), ParseError> {
+        let (entry, mut exit) = self.repetition()?;
+        while self.peek() == Some('/') {
+            self.position += 1;
+            let (a, b) = self.repetition()?;
+            self.link(exit, a, LabelMatch::Epsilon);
+            exit = b;
+        }
+        Ok((entry, exit))
+    }
+
+    fn repetition(&mut self) -> Result<(usize, usize), ParseError> {
+        let (mut entry, mut exit) = self.atom()?;
+        while let Some(op @ ('*' | '+' | '?')) = self.peek() {
+            self.position += 1;
+            let (new_entry, new_exit) = (self.state(), self.state());
+            self.link(new_entry, entry, LabelMatch::Epsilon);
+            self.link(exit, new_exit, LabelMatch::Epsilon);
+            if op != '+' {
+                self.link(new_entry, new_exit, LabelMatch::Epsilon);
+            }
+            if op != '?' {
+                self.link(exit, entry, LabelMatch::Epsilon);
+            }
+            (entry, exit) = (new_entry, new_exit);
+        }
+        Ok((entry, exit))
+    }
+
+    fn atom(&mut self) -> Result<(usize, usize), ParseError> {
+        match self.peek() {
+            Some('(') => {
+                self.position += 1;
+                let inner = self.alternation()?;
+                if self.peek() != Some(')') {
+                    return Err(self.error("expected ')'"));
+                }
+                self.position += 1;
+                Ok(inner)
+            }
+            Some('.') => {
+                self.position += 1;
+                let (entry, exit) = (self.state(), self.state());
+                self.link(entry, exit, LabelMatch::Any);
+                Ok((entry, exit))
+            }
+            Some(c) if c.is_alphanumeric() || c == '_' => {
+                let rest = &self.source[self.position..];
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                let label = rest[..len].to_string();
+                self.position += len;
+                let (entry, exit) = (self.state(), self.state());
+                self.link(entry, exit, LabelMatch::Label(label));
+                Ok((entry, exit))
+            }
+            Some(_) => Err(self.error("expected a label, '.' or '('")),
+            None => Err(self.error("unexpected end of expression")),
+        }
+    }
+}
+
+impl LabelAutomaton {
+    fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut parser = PathExprParser {
+            source,
+            position: 0,
+            transitions: Vec::new(),
+        };
+        let (entry, accept) = parser.alternation()?;
+        if parser.peek().is_some() {
+            return Err(parser.error("unexpected trailing input"));
+        }
+        debug_assert_eq!(entry, 0);
+        Ok(Self {
+            transitions: parser.transitions,
+            accept,
+        })
+    }
+}
+
+impl<T> Graph<T> {
+    /// Evaluate a regular path query: every node reachable from `start` along
+    /// a path whose edge labels spell a word of `expression`, each with one
+    /// shortest witness path. Expressions combine labels with `/` (then),
+    /// `|` (or), `*`, `+`, `?` and parentheses; `.` matches any label.
+    /// Runs a BFS over the product of the graph and the label automaton; an
+    /// out-of-range `start` reaches nothing.
+    pub fn query_paths(
+        &self,
+        labels: &EdgeLabels,
+        start: NodeId,
+        expression: &str,
+    ) -> Result<Vec<(NodeId, Vec<NodeId>)>, ParseError> {
+        let automaton = LabelAutomaton::parse(expression)?;
+        if self.check_node(start).is_err() {
+            return Ok(Vec::new());
+        }
+        let start = start.index();
+        let mut previous: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        let mut queue = VecDeque::from([(start, 0)]);
+        previous.insert((start, 0), (start, 0));
+        let mut results = Vec::new();
+        let mut reported = HashSet::new();
+
+        while let Some((node, state)) = queue.pop_front() {
+            if state == automaton.accept && reported.insert(node) {
+                // Walk the product predecessors back to the start
+                let mut path = vec![node];
+                let mut at = (node, state);
+                while at != (start, 0) {
+                    let before = previous[&at];
+                    if before.0 != at.0 {
+                        path.push(before.0);
+                    }
+                    at = before;
+                }
+                let path = path.into_iter().rev().map(NodeId::new).collect();
+                results.push((NodeId::new(node), path));
+            }
+
+            let mut visit = |next: (usize, usize), queue: &mut VecDeque<(usize, usize)>| {
+                if let std::collections::hash_map::Entry::Vacant(entry) = previous.entry(next) {
+                    entry.insert((node, state));
+                    queue.push_back(next);
+                }
+            };
+            for (label_match, target) in &automaton.transitions[state] {
+                match label_match {
+                    LabelMatch::Epsilon => visit((node, *target), &mut queue),
+                    _ => {
+                        for &neighbor in &self.nodes[node].neighbors {
+                            let matches = match (
+                                label_match,
+                                labels.get(NodeId::new(node), NodeId::new(neighbor)),
+                            ) {
+                                (LabelMatch::Any, _) => true,
+                                (LabelMatch::Label(want), Some(have)) => want == have,
+                                _ => false,
+                            };
+                            if matches {
+                                visit((neighbor, *target), &mut queue);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(results)
+    }
+}
@@ -0,0 +1,740 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+
+use crate::base::GraphBase;
+use crate::error::NegativeCycle;
+use crate::graph::{Graph, Node, NodeId};
+use crate::traversal::{BfsIterator, DfsIterator, Traversal, TraversalTree};
+
+/// Result of a single-source shortest path search. Distances are `u64`
+/// except from `bellman_ford`, whose weights may be negative.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<D = u64> {
+    pub(crate) start: usize,
+    pub(crate) distances: Vec<Option<D>>, // None for unreachable nodes
+    pub(crate) predecessors: Vec<Option<usize>>,
+}
+
+impl<D: Copy> ShortestPaths<D> {
+    pub(crate) fn new(start: usize, len: usize) -> Self {
+        Self {
+            start,
+            distances: vec![None; len],
+            predecessors: vec![None; len],
+        }
+    }
+
+    /// Distance from the start node, if the node is reachable
+    pub fn distance(&self, node: NodeId) -> Option<D> {
+        self.distances.get(node.index()).copied().flatten()
+    }
+
+    /// The node before `node` on a shortest path from the start node; None for
+    /// the start node and unreachable nodes
+    pub fn predecessor(&self, node: NodeId) -> Option<NodeId> {
+        self.predecessors
+            .get(node.index())
+            .copied()
+            .flatten()
+            .map(NodeId::new)
+    }
+
+    /// Nodes along a shortest path from the start node to `goal`, both included
+    pub fn path_to(&self, goal: NodeId) -> Option<Vec<NodeId>> {
+        self.distance(goal)?;
+        let mut path = vec![goal];
+        let mut current = goal.index();
+        while current != self.start {
+            current = self.predecessors[current]?;
+            path.push(NodeId::new(current));
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl<T> Graph<T> {
+    /// Shortest paths from `start` when every edge weight is 0 or 1 (0-1 BFS).
+    /// Zero-weight edges go to the front of the deque and unit edges to the back,
+    /// so nodes leave the deque in distance order in O(V + E) overall. An
+    /// out-of-range `start` reaches nothing. Panics if `weight` returns more
+    /// than 1 for an edge it examines; use `dijkstra_by` for other weights.
+    pub fn zero_one_bfs<W>(&self, start: NodeId, weight: W) -> ShortestPaths
+    where
+        W: Fn(NodeId, NodeId) -> u8,
+    {
+        let start = start.index();
+        let mut paths = ShortestPaths::new(start, self.nodes.len());
+        if start >= self.nodes.len() {
+            return paths;
+        }
+        let mut done = vec![false; self.nodes.len()];
+        let mut deque = VecDeque::new();
+        paths.distances[start] = Some(0);
+        deque.push_back(start);
+
+        while let Some(node_index) = deque.pop_front() {
+            // A node can be queued twice; only the first pop is final
+            if std::mem::replace(&mut done[node_index], true) {
+                continue;
+            }
+            let distance = paths.distances[node_index].unwrap();
+
+            for &neighbor_index in &self.nodes[node_index].neighbors {
+                let edge_weight = weight(NodeId::new(node_index), NodeId::new(neighbor_index));
+                assert!(edge_weight <= 1, "0-1 BFS requires edge weights of 0 or 1");
+
+                let candidate = distance + u64::from(edge_weight);
+                if paths.distances[neighbor_index].is_none_or(|d| candidate < d) {
+                    paths.distances[neighbor_index] = Some(candidate);
+                    paths.predecessors[neighbor_index] = Some(node_index);
+                    if edge_weight == 0 {
+                        deque.push_front(neighbor_index);
+                    } else {
+                        deque.push_back(neighbor_index);
+                    }
+                }
+            }
+        }
+        paths
+    }
+}
+
+/// Non-negative edge weights keyed by (from, to); edges without an entry weigh
+/// `default`. Parallel edges share the weight of their (from, to) pair.
+#[derive(Debug, Clone)]
+pub struct EdgeWeights {
+    weights: HashMap<(usize, usize), u64>,
+    default: u64,
+}
+
+impl Default for EdgeWeights {
+    fn default() -> Self {
+        Self::with_default(1)
+    }
+}
+
+impl EdgeWeights {
+    /// No per-edge entries; every edge weighs `default`
+    pub fn with_default(default: u64) -> Self {
+        Self {
+            weights: HashMap::new(),
+            default,
+        }
+    }
+
+    pub fn set(&mut self, from: NodeId, to: NodeId, weight: u64) {
+        self.weights.insert((from.index(), to.index()), weight);
+    }
+
+    pub fn get(&self, from: NodeId, to: NodeId) -> u64 {
+        self.weights
+            .get(&(from.index(), to.index()))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// Weight of the edge from -> to
+pub type EdgeWeight<'a> = Rc<dyn Fn(NodeId, NodeId) -> u64 + 'a>;
+
+/// Dijkstra iterator: yields the nodes reachable from the start in order of
+/// increasing weighted distance, ties broken by smaller index. A node's
+/// distance and path are final once it has been yielded.
+pub struct DijkstraIterator<'a, T = i32> {
+    graph: &'a Graph<T>,
+    weight: EdgeWeight<'a>,
+    heap: BinaryHeap<Reverse<(u64, usize)>>, // (tentative distance, node index)
+    settled: Vec<bool>,
+    paths: ShortestPaths,
+    last: Option<usize>, // Node last returned by next_index
+}
+
+impl<T> Clone for DijkstraIterator<'_, T> {
+    fn clone(&self) -> Self {
+        DijkstraIterator {
+            graph: self.graph,
+            weight: Rc::clone(&self.weight),
+            heap: self.heap.clone(),
+            settled: self.settled.clone(),
+            paths: self.paths.clone(),
+            last: self.last,
+        }
+    }
+}
+
+impl<'a, T> DijkstraIterator<'a, T> {
+    /// Dijkstra from `start` with the weights in `weights`
+    pub fn new(graph: &'a Graph<T>, start: NodeId, weights: &'a EdgeWeights) -> Self {
+        Self::with_weight(graph, start, Rc::new(|from, to| weights.get(from, to)))
+    }
+
+    /// Dijkstra from `start` with edge weights computed by `weight`
+    pub fn with_weight(graph: &'a Graph<T>, start: NodeId, weight: EdgeWeight<'a>) -> Self {
+        let mut paths = ShortestPaths::new(start.index(), graph.nodes.len());
+        let mut heap = BinaryHeap::new();
+        if start.index() < graph.nodes.len() {
+            paths.distances[start.index()] = Some(0);
+            heap.push(Reverse((0, start.index())));
+        }
+        Self {
+            graph,
+            weight,
+            heap,
+            settled: vec![false; graph.nodes.len()],
+            paths,
+            last: None,
+        }
+    }
+
+    /// Distance of a node from the start: final once the node has been
+    /// yielded, otherwise the best distance found so far
+    pub fn distance(&self, node: NodeId) -> Option<u64> {
+        self.paths.distance(node)
+    }
+
+    /// Yield (node id, distance, node) triples instead of bare nodes
+    pub fn with_distances(self) -> WithDistances<'a, T> {
+        WithDistances { inner: self }
+    }
+
+    /// Shortest paths found so far, final for every yielded node
+    pub fn into_paths(self) -> ShortestPaths {
+        self.paths
+    }
+}
+
+impl<'a, T> Traversal<'a> for DijkstraIterator<'a, T> {
+    type Value = T;
+    type Graph = Graph<T>;
+
+    fn graph(&self) -> &'a Graph<T> {
+        self.graph
+    }
+
+    fn parent(&self) -> Option<usize> {
+        self.paths.predecessors[self.last?]
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        while let Some(Reverse((distance, node_index))) = self.heap.pop() {
+            // Lazy deletion: skip entries superseded by a shorter distance
+            if std::mem::replace(&mut self.settled[node_index], true) {
+                continue;
+            }
+
+            for &neighbor_index in &self.graph.nodes[node_index].neighbors {
+                if self.settled.get(neighbor_index) != Some(&false) {
+                    continue;
+                }
+                let edge_weight =
+                    (self.weight)(NodeId::new(node_index), NodeId::new(neighbor_index));
+                let candidate = distance.saturating_add(edge_weight);
+                if self.paths.distances[neighbor_index].is_none_or(|d| candidate < d) {
+                    self.paths.distances[neighbor_index] = Some(candidate);
+                    self.paths.predecessors[neighbor_index] = Some(node_index);
+                    self.heap.push(Reverse((candidate, neighbor_index)));
+                }
+            }
+            self.last = Some(node_index);
+            return Some(node_index);
+        }
+        None
+    }
+}
+
+impl<'a, T> Iterator for DijkstraIterator<'a, T> {
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+        self.next_index().map(|index| &graph.nodes[index])
+    }
+}
+
+/// Adapter returned by `DijkstraIterator::with_distances`
+pub struct WithDistances<'a, T> {
+    inner: DijkstraIterator<'a, T>,
+}
+
+impl<T> Clone for WithDistances<'_, T> {
+    fn clone(&self) -> Self {
+        WithDistances {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for WithDistances<'a, T> {
+    type Item = (NodeId, u64, &'a Node<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.inner.graph;
+        let index = self.inner.next_index()?;
+        let distance = self.inner.paths.distances[index].unwrap();
+        Some((NodeId::new(index), distance, &graph.nodes[index]))
+    }
+}
+
+impl<T> Graph<T> {
+    /// A* search from `start` to `goal`, returning the path cost and the path.
+    /// `heuristic` estimates the remaining cost to `goal`; with a heuristic that
+    /// never overestimates, the path found is a shortest one. Nodes are
+    /// re-expanded when a cheaper route to them turns up, so the heuristic need
+    /// not be consistent.
+    pub fn astar<W, H>(
+        &self,
+        start: NodeId,
+        goal: NodeId,
+        weight: W,
+        heuristic: H,
+    ) -> Option<(u64, Vec<NodeId>)>
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+        H: Fn(NodeId) -> u64,
+    {
+        let (start, goal) = (start.index(), goal.index());
+        if start >= self.nodes.len() || goal >= self.nodes.len() {
+            return None;
+        }
+        let mut paths = ShortestPaths::new(start, self.nodes.len());
+        let mut heap = BinaryHeap::new();
+        paths.distances[start] = Some(0);
+        heap.push(Reverse((heuristic(NodeId::new(start)), 0, start)));
+
+        while let Some(Reverse((_, cost, node_index))) = heap.pop() {
+            // Skip entries for nodes reached more cheaply since they were pushed
+            if paths.distances[node_index] != Some(cost) {
+                continue;
+            }
+            if node_index == goal {
+                return Some((cost, paths.path_to(NodeId::new(goal))?));
+            }
+            for &neighbor_index in &self.nodes[node_index].neighbors {
+                let candidate = cost
+                    .saturating_add(weight(NodeId::new(node_index), NodeId::new(neighbor_index)));
+                if paths.distances[neighbor_index].is_none_or(|d| candidate < d) {
+                    paths.distances[neighbor_index] = Some(candidate);
+                    paths.predecessors[neighbor_index] = Some(node_index);
+                    let estimate = candidate.saturating_add(heuristic(NodeId::new(neighbor_index)));
+                    heap.push(Reverse((estimate, candidate, neighbor_index)));
+                }
+            }
+        }
+        None
+    }
+
+    /// Dijkstra from `start` with each edge's cost computed on demand by
+    /// `cost(from, to)`, for costs that change between searches (time of
+    /// day, penalties) without rebuilding the graph or an `EdgeWeights`
+    /// table. Unlike `DijkstraIterator::with_weight` the closure is a type
+    /// parameter, so its calls are inlined rather than made through a
+    /// pointer. It is called once per edge out of each settled node, skipping
+    /// edges into nodes already settled, and in adjacency order, so a cost
+    /// table laid out as the adjacency lists is read front to back. Being
+    /// `FnMut`, it may memoize expensive costs. An out-of-range `start`
+    /// reaches nothing.
+    pub fn dijkstra_by<C>(&self, start: NodeId, cost: C) -> ShortestPaths
+    where
+        C: FnMut(NodeId, NodeId) -> u64,
+    {
+        self.dijkstra_until(start, None, cost)
+    }
+
+    /// Cheapest path from `start` to `goal` under `cost`, as (total cost,
+    /// path), calling `cost` as `dijkstra_by` does but stopping once `goal`
+    /// is settled; None if it is unreachable or either node is out of range
+    pub fn shortest_path_by<C>(
+        &self,
+        start: NodeId,
+        goal: NodeId,
+        cost: C,
+    ) -> Option<(u64, Vec<NodeId>)>
+    where
+        C: FnMut(NodeId, NodeId) -> u64,
+    {
+        self.check_node(goal).ok()?;
+        let paths = self.dijkstra_until(start, Some(goal.index()), cost);
+        Some((paths.distance(goal)?, paths.path_to(goal)?))
+    }
+
+    fn dijkstra_until<C>(&self, start: NodeId, goal: Option<usize>, mut cost: C) -> ShortestPaths
+    where
+        C: FnMut(NodeId, NodeId) -> u64,
+    {
+        let len = self.nodes.len();
+        let mut paths = ShortestPaths::new(start.index(), len);
+        if start.index() >= len {
+            return paths;
+        }
+        let mut settled = vec![false; len];
+        let mut heap = BinaryHeap::new();
+        paths.distances[start.index()] = Some(0);
+        heap.push(Reverse((0u64, start.index())));
+        while let Some(Reverse((distance, node_index))) = heap.pop() {
+            // Lazy deletion: skip entries superseded by a shorter distance
+            if std::mem::replace(&mut settled[node_index], true) {
+                continue;
+            }
+            if goal == Some(node_index) {
+                break;
+            }
+            for &neighbor_index in &self.nodes[node_index].neighbors {
+                if settled[neighbor_index] {
+                    continue;
+                }
+                let candidate = distance
+                    .saturating_add(cost(NodeId::new(node_index), NodeId::new(neighbor_index)));
+                if paths.distances[neighbor_index].is_none_or(|d| candidate < d) {
+                    paths.distances[neighbor_index] = Some(candidate);
+                    paths.predecessors[neighbor_index] = Some(node_index);
+                    heap.push(Reverse((candidate, neighbor_index)));
+                }
+            }
+        }
+        paths
+    }
+}
+
+impl<T> Graph<T> {
+    /// Shortest paths from `start` when edge weights may be negative
+    /// (Bellman-Ford), in O(V * E). Relaxes every edge out of each reached node
+    /// in rounds, stopping early once a round changes nothing. Fails with the
+    /// offending cycle if a negative-weight cycle is reachable from `start`;
+    /// unreachable ones do not matter. An out-of-range `start` reaches nothing.
+    pub fn bellman_ford<W>(
+        &self,
+        start: NodeId,
+        weight: W,
+    ) -> Result<ShortestPaths<i64>, NegativeCycle>
+    where
+        W: Fn(NodeId, NodeId) -> i64,
+    {
+        let len = self.nodes.len();
+        let mut paths = ShortestPaths::new(start.index(), len);
+        if start.index() >= len {
+            return Ok(paths);
+        }
+        paths.distances[start.index()] = Some(0);
+
+        // Without negative cycles every shortest path has fewer than `len` edges,
+        // so round `len` relaxing anything proves there is one
+        for round in 1..=len {
+            let mut relaxed = None;
+            for node_index in 0..len {
+                let Some(distance) = paths.distances[node_index] else {
+                    continue;
+                };
+                for &neighbor_index in &self.nodes[node_index].neighbors {
+                    let candidate = distance.saturating_add(weight(
+                        NodeId::new(node_index),
+                        NodeId::new(neighbor_index),
+                    ));
+                    if paths.distances[neighbor_index].is_none_or(|d| candidate < d) {
+                        paths.distances[neighbor_index] = Some(candidate);
+                        paths.predecessors[neighbor_index] = Some(node_index);
+                        relaxed = Some(neighbor_index);
+                    }
+                }
+            }
+            match relaxed {
+                None => break,
+                Some(node_index) if round == len => {
+                    return Err(negative_cycle(&paths.predecessors, node_index, len));
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(paths)
+    }
+}
+
+/// The cycle in the predecessor links behind `relaxed`, a node whose distance
+/// still dropped in the last Bellman-Ford round
+fn negative_cycle(predecessors: &[Option<usize>], relaxed: usize, len: usize) -> NegativeCycle {
+    // Walking back `len` links from a node relaxed that late lands on the cycle
+    let mut on_cycle = relaxed;
+    for _ in 0..len {
+        on_cycle = predecessors[on_cycle].expect("relaxed nodes have predecessors");
+    }
+    let mut cycle = vec![NodeId::new(on_cycle)];
+    let mut node_index = predecessors[on_cycle].unwrap();
+    while node_index != on_cycle {
+        cycle.push(NodeId::new(node_index));
+        node_index = predecessors[node_index].unwrap();
+    }
+    cycle.reverse();
+    NegativeCycle { cycle }
+}
+
+impl<T> Graph<T> {
+    /// Up to `k` loopless paths from `start` to `goal` in order of increasing
+    /// cost (Yen's algorithm), each with its cost; ties are ordered by node ids.
+    /// Every path after the first deviates from an earlier one at some node,
+    /// and the rest of it is a shortest path from there found by `astar` with
+    /// the earlier prefix's nodes and the edges the earlier paths took at that
+    /// point removed. Edges weighing `u64::MAX` count as missing.
+    pub fn k_shortest_paths<W>(
+        &self,
+        start: NodeId,
+        goal: NodeId,
+        k: usize,
+        weight: W,
+    ) -> Vec<(u64, Vec<NodeId>)>
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        let mut found: Vec<(u64, Vec<NodeId>)> = Vec::new();
+        if k == 0 {
+            return found;
+        }
+        match self.astar(start, goal, &weight, |_| 0) {
+            Some((cost, path)) if cost < u64::MAX => found.push((cost, path)),
+            _ => return found,
+        }
+        let mut candidates: BTreeSet<(u64, Vec<NodeId>)> = BTreeSet::new();
+
+        while found.len() < k {
+            let previous = found.last().unwrap().1.clone();
+            let mut root_cost = 0u64;
+            for spur_at in 0..previous.len() - 1 {
+                let (root, spur) = (&previous[..spur_at], previous[spur_at]);
+                // Edges leaving the spur node along earlier paths with this root
+                let taken: HashSet<NodeId> = found
+                    .iter()
+                    .filter(|(_, path)| {
+                        path.len() > spur_at + 1 && path[..=spur_at] == previous[..=spur_at]
+                    })
+                    .map(|(_, path)| path[spur_at + 1])
+                    .collect();
+                let removed: HashSet<NodeId> = root.iter().copied().collect();
+                let spur_weight = |from: NodeId, to: NodeId| {
+                    if removed.contains(&to) || (from == spur && taken.contains(&to)) {
+                        u64::MAX
+                    } else {
+                        weight(from, to)
+                    }
+                };
+                if let Some((cost, spur_path)) = self.astar(spur, goal, spur_weight, |_| 0) {
+                    if cost < u64::MAX {
+                        let mut path = root.to_vec();
+                        path.extend(spur_path);
+                        candidates.insert((root_cost.saturating_add(cost), path));
+                    }
+                }
+                root_cost = root_cost.saturating_add(weight(spur, previous[spur_at + 1]));
+            }
+
+            // Candidates already taken can come back from a later deviation
+            let next = loop {
+                let Some(candidate) = candidates.pop_first() else {
+                    return found;
+                };
+                if !found.contains(&candidate) {
+                    break candidate;
+                }
+            };
+            found.push(next);
+        }
+        found
+    }
+}
+
+/// Distances between every ordered pair of nodes, from
+/// `Graph::all_pairs_shortest_paths` or `Graph::all_pairs_distances`. Only the
+/// former keeps a next-hop matrix, which `path` needs.
+#[derive(Debug, Clone)]
+pub struct AllPairsShortestPaths {
+    len: usize,
+    distances: Vec<Option<u64>>, // Row-major; None when `to` is unreachable from `from`
+    next_hops: Option<Vec<Option<usize>>>, // First node after `from` on a shortest path
+}
+
+impl AllPairsShortestPaths {
+    /// Number of nodes, the side of each matrix
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Shortest distance from `from` to `to`, if `to` is reachable; every node
+    /// is at distance 0 from itself
+    pub fn distance(&self, from: NodeId, to: NodeId) -> Option<u64> {
+        self.cell(from, to).and_then(|cell| self.distances[cell])
+    }
+
+    /// Distances from `from` to every node, indexed by node; panics if `from`
+    /// is out of range
+    pub fn distances_from(&self, from: NodeId) -> &[Option<u64>] {
+        &self.distances[from.index() * self.len..][..self.len]
+    }
+
+    /// The node after `from` on a shortest path to `to`; None without a
+    /// next-hop matrix, when `to` is unreachable, or when `from == to`
+    pub fn next_hop(&self, from: NodeId, to: NodeId) -> Option<NodeId> {
+        let cell = self.cell(from, to)?;
+        self.next_hops.as_ref()?[cell].map(NodeId::new)
+    }
+
+    /// Nodes along a shortest path from `from` to `to`, both included; None
+    /// without a next-hop matrix or when `to` is unreachable
+    pub fn path(&self, from: NodeId, to: NodeId) -> Option<Vec<NodeId>> {
+        self.next_hops.as_ref()?;
+        self.distance(from, to)?;
+        let mut path = vec![from];
+        let mut current = from;
+        while current != to {
+            current = self.next_hop(current, to)?;
+            path.push(current);
+        }
+        Some(path)
+    }
+
+    fn cell(&self, from: NodeId, to: NodeId) -> Option<usize> {
+        (from.index() < self.len && to.index() < self.len)
+            .then(|| from.index() * self.len + to.index())
+    }
+}
+
+impl<T> Graph<T> {
+    /// Shortest distances between all pairs of nodes with non-negative edge
+    /// weights (Floyd-Warshall), plus a next-hop matrix for rebuilding paths.
+    /// O(V^3) time and O(V^2) memory whatever the edge count, so it suits small
+    /// dense graphs; on sparse ones a Dijkstra per node is faster.
+    pub fn all_pairs_shortest_paths<W>(&self, weight: W) -> AllPairsShortestPaths
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        self.floyd_warshall(weight, true)
+    }
+
+    /// Like `all_pairs_shortest_paths`, without the next-hop matrix
+    pub fn all_pairs_distances<W>(&self, weight: W) -> AllPairsShortestPaths
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        self.floyd_warshall(weight, false)
+    }
+
+    fn floyd_warshall<W>(&self, weight: W, keep_next_hops: bool) -> AllPairsShortestPaths
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        let len = self.nodes.len();
+        let mut distances = vec![None; len * len];
+        let mut next_hops = vec![None; if keep_next_hops { len * len } else { 0 }];
+        for (from, node) in self.nodes.iter().enumerate() {
+            distances[from * len + from] = Some(0);
+            for &to in &node.neighbors {
+                let edge_weight = weight(NodeId::new(from), NodeId::new(to));
+                let cell = from * len + to;
+                // Parallel edges keep the lightest; self-loops never beat 0
+                if distances[cell].is_none_or(|d| edge_weight < d) {
+                    distances[cell] = Some(edge_weight);
+                    if keep_next_hops {
+                        next_hops[cell] = Some(to);
+                    }
+                }
+            }
+        }
+
+        for via in 0..len {
+            for from in 0..len {
+                let Some(first_leg) = distances[from * len + via] else {
+                    continue;
+                };
+                for to in 0..len {
+                    let Some(second_leg) = distances[via * len + to] else {
+                        continue;
+                    };
+                    let candidate = first_leg.saturating_add(second_leg);
+                    let cell = from * len + to;
+                    if distances[cell].is_none_or(|d| candidate < d) {
+                        distances[cell] = Some(candidate);
+                        if keep_next_hops {
+                            next_hops[cell] = next_hops[from * len + via];
+                        }
+                    }
+                }
+            }
+        }
+
+        AllPairsShortestPaths {
+            len,
+            distances,
+            next_hops: keep_next_hops.then_some(next_hops),
+        }
+    }
+}
+
+impl<T> Graph<T> {
+    /// Fewest-hop path from `start` to `goal` by BFS, stopping once the goal is
+    /// reached; None if it is unreachable or either node is out of range
+    pub fn shortest_path(&self, start: NodeId, goal: NodeId) -> Option<Vec<NodeId>> {
+        self.check_node(start).ok()?;
+        let (_, path) = search(BfsIterator::new(self, start), |id, _| id == goal)?;
+        Some(path)
+    }
+
+    /// First node matching `predicate` in BFS order from `start`, with the
+    /// tree path to it (a fewest-hop path). The search stops at the match.
+    pub fn find_bfs<P>(&self, start: NodeId, predicate: P) -> Option<(NodeId, Vec<NodeId>)>
+    where
+        P: Fn(&Node<T>) -> bool,
+    {
+        self.check_node(start).ok()?;
+        search(BfsIterator::new(self, start), |_, node| predicate(node))
+    }
+
+    /// First node matching `predicate` in DFS order from `start`, with the
+    /// tree path to it. The search stops at the match.
+    pub fn find_dfs<P>(&self, start: NodeId, predicate: P) -> Option<(NodeId, Vec<NodeId>)>
+    where
+        P: Fn(&Node<T>) -> bool,
+    {
+        self.check_node(start).ok()?;
+        search(DfsIterator::new(self, start), |_, node| predicate(node))
+    }
+}
+
+/// Run `traversal` until a node matches, returning it with its tree path
+fn search<'a, I, P>(mut traversal: I, matches: P) -> Option<(NodeId, Vec<NodeId>)>
+where
+    I: Traversal<'a>,
+    P: Fn(NodeId, &<I::Graph as GraphBase>::Node) -> bool,
+{
+    let graph = traversal.graph();
+    let mut tree = TraversalTree::new(graph.node_count());
+    while let Some(index) = traversal.next_index() {
+        tree.record(index, traversal.parent());
+        let id = NodeId::new(index);
+        if matches(id, graph.node(id)) {
+            return Some((id, tree.path_to(id)?));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph;
+
+    #[test]
+    fn zero_one_bfs_from_an_out_of_range_start_reaches_nothing() {
+        let g = graph! { a -> b };
+        let paths = g.zero_one_bfs(NodeId::new(5), |_, _| 1);
+        assert_eq!(paths.distance(NodeId::new(0)), None);
+        assert_eq!(paths.path_to(NodeId::new(5)), None);
+        let paths = g.zero_one_bfs(NodeId::new(0), |_, _| 0);
+        assert_eq!(paths.distance(NodeId::new(1)), Some(0));
+    }
+}
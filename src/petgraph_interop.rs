@@ -0,0 +1,139 @@
+use petgraph::csr::Csr;
+use petgraph::graph::{DiGraph as PetDiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+
+use crate::graph::{Graph, Node, NodeId};
+use crate::paths::EdgeWeights;
+
+/// Node i becomes `NodeIndex` i, and edges are added in node order, then
+/// neighbor order, so converting back gives the same graph
+impl<T> From<Graph<T>> for PetDiGraph<T, ()> {
+    fn from(graph: Graph<T>) -> Self {
+        let edge_count = graph.nodes.iter().map(|node| node.neighbors.len()).sum();
+        let mut pet = PetDiGraph::with_capacity(graph.nodes.len(), edge_count);
+        let mut edges = Vec::with_capacity(edge_count);
+        for (from, node) in graph.nodes.into_iter().enumerate() {
+            pet.add_node(node.value);
+            edges.extend(node.neighbors.into_iter().map(|to| (from, to)));
+        }
+        for (from, to) in edges {
+            pet.add_edge(NodeIndex::new(from), NodeIndex::new(to), ());
+        }
+        pet
+    }
+}
+
+/// `NodeIndex` i becomes node i, with neighbors in edge index order; edge
+/// weights are dropped (see `Graph::from_petgraph` to keep them)
+impl<T, E> From<PetDiGraph<T, E>> for Graph<T> {
+    fn from(pet: PetDiGraph<T, E>) -> Self {
+        let (pet_nodes, pet_edges) = pet.into_nodes_edges();
+        let mut nodes: Vec<Node<T>> = pet_nodes
+            .into_iter()
+            .map(|node| Node::new(node.weight, Vec::new()))
+            .collect();
+        for edge in pet_edges {
+            nodes[edge.source().index()]
+                .neighbors
+                .push(edge.target().index());
+        }
+        Graph { nodes }
+    }
+}
+
+/// `Csr` keeps each neighbor list sorted and holds each edge once, so
+/// neighbor order is lost and parallel edges collapse into one
+impl<T> From<Graph<T>> for Csr<T, ()> {
+    fn from(graph: Graph<T>) -> Self {
+        let mut csr = Csr::new();
+        let mut edges = Vec::new();
+        for (from, node) in graph.nodes.into_iter().enumerate() {
+            csr.add_node(node.value);
+            edges.extend(node.neighbors.into_iter().map(|to| (from, to)));
+        }
+        // Row-major order keeps the inserts cheap
+        edges.sort_unstable();
+        for (from, to) in edges {
+            csr.add_edge(from as u32, to as u32, ());
+        }
+        csr
+    }
+}
+
+/// Node i of the `Csr` becomes node i, with its sorted neighbors; edge
+/// weights are dropped (see `Graph::from_petgraph_csr` to keep them)
+impl<T: Clone, E> From<&Csr<T, E>> for Graph<T> {
+    fn from(csr: &Csr<T, E>) -> Self {
+        let nodes = (0..csr.node_count() as u32)
+            .map(|node| {
+                let neighbors = csr
+                    .neighbors_slice(node)
+                    .iter()
+                    .map(|&to| to as usize)
+                    .collect();
+                Node::new(csr[node].clone(), neighbors)
+            })
+            .collect();
+        Graph { nodes }
+    }
+}
+
+impl<T: Clone> Graph<T> {
+    /// Copy as a petgraph graph whose edge weights come from `weights`, nodes
+    /// and edges in the order of `From<Graph<T>>`
+    pub fn to_petgraph(&self, weights: &EdgeWeights) -> PetDiGraph<T, u64> {
+        let mut pet = PetDiGraph::with_capacity(self.nodes.len(), 0);
+        for node in &self.nodes {
+            pet.add_node(node.value.clone());
+        }
+        for (from, to) in self.edges() {
+            pet.add_edge(
+                NodeIndex::new(from.index()),
+                NodeIndex::new(to.index()),
+                weights.get(from, to),
+            );
+        }
+        pet
+    }
+
+    /// Graph of a petgraph graph, as by `From`, with each edge's `weight` in
+    /// the returned `EdgeWeights`. Parallel edges share one entry there, so
+    /// the last of them listed sets it.
+    pub fn from_petgraph<E>(
+        pet: &PetDiGraph<T, E>,
+        weight: impl Fn(&E) -> u64,
+    ) -> (Graph<T>, EdgeWeights) {
+        let mut nodes: Vec<Node<T>> = pet
+            .raw_nodes()
+            .iter()
+            .map(|node| Node::new(node.weight.clone(), Vec::new()))
+            .collect();
+        let mut weights = EdgeWeights::default();
+        for edge in pet.raw_edges() {
+            let (from, to) = (edge.source().index(), edge.target().index());
+            nodes[from].neighbors.push(to);
+            weights.set(NodeId::new(from), NodeId::new(to), weight(&edge.weight));
+        }
+        (Graph { nodes }, weights)
+    }
+
+    /// Graph of a petgraph `Csr`, as by `From`, with each edge's `weight` in
+    /// the returned `EdgeWeights`
+    pub fn from_petgraph_csr<E>(
+        csr: &Csr<T, E>,
+        weight: impl Fn(&E) -> u64,
+    ) -> (Graph<T>, EdgeWeights) {
+        let graph = Graph::from(csr);
+        let mut weights = EdgeWeights::default();
+        for node in 0..csr.node_count() as u32 {
+            for edge in csr.edges(node) {
+                weights.set(
+                    NodeId::new(node as usize),
+                    NodeId::new(edge.target() as usize),
+                    weight(edge.weight()),
+                );
+            }
+        }
+        (graph, weights)
+    }
+}
@@ -0,0 +1,629 @@
+use crate::graph::{Graph, NodeId};
+
+/// Outcome of a planarity test, reading edges as undirected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Planarity {
+    /// Combinatorial embedding as a rotation system: the neighbors of each
+    /// node in the cyclic order they appear around it in a plane drawing
+    Planar(Vec<Vec<NodeId>>),
+    /// Edges of a Kuratowski subgraph (a subdivision of K5 or K3,3)
+    NonPlanar(Vec<(NodeId, NodeId)>),
+}
+
+impl<T> Graph<T> {
+    /// Test planarity with the linear-time left-right algorithm, returning an
+    /// embedding or a Kuratowski witness. Finding the witness takes O(V)
+    /// further tests, so a non-planar graph costs O(V^2) in all.
+    pub fn planarity(&self) -> Planarity {
+        let n = self.nodes.len();
+        let edges = self.undirected_edge_indices();
+        match planar_embedding(n, &edges) {
+            Some(rotation) => Planarity::Planar(
+                rotation
+                    .into_iter()
+                    .map(|around| around.into_iter().map(NodeId::new).collect())
+                    .collect(),
+            ),
+            None => Planarity::NonPlanar(
+                kuratowski_edges(n, edges)
+                    .into_iter()
+                    .map(|(a, b)| (NodeId::new(a), NodeId::new(b)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Planarity test alone, in O(V + E)
+    pub fn is_planar(&self) -> bool {
+        is_planar(self.nodes.len(), &self.undirected_edge_indices())
+    }
+}
+
+/// Shrink a non-planar edge set to a minimal non-planar one by dropping every
+/// edge whose removal keeps it non-planar. By Kuratowski's theorem what remains
+/// is a subdivision of K5 or K3,3. The set is first cut to its shortest
+/// non-planar prefix, which has at most 3n - 5 edges, so this runs O(n) tests.
+fn kuratowski_edges(n: usize, mut edges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    // Any 3n - 5 edges of a simple graph are already non-planar
+    let (mut planar, mut non_planar) = (0, edges.len().min((3 * n).saturating_sub(5)));
+    while non_planar - planar > 1 {
+        let middle = (planar + non_planar) / 2;
+        if is_planar(n, &edges[..middle]) {
+            planar = middle;
+        } else {
+            non_planar = middle;
+        }
+    }
+    edges.truncate(non_planar);
+
+    // The last edge is needed, since the prefix without it is planar
+    let mut i = 0;
+    while i + 1 < edges.len() {
+        let edge = edges.remove(i);
+        if is_planar(n, &edges) {
+            edges.insert(i, edge);
+            i += 1;
+        }
+    }
+    edges
+}
+
+fn is_planar(n: usize, edges: &[(usize, usize)]) -> bool {
+    if n >= 3 && edges.len() > 3 * n - 6 {
+        return false;
+    }
+    let mut state = LeftRight::new(n, edges);
+    state.orient();
+    state.test()
+}
+
+/// Rotation system of a simple undirected graph on n nodes, or None if it is
+/// not planar
+fn planar_embedding(n: usize, edges: &[(usize, usize)]) -> Option<Vec<Vec<usize>>> {
+    if n >= 3 && edges.len() > 3 * n - 6 {
+        return None;
+    }
+    let mut state = LeftRight::new(n, edges);
+    state.orient();
+    state.test().then(|| state.embed())
+}
+
+const UNSEEN: usize = usize::MAX;
+
+/// Return edges (arcs) from `low` up to `high`, linked by `LeftRight::reference`
+#[derive(Debug, Clone, Copy, Default)]
+struct Interval {
+    low: Option<usize>,
+    high: Option<usize>,
+}
+
+impl Interval {
+    fn is_empty(&self) -> bool {
+        self.low.is_none() && self.high.is_none()
+    }
+}
+
+/// Return edges that must go on opposite sides of the DFS tree
+#[derive(Debug, Clone, Copy, Default)]
+struct ConflictPair {
+    left: Interval,
+    right: Interval,
+}
+
+impl ConflictPair {
+    fn swap(&mut self) {
+        std::mem::swap(&mut self.left, &mut self.right);
+    }
+}
+
+/// State of the left-right planarity test (de Fraysseix and Rosenstiehl, as
+/// given by Brandes). A DFS orients every edge into an arc, tree arcs down and
+/// back arcs up; a graph is planar exactly when the back arcs can be split
+/// into a left and a right side without crossings, which the conflict pairs
+/// track. All three phases are iterative, so deep DFS trees cannot overflow
+/// the stack. Arc i is undirected edge i; every index below is a node or arc.
+struct LeftRight {
+    adjacency: Vec<Vec<(usize, usize)>>, // (neighbor, edge) pairs
+    arcs: Vec<(usize, usize)>,           // (tail, head) once oriented
+    oriented: Vec<bool>,
+    out: Vec<Vec<usize>>, // Arcs leaving each node, later by nesting depth
+    roots: Vec<usize>,
+    height: Vec<usize>,
+    parent_arc: Vec<Option<usize>>,
+    lowpt: Vec<usize>,
+    lowpt2: Vec<usize>,
+    nesting: Vec<i64>,
+    conflicts: Vec<ConflictPair>,
+    stack_bottom: Vec<usize>, // Length of `conflicts` when each arc was reached
+    lowpt_arc: Vec<Option<usize>>,
+    reference: Vec<Option<usize>>,
+    side: Vec<i64>,
+}
+
+impl LeftRight {
+    fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        let m = edges.len();
+        let mut adjacency = vec![Vec::new(); n];
+        for (edge, &(a, b)) in edges.iter().enumerate() {
+            adjacency[a].push((b, edge));
+            adjacency[b].push((a, edge));
+        }
+        LeftRight {
+            adjacency,
+            arcs: edges.to_vec(),
+            oriented: vec![false; m],
+            out: vec![Vec::new(); n],
+            roots: Vec::new(),
+            height: vec![UNSEEN; n],
+            parent_arc: vec![None; n],
+            lowpt: vec![0; m],
+            lowpt2: vec![0; m],
+            nesting: vec![0; m],
+            conflicts: Vec::new(),
+            stack_bottom: vec![0; m],
+            lowpt_arc: vec![None; m],
+            reference: vec![None; m],
+            side: vec![1; m],
+        }
+    }
+
+    /// Phase 1: orient the edges by DFS and compute lowpoints and nesting
+    /// depths, then order each node's arcs by nesting depth
+    fn orient(&mut self) {
+        for root in 0..self.height.len() {
+            if self.height[root] != UNSEEN {
+                continue;
+            }
+            self.height[root] = 0;
+            self.roots.push(root);
+            let mut stack = vec![(root, 0)];
+            while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+                let Some(&(w, arc)) = self.adjacency[v].get(*next) else {
+                    stack.pop();
+                    if let Some(arc) = self.parent_arc[v] {
+                        self.finish_arc(arc);
+                    }
+                    continue;
+                };
+                *next += 1;
+                if std::mem::replace(&mut self.oriented[arc], true) {
+                    continue;
+                }
+                self.arcs[arc] = (v, w);
+                self.out[v].push(arc);
+                self.lowpt[arc] = self.height[v];
+                self.lowpt2[arc] = self.height[v];
+                if self.height[w] == UNSEEN {
+                    self.parent_arc[w] = Some(arc);
+                    self.height[w] = self.height[v] + 1;
+                    stack.push((w, 0));
+                } else {
+                    self.lowpt[arc] = self.height[w];
+                    self.finish_arc(arc);
+                }
+            }
+        }
+        self.sort_arcs();
+    }
+
+    /// Nesting depth of a traversed arc v -> w, and its lowpoints folded into
+    /// those of the arc into v
+    fn finish_arc(&mut self, arc: usize) {
+        let v = self.arcs[arc].0;
+        let chordal = self.lowpt2[arc] < self.height[v];
+        self.nesting[arc] = 2 * self.lowpt[arc] as i64 + i64::from(chordal);
+        if let Some(parent) = self.parent_arc[v] {
+            let (low, low2) = (self.lowpt[arc], self.lowpt2[arc]);
+            if low < self.lowpt[parent] {
+                self.lowpt2[parent] = self.lowpt[parent].min(low2);
+                self.lowpt[parent] = low;
+            } else if low > self.lowpt[parent] {
+                self.lowpt2[parent] = self.lowpt2[parent].min(low);
+            } else {
+                self.lowpt2[parent] = self.lowpt2[parent].min(low2);
+            }
+        }
+    }
+
+    fn sort_arcs(&mut self) {
+        let nesting = &self.nesting;
+        for arcs in &mut self.out {
+            arcs.sort_by_key(|&arc| nesting[arc]);
+        }
+    }
+
+    /// Phase 2: false if some back arcs conflict on both sides
+    fn test(&mut self) -> bool {
+        for r in 0..self.roots.len() {
+            let mut stack = vec![(self.roots[r], 0)];
+            while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+                let Some(&arc) = self.out[v].get(*next) else {
+                    stack.pop();
+                    if let Some(arc) = self.parent_arc[v] {
+                        self.remove_back_arcs(arc);
+                        if !self.integrate(arc) {
+                            return false;
+                        }
+                    }
+                    continue;
+                };
+                *next += 1;
+                let w = self.arcs[arc].1;
+                self.stack_bottom[arc] = self.conflicts.len();
+                if self.parent_arc[w] == Some(arc) {
+                    stack.push((w, 0));
+                    continue;
+                }
+                self.lowpt_arc[arc] = Some(arc);
+                self.conflicts.push(ConflictPair {
+                    left: Interval::default(),
+                    right: Interval {
+                        low: Some(arc),
+                        high: Some(arc),
+                    },
+                });
+                if !self.integrate(arc) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Take in the return arcs of a traversed arc v -> w
+    fn integrate(&mut self, arc: usize) -> bool {
+        let v = self.arcs[arc].0;
+        if self.lowpt[arc] >= self.height[v] {
+            return true;
+        }
+        let parent = self.parent_arc[v].expect("arcs out of a root return nowhere");
+        if self.out[v][0] == arc {
+            self.lowpt_arc[parent] = self.lowpt_arc[arc];
+            true
+        } else {
+            self.add_constraints(arc, parent)
+        }
+    }
+
+    fn conflicting(&self, interval: &Interval, arc: usize) -> bool {
+        interval
+            .high
+            .is_some_and(|high| self.lowpt[high] > self.lowpt[arc])
+    }
+
+    fn lowest(&self, pair: &ConflictPair) -> usize {
+        match (pair.left.low, pair.right.low) {
+            (Some(left), Some(right)) => self.lowpt[left].min(self.lowpt[right]),
+            (Some(low), None) | (None, Some(low)) => self.lowpt[low],
+            (None, None) => unreachable!("conflict pairs on the stack are not empty"),
+        }
+    }
+
+    /// Merge the conflict pairs pushed since `arc` was reached with those of
+    /// its earlier siblings below `parent`
+    fn add_constraints(&mut self, arc: usize, parent: usize) -> bool {
+        let mut pair = ConflictPair::default();
+        loop {
+            let mut q = self.conflicts.pop().expect("arc has return arcs");
+            if !q.left.is_empty() {
+                q.swap();
+            }
+            if !q.left.is_empty() {
+                return false;
+            }
+            let low = q.right.low.expect("right interval is not empty");
+            if self.lowpt[low] > self.lowpt[parent] {
+                match pair.right.low {
+                    None => pair.right = q.right,
+                    Some(right) => self.reference[right] = q.right.high,
+                }
+                pair.right.low = q.right.low;
+            } else {
+                self.reference[low] = self.lowpt_arc[parent];
+            }
+            if self.conflicts.len() == self.stack_bottom[arc] {
+                break;
+            }
+        }
+
+        while let Some(top) = self.conflicts.last() {
+            if !self.conflicting(&top.left, arc) && !self.conflicting(&top.right, arc) {
+                break;
+            }
+            let mut q = self.conflicts.pop().unwrap();
+            if self.conflicting(&q.right, arc) {
+                q.swap();
+            }
+            if self.conflicting(&q.right, arc) {
+                return false;
+            }
+            if let Some(right) = pair.right.low {
+                self.reference[right] = q.right.high;
+            }
+            if q.right.low.is_some() {
+                pair.right.low = q.right.low;
+            }
+            match pair.left.low {
+                None => pair.left = q.left,
+                Some(left) => self.reference[left] = q.left.high,
+            }
+            pair.left.low = q.left.low;
+        }
+        if !pair.left.is_empty() || !pair.right.is_empty() {
+            self.conflicts.push(pair);
+        }
+        true
+    }
+
+    /// Drop the back arcs ending at the tail u of tree arc `arc` once its
+    /// subtree is done, and record the side of `arc`
+    fn remove_back_arcs(&mut self, arc: usize) {
+        let u = self.arcs[arc].0;
+        while let Some(top) = self.conflicts.last() {
+            if self.lowest(top) != self.height[u] {
+                break;
+            }
+            let pair = self.conflicts.pop().unwrap();
+            if let Some(low) = pair.left.low {
+                self.side[low] = -1;
+            }
+        }
+        if let Some(mut pair) = self.conflicts.pop() {
+            while let Some(high) = pair.left.high.filter(|&high| self.arcs[high].1 == u) {
+                pair.left.high = self.reference[high];
+            }
+            if let (None, Some(low)) = (pair.left.high, pair.left.low) {
+                self.reference[low] = pair.right.low;
+                self.side[low] = -1;
+                pair.left.low = None;
+            }
+            while let Some(high) = pair.right.high.filter(|&high| self.arcs[high].1 == u) {
+                pair.right.high = self.reference[high];
+            }
+            if let (None, Some(low)) = (pair.right.high, pair.right.low) {
+                self.reference[low] = pair.left.low;
+                self.side[low] = -1;
+                pair.right.low = None;
+            }
+            self.conflicts.push(pair);
+        }
+
+        if self.lowpt[arc] < self.height[u] {
+            let top = self.conflicts.last().expect("arc has return arcs");
+            self.reference[arc] = match (top.left.high, top.right.high) {
+                (Some(left), Some(right)) if self.lowpt[left] > self.lowpt[right] => Some(left),
+                (Some(left), None) => Some(left),
+                (_, right) => right,
+            };
+        }
+    }
+
+    /// Final side of `arc`: its own side times that of the chain of arcs it
+    /// refers to, resolved from the far end
+    fn sign(&mut self, arc: usize) -> i64 {
+        let mut chain = vec![arc];
+        while let Some(next) = self.reference[*chain.last().unwrap()] {
+            chain.push(next);
+        }
+        for pair in chain.windows(2).rev() {
+            self.side[pair[0]] *= self.side[pair[1]];
+            self.reference[pair[0]] = None;
+        }
+        self.side[arc]
+    }
+
+    /// Phase 3: the rotation system, from the arcs ordered by signed nesting
+    /// depth and the back arcs inserted on their side
+    fn embed(&mut self) -> Vec<Vec<usize>> {
+        for arc in 0..self.arcs.len() {
+            self.nesting[arc] *= self.sign(arc);
+        }
+        self.sort_arcs();
+
+        // Dart 2a sits at the tail of arc a, dart 2a + 1 at its head
+        let n = self.out.len();
+        let mut rotation = Rotation::new(n, self.arcs.len());
+        for (v, arcs) in self.out.iter().enumerate() {
+            let mut previous = None;
+            for &arc in arcs {
+                rotation.add_cw(v, 2 * arc, previous);
+                previous = Some(2 * arc);
+            }
+        }
+        let mut left_ref = vec![0; n];
+        let mut right_ref = vec![0; n];
+        for &root in &self.roots {
+            let mut stack = vec![(root, 0)];
+            while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+                let Some(&arc) = self.out[v].get(*next) else {
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+                let w = self.arcs[arc].1;
+                if self.parent_arc[w] == Some(arc) {
+                    rotation.add_first(w, 2 * arc + 1);
+                    left_ref[v] = 2 * arc;
+                    right_ref[v] = 2 * arc;
+                    stack.push((w, 0));
+                } else if self.side[arc] == 1 {
+                    rotation.add_cw(w, 2 * arc + 1, Some(right_ref[w]));
+                } else {
+                    rotation.add_ccw(w, 2 * arc + 1, Some(left_ref[w]));
+                    left_ref[w] = 2 * arc + 1;
+                }
+            }
+        }
+
+        let neighbor = |dart: usize| {
+            let (tail, head) = self.arcs[dart / 2];
+            if dart.is_multiple_of(2) {
+                head
+            } else {
+                tail
+            }
+        };
+        (0..n)
+            .map(|v| {
+                let Some(first) = rotation.first[v] else {
+                    return Vec::new();
+                };
+                let mut order = vec![neighbor(first)];
+                let mut dart = rotation.cw[first];
+                while dart != first {
+                    order.push(neighbor(dart));
+                    dart = rotation.cw[dart];
+                }
+                order
+            })
+            .collect()
+    }
+}
+
+/// Darts around each node as circular clockwise and counterclockwise lists
+struct Rotation {
+    cw: Vec<usize>,
+    ccw: Vec<usize>,
+    first: Vec<Option<usize>>,
+}
+
+impl Rotation {
+    fn new(n: usize, m: usize) -> Self {
+        Rotation {
+            cw: vec![0; 2 * m],
+            ccw: vec![0; 2 * m],
+            first: vec![None; n],
+        }
+    }
+
+    /// Put `dart` right after `reference` clockwise around v, or make it the
+    /// only dart there
+    fn add_cw(&mut self, v: usize, dart: usize, reference: Option<usize>) {
+        let Some(reference) = reference else {
+            self.cw[dart] = dart;
+            self.ccw[dart] = dart;
+            self.first[v] = Some(dart);
+            return;
+        };
+        let after = self.cw[reference];
+        self.cw[reference] = dart;
+        self.cw[dart] = after;
+        self.ccw[after] = dart;
+        self.ccw[dart] = reference;
+    }
+
+    /// Put `dart` right before `reference` clockwise around v
+    fn add_ccw(&mut self, v: usize, dart: usize, reference: Option<usize>) {
+        let Some(reference) = reference else {
+            return self.add_cw(v, dart, None);
+        };
+        self.add_cw(v, dart, Some(self.ccw[reference]));
+        if self.first[v] == Some(reference) {
+            self.first[v] = Some(dart);
+        }
+    }
+
+    fn add_first(&mut self, v: usize, dart: usize) {
+        self.add_ccw(v, dart, self.first[v]);
+        self.first[v] = Some(dart);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Faces traced through a rotation system, following each dart
+    /// (a, b) to (b, c) with c after a around b
+    fn face_count(rotation: &[Vec<NodeId>]) -> usize {
+        let mut faces = 0;
+        let mut seen = HashSet::new();
+        for (u, around) in rotation.iter().enumerate() {
+            for &v in around {
+                let (mut a, mut b) = (NodeId::new(u), v);
+                if !seen.insert((a, b)) {
+                    continue;
+                }
+                faces += 1;
+                loop {
+                    let around = &rotation[b.index()];
+                    let at = around.iter().position(|&x| x == a).unwrap();
+                    (a, b) = (b, around[(at + 1) % around.len()]);
+                    if !seen.insert((a, b)) {
+                        break;
+                    }
+                }
+            }
+        }
+        faces
+    }
+
+    fn from_edges(n: usize, edges: &[(NodeId, NodeId)]) -> Graph {
+        Graph::from_undirected_edges(n, edges.iter().map(|(a, b)| (a.index(), b.index())))
+    }
+
+    #[test]
+    fn planar_graphs_get_embeddings_satisfying_eulers_formula() {
+        for g in [Graph::grid(4, 5), Graph::complete(4), Graph::cycle(6)] {
+            let Planarity::Planar(rotation) = g.planarity() else {
+                panic!("expected a planar graph");
+            };
+            // Euler's formula for a connected plane graph: F = E - V + 2
+            let edges = g.undirected_edges().len();
+            assert_eq!(face_count(&rotation) + g.len(), edges + 2);
+        }
+    }
+
+    #[test]
+    fn kuratowski_witnesses_are_minimal_non_planar_subgraphs() {
+        for g in [Graph::complete(5), Graph::complete_bipartite(3, 3)] {
+            assert!(!g.is_planar());
+            assert_eq!(g.planarity(), Planarity::NonPlanar(g.undirected_edges()));
+        }
+
+        let petersen = Graph::from_undirected_edges(
+            10,
+            (0..5).flat_map(|i| [(i, (i + 1) % 5), (i, i + 5), (i + 5, (i + 2) % 5 + 5)]),
+        );
+        // K5 on 0..5 with a tail and chords that are not needed for the witness
+        let k5_plus = Graph::from_undirected_edges(
+            9,
+            (0..5).flat_map(|a| (a + 1..5).map(move |b| (a, b))).chain([
+                (5, 0),
+                (5, 1),
+                (6, 5),
+                (6, 2),
+                (7, 6),
+                (8, 7),
+                (8, 3),
+                (8, 0),
+            ]),
+        );
+        for g in [petersen, k5_plus] {
+            let Planarity::NonPlanar(witness) = g.planarity() else {
+                panic!("expected a non-planar graph");
+            };
+            let edges: HashSet<_> = g.undirected_edges().into_iter().collect();
+            assert!(witness.len() < edges.len());
+            assert!(witness.iter().all(|edge| edges.contains(edge)));
+            assert!(!from_edges(g.len(), &witness).is_planar());
+            for i in 0..witness.len() {
+                let mut smaller = witness.clone();
+                smaller.remove(i);
+                assert!(from_edges(g.len(), &smaller).is_planar());
+            }
+
+            // A subdivision of K5 or K3,3: branch nodes of degree 4 or 3,
+            // joined by paths of degree-2 nodes
+            let mut degree = vec![0; g.len()];
+            for (a, b) in &witness {
+                degree[a.index()] += 1;
+                degree[b.index()] += 1;
+            }
+            let count = |d: usize| degree.iter().filter(|&&x| x == d).count();
+            assert!(degree.iter().all(|&d| [0, 2, 3, 4].contains(&d)));
+            assert!((count(4), count(3)) == (5, 0) || (count(4), count(3)) == (0, 6));
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::error::{GraphDefect, GraphError};
+use crate::graph::{Graph, Node, NodeId};
+
+/// Which edges a `PolicyGraph` accepts. `Graph` itself is a multigraph:
+/// parallel edges and self-loops are stored as given, traversals still visit
+/// each node once, and edge listings report every copy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgePolicy {
+    /// Whether from -> to may be added while it is already an edge
+    pub parallel_edges: bool,
+    /// Whether a node may have an edge to itself
+    pub self_loops: bool,
+}
+
+impl EdgePolicy {
+    /// Everything allowed, as in `Graph`
+    pub const MULTIGRAPH: EdgePolicy = EdgePolicy {
+        parallel_edges: true,
+        self_loops: true,
+    };
+
+    /// At most one edge per ordered pair, and no self-loops
+    pub const SIMPLE: EdgePolicy = EdgePolicy {
+        parallel_edges: false,
+        self_loops: false,
+    };
+
+    /// Whether the policy forbids `defect`; dangling neighbors always are
+    fn forbids(&self, defect: &GraphDefect) -> bool {
+        match defect {
+            GraphDefect::DanglingNeighbor { .. } => true,
+            GraphDefect::SelfLoop { .. } => !self.self_loops,
+            GraphDefect::DuplicateEdge { .. } => !self.parallel_edges,
+        }
+    }
+}
+
+impl Default for EdgePolicy {
+    /// `MULTIGRAPH`
+    fn default() -> Self {
+        EdgePolicy::MULTIGRAPH
+    }
+}
+
+/// A directed `Graph` that enforces an `EdgePolicy`: `add_edge` rejects the
+/// edges the policy forbids with an error instead of storing them, so the
+/// graph never holds a parallel edge or self-loop it should not.
+#[derive(Debug, Clone)]
+pub struct PolicyGraph<T = i32> {
+    graph: Graph<T>,
+    policy: EdgePolicy,
+}
+
+impl<T> PolicyGraph<T> {
+    /// Empty graph under `policy`
+    pub fn new(policy: EdgePolicy) -> Self {
+        PolicyGraph {
+            graph: Graph::new(),
+            policy,
+        }
+    }
+
+    /// `graph` under `policy`, or every defect of it the policy forbids (see
+    /// `Graph::validate_all`)
+    pub fn from_graph(graph: Graph<T>, policy: EdgePolicy) -> Result<Self, Vec<GraphDefect>> {
+        if let Err(mut defects) = graph.validate_all() {
+            defects.retain(|defect| policy.forbids(defect));
+            if !defects.is_empty() {
+                return Err(defects);
+            }
+        }
+        Ok(PolicyGraph { graph, policy })
+    }
+
+    pub fn policy(&self) -> EdgePolicy {
+        self.policy
+    }
+
+    /// Append a node without neighbors and return its id
+    pub fn add_node(&mut self, value: T) -> NodeId {
+        self.graph.add_node(value)
+    }
+
+    /// Add the edge from -> to if the policy allows it
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), GraphError> {
+        self.graph.check_node(from)?;
+        self.graph.check_node(to)?;
+        if from == to && !self.policy.self_loops {
+            return Err(GraphError::SelfLoop { node: from.index() });
+        }
+        let neighbors = &self.graph.nodes[from.index()].neighbors;
+        if !self.policy.parallel_edges && neighbors.contains(&to.index()) {
+            return Err(GraphError::ParallelEdge {
+                from: from.index(),
+                to: to.index(),
+            });
+        }
+        self.graph.add_edge(from, to)
+    }
+
+    /// Remove one edge from -> to, returning whether there was one
+    pub fn remove_edge(&mut self, from: NodeId, to: NodeId) -> Result<bool, GraphError> {
+        self.graph.remove_edge(from, to)
+    }
+
+    /// The graph, for traversals and the algorithms on `Graph`
+    pub fn as_graph(&self) -> &Graph<T> {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> Graph<T> {
+        self.graph
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&Node<T>> {
+        self.graph.node(id)
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.graph.is_empty()
+    }
+}
+
+impl<T> std::ops::Index<NodeId> for PolicyGraph<T> {
+    type Output = Node<T>;
+
+    fn index(&self, id: NodeId) -> &Node<T> {
+        &self.graph[id]
+    }
+}
+
+impl<T> Default for PolicyGraph<T> {
+    /// Empty graph under the default (multigraph) policy
+    fn default() -> Self {
+        Self::new(EdgePolicy::default())
+    }
+}
@@ -0,0 +1,136 @@
+//! Invariants of the traversals checked on random graphs
+
+use std::collections::{HashMap, HashSet};
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use crate::graph::{Graph, Node, NodeId};
+use crate::paths::{DijkstraIterator, EdgeWeights};
+use crate::traversal::{BfsIterator, DfsIterator, NextSmallestIterator, Traversal};
+
+/// Graphs of 1 to 24 nodes with values in -5..5 and up to 4 edges per node,
+/// self-loops and parallel edges included
+fn graphs() -> impl Strategy<Value = Graph> {
+    (1usize..24).prop_flat_map(|n| {
+        (vec(-5i32..5, n), vec((0..n, 0..n), 0..=4 * n)).prop_map(|(values, edges)| {
+            let mut nodes: Vec<Node> = values
+                .into_iter()
+                .map(|value| Node::new(value, Vec::new()))
+                .collect();
+            for (from, to) in edges {
+                nodes[from].neighbors.push(to);
+            }
+            Graph::from_nodes(nodes)
+        })
+    })
+}
+
+/// A graph, a start node in it and a weight in 0..20 for each of its edges
+fn weighted_graphs() -> impl Strategy<Value = (Graph, usize, EdgeWeights)> {
+    graphs().prop_flat_map(|graph| {
+        let (len, edges) = (graph.len(), graph.edge_count());
+        (Just(graph), 0..len, vec(0u64..20, edges)).prop_map(|(graph, start, weights)| {
+            let mut edge_weights = EdgeWeights::default();
+            for ((from, to), weight) in graph.edges().zip(weights) {
+                edge_weights.set(from, to, weight);
+            }
+            (graph, start, edge_weights)
+        })
+    })
+}
+
+/// Nodes reachable from `start`, by closure over out-edges
+fn reachable(graph: &Graph, start: usize) -> HashSet<usize> {
+    let mut reached = HashSet::from([start]);
+    let mut pending = vec![start];
+    while let Some(node) = pending.pop() {
+        for &neighbor in &graph.nodes[node].neighbors {
+            if reached.insert(neighbor) {
+                pending.push(neighbor);
+            }
+        }
+    }
+    reached
+}
+
+/// Assert that `traversal` yields every node of `reachable` exactly once
+fn assert_visits_reachable_once<'a>(
+    mut traversal: impl Traversal<'a>,
+    reachable: &HashSet<usize>,
+) -> Result<(), TestCaseError> {
+    let mut yielded = HashSet::new();
+    while let Some(index) = traversal.next_index() {
+        prop_assert!(yielded.insert(index), "node {} yielded twice", index);
+    }
+    prop_assert_eq!(&yielded, reachable);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn bfs_and_dfs_visit_each_reachable_node_once(graph in graphs(), start in 0usize..24) {
+        let start = start % graph.len();
+        let reachable = reachable(&graph, start);
+        assert_visits_reachable_once(BfsIterator::new(&graph, NodeId::new(start)), &reachable)?;
+        assert_visits_reachable_once(DfsIterator::new(&graph, NodeId::new(start)), &reachable)?;
+    }
+
+    #[test]
+    fn bfs_depth_never_decreases(graph in graphs(), start in 0usize..24) {
+        let mut bfs = BfsIterator::new(&graph, NodeId::new(start % graph.len()));
+        let mut depths = HashMap::new();
+        let mut last = 0;
+        while let Some(index) = bfs.next_index() {
+            let depth = bfs.depth();
+            prop_assert!(depth >= last, "node {} at depth {} after {}", index, depth, last);
+            // One edge deeper than the node it was reached from
+            let expected = bfs.parent().map_or(0, |parent| depths[&parent] + 1);
+            prop_assert_eq!(depth, expected);
+            depths.insert(index, depth);
+            last = depth;
+        }
+    }
+
+    #[test]
+    fn next_smallest_yields_the_smallest_frontier_value(graph in graphs(), start in 0usize..24) {
+        let start = start % graph.len();
+        let mut traversal = NextSmallestIterator::new(&graph, NodeId::new(start));
+        let mut yielded = HashSet::new();
+        while let Some(index) = traversal.next_index() {
+            // The frontier is every unvisited out-neighbor of the nodes yielded
+            // so far, or just the start before the first step
+            let frontier: HashSet<usize> = if yielded.is_empty() {
+                HashSet::from([start])
+            } else {
+                yielded
+                    .iter()
+                    .flat_map(|&node: &usize| graph.nodes[node].neighbors.iter().copied())
+                    .filter(|neighbor| !yielded.contains(neighbor))
+                    .collect()
+            };
+            prop_assert!(frontier.contains(&index), "node {} is not on the frontier", index);
+            let smallest = frontier.iter().map(|&node| graph.nodes[node].value).min();
+            prop_assert_eq!(Some(graph.nodes[index].value), smallest);
+            yielded.insert(index);
+        }
+    }
+
+    #[test]
+    fn dijkstra_distances_match_bellman_ford((graph, start, weights) in weighted_graphs()) {
+        let mut dijkstra = DijkstraIterator::new(&graph, NodeId::new(start), &weights);
+        while dijkstra.next_index().is_some() {}
+        let dijkstra = dijkstra.into_paths();
+        let bellman_ford = graph
+            .bellman_ford(NodeId::new(start), |from, to| weights.get(from, to) as i64)
+            .expect("weights are non-negative");
+        for node in graph.node_ids() {
+            prop_assert_eq!(
+                dijkstra.distance(node).map(|distance| distance as i64),
+                bellman_ford.distance(node),
+                "node {}",
+                node
+            );
+        }
+    }
+}
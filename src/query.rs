@@ -0,0 +1,437 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::error::ParseError;
+use crate::graph::Graph;
+
+/// Comparison operators of the query language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+}
+
+/// Value in a WHERE condition or RETURN item: `a.value`, `a.id` (the node index),
+/// `a` (same as `a.id`) or an integer literal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Value(String),
+    Id(String),
+    Literal(i64),
+}
+
+/// Edge step of a MATCH pattern, matching walks of min..=max edges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HopRange {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+/// A parsed query: MATCH chains of (var)-[*min..max]->(var), optional WHERE
+/// conditions joined by AND, and the RETURN items
+#[derive(Debug, Clone)]
+pub struct Query {
+    pub chains: Vec<(String, Vec<(HopRange, String)>)>,
+    pub conditions: Vec<(Operand, Comparison, Operand)>,
+    pub returns: Vec<Operand>,
+}
+
+/// Result rows of a query; node variables and `.id` return node indices
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<i64>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum QueryToken {
+    Word(String),
+    Number(i64),
+    Symbol(&'static str),
+}
+
+fn tokenize_query(source: &str) -> Result<Vec<(usize, QueryToken)>, ParseError> {
+    const SYMBOLS: [&str; 14] = [
+        "..", "<=", ">=", "<>", "(", ")", "[", "]", "-", ">", "<", "=", "*", ",",
+    ];
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    while position < source.len() {
+        let rest = &source[position..];
+        let c = rest.chars().next().unwrap();
+        if c.is_whitespace() {
+            position += c.len_utf8();
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            tokens.push((position, QueryToken::Word(rest[..len].to_string())));
+            position += len;
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let number = rest[..len].parse().map_err(|_| ParseError {
+                position,
+                message: "number out of range".to_string(),
+            })?;
+            tokens.push((position, QueryToken::Number(number)));
+            position += len;
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+            tokens.push((position, QueryToken::Symbol(symbol)));
+            position += symbol.len();
+        } else if c == '.' {
+            tokens.push((position, QueryToken::Symbol(".")));
+            position += 1;
+        } else {
+            return Err(ParseError {
+                position,
+                message: format!("unexpected character '{}'", c),
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+struct QueryParser {
+    pub tokens: Vec<(usize, QueryToken)>,
+    pub next: usize,
+    pub end: usize,
+}
+
+impl QueryParser {
+    fn error(&self, message: &str) -> ParseError {
+        let position = self
+            .tokens
+            .get(self.next)
+            .map_or(self.end, |(position, _)| *position);
+        ParseError {
+            position,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<&QueryToken> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(QueryToken::Symbol(s)) if *s == symbol);
+        self.next += usize::from(found);
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), ParseError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", symbol)))
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found =
+            matches!(self.peek(), Some(QueryToken::Word(w)) if w.eq_ignore_ascii_case(keyword));
+        self.next += usize::from(found);
+        found
+    }
+
+    fn identifier(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(QueryToken::Word(word)) => {
+                let word = word.clone();
+                self.next += 1;
+                Ok(word)
+            }
+            _ => Err(self.error("expected a variable name")),
+        }
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        match self.peek() {
+            Some(&QueryToken::Number(n)) => {
+                self.next += 1;
+                Some(n as usize)
+            }
+            _ => None,
+        }
+    }
+
+    fn node(&mut self) -> Result<String, ParseError> {
+        self.expect_symbol("(")?;
+        let name = self.identifier()?;
+        self.expect_symbol(")")?;
+        Ok(name)
+    }
+
+    /// `-->`, `-[]->`, `-[*]->`, `-[*n]->` or `-[*min..max]->` (either bound optional)
+    fn hop(&mut self) -> Result<HopRange, ParseError> {
+        self.expect_symbol("-")?;
+        let mut range = HopRange {
+            min: 1,
+            max: Some(1),
+        };
+        if self.eat_symbol("[") {
+            if self.eat_symbol("*") {
+                let min = self.number();
+                if self.eat_symbol("..") {
+                    range = HopRange {
+                        min: min.unwrap_or(1),
+                        max: self.number(),
+                    };
+                } else {
+                    range = HopRange {
+                        min: min.unwrap_or(1),
+                        max: min,
+                    };
+                }
+            }
+            self.expect_symbol("]")?;
+        }
+        self.expect_symbol("-")?;
+        self.expect_symbol(">")?;
+        Ok(range)
+    }
+
+    fn operand(&mut self) -> Result<Operand, ParseError> {
+        if self.eat_symbol("-") {
+            return match self.number() {
+                Some(n) => Ok(Operand::Literal(-(n as i64))),
+                None => Err(self.error("expected a number")),
+            };
+        }
+        if let Some(n) = self.number() {
+            return Ok(Operand::Literal(n as i64));
+        }
+        let name = self.identifier()?;
+        if !self.eat_symbol(".") {
+            return Ok(Operand::Id(name));
+        }
+        match self.identifier()?.as_str() {
+            "value" => Ok(Operand::Value(name)),
+            "id" => Ok(Operand::Id(name)),
+            _ => {
+                self.next -= 1;
+                Err(self.error("expected 'value' or 'id'"))
+            }
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Comparison, ParseError> {
+        let operator = match self.peek() {
+            Some(QueryToken::Symbol("<")) => Comparison::Less,
+            Some(QueryToken::Symbol("<=")) => Comparison::LessEqual,
+            Some(QueryToken::Symbol(">")) => Comparison::Greater,
+            Some(QueryToken::Symbol(">=")) => Comparison::GreaterEqual,
+            Some(QueryToken::Symbol("=")) => Comparison::Equal,
+            Some(QueryToken::Symbol("<>")) => Comparison::NotEqual,
+            _ => return Err(self.error("expected a comparison operator")),
+        };
+        self.next += 1;
+        Ok(operator)
+    }
+
+    fn query(&mut self) -> Result<Query, ParseError> {
+        if !self.eat_keyword("MATCH") {
+            return Err(self.error("expected MATCH"));
+        }
+        let mut chains = Vec::new();
+        loop {
+            let first = self.node()?;
+            let mut steps = Vec::new();
+            while matches!(self.peek(), Some(QueryToken::Symbol("-"))) {
+                let hop = self.hop()?;
+                steps.push((hop, self.node()?));
+            }
+            chains.push((first, steps));
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+
+        let mut conditions = Vec::new();
+        if self.eat_keyword("WHERE") {
+            loop {
+                let left = self.operand()?;
+                let operator = self.comparison()?;
+                conditions.push((left, operator, self.operand()?));
+                if !self.eat_keyword("AND") {
+                    break;
+                }
+            }
+        }
+
+        if !self.eat_keyword("RETURN") {
+            return Err(self.error("expected RETURN"));
+        }
+        let mut returns = vec![self.operand()?];
+        while self.eat_symbol(",") {
+            returns.push(self.operand()?);
+        }
+        if self.peek().is_some() {
+            return Err(self.error("unexpected trailing input"));
+        }
+        Ok(Query {
+            chains,
+            conditions,
+            returns,
+        })
+    }
+}
+
+impl Query {
+    /// Parse `MATCH (a)-[*1..3]->(b) WHERE a.value < 5 RETURN b` style queries
+    pub fn parse(source: &str) -> Result<Query, ParseError> {
+        let mut parser = QueryParser {
+            tokens: tokenize_query(source)?,
+            next: 0,
+            end: source.len(),
+        };
+        let query = parser.query()?;
+
+        // Every referenced variable must be bound by the MATCH clause
+        let bound: HashSet<&String> = query
+            .chains
+            .iter()
+            .flat_map(|(first, steps)| std::iter::once(first).chain(steps.iter().map(|(_, v)| v)))
+            .collect();
+        for operand in query
+            .conditions
+            .iter()
+            .flat_map(|(a, _, b)| [a, b])
+            .chain(&query.returns)
+        {
+            if let Operand::Value(name) | Operand::Id(name) = operand {
+                if !bound.contains(name) {
+                    return Err(ParseError {
+                        position: source.len(),
+                        message: format!("unbound variable '{}'", name),
+                    });
+                }
+            }
+        }
+        Ok(query)
+    }
+}
+
+impl Graph {
+    /// Nodes at the end of some walk of min..=max edges from `start`
+    fn hop_targets(&self, start: usize, range: &HopRange) -> Vec<usize> {
+        let step = |frontier: &[usize]| {
+            let mut next: Vec<usize> = frontier
+                .iter()
+                .flat_map(|&v| self.nodes[v].neighbors.iter().copied())
+                .collect();
+            next.sort_unstable();
+            next.dedup();
+            next
+        };
+        let mut frontier = vec![start];
+        for _ in 0..range.min {
+            frontier = step(&frontier);
+        }
+        let mut targets: BTreeSet<usize> = frontier.iter().copied().collect();
+        match range.max {
+            Some(max) => {
+                for _ in range.min..max {
+                    frontier = step(&frontier);
+                    targets.extend(&frontier);
+                }
+            }
+            None => {
+                // Unbounded: everything reachable from the exact-min frontier
+                let mut stack = frontier;
+                while let Some(v) = stack.pop() {
+                    for &w in &self.nodes[v].neighbors {
+                        if targets.insert(w) {
+                            stack.push(w);
+                        }
+                    }
+                }
+            }
+        }
+        targets.into_iter().collect()
+    }
+
+    /// Run a Cypher-like query (see `Query::parse`). Variable-length steps match
+    /// walks, so repeated nodes are allowed, and each distinct binding of the
+    /// MATCH variables produces one row.
+    pub fn run_query(&self, source: &str) -> Result<QueryResult, ParseError> {
+        let query = Query::parse(source)?;
+        let mut bindings: Vec<HashMap<String, usize>> = vec![HashMap::new()];
+
+        // Bind (or check) a variable, keeping each binding that agrees
+        let bind = |bindings: Vec<HashMap<String, usize>>,
+                    name: &str,
+                    choices: &dyn Fn(&HashMap<String, usize>) -> Vec<usize>| {
+            let mut extended = Vec::new();
+            for binding in bindings {
+                for node in choices(&binding) {
+                    match binding.get(name) {
+                        Some(&bound) if bound != node => {}
+                        Some(_) => extended.push(binding.clone()),
+                        None => {
+                            let mut binding = binding.clone();
+                            binding.insert(name.to_string(), node);
+                            extended.push(binding);
+                        }
+                    }
+                }
+            }
+            extended
+        };
+
+        for (first, steps) in &query.chains {
+            bindings = bind(bindings, first, &|_| (0..self.nodes.len()).collect());
+            let mut previous = first;
+            for (range, name) in steps {
+                bindings = bind(bindings, name, &|binding| {
+                    self.hop_targets(binding[previous], range)
+                });
+                previous = name;
+            }
+        }
+
+        let evaluate = |operand: &Operand, binding: &HashMap<String, usize>| match operand {
+            Operand::Value(name) => i64::from(self.nodes[binding[name]].value),
+            Operand::Id(name) => binding[name] as i64,
+            Operand::Literal(n) => *n,
+        };
+        let rows = bindings
+            .iter()
+            .filter(|binding| {
+                query.conditions.iter().all(|(left, operator, right)| {
+                    let (a, b) = (evaluate(left, binding), evaluate(right, binding));
+                    match operator {
+                        Comparison::Less => a < b,
+                        Comparison::LessEqual => a <= b,
+                        Comparison::Greater => a > b,
+                        Comparison::GreaterEqual => a >= b,
+                        Comparison::Equal => a == b,
+                        Comparison::NotEqual => a != b,
+                    }
+                })
+            })
+            .map(|binding| {
+                query
+                    .returns
+                    .iter()
+                    .map(|item| evaluate(item, binding))
+                    .collect()
+            })
+            .collect();
+        let columns = query
+            .returns
+            .iter()
+            .map(|item| match item {
+                Operand::Value(name) => format!("{}.value", name),
+                Operand::Id(name) => name.clone(),
+                Operand::Literal(n) => n.to_string(),
+            })
+            .collect();
+        Ok(QueryResult { columns, rows })
+    }
+}
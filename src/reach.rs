@@ -0,0 +1,114 @@
+use crate::graph::{Graph, NodeId};
+
+/// Precomputed reachability for many `is_reachable` queries on a graph that
+/// does not change. Strongly connected components are collapsed, and the
+/// resulting DAG gets the compressed transitive closure by interval labeling
+/// (Agrawal, Borgida and Jagadish): its components are numbered in post-order
+/// of a spanning forest, and each one keeps the sorted, disjoint intervals of
+/// numbers it reaches. A query is a binary search over one such list.
+///
+/// Tree-like DAGs need about one interval per node. Each cross edge can add
+/// more, up to one per component in the worst case, so the index is
+/// O(V^2) for adversarial DAGs, against O(V + E) per query for a BFS.
+#[derive(Debug, Clone)]
+pub struct ReachIndex {
+    /// Component of each node of the graph
+    component_of: Vec<usize>,
+    /// Post-order number of each component in the spanning forest
+    post: Vec<usize>,
+    /// Inclusive post-order ranges each component reaches, by start
+    intervals: Vec<Vec<(usize, usize)>>,
+}
+
+impl ReachIndex {
+    /// Index `graph`, in O(V + E) plus the work of merging interval lists
+    pub fn build<T>(graph: &Graph<T>) -> Self {
+        let condensation = graph.condensation();
+        let dag = &condensation.graph;
+        let len = dag.nodes.len();
+
+        // Spanning forest by DFS from the sources; `low` is the first number
+        // given out inside a subtree, so the subtree of c is low[c]..=post[c]
+        let mut has_parent = vec![false; len];
+        for (_, to) in dag.edges() {
+            has_parent[to.index()] = true;
+        }
+        let mut post = vec![0; len];
+        let mut low = vec![0; len];
+        let mut visited = vec![false; len];
+        let mut counter = 0;
+        for root in (0..len).filter(|&c| !has_parent[c]) {
+            visited[root] = true;
+            low[root] = counter;
+            let mut calls = vec![(root, 0)];
+            while let Some(&mut (c, ref mut next)) = calls.last_mut() {
+                if let Some(&d) = dag.nodes[c].neighbors.get(*next) {
+                    *next += 1;
+                    if !visited[d] {
+                        visited[d] = true;
+                        low[d] = counter;
+                        calls.push((d, 0));
+                    }
+                    continue;
+                }
+                calls.pop();
+                post[c] = counter;
+                counter += 1;
+            }
+        }
+
+        // Components come in topological order, so every successor's list is
+        // complete before its predecessors merge it in
+        let mut intervals: Vec<Vec<(usize, usize)>> = vec![Vec::new(); len];
+        for c in (0..len).rev() {
+            let mut merged = vec![(low[c], post[c])];
+            for &d in &dag.nodes[c].neighbors {
+                merged.extend_from_slice(&intervals[d]);
+            }
+            intervals[c] = coalesce(merged);
+        }
+
+        ReachIndex {
+            component_of: condensation.component_of,
+            post,
+            intervals,
+        }
+    }
+
+    /// Whether `to` can be reached from `from` along directed edges, as
+    /// `Graph::is_reachable` answers for the indexed graph: every node
+    /// reaches itself, and out-of-range nodes reach nothing. O(log k) for k
+    /// intervals at `from`.
+    pub fn is_reachable(&self, from: NodeId, to: NodeId) -> bool {
+        let (Some(&from), Some(&to)) = (
+            self.component_of.get(from.index()),
+            self.component_of.get(to.index()),
+        ) else {
+            return false;
+        };
+        let number = self.post[to];
+        let intervals = &self.intervals[from];
+        let position = intervals.partition_point(|&(_, end)| end < number);
+        intervals
+            .get(position)
+            .is_some_and(|&(start, _)| start <= number)
+    }
+
+    /// Intervals stored over all components, a measure of the index's size
+    pub fn interval_count(&self) -> usize {
+        self.intervals.iter().map(Vec::len).sum()
+    }
+}
+
+/// Sort inclusive ranges and merge those that overlap or touch
+fn coalesce(mut ranges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
@@ -0,0 +1,101 @@
+use std::fmt::Display;
+
+use crate::graph::{Graph, NodeId};
+use crate::traversal::{BfsIterator, Traversal};
+
+impl<T: Display> Graph<T> {
+    /// Plain-text picture of a small graph for the terminal: an adjacency
+    /// table (index, value, out-neighbors) followed by the BFS tree from
+    /// `root`, one node per line as `index (value)` under its parent. An
+    /// out-of-range `root` leaves out the tree.
+    ///
+    /// ```text
+    /// node  value  neighbors
+    ///    0  a      1 2
+    ///    1  b      2
+    ///    2  c      -
+    ///
+    /// BFS tree from 0
+    /// 0 (a)
+    /// |-- 1 (b)
+    /// `-- 2 (c)
+    /// ```
+    pub fn render_ascii(&self, root: NodeId) -> String {
+        let values: Vec<String> = self
+            .nodes
+            .iter()
+            .map(|node| node.value.to_string())
+            .collect();
+        let index_width = self.nodes.len().saturating_sub(1).to_string().len().max(4);
+        let value_width = values
+            .iter()
+            .map(|value| value.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(5);
+
+        let mut out = format!(
+            "{:>index_width$}  {:<value_width$}  neighbors\n",
+            "node", "value"
+        );
+        for (index, node) in self.nodes.iter().enumerate() {
+            let neighbors = if node.neighbors.is_empty() {
+                "-".to_string()
+            } else {
+                let neighbors: Vec<String> = node
+                    .neighbors
+                    .iter()
+                    .map(|neighbor| neighbor.to_string())
+                    .collect();
+                neighbors.join(" ")
+            };
+            out += &format!(
+                "{:>index_width$}  {:<value_width$}  {}\n",
+                index, values[index], neighbors
+            );
+        }
+        if self.check_node(root).is_err() {
+            return out;
+        }
+
+        // Children in visit order, so siblings are listed as BFS reached them
+        let tree = BfsIterator::new(self, root).tree();
+        let mut children = vec![Vec::new(); self.nodes.len()];
+        for &node in &tree.order()[1..] {
+            if let Some(parent) = tree.parent_of(node) {
+                children[parent.index()].push(node.index());
+            }
+        }
+        out += &format!(
+            "\nBFS tree from {}\n{} ({})\n",
+            root.index(),
+            root.index(),
+            values[root.index()]
+        );
+        // (node, prefix for its line, prefix for its children's lines)
+        let mut pending: Vec<(usize, String, String)> = Vec::new();
+        let push_children =
+            |pending: &mut Vec<(usize, String, String)>, node: usize, indent: &str| {
+                let count = children[node].len();
+                // Pushed last first, so the first child pops first
+                for (position, &child) in children[node].iter().enumerate().rev() {
+                    let (branch, continuation) = if position + 1 == count {
+                        ("`-- ", "    ")
+                    } else {
+                        ("|-- ", "|   ")
+                    };
+                    pending.push((
+                        child,
+                        format!("{}{}", indent, branch),
+                        format!("{}{}", indent, continuation),
+                    ));
+                }
+            };
+        push_children(&mut pending, root.index(), "");
+        while let Some((node, line, indent)) = pending.pop() {
+            out += &format!("{}{} ({})\n", line, node, values[node]);
+            push_children(&mut pending, node, &indent);
+        }
+        out
+    }
+}
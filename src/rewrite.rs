@@ -0,0 +1,150 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::graph::{Graph, Node, NodeId};
+use crate::isomorphism::find_subgraph_matches_with;
+
+/// A graph rewrite rule: an occurrence of `pattern` is replaced by `replacement`.
+/// `interface` pairs pattern nodes with the replacement nodes they become; those
+/// nodes survive with their value and their edges to the rest of the graph. The
+/// other matched nodes are deleted, unpaired replacement nodes are added, and
+/// the matched pattern edges are swapped for the replacement's edges. Pairing
+/// several pattern nodes with one replacement node merges them.
+#[derive(Clone)]
+pub struct RewriteRule {
+    pattern: Graph,
+    replacement: Graph,
+    interface: Vec<(usize, usize)>, // (pattern index, replacement index)
+    node_match: NodeMatch,
+}
+
+// Predicate over (pattern node, graph node) pairs
+type NodeMatch = Rc<dyn Fn(&Node, &Node) -> bool>;
+
+impl RewriteRule {
+    pub fn new(pattern: Graph, replacement: Graph, interface: Vec<(NodeId, NodeId)>) -> Self {
+        Self {
+            pattern,
+            replacement,
+            interface: interface
+                .into_iter()
+                .map(|(p, r)| (p.index(), r.index()))
+                .collect(),
+            node_match: Rc::new(|_, _| true),
+        }
+    }
+
+    /// Only match graph nodes accepted by `node_match` (e.g. comparing values)
+    pub fn with_node_match<F: Fn(&Node, &Node) -> bool + 'static>(mut self, node_match: F) -> Self {
+        self.node_match = Rc::new(node_match);
+        self
+    }
+
+    /// Rewrite one occurrence; `mapping` gives the graph node of each pattern node
+    pub fn apply(&self, graph: &mut Graph, mapping: &[NodeId]) {
+        let mapping: Vec<usize> = mapping.iter().map(|node| node.index()).collect();
+        let mapping = &mapping;
+        let kept: HashMap<usize, usize> = self
+            .interface
+            .iter()
+            .map(|&(p, r)| (mapping[p], r))
+            .collect();
+        let deleted: HashSet<usize> = mapping
+            .iter()
+            .copied()
+            .filter(|target| !kept.contains_key(target))
+            .collect();
+        let matched_edges: HashSet<(usize, usize)> = self
+            .pattern
+            .nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(a, node)| {
+                node.neighbors
+                    .iter()
+                    .map(move |&b| (mapping[a], mapping[b]))
+            })
+            .collect();
+
+        // Kept nodes paired with the same replacement node merge into the first one
+        let mut representative: HashMap<usize, usize> = HashMap::new();
+        for &(p, r) in &self.interface {
+            representative.entry(r).or_insert(mapping[p]);
+        }
+        let alias = |index: usize| kept.get(&index).map_or(index, |r| representative[r]);
+
+        // Surviving nodes keep their order, followed by the new replacement nodes
+        let mut renumber = vec![usize::MAX; graph.nodes.len()];
+        let mut survivors = Vec::new();
+        for (index, slot) in renumber.iter_mut().enumerate() {
+            if !deleted.contains(&index) && alias(index) == index {
+                *slot = survivors.len();
+                survivors.push(index);
+            }
+        }
+        for index in 0..graph.nodes.len() {
+            if !deleted.contains(&index) {
+                renumber[index] = renumber[alias(index)];
+            }
+        }
+
+        let mut nodes: Vec<Node> = survivors
+            .iter()
+            .map(|&index| Node {
+                value: graph.nodes[index].value,
+                neighbors: Vec::new(),
+            })
+            .collect();
+        for (index, node) in graph.nodes.iter().enumerate() {
+            if deleted.contains(&index) {
+                continue;
+            }
+            let edges = node.neighbors.iter().copied().filter(|&neighbor| {
+                !deleted.contains(&neighbor) && !matched_edges.contains(&(index, neighbor))
+            });
+            nodes[renumber[index]]
+                .neighbors
+                .extend(edges.map(|neighbor| renumber[neighbor]));
+        }
+
+        let mut image = vec![usize::MAX; self.replacement.nodes.len()];
+        for (&r, &target) in &representative {
+            image[r] = renumber[target];
+        }
+        for (r, node) in self.replacement.nodes.iter().enumerate() {
+            if image[r] == usize::MAX {
+                image[r] = nodes.len();
+                nodes.push(Node {
+                    value: node.value,
+                    neighbors: Vec::new(),
+                });
+            }
+        }
+        for (r, node) in self.replacement.nodes.iter().enumerate() {
+            for &neighbor in &node.neighbors {
+                nodes[image[r]].neighbors.push(image[neighbor]);
+            }
+        }
+        graph.nodes = nodes;
+    }
+}
+
+/// Repeatedly rewrite the first occurrence of the first matching rule until no
+/// rule matches or `max_steps` rewrites were made. Returns the rewrite count.
+pub fn apply_rules(graph: &mut Graph, rules: &[RewriteRule], max_steps: usize) -> usize {
+    let mut steps = 0;
+    'rewrite: while steps < max_steps {
+        for rule in rules {
+            let node_match = |p: &Node, t: &Node| (rule.node_match)(p, t);
+            let found =
+                find_subgraph_matches_with(&rule.pattern, graph, 1, node_match, |_, _| true);
+            if let Some(mapping) = found.first() {
+                rule.apply(graph, mapping);
+                steps += 1;
+                continue 'rewrite;
+            }
+        }
+        break;
+    }
+    steps
+}
@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+
+use crate::graph::{Graph, Node};
+use crate::transform::IdMapping;
+
+/// Small seeded pseudo-random generator (SplitMix64), so randomized
+/// algorithms are reproducible from a seed without pulling in a dependency
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in 0..n
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    /// Uniform value in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+impl<T: Clone> Graph<T> {
+    /// Induced subgraph on `k` nodes chosen uniformly at random, with the
+    /// original id of each sampled node (kept in ascending order); the whole
+    /// graph when k >= n
+    pub fn sample_nodes(&self, k: usize, seed: u64) -> (Graph<T>, IdMapping) {
+        let mut rng = Rng::new(seed);
+        let mut indices: Vec<usize> = (0..self.nodes.len()).collect();
+        rng.shuffle(&mut indices);
+        indices.truncate(k);
+        indices.sort_unstable();
+        let mapping = IdMapping::new(&indices, self.nodes.len());
+        (self.induced_on(&indices), mapping)
+    }
+
+    /// Subgraph made of `k` edges chosen uniformly at random and their endpoints.
+    /// Unlike the node samplers this is edge-induced: only sampled edges are kept.
+    /// Parallel edges are drawn separately; every edge is kept when k >= E.
+    pub fn sample_edges(&self, k: usize, seed: u64) -> (Graph<T>, IdMapping) {
+        let mut rng = Rng::new(seed);
+        let mut edges: Vec<(usize, usize)> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(index, node)| {
+                node.neighbors
+                    .iter()
+                    .map(move |&neighbor| (index, neighbor))
+            })
+            .collect();
+        rng.shuffle(&mut edges);
+        edges.truncate(k);
+        edges.sort_unstable();
+
+        let mut indices: Vec<usize> = edges.iter().flat_map(|&(a, b)| [a, b]).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        let mut sample = Graph {
+            nodes: indices
+                .iter()
+                .map(|&index| Node {
+                    value: self.nodes[index].value.clone(),
+                    neighbors: Vec::new(),
+                })
+                .collect(),
+        };
+        for (a, b) in edges {
+            let from = indices.binary_search(&a).unwrap();
+            let to = indices.binary_search(&b).unwrap();
+            sample.nodes[from].neighbors.push(to);
+        }
+        let mapping = IdMapping::new(&indices, self.nodes.len());
+        (sample, mapping)
+    }
+
+    /// Snowball sample of `target` nodes: grow breadth-first from a random seed
+    /// node, jumping to a new random seed whenever the current ball is exhausted;
+    /// at most n nodes
+    pub fn snowball_sample(&self, target: usize, seed: u64) -> (Graph<T>, IdMapping) {
+        self.spreading_sample(target, seed, |_, neighbors| neighbors.len())
+    }
+
+    /// Forest-fire sample of `target` nodes: each burning node ignites a
+    /// geometrically distributed number of its unburned neighbors (mean
+    /// p / (1 - p)), and a new fire starts at a random node when one dies out
+    pub fn forest_fire_sample(
+        &self,
+        target: usize,
+        forward_prob: f64,
+        seed: u64,
+    ) -> (Graph<T>, IdMapping) {
+        assert!(
+            (0.0..1.0).contains(&forward_prob),
+            "forward probability must be in [0, 1)"
+        );
+        self.spreading_sample(target, seed, |rng, neighbors| {
+            let mut burned = 0;
+            while burned < neighbors.len() && rng.next_f64() < forward_prob {
+                burned += 1;
+            }
+            burned
+        })
+    }
+
+    /// Shared driver for the spreading samplers. `spread` decides how many of the
+    /// shuffled unvisited neighbors of the current node join the sample.
+    fn spreading_sample<F>(&self, target: usize, seed: u64, mut spread: F) -> (Graph<T>, IdMapping)
+    where
+        F: FnMut(&mut Rng, &[usize]) -> usize,
+    {
+        let mut rng = Rng::new(seed);
+        let target = target.min(self.nodes.len());
+        let mut sampled = vec![false; self.nodes.len()];
+        let mut indices = Vec::new();
+        let mut queue = VecDeque::new();
+
+        while indices.len() < target {
+            let node_index = match queue.pop_front() {
+                Some(node_index) => node_index,
+                None => {
+                    // Start a new ball or fire from a random unsampled node
+                    let unsampled: Vec<usize> = (0..self.nodes.len())
+                        .filter(|&index| !sampled[index])
+                        .collect();
+                    let start = unsampled[rng.below(unsampled.len())];
+                    sampled[start] = true;
+                    indices.push(start);
+                    start
+                }
+            };
+
+            let mut neighbors: Vec<usize> = self
+                .simple_neighbor_indices(node_index)
+                .into_iter()
+                .filter(|&neighbor| !sampled[neighbor])
+                .collect();
+            rng.shuffle(&mut neighbors);
+            let count = spread(&mut rng, &neighbors);
+            for &neighbor in neighbors.iter().take(count) {
+                if indices.len() == target {
+                    break;
+                }
+                sampled[neighbor] = true;
+                indices.push(neighbor);
+                queue.push_back(neighbor);
+            }
+        }
+
+        indices.sort_unstable();
+        let mapping = IdMapping::new(&indices, self.nodes.len());
+        (self.induced_on(&indices), mapping)
+    }
+}
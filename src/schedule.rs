@@ -0,0 +1,178 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap};
+
+use crate::graph::{Graph, NodeId};
+
+/// Critical path analysis of a task DAG, where an edge u -> v means task u must
+/// finish before task v starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CriticalPath {
+    pub earliest_start: Vec<u64>,
+    pub latest_start: Vec<u64>,
+    pub slack: Vec<u64>,
+    /// Zero-slack chain of tasks from a start task to one finishing last
+    pub path: Vec<usize>,
+    pub length: u64,
+}
+
+/// Task placement from list scheduling: (worker, start time) per task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerSchedule {
+    pub assignments: Vec<(usize, u64)>,
+    pub makespan: u64,
+}
+
+impl<T> Graph<T> {
+    /// Earliest and latest start times, slack and the critical path for tasks
+    /// with the given durations; None if the graph has a cycle
+    pub fn critical_path<F>(&self, duration: F) -> Option<CriticalPath>
+    where
+        F: Fn(usize) -> u64,
+    {
+        let order = self.topological_indices()?;
+        let n = self.nodes.len();
+        let mut earliest_start = vec![0; n];
+        for &v in &order {
+            let finish = earliest_start[v] + duration(v);
+            for &w in &self.nodes[v].neighbors {
+                earliest_start[w] = earliest_start[w].max(finish);
+            }
+        }
+        let length = (0..n)
+            .map(|v| earliest_start[v] + duration(v))
+            .max()
+            .unwrap_or(0);
+
+        let mut latest_finish = vec![length; n];
+        for &v in order.iter().rev() {
+            for &w in &self.nodes[v].neighbors {
+                latest_finish[v] = latest_finish[v].min(latest_finish[w] - duration(w));
+            }
+        }
+        let latest_start: Vec<u64> = (0..n).map(|v| latest_finish[v] - duration(v)).collect();
+        let slack: Vec<u64> = (0..n)
+            .map(|v| latest_start[v] - earliest_start[v])
+            .collect();
+
+        // Follow tight zero-slack edges from the first critical start task
+        let mut path = Vec::new();
+        let mut current = order
+            .iter()
+            .copied()
+            .find(|&v| slack[v] == 0 && earliest_start[v] == 0);
+        while let Some(v) = current {
+            path.push(v);
+            let finish = earliest_start[v] + duration(v);
+            current = self.nodes[v]
+                .neighbors
+                .iter()
+                .copied()
+                .filter(|&w| slack[w] == 0 && earliest_start[w] == finish)
+                .min();
+        }
+        Some(CriticalPath {
+            earliest_start,
+            latest_start,
+            slack,
+            path,
+            length,
+        })
+    }
+
+    /// Heaviest path starting at `start` in a DAG with edge weights, as
+    /// (length, path), by relaxing edges in topological order. The path ends
+    /// at the reachable node farthest from `start`, the lowest index on ties;
+    /// a `start` without edges gives (0, [start]). None if the graph has a
+    /// cycle or `start` is out of range.
+    pub fn dag_longest_path<W>(&self, start: NodeId, weight: W) -> Option<(u64, Vec<NodeId>)>
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        self.check_node(start).ok()?;
+        let order = self.topological_indices()?;
+        let n = self.nodes.len();
+        let mut length: Vec<Option<u64>> = vec![None; n];
+        let mut predecessor = vec![None; n];
+        length[start.index()] = Some(0);
+        for &v in &order {
+            let Some(at) = length[v] else {
+                continue;
+            };
+            for &w in &self.nodes[v].neighbors {
+                let candidate = at.saturating_add(weight(NodeId::new(v), NodeId::new(w)));
+                if length[w].is_none_or(|longest| candidate > longest) {
+                    length[w] = Some(candidate);
+                    predecessor[w] = Some(v);
+                }
+            }
+        }
+
+        let (end, longest) = length
+            .iter()
+            .enumerate()
+            .filter_map(|(v, length)| length.map(|length| (v, length)))
+            .min_by_key(|&(v, length)| (Reverse(length), v))?;
+        let mut path = vec![NodeId::new(end)];
+        let mut current = end;
+        while let Some(previous) = predecessor[current] {
+            path.push(NodeId::new(previous));
+            current = previous;
+        }
+        path.reverse();
+        Some((longest, path))
+    }
+
+    /// List scheduling onto `workers` identical workers: whenever a worker is
+    /// idle it takes the ready task with the longest remaining path to the end
+    /// (ties to the lower index). None if the graph has a cycle.
+    pub fn list_schedule<F>(&self, duration: F, workers: usize) -> Option<WorkerSchedule>
+    where
+        F: Fn(usize) -> u64,
+    {
+        assert!(workers > 0, "list scheduling needs at least one worker");
+        let analysis = self.critical_path(&duration)?;
+        let n = self.nodes.len();
+        let priority = |v: usize| analysis.length - analysis.latest_start[v];
+
+        let mut indegree = vec![0usize; n];
+        for node in &self.nodes {
+            for &w in &node.neighbors {
+                indegree[w] += 1;
+            }
+        }
+        let mut ready: BinaryHeap<(u64, Reverse<usize>)> = (0..n)
+            .filter(|&v| indegree[v] == 0)
+            .map(|v| (priority(v), Reverse(v)))
+            .collect();
+        let mut idle: BTreeSet<usize> = (0..workers).collect();
+        let mut running: BinaryHeap<Reverse<(u64, usize, usize)>> = BinaryHeap::new();
+        let mut assignments = vec![(0, 0); n];
+        let (mut time, mut makespan) = (0, 0);
+        loop {
+            while !idle.is_empty() {
+                let Some((_, Reverse(task))) = ready.pop() else {
+                    break;
+                };
+                let worker = idle.pop_first().unwrap();
+                assignments[task] = (worker, time);
+                makespan = makespan.max(time + duration(task));
+                running.push(Reverse((time + duration(task), worker, task)));
+            }
+            let Some(Reverse((finish, worker, task))) = running.pop() else {
+                break;
+            };
+            time = finish;
+            idle.insert(worker);
+            for &w in &self.nodes[task].neighbors {
+                indegree[w] -= 1;
+                if indegree[w] == 0 {
+                    ready.push((priority(w), Reverse(w)));
+                }
+            }
+        }
+        Some(WorkerSchedule {
+            assignments,
+            makespan,
+        })
+    }
+}
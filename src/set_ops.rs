@@ -0,0 +1,167 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::graph::{Graph, Node};
+
+/// How `Graph::union`, `intersection` and `difference` decide that a node of
+/// one graph is a node of the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeIdentity {
+    /// Nodes with the same index are the same; values come from `self` where
+    /// it has the node, else from `other`
+    Index,
+    /// Nodes with equal values are the same, so equal values within one graph
+    /// also merge into a single node. Nodes are numbered in order of first
+    /// appearance, `self` before `other`.
+    Value,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SetOp {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl<T: Clone + Eq + Hash> Graph<T> {
+    /// Every node and edge of either graph. Edges are kept once each: those of
+    /// `self` in listed order, then those only `other` has.
+    pub fn union(&self, other: &Graph<T>, identity: NodeIdentity) -> Graph<T> {
+        self.combine(other, identity, SetOp::Union)
+    }
+
+    /// The nodes of both graphs with the edges both have between them
+    pub fn intersection(&self, other: &Graph<T>, identity: NodeIdentity) -> Graph<T> {
+        self.combine(other, identity, SetOp::Intersection)
+    }
+
+    /// The nodes of `self` with the edges of `self` that `other` lacks
+    pub fn difference(&self, other: &Graph<T>, identity: NodeIdentity) -> Graph<T> {
+        self.combine(other, identity, SetOp::Difference)
+    }
+
+    fn combine(&self, other: &Graph<T>, identity: NodeIdentity, op: SetOp) -> Graph<T> {
+        let NodeMatch {
+            values,
+            ours,
+            theirs,
+        } = match identity {
+            NodeIdentity::Index => self.match_by_index(other, op),
+            NodeIdentity::Value => self.match_by_value(other, op),
+        };
+        let their_edges: HashSet<(usize, usize)> = mapped_edges(other, &theirs).collect();
+        let mut nodes: Vec<Node<T>> = values
+            .into_iter()
+            .map(|value| Node {
+                value,
+                neighbors: Vec::new(),
+            })
+            .collect();
+        let mut kept = HashSet::new();
+        for (from, to) in mapped_edges(self, &ours) {
+            let keep = match op {
+                SetOp::Union => true,
+                SetOp::Intersection => their_edges.contains(&(from, to)),
+                SetOp::Difference => !their_edges.contains(&(from, to)),
+            };
+            if keep && kept.insert((from, to)) {
+                nodes[from].neighbors.push(to);
+            }
+        }
+        if op == SetOp::Union {
+            for (from, to) in mapped_edges(other, &theirs) {
+                if kept.insert((from, to)) {
+                    nodes[from].neighbors.push(to);
+                }
+            }
+        }
+        Graph { nodes }
+    }
+
+    /// Nodes matched by index
+    fn match_by_index(&self, other: &Graph<T>, op: SetOp) -> NodeMatch<T> {
+        let len = match op {
+            SetOp::Union => self.nodes.len().max(other.nodes.len()),
+            SetOp::Intersection => self.nodes.len().min(other.nodes.len()),
+            SetOp::Difference => self.nodes.len(),
+        };
+        let values = (0..len)
+            .map(|index| {
+                self.nodes
+                    .get(index)
+                    .unwrap_or_else(|| &other.nodes[index])
+                    .value
+                    .clone()
+            })
+            .collect();
+        let map = |graph: &Graph<T>| {
+            (0..graph.nodes.len())
+                .map(|index| (index < len).then_some(index))
+                .collect()
+        };
+        NodeMatch {
+            values,
+            ours: map(self),
+            theirs: map(other),
+        }
+    }
+
+    /// Nodes matched by value
+    fn match_by_value(&self, other: &Graph<T>, op: SetOp) -> NodeMatch<T> {
+        let in_other: HashSet<&T> = other.nodes.iter().map(|node| &node.value).collect();
+        let mut ids: HashMap<&T, usize> = HashMap::new();
+        let mut values = Vec::new();
+        let mut ours = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            if op == SetOp::Intersection && !in_other.contains(&node.value) {
+                ours.push(None);
+                continue;
+            }
+            let id = *ids.entry(&node.value).or_insert_with(|| {
+                values.push(node.value.clone());
+                values.len() - 1
+            });
+            ours.push(Some(id));
+        }
+        let mut theirs = Vec::with_capacity(other.nodes.len());
+        for node in &other.nodes {
+            let id = match op {
+                SetOp::Union => Some(*ids.entry(&node.value).or_insert_with(|| {
+                    values.push(node.value.clone());
+                    values.len() - 1
+                })),
+                _ => ids.get(&node.value).copied(),
+            };
+            theirs.push(id);
+        }
+        NodeMatch {
+            values,
+            ours,
+            theirs,
+        }
+    }
+}
+
+/// Nodes of a combined graph: their values, and the combined node of each
+/// node of `self` and of `other` (None for nodes left out)
+struct NodeMatch<T> {
+    values: Vec<T>,
+    ours: Vec<Option<usize>>,
+    theirs: Vec<Option<usize>>,
+}
+
+/// Edges of `graph` between mapped nodes, in result numbering
+fn mapped_edges<'a, T>(
+    graph: &'a Graph<T>,
+    map: &'a [Option<usize>],
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    graph
+        .nodes
+        .iter()
+        .enumerate()
+        .flat_map(move |(from, node)| {
+            node.neighbors
+                .iter()
+                .filter_map(move |&to| Some((map[from]?, map[to]?)))
+        })
+}
@@ -0,0 +1,146 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+
+use crate::graph::{Graph, NodeId};
+use crate::undirected::UnGraph;
+use crate::union_find::UnionFind;
+
+/// Algorithm behind `Graph::minimum_spanning_tree`. Both find a minimum
+/// spanning forest of the same total weight; with tied weights they may pick
+/// different edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MstAlgorithm {
+    /// Sort the edges by weight and keep each one joining two trees, tracked
+    /// with union-find; O(E log E)
+    Kruskal,
+    /// Grow each tree from its smallest node, always attaching the frontier
+    /// node with the lightest edge into the tree, as Dijkstra does with
+    /// distances; O(E log V)
+    Prim,
+}
+
+/// Edges and total weight of a minimum spanning forest: one tree per connected
+/// component, V - C edges for V nodes and C components
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanningTree {
+    edges: Vec<(NodeId, NodeId, u64)>,
+    total_weight: u64,
+}
+
+impl SpanningTree {
+    /// Tree edges as (a, b, weight) with a < b, in the order the algorithm
+    /// chose them
+    pub fn edges(&self) -> &[(NodeId, NodeId, u64)] {
+        &self.edges
+    }
+
+    pub fn total_weight(&self) -> u64 {
+        self.total_weight
+    }
+
+    fn add(&mut self, a: usize, b: usize, weight: u64) {
+        let (a, b) = (a.min(b), a.max(b));
+        self.edges.push((NodeId::new(a), NodeId::new(b), weight));
+        self.total_weight = self.total_weight.saturating_add(weight);
+    }
+}
+
+impl<T> Graph<T> {
+    /// Minimum spanning forest, reading every edge as undirected. When both
+    /// a -> b and b -> a are listed, or an edge is listed twice, the lightest
+    /// weight counts; self-loops are ignored.
+    pub fn minimum_spanning_tree<W>(&self, weight: W, algorithm: MstAlgorithm) -> SpanningTree
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        let edges = self.undirected_weights(weight);
+        match algorithm {
+            MstAlgorithm::Kruskal => kruskal(self.nodes.len(), &edges),
+            MstAlgorithm::Prim => prim(self.nodes.len(), &edges),
+        }
+    }
+
+    /// Lightest weight of each undirected edge {a, b}, keyed by (a, b) with a < b
+    fn undirected_weights<W>(&self, weight: W) -> BTreeMap<(usize, usize), u64>
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        let mut edges = BTreeMap::new();
+        for (from, node) in self.nodes.iter().enumerate() {
+            for &to in node.neighbors.iter().filter(|&&to| to != from) {
+                let edge_weight = weight(NodeId::new(from), NodeId::new(to));
+                edges
+                    .entry((from.min(to), from.max(to)))
+                    .and_modify(|lightest: &mut u64| *lightest = (*lightest).min(edge_weight))
+                    .or_insert(edge_weight);
+            }
+        }
+        edges
+    }
+}
+
+impl<T> UnGraph<T> {
+    /// Minimum spanning forest; see `Graph::minimum_spanning_tree`
+    pub fn minimum_spanning_tree<W>(&self, weight: W, algorithm: MstAlgorithm) -> SpanningTree
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        self.as_graph().minimum_spanning_tree(weight, algorithm)
+    }
+}
+
+fn kruskal(len: usize, edges: &BTreeMap<(usize, usize), u64>) -> SpanningTree {
+    let mut by_weight: Vec<(u64, usize, usize)> = edges
+        .iter()
+        .map(|(&(a, b), &weight)| (weight, a, b))
+        .collect();
+    by_weight.sort_unstable();
+
+    let mut sets = UnionFind::new(len);
+    let mut tree = SpanningTree {
+        edges: Vec::new(),
+        total_weight: 0,
+    };
+    for (weight, a, b) in by_weight {
+        if sets.union(a, b) {
+            tree.add(a, b, weight);
+        }
+    }
+    tree
+}
+
+fn prim(len: usize, edges: &BTreeMap<(usize, usize), u64>) -> SpanningTree {
+    let mut adjacency = vec![Vec::new(); len];
+    for (&(a, b), &weight) in edges {
+        adjacency[a].push((b, weight));
+        adjacency[b].push((a, weight));
+    }
+
+    let mut tree = SpanningTree {
+        edges: Vec::new(),
+        total_weight: 0,
+    };
+    let mut in_tree = vec![false; len];
+    let mut heap = BinaryHeap::new(); // (edge weight, node index, tree node it hangs from)
+    for root in 0..len {
+        if in_tree[root] {
+            continue;
+        }
+        heap.push(Reverse((0, root, usize::MAX)));
+        while let Some(Reverse((weight, node_index, from))) = heap.pop() {
+            // Lazy deletion: skip nodes attached by a lighter edge already
+            if std::mem::replace(&mut in_tree[node_index], true) {
+                continue;
+            }
+            if from != usize::MAX {
+                tree.add(from, node_index, weight);
+            }
+            for &(neighbor_index, edge_weight) in &adjacency[node_index] {
+                if !in_tree[neighbor_index] {
+                    heap.push(Reverse((edge_weight, neighbor_index, node_index)));
+                }
+            }
+        }
+    }
+    tree
+}
@@ -0,0 +1,187 @@
+use nalgebra::{DMatrix, DVector};
+
+use crate::graph::Graph;
+use crate::partition::Partition;
+use crate::sampling::Rng;
+
+impl<T> Graph<T> {
+    /// Combinatorial Laplacian L = D - A as a dense matrix, reading edges as undirected
+    pub fn laplacian(&self) -> DMatrix<f64> {
+        let adjacency = self.undirected_adjacency();
+        let n = adjacency.len();
+        let mut matrix = DMatrix::zeros(n, n);
+        for (i, neighbors) in adjacency.iter().enumerate() {
+            matrix[(i, i)] = neighbors.len() as f64;
+            for &j in neighbors {
+                matrix[(i, j)] = -1.0;
+            }
+        }
+        matrix
+    }
+
+    /// Normalized Laplacian I - D^-1/2 A D^-1/2 (isolated nodes get a zero row)
+    pub fn normalized_laplacian(&self) -> DMatrix<f64> {
+        let adjacency = self.undirected_adjacency();
+        let n = adjacency.len();
+        let mut matrix = DMatrix::zeros(n, n);
+        for (i, neighbors) in adjacency.iter().enumerate() {
+            if neighbors.is_empty() {
+                continue;
+            }
+            matrix[(i, i)] = 1.0;
+            for &j in neighbors {
+                matrix[(i, j)] = -1.0 / ((neighbors.len() * adjacency[j].len()) as f64).sqrt();
+            }
+        }
+        matrix
+    }
+
+    /// Fiedler vector (eigenvector of the second smallest Laplacian eigenvalue)
+    /// and its eigenvalue, the algebraic connectivity
+    pub fn fiedler_vector(&self) -> (f64, DVector<f64>) {
+        self.laplacian_eigenvectors(1)
+            .pop()
+            .unwrap_or((0.0, DVector::zeros(0)))
+    }
+
+    /// The `count` smallest non-trivial eigenpairs of the Laplacian, skipping the
+    /// constant vector. Uses power iteration on c*I - L (c bounds the spectrum)
+    /// with sparse products and Gram-Schmidt deflation against earlier vectors,
+    /// so only vectors of length n are stored, never the matrix.
+    pub fn laplacian_eigenvectors(&self, count: usize) -> Vec<(f64, DVector<f64>)> {
+        let adjacency = self.undirected_adjacency();
+        let n = adjacency.len();
+        if n < 2 {
+            return Vec::new();
+        }
+        let laplacian_times = |x: &DVector<f64>| {
+            DVector::from_fn(n, |i, _| {
+                adjacency[i].len() as f64 * x[i] - adjacency[i].iter().map(|&j| x[j]).sum::<f64>()
+            })
+        };
+        let shift = 2.0 * adjacency.iter().map(Vec::len).max().unwrap_or(0) as f64 + 1.0;
+
+        let mut found = vec![DVector::from_element(n, 1.0 / (n as f64).sqrt())];
+        let mut pairs = Vec::new();
+        let mut rng = Rng::new(0x5EED);
+        for _ in 0..count.min(n - 1) {
+            let mut x = DVector::from_fn(n, |_, _| rng.next_f64() - 0.5);
+            for _ in 0..10_000 {
+                orthonormalize(&mut x, &found);
+                let mut next = &x * shift - laplacian_times(&x);
+                orthonormalize(&mut next, &found);
+                let change = (&next - &x).lp_norm(1);
+                x = next;
+                if change < 1e-10 {
+                    break;
+                }
+            }
+            let eigenvalue = x.dot(&laplacian_times(&x));
+            found.push(x.clone());
+            pairs.push((eigenvalue, x));
+        }
+        pairs
+    }
+}
+
+/// Remove the components of `x` along the orthonormal `basis` and rescale to unit length
+fn orthonormalize(x: &mut DVector<f64>, basis: &[DVector<f64>]) {
+    for b in basis {
+        let dot = x.dot(b);
+        x.axpy(-dot, b, 1.0);
+    }
+    x.try_normalize_mut(0.0);
+}
+
+impl<T> Graph<T> {
+    /// Split the nodes in two halves at the median of the Fiedler vector
+    pub fn spectral_bisection(&self) -> Partition {
+        let (_, fiedler) = self.fiedler_vector();
+        let mut ranked: Vec<usize> = (0..self.nodes.len()).collect();
+        ranked.sort_by(|&a, &b| fiedler[a].total_cmp(&fiedler[b]));
+        let mut parts = vec![0; self.nodes.len()];
+        for &v in &ranked[self.nodes.len() / 2..] {
+            parts[v] = 1;
+        }
+        Partition::new(self, parts)
+    }
+
+    /// k-way spectral clustering: embed each node by the k - 1 smallest
+    /// non-trivial Laplacian eigenvectors and cluster the embedding with
+    /// seeded k-means (k-means++ initialization)
+    pub fn spectral_clustering(&self, k: usize, seed: u64) -> Partition {
+        let n = self.nodes.len();
+        let k = k.clamp(1, n.max(1));
+        let vectors = self.laplacian_eigenvectors(k - 1);
+        let points: Vec<Vec<f64>> = (0..n)
+            .map(|v| vectors.iter().map(|(_, vector)| vector[v]).collect())
+            .collect();
+        Partition::new(self, k_means(&points, k, seed))
+    }
+}
+
+/// Lloyd's k-means with k-means++ seeding; returns a cluster per point
+fn k_means(points: &[Vec<f64>], k: usize, seed: u64) -> Vec<usize> {
+    let distance =
+        |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>();
+    let mut rng = Rng::new(seed);
+    let mut assignment = vec![0; points.len()];
+    if points.is_empty() || k <= 1 {
+        return assignment;
+    }
+
+    let mut centers = vec![points[rng.below(points.len())].clone()];
+    while centers.len() < k {
+        let weights: Vec<f64> = points
+            .iter()
+            .map(|p| {
+                centers
+                    .iter()
+                    .map(|c| distance(p, c))
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect();
+        let total: f64 = weights.iter().sum();
+        let mut pick = rng.next_f64() * total;
+        let mut chosen = points.len() - 1;
+        for (i, &w) in weights.iter().enumerate() {
+            if pick < w {
+                chosen = i;
+                break;
+            }
+            pick -= w;
+        }
+        centers.push(points[chosen].clone());
+    }
+
+    for _ in 0..100 {
+        let next: Vec<usize> = points
+            .iter()
+            .map(|p| {
+                (0..k)
+                    .min_by(|&a, &b| distance(p, &centers[a]).total_cmp(&distance(p, &centers[b])))
+                    .unwrap()
+            })
+            .collect();
+        let changed = next != assignment;
+        assignment = next;
+        for (c, center) in centers.iter_mut().enumerate() {
+            let members: Vec<&Vec<f64>> = points
+                .iter()
+                .zip(&assignment)
+                .filter(|&(_, &a)| a == c)
+                .map(|(p, _)| p)
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            for (d, coordinate) in center.iter_mut().enumerate() {
+                *coordinate = members.iter().map(|p| p[d]).sum::<f64>() / members.len() as f64;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    assignment
+}
@@ -0,0 +1,312 @@
+use crate::error::GraphError;
+use crate::graph::{Graph, Node};
+
+/// Handle to a node of a `StableGraph`: a slot index plus the generation of
+/// the node that held the slot when the handle was made. Removing the node
+/// bumps the generation, so an old handle is detectably stale even after the
+/// slot is reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StableId {
+    index: usize,
+    generation: u32,
+}
+
+impl StableId {
+    pub fn index(self) -> usize {
+        self.index
+    }
+
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+impl std::fmt::Display for StableId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T> {
+    generation: u32,
+    node: Option<StableNode<T>>,
+}
+
+#[derive(Debug, Clone)]
+struct StableNode<T> {
+    value: T,
+    out: Vec<usize>,      // Targets, with repeats for parallel edges
+    incoming: Vec<usize>, // Sources, likewise
+}
+
+/// A directed graph that supports removal. `Graph` numbers nodes densely, so
+/// deleting one would shift every later index; here nodes live in slots that
+/// stay put, a removed node leaves a vacant slot for the next `add_node` to
+/// reuse, and handles carry a generation so a handle to a removed node is
+/// rejected rather than silently meaning its successor. Each node also keeps
+/// its in-edges, so removing it drops its incident edges in time proportional
+/// to their count. Convert with `to_graph` to run the algorithms on `Graph`.
+#[derive(Debug, Clone)]
+pub struct StableGraph<T = i32> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    len: usize,
+    edge_count: usize,
+}
+
+impl<T> StableGraph<T> {
+    /// Empty graph
+    pub fn new() -> Self {
+        StableGraph {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            edge_count: 0,
+        }
+    }
+
+    /// The nodes and edges of `graph`, node i in slot i at generation 0
+    pub fn from_graph(graph: Graph<T>) -> Self {
+        let mut incoming = vec![Vec::new(); graph.nodes.len()];
+        for (from, node) in graph.nodes.iter().enumerate() {
+            for &to in &node.neighbors {
+                incoming[to].push(from);
+            }
+        }
+        let mut edge_count = 0;
+        let slots: Vec<Slot<T>> = graph
+            .nodes
+            .into_iter()
+            .zip(incoming)
+            .map(|(node, incoming)| {
+                edge_count += node.neighbors.len();
+                Slot {
+                    generation: 0,
+                    node: Some(StableNode {
+                        value: node.value,
+                        out: node.neighbors,
+                        incoming,
+                    }),
+                }
+            })
+            .collect();
+        StableGraph {
+            len: slots.len(),
+            slots,
+            free: Vec::new(),
+            edge_count,
+        }
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of edges, parallel edges counted separately
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    fn id(&self, index: usize) -> StableId {
+        StableId {
+            index,
+            generation: self.slots[index].generation,
+        }
+    }
+
+    /// Ok if `node` is a node of this graph that has not been removed
+    pub fn check_node(&self, node: StableId) -> Result<(), GraphError> {
+        match self.slots.get(node.index) {
+            None => Err(GraphError::NodeOutOfRange {
+                node: node.index,
+                len: self.slots.len(),
+            }),
+            Some(slot) if slot.generation != node.generation || slot.node.is_none() => {
+                Err(GraphError::RemovedNode { node: node.index })
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    pub fn contains(&self, node: StableId) -> bool {
+        self.check_node(node).is_ok()
+    }
+
+    fn entry(&self, node: StableId) -> Option<&StableNode<T>> {
+        let slot = self.slots.get(node.index)?;
+        slot.node
+            .as_ref()
+            .filter(|_| slot.generation == node.generation)
+    }
+
+    fn entry_mut(&mut self, node: StableId) -> Option<&mut StableNode<T>> {
+        let slot = self.slots.get_mut(node.index)?;
+        slot.node
+            .as_mut()
+            .filter(|_| slot.generation == node.generation)
+    }
+
+    /// Value of `node`; None if the handle is stale
+    pub fn value(&self, node: StableId) -> Option<&T> {
+        self.entry(node).map(|entry| &entry.value)
+    }
+
+    pub fn value_mut(&mut self, node: StableId) -> Option<&mut T> {
+        self.entry_mut(node).map(|entry| &mut entry.value)
+    }
+
+    /// Add a node without neighbors, in a vacant slot if there is one
+    pub fn add_node(&mut self, value: T) -> StableId {
+        let node = Some(StableNode {
+            value,
+            out: Vec::new(),
+            incoming: Vec::new(),
+        });
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                self.slots[index].node = node;
+                self.id(index)
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    node,
+                });
+                self.id(self.slots.len() - 1)
+            }
+        }
+    }
+
+    /// Remove `node` with all its in- and out-edges, returning its value, or
+    /// None if the handle is stale. Every handle to it becomes stale.
+    pub fn remove_node(&mut self, node: StableId) -> Option<T> {
+        self.check_node(node).ok()?;
+        let slot = &mut self.slots[node.index];
+        let entry = slot.node.take().expect("checked above");
+        slot.generation = slot.generation.wrapping_add(1);
+        let mut removed = entry.out.len();
+        for &to in entry.out.iter().filter(|&&to| to != node.index) {
+            let incoming = &mut self.slots[to].node.as_mut().expect("live target").incoming;
+            incoming.retain(|&from| from != node.index);
+        }
+        for &from in entry.incoming.iter().filter(|&&from| from != node.index) {
+            let out = &mut self.slots[from].node.as_mut().expect("live source").out;
+            out.retain(|&to| to != node.index);
+            removed += 1;
+        }
+        self.edge_count -= removed;
+        self.free.push(node.index);
+        self.len -= 1;
+        Some(entry.value)
+    }
+
+    /// Add a directed edge from -> to
+    pub fn add_edge(&mut self, from: StableId, to: StableId) -> Result<(), GraphError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+        self.live_mut(from.index).out.push(to.index);
+        self.live_mut(to.index).incoming.push(from.index);
+        self.edge_count += 1;
+        Ok(())
+    }
+
+    /// Remove one edge from -> to, returning whether there was one
+    pub fn remove_edge(&mut self, from: StableId, to: StableId) -> Result<bool, GraphError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+        let out = &mut self.live_mut(from.index).out;
+        let Some(position) = out.iter().position(|&target| target == to.index) else {
+            return Ok(false);
+        };
+        out.remove(position);
+        let incoming = &mut self.live_mut(to.index).incoming;
+        let position = incoming.iter().position(|&source| source == from.index);
+        incoming.remove(position.expect("in-edges mirror out-edges"));
+        self.edge_count -= 1;
+        Ok(true)
+    }
+
+    fn live_mut(&mut self, index: usize) -> &mut StableNode<T> {
+        self.slots[index].node.as_mut().expect("checked node")
+    }
+
+    /// Whether from -> to is an edge; false if either handle is stale
+    pub fn has_edge(&self, from: StableId, to: StableId) -> bool {
+        self.contains(to)
+            && self
+                .entry(from)
+                .is_some_and(|entry| entry.out.contains(&to.index))
+    }
+
+    /// Handles of the live nodes, in slot order
+    pub fn node_ids(&self) -> impl Iterator<Item = StableId> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.node.is_some())
+            .map(|(index, slot)| StableId {
+                index,
+                generation: slot.generation,
+            })
+    }
+
+    /// Out-neighbors of `node` in insertion order; none if the handle is stale
+    pub fn neighbors(&self, node: StableId) -> impl Iterator<Item = StableId> + '_ {
+        self.entry(node)
+            .into_iter()
+            .flat_map(|entry| entry.out.iter().map(|&to| self.id(to)))
+    }
+
+    /// In-neighbors of `node`; none if the handle is stale
+    pub fn incoming(&self, node: StableId) -> impl Iterator<Item = StableId> + '_ {
+        self.entry(node)
+            .into_iter()
+            .flat_map(|entry| entry.incoming.iter().map(|&from| self.id(from)))
+    }
+
+    /// Every edge as (from, to), in slot order of `from`
+    pub fn edges(&self) -> impl Iterator<Item = (StableId, StableId)> + '_ {
+        self.node_ids()
+            .flat_map(move |from| self.neighbors(from).map(move |to| (from, to)))
+    }
+}
+
+impl<T: Clone> StableGraph<T> {
+    /// Compact copy as a `Graph`, live nodes renumbered 0.. in slot order,
+    /// with the handle of each: `handles[i]` is the node that became i
+    pub fn to_graph(&self) -> (Graph<T>, Vec<StableId>) {
+        let handles: Vec<StableId> = self.node_ids().collect();
+        let mut dense = vec![usize::MAX; self.slots.len()];
+        for (new, handle) in handles.iter().enumerate() {
+            dense[handle.index] = new;
+        }
+        let nodes = handles
+            .iter()
+            .map(|handle| {
+                let entry = self.entry(*handle).expect("live node");
+                let neighbors = entry.out.iter().map(|&to| dense[to]).collect();
+                Node::new(entry.value.clone(), neighbors)
+            })
+            .collect();
+        (Graph { nodes }, handles)
+    }
+}
+
+impl<T> Default for StableGraph<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Graph<T>> for StableGraph<T> {
+    fn from(graph: Graph<T>) -> Self {
+        StableGraph::from_graph(graph)
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::graph::{Graph, NodeId};
+
+impl<T> Graph<T> {
+    /// Number of directed edges, counting parallel edges and self-loops
+    pub fn edge_count(&self) -> usize {
+        self.nodes.iter().map(|node| node.neighbors.len()).sum()
+    }
+
+    /// Out-degree histogram as (degree, node count) pairs, by ascending degree;
+    /// degrees no node has are left out
+    pub fn degree_distribution(&self) -> Vec<(usize, usize)> {
+        let mut histogram = BTreeMap::new();
+        for node in &self.nodes {
+            *histogram.entry(node.neighbors.len()).or_insert(0) += 1;
+        }
+        histogram.into_iter().collect()
+    }
+
+    /// Mean out-degree, edges / nodes; 0 for an empty graph
+    pub fn average_degree(&self) -> f64 {
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+        self.edge_count() as f64 / self.nodes.len() as f64
+    }
+
+    /// Fraction of the n * (n - 1) possible directed edges present; 0 with
+    /// fewer than two nodes. Parallel edges and self-loops count too, so a
+    /// multigraph can exceed 1.
+    pub fn density(&self) -> f64 {
+        let n = self.nodes.len();
+        if n < 2 {
+            return 0.0;
+        }
+        self.edge_count() as f64 / (n * (n - 1)) as f64
+    }
+
+    /// Largest hop distance from `node` along out-edges; None if some node is
+    /// unreachable from it, the eccentricity then being infinite. Panics if
+    /// `node` is out of range.
+    pub fn eccentricity(&self, node: NodeId) -> Option<usize> {
+        let mut distance = vec![usize::MAX; self.nodes.len()];
+        distance[node.index()] = 0;
+        let (mut reached, mut farthest) = (1, 0);
+        let mut queue = VecDeque::from([node.index()]);
+        while let Some(v) = queue.pop_front() {
+            farthest = distance[v];
+            for &w in &self.nodes[v].neighbors {
+                if distance[w] == usize::MAX {
+                    distance[w] = distance[v] + 1;
+                    reached += 1;
+                    queue.push_back(w);
+                }
+            }
+        }
+        (reached == self.nodes.len()).then_some(farthest)
+    }
+
+    /// Largest eccentricity, by one BFS per node; None if the graph is empty
+    /// or not strongly connected
+    pub fn diameter(&self) -> Option<usize> {
+        let mut largest = None;
+        for node in self.node_ids() {
+            largest = largest.max(Some(self.eccentricity(node)?));
+        }
+        largest
+    }
+
+    /// Smallest eccentricity, by one BFS per node; None if the graph is empty
+    /// or no node reaches every other
+    pub fn radius(&self) -> Option<usize> {
+        self.node_ids()
+            .filter_map(|node| self.eccentricity(node))
+            .min()
+    }
+
+    /// Eccentricity of every node, by index, from one BFS per node
+    pub fn eccentricities(&self) -> Vec<Option<usize>> {
+        self.node_ids()
+            .map(|node| self.eccentricity(node))
+            .collect()
+    }
+
+    /// Nodes of smallest eccentricity, the radius, in index order: those
+    /// that reach every node in the fewest hops, good hubs to start from.
+    /// Empty if no node reaches every other.
+    pub fn center(&self) -> Vec<NodeId> {
+        extremes(&self.eccentricities(), false)
+    }
+
+    /// Nodes of largest eccentricity, the diameter, in index order. Empty
+    /// unless the graph is strongly connected, as `diameter` is then None.
+    pub fn periphery(&self) -> Vec<NodeId> {
+        extremes(&self.eccentricities(), true)
+    }
+}
+
+/// Nodes whose finite eccentricity is the smallest, or with `largest` the
+/// largest provided every eccentricity is finite
+pub(crate) fn extremes(eccentricities: &[Option<usize>], largest: bool) -> Vec<NodeId> {
+    let finite = eccentricities.iter().flatten().copied();
+    let target = if !largest {
+        finite.min()
+    } else if eccentricities.contains(&None) {
+        None
+    } else {
+        finite.max()
+    };
+    eccentricities
+        .iter()
+        .enumerate()
+        .filter(|&(_, &eccentricity)| target.is_some() && eccentricity == target)
+        .map(|(node, _)| NodeId::new(node))
+        .collect()
+}
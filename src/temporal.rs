@@ -0,0 +1,225 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::error::GraphError;
+use crate::graph::{Graph, Node, NodeId};
+
+/// Directed edge of a `TemporalGraph`, usable at any time in start..=end; a
+/// timestamped contact has start == end
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TemporalEdge {
+    pub from: NodeId,
+    pub to: NodeId,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Graph whose edges exist only at certain times, such as contacts between
+/// people or calls between services in a log. Travel along an edge takes no
+/// time, and a time-respecting path crosses its edges at non-decreasing
+/// times. `as_of(t)` gives the ordinary `Graph` of the edges valid at `t`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemporalGraph<T = i32> {
+    values: Vec<T>,
+    edges: Vec<TemporalEdge>,
+    /// Indices into `edges` of each node's out-edges, in insertion order
+    out: Vec<Vec<usize>>,
+}
+
+impl<T> Default for TemporalGraph<T> {
+    fn default() -> Self {
+        TemporalGraph {
+            values: Vec::new(),
+            edges: Vec::new(),
+            out: Vec::new(),
+        }
+    }
+}
+
+impl<T> TemporalGraph<T> {
+    /// Empty temporal graph
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_node(&mut self, value: T) -> NodeId {
+        self.values.push(value);
+        self.out.push(Vec::new());
+        NodeId::new(self.values.len() - 1)
+    }
+
+    /// Add a from -> to contact at `time`
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, time: u64) -> Result<(), GraphError> {
+        self.add_interval_edge(from, to, time, time)
+    }
+
+    /// Add a from -> to edge valid from `start` to `end`, both included; an
+    /// empty interval (end < start) is stored but never usable
+    pub fn add_interval_edge(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        start: u64,
+        end: u64,
+    ) -> Result<(), GraphError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+        self.out[from.index()].push(self.edges.len());
+        self.edges.push(TemporalEdge {
+            from,
+            to,
+            start,
+            end,
+        });
+        Ok(())
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Every edge, in insertion order
+    pub fn edges(&self) -> &[TemporalEdge] {
+        &self.edges
+    }
+
+    pub fn check_node(&self, node: NodeId) -> Result<(), GraphError> {
+        if node.index() < self.values.len() {
+            Ok(())
+        } else {
+            Err(GraphError::NodeOutOfRange {
+                node: node.index(),
+                len: self.values.len(),
+            })
+        }
+    }
+
+    /// Earliest time each node can be reached from `start` by a
+    /// time-respecting path leaving no earlier than `time`; None where it
+    /// cannot. `start` itself is reached at `time`.
+    pub fn earliest_arrival(
+        &self,
+        start: NodeId,
+        time: u64,
+    ) -> Result<Vec<Option<u64>>, GraphError> {
+        Ok(self.search(start, time)?.arrival)
+    }
+
+    /// Time-respecting BFS from `start` at `time`: the reachable nodes with
+    /// their earliest arrival times, in order of arrival (ties by index), so
+    /// `start` comes first
+    pub fn time_respecting_bfs(
+        &self,
+        start: NodeId,
+        time: u64,
+    ) -> Result<Vec<(NodeId, u64)>, GraphError> {
+        let search = self.search(start, time)?;
+        Ok(search
+            .order
+            .into_iter()
+            .map(|node| (NodeId::new(node), search.arrival[node].expect("visited")))
+            .collect())
+    }
+
+    /// Time-respecting path from `from` to `to` that arrives as early as
+    /// possible, leaving no earlier than `time`: each node with the time it
+    /// is reached, from (`from`, `time`) to `to`. None if `to` is out of
+    /// range or cannot be reached.
+    pub fn time_respecting_path(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        time: u64,
+    ) -> Result<Option<Vec<(NodeId, u64)>>, GraphError> {
+        let search = self.search(from, time)?;
+        let Some(Some(arrival)) = search.arrival.get(to.index()) else {
+            return Ok(None);
+        };
+        let mut path = vec![(to, *arrival)];
+        let mut current = to.index();
+        while let Some(parent) = search.parent[current] {
+            path.push((
+                NodeId::new(parent),
+                search.arrival[parent].expect("visited"),
+            ));
+            current = parent;
+        }
+        path.reverse();
+        Ok(Some(path))
+    }
+
+    /// Dijkstra on arrival times: an edge valid in start..=end, left at the
+    /// earliest moment after arriving at t, delivers at max(t, start) if
+    /// that is no later than end. Waiting never lets a path arrive sooner,
+    /// so each node's first settling is final.
+    fn search(&self, start: NodeId, time: u64) -> Result<TemporalSearch, GraphError> {
+        self.check_node(start)?;
+        let len = self.values.len();
+        let mut arrival = vec![None; len];
+        let mut parent = vec![None; len];
+        let mut settled = vec![false; len];
+        let mut order = Vec::new();
+        let mut heap = BinaryHeap::new();
+        arrival[start.index()] = Some(time);
+        heap.push(Reverse((time, start.index())));
+        while let Some(Reverse((now, node))) = heap.pop() {
+            if std::mem::replace(&mut settled[node], true) {
+                continue;
+            }
+            order.push(node);
+            for &edge in &self.out[node] {
+                let edge = &self.edges[edge];
+                let departure = now.max(edge.start);
+                let next = edge.to.index();
+                if departure > edge.end || arrival[next].is_some_and(|best| best <= departure) {
+                    continue;
+                }
+                arrival[next] = Some(departure);
+                parent[next] = Some(node);
+                heap.push(Reverse((departure, next)));
+            }
+        }
+        Ok(TemporalSearch {
+            arrival,
+            parent,
+            order,
+        })
+    }
+}
+
+impl<T: Clone> TemporalGraph<T> {
+    /// Snapshot of the graph at `time`: every node, and the edges valid then
+    pub fn as_of(&self, time: u64) -> Graph<T> {
+        let nodes = self
+            .values
+            .iter()
+            .zip(&self.out)
+            .map(|(value, out)| {
+                let neighbors = out
+                    .iter()
+                    .map(|&edge| &self.edges[edge])
+                    .filter(|edge| edge.start <= time && time <= edge.end)
+                    .map(|edge| edge.to.index())
+                    .collect();
+                Node::new(value.clone(), neighbors)
+            })
+            .collect();
+        Graph::from_nodes(nodes)
+    }
+}
+
+struct TemporalSearch {
+    arrival: Vec<Option<u64>>,
+    parent: Vec<Option<usize>>,
+    /// Nodes in the order they were settled
+    order: Vec<usize>,
+}
@@ -0,0 +1,732 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use crate::graph::{dedup_neighbors, Graph, Node, NodeId};
+use crate::matrix::AdjMatrixGraph;
+
+impl<T> Graph<T> {
+    /// Sorted neighbors of a node with duplicates and self-loops removed;
+    /// panics if `node` is out of range
+    pub fn simple_neighbors(&self, node: NodeId) -> Vec<NodeId> {
+        self.simple_neighbor_indices(node.index())
+            .into_iter()
+            .map(NodeId::new)
+            .collect()
+    }
+
+    pub(crate) fn simple_neighbor_indices(&self, index: usize) -> Vec<usize> {
+        let mut neighbors: Vec<usize> = self.nodes[index]
+            .neighbors
+            .iter()
+            .copied()
+            .filter(|&neighbor| neighbor != index)
+            .collect();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+
+    /// Number of edges the complement would have.
+    /// Check this before calling `complement` on a sparse graph: the complement
+    /// of a sparse graph is dense, with close to V^2 edges.
+    pub fn complement_edge_count(&self) -> usize {
+        let n = self.nodes.len();
+        let present: usize = (0..n)
+            .map(|index| self.simple_neighbor_indices(index).len())
+            .sum();
+        n * n.saturating_sub(1) - present
+    }
+
+    /// Stream the complement's edges (i, j) in index order without materializing them.
+    /// Only one node's neighbor list is held in memory at a time.
+    pub fn complement_edges(&self) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        let n = self.nodes.len();
+        (0..n).flat_map(move |index| {
+            let neighbors = self.simple_neighbor_indices(index);
+            (0..n)
+                .filter(move |&other| other != index && neighbors.binary_search(&other).is_err())
+                .map(move |other| (NodeId::new(index), NodeId::new(other)))
+        })
+    }
+}
+
+impl<T: Clone> Graph<T> {
+    /// The complement graph: i -> j is an edge exactly when it is not one here.
+    /// Node values are kept and self-loops are never produced.
+    pub fn complement(&self) -> Graph<T> {
+        let mut nodes: Vec<Node<T>> = self
+            .nodes
+            .iter()
+            .map(|node| Node {
+                value: node.value.clone(),
+                neighbors: Vec::new(),
+            })
+            .collect();
+        for (from, to) in self.complement_edges() {
+            nodes[from.index()].neighbors.push(to.index());
+        }
+        Graph { nodes }
+    }
+}
+
+impl<T: Clone> Graph<T> {
+    /// The transpose: every edge a -> b becomes b -> a, values kept
+    pub fn transpose(&self) -> Graph<T> {
+        let mut transposed = self.clone();
+        transposed.reverse_edges();
+        transposed
+    }
+}
+
+impl<T: Clone> Graph<T> {
+    /// Reachability as a bit matrix: u -> v is set when a path of one or more
+    /// edges leads from u to v, so u -> u only when u lies on a cycle. Built
+    /// on the condensation, sink components first, each row the OR of its
+    /// successors' rows 64 nodes per word: O((V + E) * V / 64) word operations.
+    pub fn transitive_closure(&self) -> AdjMatrixGraph<T> {
+        let condensation = self.condensation();
+        let mut closure =
+            AdjMatrixGraph::from_values(self.nodes.iter().map(|node| node.value.clone()).collect());
+        let words = self.nodes.len().div_ceil(64);
+        // Reach of each component, filled in reverse topological order
+        let mut reach: Vec<Vec<u64>> = vec![Vec::new(); condensation.components.len()];
+        for c in (0..condensation.components.len()).rev() {
+            let mut row = vec![0u64; words];
+            let members = &condensation.components[c];
+            let cyclic = members.len() > 1 || {
+                let v = members[0].index();
+                self.nodes[v].neighbors.contains(&v)
+            };
+            if cyclic {
+                for member in members {
+                    row[member.index() / 64] |= 1 << (member.index() % 64);
+                }
+            }
+            for &d in &condensation.graph.nodes[c].neighbors {
+                for (word, &reached) in row.iter_mut().zip(&reach[d]) {
+                    *word |= reached;
+                }
+                for member in &condensation.components[d] {
+                    row[member.index() / 64] |= 1 << (member.index() % 64);
+                }
+            }
+            for member in members {
+                closure.row_mut(member.index()).copy_from_slice(&row);
+            }
+            reach[c] = row;
+        }
+        closure
+    }
+
+    /// The fewest edges with the same reachability, for DAGs: edge u -> v
+    /// stays unless v is also reachable through another successor of u.
+    /// Parallel edges merge into one and neighbors keep their order. None if
+    /// the graph has a cycle, where the reduction is not unique.
+    pub fn transitive_reduction(&self) -> Option<Graph<T>> {
+        self.topological_indices()?;
+        let closure = self.transitive_closure();
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let mut neighbors = node.neighbors.clone();
+                dedup_neighbors(&mut neighbors);
+                let successors = neighbors.clone();
+                neighbors.retain(|&v| {
+                    !successors
+                        .iter()
+                        .any(|&w| w != v && closure.has_edge(NodeId::new(w), NodeId::new(v)))
+                });
+                Node::new(node.value.clone(), neighbors)
+            })
+            .collect();
+        Some(Graph { nodes })
+    }
+}
+
+impl<T> Graph<T> {
+    /// Flip every edge in place. Each node's new neighbors are the nodes that
+    /// pointed to it, in index order, with parallel edges kept.
+    pub fn reverse_edges(&mut self) {
+        let mut reversed = vec![Vec::new(); self.nodes.len()];
+        for (index, node) in self.nodes.iter().enumerate() {
+            for &neighbor_index in &node.neighbors {
+                reversed[neighbor_index].push(index);
+            }
+        }
+        for (node, neighbors) in self.nodes.iter_mut().zip(reversed) {
+            node.neighbors = neighbors;
+        }
+    }
+}
+
+impl<T> Graph<T> {
+    /// Edges read as undirected pairs (a, b) with a < b, sorted and deduplicated.
+    /// Self-loops are dropped.
+    pub fn undirected_edges(&self) -> Vec<(NodeId, NodeId)> {
+        self.undirected_edge_indices()
+            .into_iter()
+            .map(|(a, b)| (NodeId::new(a), NodeId::new(b)))
+            .collect()
+    }
+
+    pub(crate) fn undirected_edge_indices(&self) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = (0..self.nodes.len())
+            .flat_map(|index| {
+                self.simple_neighbor_indices(index)
+                    .into_iter()
+                    .map(move |neighbor| (index.min(neighbor), index.max(neighbor)))
+            })
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    /// Symmetrized simple adjacency lists (edges read as undirected)
+    pub(crate) fn undirected_adjacency(&self) -> Vec<Vec<usize>> {
+        let mut adjacency = vec![Vec::new(); self.nodes.len()];
+        for (a, b) in self.undirected_edge_indices() {
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+        }
+        adjacency
+    }
+
+    /// The line graph, reading edges as undirected: one node per edge, with two
+    /// nodes adjacent when their edges share an endpoint. Node i stands for
+    /// `edges[i]` of the returned mapping and has value i.
+    pub fn line_graph(&self) -> (Graph, Vec<(NodeId, NodeId)>) {
+        let edges = self.undirected_edge_indices();
+        let mut incident = vec![Vec::new(); self.nodes.len()];
+        for (edge_index, &(a, b)) in edges.iter().enumerate() {
+            incident[a].push(edge_index);
+            incident[b].push(edge_index);
+        }
+
+        let mut nodes: Vec<Node> = (0..edges.len())
+            .map(|edge_index| Node {
+                value: edge_index as i32,
+                neighbors: Vec::new(),
+            })
+            .collect();
+        for edge_indices in &incident {
+            for &e in edge_indices {
+                for &f in edge_indices {
+                    if e != f {
+                        nodes[e].neighbors.push(f);
+                    }
+                }
+            }
+        }
+        for node in &mut nodes {
+            node.neighbors.sort_unstable();
+        }
+        let edges = edges
+            .into_iter()
+            .map(|(a, b)| (NodeId::new(a), NodeId::new(b)))
+            .collect();
+        (Graph { nodes }, edges)
+    }
+}
+
+/// Product of two graphs, labeling each node by a coordinate pair.
+/// Node (a, b) has index `a * right_len + b` and that index as its value.
+#[derive(Debug)]
+pub struct ProductGraph {
+    pub graph: Graph,
+    right_len: usize,
+}
+
+impl ProductGraph {
+    fn new(left_len: usize, right_len: usize) -> Self {
+        let nodes = (0..left_len * right_len)
+            .map(|index| Node {
+                value: index as i32,
+                neighbors: Vec::new(),
+            })
+            .collect();
+        Self {
+            graph: Graph { nodes },
+            right_len,
+        }
+    }
+
+    /// Node labeled (a, b), for a in the left graph and b in the right
+    pub fn index_of(&self, a: NodeId, b: NodeId) -> NodeId {
+        NodeId::new(a.index() * self.right_len + b.index())
+    }
+
+    /// Coordinate pair labeling `node`
+    pub fn coords(&self, node: NodeId) -> (NodeId, NodeId) {
+        let index = node.index();
+        (
+            NodeId::new(index / self.right_len),
+            NodeId::new(index % self.right_len),
+        )
+    }
+
+    fn add_edge(&mut self, from: (usize, usize), to: (usize, usize)) {
+        let from = from.0 * self.right_len + from.1;
+        self.graph.nodes[from]
+            .neighbors
+            .push(to.0 * self.right_len + to.1);
+    }
+}
+
+impl<T> Graph<T> {
+    /// Cartesian product: (a, b) -> (a', b) for every edge a -> a', and
+    /// (a, b) -> (a, b') for every edge b -> b'. Two paths give a grid and two
+    /// cycles give a torus.
+    pub fn cartesian_product<U>(&self, other: &Graph<U>) -> ProductGraph {
+        let mut product = ProductGraph::new(self.nodes.len(), other.nodes.len());
+        for a in 0..self.nodes.len() {
+            for b in 0..other.nodes.len() {
+                for &next_a in &self.nodes[a].neighbors {
+                    product.add_edge((a, b), (next_a, b));
+                }
+                for &next_b in &other.nodes[b].neighbors {
+                    product.add_edge((a, b), (a, next_b));
+                }
+            }
+        }
+        product
+    }
+
+    /// Tensor (categorical) product: (a, b) -> (a', b') whenever a -> a' and b -> b'
+    pub fn tensor_product<U>(&self, other: &Graph<U>) -> ProductGraph {
+        let mut product = ProductGraph::new(self.nodes.len(), other.nodes.len());
+        for a in 0..self.nodes.len() {
+            for b in 0..other.nodes.len() {
+                for &next_a in &self.nodes[a].neighbors {
+                    for &next_b in &other.nodes[b].neighbors {
+                        product.add_edge((a, b), (next_a, next_b));
+                    }
+                }
+            }
+        }
+        product
+    }
+}
+
+impl<T: Clone> Graph<T> {
+    /// Merge node `b` into node `a` with `contract_nodes` and return the
+    /// merged node's new id, valued `combine(a, b)`. It takes the smaller of
+    /// the two ids, and ids above the larger shift down by one. Edges between
+    /// the two disappear, and every other edge of `b` is rewired to the merged
+    /// node. With `dedup`, the parallel edges created by the merge are
+    /// collapsed into one.
+    pub fn merge_nodes<F>(&mut self, a: NodeId, b: NodeId, combine: F, dedup: bool) -> NodeId
+    where
+        F: Fn(&T, &T) -> T,
+    {
+        assert!(a != b, "cannot merge a node with itself");
+        let (mut merged, contraction) =
+            self.contract_nodes(&[vec![a, b]], |values| match *values {
+                [value] => value.clone(),
+                // Values come in index order
+                [low, high] if a < b => combine(low, high),
+                [low, high] => combine(high, low),
+                _ => unreachable!("super-nodes hold one or two nodes"),
+            });
+        let lists = self.contracted_neighbors(&contraction, false);
+        for (node, mut neighbors) in merged.nodes.iter_mut().zip(lists) {
+            if dedup {
+                dedup_neighbors(&mut neighbors);
+            }
+            node.neighbors = neighbors;
+        }
+        *self = merged;
+        contraction.super_node(a)
+    }
+
+    /// Contract the edge between `a` and `b` (in either direction), keeping the
+    /// value of `a`. Returns the merged node's id, or None if there is no edge
+    /// or either id is out of range.
+    pub fn contract_edge(&mut self, a: NodeId, b: NodeId, dedup: bool) -> Option<NodeId> {
+        let (node_a, node_b) = (self.nodes.get(a.index())?, self.nodes.get(b.index())?);
+        let connected =
+            node_a.neighbors.contains(&b.index()) || node_b.neighbors.contains(&a.index());
+        if !connected || a == b {
+            return None;
+        }
+        Some(self.merge_nodes(a, b, |value, _| value.clone(), dedup))
+    }
+}
+
+/// A graph collapsed by a node partition with `contract_nodes`: one super-node
+/// per block, valued by combining its members' values. Edge weights count the
+/// original edges between two blocks; edges inside a block become a weighted
+/// self-loop.
+#[derive(Debug)]
+pub struct QuotientGraph<U = i32> {
+    pub graph: Graph<U>,
+    pub weights: HashMap<(NodeId, NodeId), usize>,
+    pub contraction: Contraction,
+}
+
+impl<U> QuotientGraph<U> {
+    /// Number of original edges from super-node `from` to super-node `to`
+    pub fn weight(&self, from: NodeId, to: NodeId) -> usize {
+        self.weights.get(&(from, to)).copied().unwrap_or(0)
+    }
+}
+
+impl<T> Graph<T> {
+    /// Collapse each block of `partition` (a block id per node, such as
+    /// `Contraction::super_nodes`) into a super-node valued by `combine` over
+    /// its members' values, as in `contract_nodes`; summing them gives a
+    /// weighted community graph. Super-nodes are numbered by their smallest
+    /// member, whatever the block ids.
+    pub fn quotient<U, F>(&self, partition: &[NodeId], combine: F) -> QuotientGraph<U>
+    where
+        F: FnMut(&[&T]) -> U,
+    {
+        assert_eq!(
+            partition.len(),
+            self.nodes.len(),
+            "partition must assign a block to every node"
+        );
+        let block_count = partition
+            .iter()
+            .map(|block| block.index() + 1)
+            .max()
+            .unwrap_or(0);
+        let mut groups = vec![Vec::new(); block_count];
+        for (index, block) in partition.iter().enumerate() {
+            groups[block.index()].push(NodeId::new(index));
+        }
+        let (mut graph, contraction) = self.contract_nodes(&groups, combine);
+
+        let mut weights = HashMap::new();
+        let lists = self.contracted_neighbors(&contraction, true);
+        for ((from, node), targets) in graph.nodes.iter_mut().enumerate().zip(lists) {
+            node.neighbors.clear();
+            for to in targets {
+                let weight = weights
+                    .entry((NodeId::new(from), NodeId::new(to)))
+                    .or_insert(0);
+                if *weight == 0 {
+                    node.neighbors.push(to);
+                }
+                *weight += 1;
+            }
+        }
+        QuotientGraph {
+            graph,
+            weights,
+            contraction,
+        }
+    }
+}
+
+/// Correspondence between the nodes of a graph and the super-nodes of its
+/// coarse graph from `Graph::contract_nodes`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contraction {
+    super_nodes: Vec<NodeId>,  // Indexed by original id
+    members: Vec<Vec<NodeId>>, // Indexed by super-node, each sorted
+}
+
+impl Contraction {
+    /// Number of super-nodes
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Super-node holding original node `original`; panics if it is out of
+    /// range
+    pub fn super_node(&self, original: NodeId) -> NodeId {
+        self.super_nodes[original.index()]
+    }
+
+    /// Super-node of every original node, by original id: the partition
+    /// `Graph::quotient` takes
+    pub fn super_nodes(&self) -> &[NodeId] {
+        &self.super_nodes
+    }
+
+    /// Original nodes merged into `super_node`, in index order; panics if it
+    /// is out of range
+    pub fn members(&self, super_node: NodeId) -> &[NodeId] {
+        &self.members[super_node.index()]
+    }
+}
+
+impl<T> Graph<T> {
+    /// Coarse graph merging each of `groups` into one super-node, and every
+    /// node in no group into a super-node of its own. Super-nodes are
+    /// numbered by their smallest member, so contracting nothing renumbers
+    /// nothing. Each is valued by `combine` over its members' values in
+    /// index order, e.g. summing them or taking the first. It has one edge to
+    /// each super-node an edge of its members leads to, in the order first
+    /// found, and edges between members of the same super-node disappear;
+    /// `quotient` keeps those and the edge counts. Panics if a node is out of
+    /// range or in two groups.
+    pub fn contract_nodes<U, F>(
+        &self,
+        groups: &[Vec<NodeId>],
+        mut combine: F,
+    ) -> (Graph<U>, Contraction)
+    where
+        F: FnMut(&[&T]) -> U,
+    {
+        let len = self.nodes.len();
+        // Label each node with its group's smallest member, then number the
+        // labels in order
+        let mut label: Vec<usize> = (0..len).collect();
+        let mut grouped = vec![false; len];
+        for group in groups {
+            let Some(first) = group.iter().map(|node| node.index()).min() else {
+                continue;
+            };
+            for node in group {
+                assert!(node.index() < len, "node out of range");
+                assert!(
+                    !std::mem::replace(&mut grouped[node.index()], true),
+                    "node in two groups"
+                );
+                label[node.index()] = first;
+            }
+        }
+        let mut number = vec![usize::MAX; len];
+        let mut members: Vec<Vec<NodeId>> = Vec::new();
+        let mut super_nodes = Vec::with_capacity(len);
+        for (index, &first) in label.iter().enumerate() {
+            if number[first] == usize::MAX {
+                number[first] = members.len();
+                members.push(Vec::new());
+            }
+            members[number[first]].push(NodeId::new(index));
+            super_nodes.push(NodeId::new(number[first]));
+        }
+
+        // Stamp each target with the super-node last linked to it, so that
+        // duplicates are skipped without sorting
+        let mut linked_from = vec![usize::MAX; members.len()];
+        let nodes = members
+            .iter()
+            .enumerate()
+            .map(|(super_node, group)| {
+                let values: Vec<&T> = group
+                    .iter()
+                    .map(|node| &self.nodes[node.index()].value)
+                    .collect();
+                let mut neighbors = Vec::new();
+                for node in group {
+                    for &neighbor in &self.nodes[node.index()].neighbors {
+                        let target = super_nodes[neighbor].index();
+                        if target != super_node
+                            && std::mem::replace(&mut linked_from[target], super_node) != super_node
+                        {
+                            neighbors.push(target);
+                        }
+                    }
+                }
+                Node {
+                    value: combine(&values),
+                    neighbors,
+                }
+            })
+            .collect();
+        (
+            Graph { nodes },
+            Contraction {
+                super_nodes,
+                members,
+            },
+        )
+    }
+
+    /// Neighbor lists of the super-nodes of `contraction`, one entry per
+    /// original edge in member order, so parallel edges are kept. Self-loops
+    /// stay self-loops; edges between two members of a super-node become
+    /// self-loops too with `keep_inner` and are dropped without.
+    fn contracted_neighbors(&self, contraction: &Contraction, keep_inner: bool) -> Vec<Vec<usize>> {
+        contraction
+            .members
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .flat_map(|node| {
+                        let from = node.index();
+                        self.nodes[from].neighbors.iter().filter_map(move |&to| {
+                            let inner = to != from
+                                && contraction.super_nodes[to] == contraction.super_nodes[from];
+                            (keep_inner || !inner).then(|| contraction.super_nodes[to].index())
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Correspondence between the nodes of a graph derived by
+/// `Graph::induced_subgraph`, `Graph::filter_nodes`, `Graph::ego_subgraph` or
+/// a sampler and those of the graph it came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdMapping {
+    original: Vec<NodeId>,        // Indexed by new id
+    new_ids: Vec<Option<NodeId>>, // Indexed by original id
+}
+
+impl IdMapping {
+    pub(crate) fn new(original: &[usize], original_len: usize) -> Self {
+        let mut new_ids = vec![None; original_len];
+        for (new_index, &old_index) in original.iter().enumerate() {
+            assert!(
+                new_ids[old_index].replace(NodeId::new(new_index)).is_none(),
+                "node selected twice"
+            );
+        }
+        IdMapping {
+            original: original.iter().copied().map(NodeId::new).collect(),
+            new_ids,
+        }
+    }
+
+    /// Number of nodes in the derived graph
+    pub fn len(&self) -> usize {
+        self.original.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.original.is_empty()
+    }
+
+    /// Id in the original graph of node `new_id`; panics if it is out of range
+    pub fn original(&self, new_id: NodeId) -> NodeId {
+        self.original[new_id.index()]
+    }
+
+    /// Id in the derived graph of original node `original`; None if it was
+    /// left out
+    pub fn new_id(&self, original: NodeId) -> Option<NodeId> {
+        self.new_ids.get(original.index()).copied().flatten()
+    }
+
+    /// Original id of every derived node, by new id
+    pub fn originals(&self) -> &[NodeId] {
+        &self.original
+    }
+}
+
+impl<T: Clone> Graph<T> {
+    /// Subgraph induced by `nodes`: node i of the result is `nodes[i]` here,
+    /// keeping its value and only the edges between selected nodes. Panics if
+    /// a node is out of range or listed twice.
+    pub fn induced_subgraph(&self, nodes: &[NodeId]) -> (Graph<T>, IdMapping) {
+        let indices: Vec<usize> = nodes.iter().map(|node| node.index()).collect();
+        let mapping = IdMapping::new(&indices, self.nodes.len());
+        (self.induced_on(&indices), mapping)
+    }
+
+    /// Subgraph induced by the nodes for which `keep` holds, in index order
+    pub fn filter_nodes<F>(&self, keep: F) -> (Graph<T>, IdMapping)
+    where
+        F: Fn(NodeId, &T) -> bool,
+    {
+        let indices: Vec<usize> = (0..self.nodes.len())
+            .filter(|&index| keep(NodeId::new(index), &self.nodes[index].value))
+            .collect();
+        let mapping = IdMapping::new(&indices, self.nodes.len());
+        (self.induced_on(&indices), mapping)
+    }
+
+    /// Copy keeping every node, under the same id, and only the edges
+    /// from -> to for which `keep` holds
+    pub fn filter_edges<F>(&self, keep: F) -> Graph<T>
+    where
+        F: Fn(NodeId, NodeId) -> bool,
+    {
+        let nodes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(from, node)| Node {
+                value: node.value.clone(),
+                neighbors: node
+                    .neighbors
+                    .iter()
+                    .copied()
+                    .filter(|&to| keep(NodeId::new(from), NodeId::new(to)))
+                    .collect(),
+            })
+            .collect();
+        Graph { nodes }
+    }
+
+    /// Subgraph induced by `indices`: node i of the result is `indices[i]` here
+    pub(crate) fn induced_on(&self, indices: &[usize]) -> Graph<T> {
+        let position: HashMap<usize, usize> = indices
+            .iter()
+            .enumerate()
+            .map(|(new_index, &old_index)| (old_index, new_index))
+            .collect();
+        let nodes = indices
+            .iter()
+            .map(|&old_index| Node {
+                value: self.nodes[old_index].value.clone(),
+                neighbors: self.nodes[old_index]
+                    .neighbors
+                    .iter()
+                    .filter_map(|neighbor| position.get(neighbor).copied())
+                    .collect(),
+            })
+            .collect();
+        Graph { nodes }
+    }
+
+    /// Induced subgraph of every node within `k` hops of `center`, in BFS
+    /// order, so the center is always node 0; panics if `center` is out of
+    /// range
+    pub fn ego_subgraph(&self, center: NodeId, k: usize) -> (Graph<T>, IdMapping) {
+        let center = center.index();
+        let mut depth = HashMap::from([(center, 0)]);
+        let mut order = vec![center];
+        let mut queue = VecDeque::from([center]);
+
+        while let Some(node_index) = queue.pop_front() {
+            let node_depth = depth[&node_index];
+            if node_depth == k {
+                continue;
+            }
+            for &neighbor_index in &self.nodes[node_index].neighbors {
+                if let Entry::Vacant(entry) = depth.entry(neighbor_index) {
+                    entry.insert(node_depth + 1);
+                    order.push(neighbor_index);
+                    queue.push_back(neighbor_index);
+                }
+            }
+        }
+
+        let mapping = IdMapping::new(&order, self.nodes.len());
+        (self.induced_on(&order), mapping)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contract_edge_rejects_missing_edges_and_out_of_range_ids() {
+        let mut g = Graph::from_undirected_edges(3, [(0, 1)]);
+        assert_eq!(g.contract_edge(NodeId::new(0), NodeId::new(7), false), None);
+        assert_eq!(g.contract_edge(NodeId::new(7), NodeId::new(0), false), None);
+        assert_eq!(g.contract_edge(NodeId::new(0), NodeId::new(2), false), None);
+        assert_eq!(g.len(), 3);
+        assert_eq!(
+            g.contract_edge(NodeId::new(1), NodeId::new(0), false),
+            Some(NodeId::new(0))
+        );
+        assert_eq!(g.len(), 2);
+        assert_eq!(g.edge_count(), 0);
+    }
+}
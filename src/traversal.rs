@@ -0,0 +1,2151 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashSet, VecDeque};
+use std::rc::Rc;
+
+use crate::base::GraphBase;
+use crate::error::GraphError;
+use crate::graph::{Graph, Node, NodeId};
+use crate::metrics::Instrumented;
+use crate::sampling::Rng;
+use crate::visited::{BitVisited, VisitedSet};
+
+/// Common interface of the traversal iterators, which visit node indices
+pub trait Traversal<'a> {
+    /// Value type of the graph's nodes
+    type Value: 'a;
+
+    /// Storage of the graph being traversed
+    type Graph: GraphBase<Value = Self::Value> + 'a;
+
+    /// The graph being traversed
+    fn graph(&self) -> &'a Self::Graph;
+
+    /// Advance the traversal and return the index of the next visited node
+    fn next_index(&mut self) -> Option<usize>;
+
+    /// Tree parent of the node last returned by `next_index`: the visited node
+    /// it was reached from, or None for the start node
+    fn parent(&self) -> Option<usize>;
+
+    /// Number of pending entries: queued, stacked or in the heap, including
+    /// stale entries kept for lazy deletion
+    fn frontier_len(&self) -> usize;
+
+    /// Run the traversal to completion, recording how each node was reached
+    fn tree(mut self) -> TraversalTree
+    where
+        Self: Sized,
+    {
+        let mut tree = TraversalTree::new(self.graph().node_count());
+        while let Some(index) = self.next_index() {
+            tree.record(index, self.parent());
+        }
+        tree
+    }
+
+    /// Count nodes, edges, frontier size and time as the traversal runs; read
+    /// them with `metrics`, during or after the traversal
+    fn instrumented(self) -> Instrumented<'a, Self>
+    where
+        Self: Sized,
+    {
+        Instrumented::new(self)
+    }
+
+    /// Yield (node id, node) pairs instead of bare nodes
+    fn with_ids(self) -> WithIds<'a, Self>
+    where
+        Self: Sized,
+    {
+        WithIds {
+            graph: self.graph(),
+            inner: self,
+        }
+    }
+}
+
+/// Spanning tree of a traversal: the visit order plus, for every visited node,
+/// the node it was reached from and its depth below the root
+#[derive(Debug, Clone)]
+pub struct TraversalTree {
+    parents: Vec<Option<usize>>,
+    reached: Vec<bool>,
+    depths: Vec<usize>,
+    order: Vec<NodeId>,
+}
+
+impl TraversalTree {
+    pub(crate) fn new(len: usize) -> Self {
+        Self {
+            parents: vec![None; len],
+            reached: vec![false; len],
+            depths: vec![0; len],
+            order: Vec::new(),
+        }
+    }
+
+    /// Record a visit; a parent is always visited before its children
+    pub(crate) fn record(&mut self, index: usize, parent: Option<usize>) {
+        self.parents[index] = parent;
+        self.reached[index] = true;
+        self.depths[index] = parent.map_or(0, |parent| self.depths[parent] + 1);
+        self.order.push(NodeId::new(index));
+    }
+
+    /// Visited nodes in visit order; the first one is the root
+    pub fn order(&self) -> &[NodeId] {
+        &self.order
+    }
+
+    pub fn contains(&self, node: NodeId) -> bool {
+        self.reached.get(node.index()).copied().unwrap_or(false)
+    }
+
+    /// The node `node` was reached from; None for the root and unvisited nodes
+    pub fn parent_of(&self, node: NodeId) -> Option<NodeId> {
+        self.parents
+            .get(node.index())
+            .copied()
+            .flatten()
+            .map(NodeId::new)
+    }
+
+    /// Tree edges from the root to `node`: hops for a BFS tree. None for
+    /// unvisited nodes.
+    pub fn depth_of(&self, node: NodeId) -> Option<usize> {
+        self.contains(node).then(|| self.depths[node.index()])
+    }
+
+    /// Tree path from the root to `node`, if the traversal visited it
+    pub fn path_to(&self, node: NodeId) -> Option<Vec<NodeId>> {
+        if !self.contains(node) {
+            return None;
+        }
+        let mut path = vec![node];
+        while let Some(parent) = self.parent_of(*path.last().unwrap()) {
+            path.push(parent);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Marks the start entry, which has no parent, in the (node, parent) containers
+const NO_PARENT: usize = usize::MAX;
+
+fn parent_of_entry(parent: usize) -> Option<usize> {
+    (parent != NO_PARENT).then_some(parent)
+}
+
+/// Advance `cursor` to the smallest node at or after it missing from `visited`.
+/// A `None` cursor means the traversal does not restart.
+fn next_unvisited<S: VisitedSet>(
+    cursor: &mut Option<usize>,
+    len: usize,
+    visited: &S,
+) -> Option<usize> {
+    let index = cursor.as_mut()?;
+    while *index < len && visited.contains(*index) {
+        *index += 1;
+    }
+    (*index < len).then_some(*index)
+}
+
+/// Adapter returned by `Traversal::with_ids`
+pub struct WithIds<'a, I: Traversal<'a>> {
+    graph: &'a I::Graph,
+    inner: I,
+}
+
+impl<'a, I: Traversal<'a> + Clone> Clone for WithIds<'a, I> {
+    fn clone(&self) -> Self {
+        WithIds {
+            graph: self.graph,
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, I: Traversal<'a>> Iterator for WithIds<'a, I> {
+    type Item = (NodeId, &'a <I::Graph as GraphBase>::Node);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+        self.inner.next_index().map(|index| {
+            let id = NodeId::new(index);
+            (id, graph.node(id))
+        })
+    }
+}
+
+/// Predicates marking nodes as walls, which are never yielded and never expanded
+struct Walls<'a, N> {
+    predicates: Vec<NodePredicate<'a, N>>,
+}
+
+type NodePredicate<'a, N> = Rc<dyn Fn(&N) -> bool + 'a>;
+
+// Manual impls: the predicates are shared, so neither needs bounds on `N`
+impl<N> Clone for Walls<'_, N> {
+    fn clone(&self) -> Self {
+        Walls {
+            predicates: self.predicates.clone(),
+        }
+    }
+}
+
+impl<N> Default for Walls<'_, N> {
+    fn default() -> Self {
+        Walls {
+            predicates: Vec::new(),
+        }
+    }
+}
+
+impl<'a, N> Walls<'a, N> {
+    fn add<P: Fn(&N) -> bool + 'a>(&mut self, predicate: P) {
+        self.predicates.push(Rc::new(predicate));
+    }
+
+    /// Whether any predicate turns this node into a wall
+    fn blocks<G: GraphBase<Node = N>>(&self, graph: &G, id: NodeId) -> bool {
+        // Without predicates the node is never looked up
+        self.predicates
+            .iter()
+            .any(|predicate| predicate(graph.node(id)))
+    }
+}
+
+/// Neighbors of `id` to expand: in storage order, or, given an `rng`,
+/// shuffled through `buffer`, which is reused to avoid an allocation per node
+fn expansion<'g, 'b, G: GraphBase>(
+    graph: &'g G,
+    id: NodeId,
+    rng: &mut Option<Rng>,
+    buffer: &'b mut Vec<NodeId>,
+) -> Expansion<'b, impl DoubleEndedIterator<Item = NodeId> + 'g> {
+    match rng {
+        None => Expansion::InOrder(graph.neighbors(id)),
+        Some(rng) => {
+            buffer.clear();
+            buffer.extend(graph.neighbors(id));
+            rng.shuffle(buffer);
+            Expansion::Shuffled(buffer.iter())
+        }
+    }
+}
+
+enum Expansion<'b, I> {
+    InOrder(I),
+    Shuffled(std::slice::Iter<'b, NodeId>),
+}
+
+impl<I: Iterator<Item = NodeId>> Iterator for Expansion<'_, I> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        match self {
+            Expansion::InOrder(neighbors) => neighbors.next(),
+            Expansion::Shuffled(neighbors) => neighbors.next().copied(),
+        }
+    }
+}
+
+impl<I: DoubleEndedIterator<Item = NodeId>> DoubleEndedIterator for Expansion<'_, I> {
+    fn next_back(&mut self) -> Option<NodeId> {
+        match self {
+            Expansion::InOrder(neighbors) => neighbors.next_back(),
+            Expansion::Shuffled(neighbors) => neighbors.next_back().copied(),
+        }
+    }
+}
+
+/// BFS iterator for the graph, over any `GraphBase` storage
+///
+/// Like every traversal constructor here, `new` accepts any start id: one
+/// outside the graph yields nothing rather than panicking, and `try_new`
+/// reports it as an error instead.
+pub struct BfsIterator<'a, T = i32, S = HashSet<usize>, G = Graph<T>>
+where
+    G: GraphBase<Value = T>,
+{
+    graph: &'a G,
+    queue: VecDeque<(usize, usize, usize)>, // (node index, parent index, depth)
+    parent: Option<usize>,
+    depth: usize,
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+    yielded: usize,
+    restart: Option<usize>, // Next candidate root, when covering the whole graph
+    roots: usize,           // Roots yielded so far
+    visited: S,
+    walls: Walls<'a, G::Node>,
+    rng: Option<Rng>,   // Shuffles each expansion, if set
+    order: Vec<NodeId>, // Reused buffer for shuffled neighbors
+}
+
+impl<T, S: Clone, G: GraphBase<Value = T>> Clone for BfsIterator<'_, T, S, G> {
+    fn clone(&self) -> Self {
+        BfsIterator {
+            graph: self.graph,
+            queue: self.queue.clone(),
+            parent: self.parent,
+            depth: self.depth,
+            max_depth: self.max_depth,
+            max_nodes: self.max_nodes,
+            yielded: self.yielded,
+            restart: self.restart,
+            roots: self.roots,
+            visited: self.visited.clone(),
+            walls: self.walls.clone(),
+            rng: self.rng.clone(),
+            order: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T> BfsIterator<'a, T> {
+    /// Like `new`, but errors on an out-of-range start or neighbor index
+    /// instead of skipping it
+    pub fn try_new(graph: &'a Graph<T>, start: NodeId) -> Result<Self, GraphError> {
+        graph.check_node(start)?;
+        graph.validate()?;
+        Ok(Self::new(graph, start))
+    }
+}
+
+impl<'a, T, G: GraphBase<Value = T>> BfsIterator<'a, T, HashSet<usize>, G> {
+    /// Create a new BFS iterator starting from a given node
+    pub fn new(graph: &'a G, start: NodeId) -> Self {
+        Self::with_visited(graph, start, HashSet::new())
+    }
+
+    /// Create a BFS iterator whose visited set iterates in index order
+    pub fn deterministic(graph: &'a G, start: NodeId) -> BfsIterator<'a, T, BTreeSet<usize>, G> {
+        BfsIterator::with_visited(graph, start, BTreeSet::new())
+    }
+
+    /// Create a BFS iterator tracking visited nodes in a bit set sized for the
+    /// graph, which beats hashing when the traversal covers much of the graph
+    pub fn dense(graph: &'a G, start: NodeId) -> BfsIterator<'a, T, BitVisited, G> {
+        BfsIterator::with_visited(graph, start, BitVisited::with_len(graph.node_count()))
+    }
+
+    /// Multi-source BFS: every source starts at depth 0, so `depth` is the hop
+    /// distance to the nearest source. Sources have no tree parent.
+    pub fn from_sources(graph: &'a G, sources: &[NodeId]) -> Self {
+        Self {
+            graph,
+            queue: sources
+                .iter()
+                .map(|source| (source.index(), NO_PARENT, 0))
+                .collect(),
+            parent: None,
+            depth: 0,
+            max_depth: None,
+            max_nodes: None,
+            yielded: 0,
+            restart: None,
+            roots: 0,
+            visited: HashSet::new(),
+            walls: Walls::default(),
+            rng: None,
+            order: Vec::new(),
+        }
+    }
+
+    /// BFS over the whole graph: whenever the queue runs dry it restarts from
+    /// the smallest unvisited node, so every node is yielded once
+    pub fn all(graph: &'a G) -> Self {
+        let mut bfs = Self::from_sources(graph, &[]);
+        bfs.restart = Some(0);
+        bfs
+    }
+}
+
+impl<'a, T, S: VisitedSet, G: GraphBase<Value = T>> BfsIterator<'a, T, S, G> {
+    /// Create a BFS iterator that records visited nodes in the given set
+    pub fn with_visited(graph: &'a G, start: NodeId, visited: S) -> Self {
+        let mut queue = VecDeque::new();
+        queue.push_back((start.index(), NO_PARENT, 0));
+
+        Self {
+            graph,
+            queue,
+            parent: None,
+            depth: 0,
+            max_depth: None,
+            max_nodes: None,
+            yielded: 0,
+            restart: None,
+            roots: 0,
+            visited,
+            walls: Walls::default(),
+            rng: None,
+            order: Vec::new(),
+        }
+    }
+
+    /// Nodes visited so far
+    pub fn visited(&self) -> &S {
+        &self.visited
+    }
+
+    /// Consume the traversal and return its visited set, e.g. to seed the next one
+    pub fn into_visited(self) -> S {
+        self.visited
+    }
+
+    /// Treat nodes matching `predicate` as walls: they are never yielded and
+    /// the traversal never continues through them
+    pub fn prune_if<P: Fn(&G::Node) -> bool + 'a>(mut self, predicate: P) -> Self {
+        self.walls.add(predicate);
+        self
+    }
+
+    /// Stop `max_depth` hops from the start: deeper nodes are never yielded
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// End after yielding `max_nodes` nodes
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Expand each node's neighbors in an order shuffled by a generator
+    /// seeded with `seed`, for randomized traversals that are reproducible
+    /// from the seed. The generator is not part of `state`.
+    pub fn shuffled(mut self, seed: u64) -> Self {
+        self.rng = Some(Rng::new(seed));
+        self
+    }
+
+    /// Hop distance from the start of the node last yielded
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Index of the traversal tree holding the node last yielded, counting roots
+    /// in the order they were yielded. For `all` on an undirected graph, this
+    /// is the node's connected component.
+    pub fn component(&self) -> usize {
+        self.roots.saturating_sub(1)
+    }
+
+    /// Yield (node id, depth, node) triples instead of bare nodes
+    pub fn with_depths(self) -> WithDepths<'a, T, S, G> {
+        WithDepths { inner: self }
+    }
+
+    /// Snapshot of the traversal for `resume`, which continues it with the same
+    /// nodes in the same order. `prune_if` predicates are not part of it.
+    pub fn state(&self) -> TraversalState {
+        TraversalState {
+            depth_limit: self.max_depth,
+            max_nodes: self.max_nodes,
+            yielded: self.yielded,
+            parent: self.parent,
+            depth: self.depth,
+            restart: self.restart,
+            roots: self.roots,
+            ..TraversalState::capture(self.graph, self.queue.iter().copied(), &self.visited)
+        }
+    }
+
+    /// Continue a traversal from `state`, adding its visited nodes to `visited`.
+    /// Errors if the state names a node the graph lacks.
+    pub fn resume_with_visited(
+        graph: &'a G,
+        state: &TraversalState,
+        mut visited: S,
+    ) -> Result<Self, GraphError> {
+        let queue = state.restore(graph, &mut visited)?.collect();
+        Ok(Self {
+            graph,
+            queue,
+            parent: state.parent,
+            depth: state.depth,
+            max_depth: state.depth_limit,
+            max_nodes: state.max_nodes,
+            yielded: state.yielded,
+            restart: state.restart,
+            roots: state.roots,
+            visited,
+            walls: Walls::default(),
+            rng: None,
+            order: Vec::new(),
+        })
+    }
+}
+
+impl<'a, T, G: GraphBase<Value = T>> BfsIterator<'a, T, HashSet<usize>, G> {
+    /// Continue a traversal from a `state` snapshot
+    pub fn resume(graph: &'a G, state: &TraversalState) -> Result<Self, GraphError> {
+        Self::resume_with_visited(graph, state, HashSet::new())
+    }
+}
+
+impl<'a, T: 'a, S: VisitedSet, G: GraphBase<Value = T> + 'a> Traversal<'a>
+    for BfsIterator<'a, T, S, G>
+{
+    type Value = T;
+    type Graph = G;
+
+    fn graph(&self) -> &'a G {
+        self.graph
+    }
+
+    fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        if self
+            .max_nodes
+            .is_some_and(|max_nodes| self.yielded >= max_nodes)
+        {
+            return None;
+        }
+        loop {
+            while let Some((node_index, parent, depth)) = self.queue.pop_front() {
+                // Skip nodes already visited and ids past the graph, such as
+                // an out-of-range start
+                if node_index >= self.graph.node_count() || !self.visited.insert(node_index) {
+                    continue;
+                }
+
+                let id = NodeId::new(node_index);
+                if self.walls.blocks(self.graph, id) {
+                    continue;
+                }
+
+                // Add all unvisited neighbors to the queue, unless they would be too deep
+                if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                    for neighbor in expansion(self.graph, id, &mut self.rng, &mut self.order) {
+                        let neighbor_index = neighbor.index();
+                        if neighbor_index < self.graph.node_count()
+                            && !self.visited.contains(neighbor_index)
+                        {
+                            self.queue
+                                .push_back((neighbor_index, node_index, depth + 1));
+                        }
+                    }
+                }
+
+                // Return the current node
+                self.parent = parent_of_entry(parent);
+                self.depth = depth;
+                self.yielded += 1;
+                if parent == NO_PARENT {
+                    self.roots += 1;
+                }
+                return Some(node_index);
+            }
+            let root = next_unvisited(&mut self.restart, self.graph.node_count(), &self.visited)?;
+            self.queue.push_back((root, NO_PARENT, 0));
+        }
+    }
+}
+
+impl<'a, T: 'a, S: VisitedSet, G: GraphBase<Value = T> + 'a> Iterator for BfsIterator<'a, T, S, G> {
+    type Item = &'a G::Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+        self.next_index()
+            .map(|index| graph.node(NodeId::new(index)))
+    }
+}
+
+/// Adapter returned by `BfsIterator::with_depths`
+pub struct WithDepths<'a, T, S, G = Graph<T>>
+where
+    G: GraphBase<Value = T>,
+{
+    inner: BfsIterator<'a, T, S, G>,
+}
+
+impl<T, S: Clone, G: GraphBase<Value = T>> Clone for WithDepths<'_, T, S, G> {
+    fn clone(&self) -> Self {
+        WithDepths {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, T: 'a, S: VisitedSet, G: GraphBase<Value = T> + 'a> Iterator for WithDepths<'a, T, S, G> {
+    type Item = (NodeId, usize, &'a G::Node);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.inner.graph;
+        let id = NodeId::new(self.inner.next_index()?);
+        Some((id, self.inner.depth, graph.node(id)))
+    }
+}
+
+/// DFS iterator for the graph, over any `GraphBase` storage
+///
+/// A start outside the graph yields nothing, as for `BfsIterator`.
+pub struct DfsIterator<'a, T = i32, S = HashSet<usize>, G = Graph<T>>
+where
+    G: GraphBase<Value = T>,
+{
+    graph: &'a G,
+    stack: Vec<(usize, usize, usize)>, // (node index, parent index, depth)
+    parent: Option<usize>,
+    depth: usize,
+    depth_limit: Option<usize>,
+    max_nodes: Option<usize>,
+    yielded: usize,
+    restart: Option<usize>, // Next candidate root, when covering the whole graph
+    roots: usize,           // Roots yielded so far
+    visited: S,
+    walls: Walls<'a, G::Node>,
+    rng: Option<Rng>,   // Shuffles each expansion, if set
+    order: Vec<NodeId>, // Reused buffer for shuffled neighbors
+}
+
+impl<T, S: Clone, G: GraphBase<Value = T>> Clone for DfsIterator<'_, T, S, G> {
+    fn clone(&self) -> Self {
+        DfsIterator {
+            graph: self.graph,
+            stack: self.stack.clone(),
+            parent: self.parent,
+            depth: self.depth,
+            depth_limit: self.depth_limit,
+            max_nodes: self.max_nodes,
+            yielded: self.yielded,
+            restart: self.restart,
+            roots: self.roots,
+            visited: self.visited.clone(),
+            walls: self.walls.clone(),
+            rng: self.rng.clone(),
+            order: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T> DfsIterator<'a, T> {
+    /// Like `new`, but errors on an out-of-range start or neighbor index
+    /// instead of skipping it
+    pub fn try_new(graph: &'a Graph<T>, start: NodeId) -> Result<Self, GraphError> {
+        graph.check_node(start)?;
+        graph.validate()?;
+        Ok(Self::new(graph, start))
+    }
+}
+
+impl<'a, T, G: GraphBase<Value = T>> DfsIterator<'a, T, HashSet<usize>, G> {
+    /// Create a new DFS iterator starting from a given node
+    pub fn new(graph: &'a G, start: NodeId) -> Self {
+        Self::with_visited(graph, start, HashSet::new())
+    }
+
+    /// Create a DFS iterator whose visited set iterates in index order
+    pub fn deterministic(graph: &'a G, start: NodeId) -> DfsIterator<'a, T, BTreeSet<usize>, G> {
+        DfsIterator::with_visited(graph, start, BTreeSet::new())
+    }
+
+    /// Create a DFS iterator tracking visited nodes in a bit set sized for the
+    /// graph, which beats hashing when the traversal covers much of the graph
+    pub fn dense(graph: &'a G, start: NodeId) -> DfsIterator<'a, T, BitVisited, G> {
+        DfsIterator::with_visited(graph, start, BitVisited::with_len(graph.node_count()))
+    }
+
+    /// DFS that never goes more than `limit` edges deep along its tree paths.
+    /// Each node is still visited once, at the depth of the path DFS first
+    /// reaches it by, so a node within `limit` hops can be missed when that
+    /// path is too long; `IddfsIterator` does not have this blind spot.
+    pub fn with_depth_limit(graph: &'a G, start: NodeId, limit: usize) -> Self {
+        Self::new(graph, start).max_depth(limit)
+    }
+
+    /// DFS over the whole graph: whenever the stack runs dry it restarts from
+    /// the smallest unvisited node, so every node is yielded once
+    pub fn all(graph: &'a G) -> Self {
+        Self {
+            graph,
+            stack: Vec::new(),
+            parent: None,
+            depth: 0,
+            depth_limit: None,
+            max_nodes: None,
+            yielded: 0,
+            restart: Some(0),
+            roots: 0,
+            visited: HashSet::new(),
+            walls: Walls::default(),
+            rng: None,
+            order: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T, S: VisitedSet, G: GraphBase<Value = T>> DfsIterator<'a, T, S, G> {
+    /// Create a DFS iterator that records visited nodes in the given set
+    pub fn with_visited(graph: &'a G, start: NodeId, visited: S) -> Self {
+        Self {
+            graph,
+            stack: vec![(start.index(), NO_PARENT, 0)],
+            parent: None,
+            depth: 0,
+            depth_limit: None,
+            max_nodes: None,
+            yielded: 0,
+            restart: None,
+            roots: 0,
+            visited,
+            walls: Walls::default(),
+            rng: None,
+            order: Vec::new(),
+        }
+    }
+
+    /// Nodes visited so far
+    pub fn visited(&self) -> &S {
+        &self.visited
+    }
+
+    /// Consume the traversal and return its visited set, e.g. to seed the next one
+    pub fn into_visited(self) -> S {
+        self.visited
+    }
+
+    /// Treat nodes matching `predicate` as walls: they are never yielded and
+    /// the traversal never continues through them
+    pub fn prune_if<P: Fn(&G::Node) -> bool + 'a>(mut self, predicate: P) -> Self {
+        self.walls.add(predicate);
+        self
+    }
+
+    /// Index of the traversal tree holding the node last yielded, counting roots
+    /// in the order they were yielded. For `all` on an undirected graph, this
+    /// is the node's connected component.
+    pub fn component(&self) -> usize {
+        self.roots.saturating_sub(1)
+    }
+
+    /// Length of the tree path from the root to the node last yielded
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Never go more than `max_depth` edges deep along tree paths; see
+    /// `with_depth_limit` for what this can miss
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.depth_limit = Some(max_depth);
+        self
+    }
+
+    /// End after yielding `max_nodes` nodes
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Expand each node's neighbors in an order shuffled by a generator
+    /// seeded with `seed`, for randomized traversals that are reproducible
+    /// from the seed. The generator is not part of `state`.
+    pub fn shuffled(mut self, seed: u64) -> Self {
+        self.rng = Some(Rng::new(seed));
+        self
+    }
+
+    /// Snapshot of the traversal for `resume`, which continues it with the same
+    /// nodes in the same order. `prune_if` predicates are not part of it.
+    pub fn state(&self) -> TraversalState {
+        TraversalState {
+            depth_limit: self.depth_limit,
+            max_nodes: self.max_nodes,
+            yielded: self.yielded,
+            parent: self.parent,
+            depth: self.depth,
+            restart: self.restart,
+            roots: self.roots,
+            ..TraversalState::capture(self.graph, self.stack.iter().copied(), &self.visited)
+        }
+    }
+
+    /// Continue a traversal from `state`, adding its visited nodes to `visited`.
+    /// Errors if the state names a node the graph lacks.
+    pub fn resume_with_visited(
+        graph: &'a G,
+        state: &TraversalState,
+        mut visited: S,
+    ) -> Result<Self, GraphError> {
+        let stack = state.restore(graph, &mut visited)?.collect();
+        Ok(Self {
+            graph,
+            stack,
+            parent: state.parent,
+            depth: state.depth,
+            depth_limit: state.depth_limit,
+            max_nodes: state.max_nodes,
+            yielded: state.yielded,
+            restart: state.restart,
+            roots: state.roots,
+            visited,
+            walls: Walls::default(),
+            rng: None,
+            order: Vec::new(),
+        })
+    }
+}
+
+impl<'a, T, G: GraphBase<Value = T>> DfsIterator<'a, T, HashSet<usize>, G> {
+    /// Continue a traversal from a `state` snapshot
+    pub fn resume(graph: &'a G, state: &TraversalState) -> Result<Self, GraphError> {
+        Self::resume_with_visited(graph, state, HashSet::new())
+    }
+}
+
+/// Checkpoint of a `BfsIterator` or `DfsIterator` from `state`, to be
+/// continued later, possibly in another process, by the same iterator's
+/// `resume`. It holds the pending entries in queue or stack order as (node,
+/// parent, depth), the visited nodes by index, and the depth and node limits;
+/// it is serializable with the `serde` feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraversalState {
+    frontier: Vec<(usize, Option<usize>, usize)>,
+    visited: Vec<usize>,
+    depth_limit: Option<usize>,
+    max_nodes: Option<usize>,
+    yielded: usize,
+    // Details of the node last yielded and of whole-graph restarts
+    parent: Option<usize>,
+    depth: usize,
+    restart: Option<usize>,
+    roots: usize,
+}
+
+impl TraversalState {
+    /// Entries waiting in the queue or stack; nodes already visited among
+    /// them will be skipped
+    pub fn pending_len(&self) -> usize {
+        self.frontier.len()
+    }
+
+    /// Number of nodes visited so far
+    pub fn visited_len(&self) -> usize {
+        self.visited.len()
+    }
+
+    /// The pending entries and visited nodes, other fields left at their
+    /// starting values
+    fn capture<G: GraphBase, S: VisitedSet>(
+        graph: &G,
+        pending: impl Iterator<Item = (usize, usize, usize)>,
+        visited: &S,
+    ) -> Self {
+        TraversalState {
+            frontier: pending
+                .map(|(node, parent, depth)| (node, parent_of_entry(parent), depth))
+                .collect(),
+            visited: (0..graph.node_count())
+                .filter(|&index| visited.contains(index))
+                .collect(),
+            depth_limit: None,
+            max_nodes: None,
+            yielded: 0,
+            parent: None,
+            depth: 0,
+            restart: None,
+            roots: 0,
+        }
+    }
+
+    /// Mark the visited nodes in `visited` and return the pending entries,
+    /// after checking that every node is in range
+    fn restore<G: GraphBase, S: VisitedSet>(
+        &self,
+        graph: &G,
+        visited: &mut S,
+    ) -> Result<impl Iterator<Item = (usize, usize, usize)> + '_, GraphError> {
+        let len = graph.node_count();
+        let nodes = self
+            .frontier
+            .iter()
+            .flat_map(|&(node, parent, _)| std::iter::once(node).chain(parent));
+        if let Some(node) = nodes
+            .chain(self.visited.iter().copied())
+            .chain(self.parent)
+            .find(|&node| node >= len)
+        {
+            return Err(GraphError::NodeOutOfRange { node, len });
+        }
+        for &node in &self.visited {
+            visited.insert(node);
+        }
+        Ok(self
+            .frontier
+            .iter()
+            .map(|&(node, parent, depth)| (node, parent.unwrap_or(NO_PARENT), depth)))
+    }
+}
+
+impl<'a, T: 'a, S: VisitedSet, G: GraphBase<Value = T> + 'a> Traversal<'a>
+    for DfsIterator<'a, T, S, G>
+{
+    type Value = T;
+    type Graph = G;
+
+    fn graph(&self) -> &'a G {
+        self.graph
+    }
+
+    fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.stack.len()
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        if self
+            .max_nodes
+            .is_some_and(|max_nodes| self.yielded >= max_nodes)
+        {
+            return None;
+        }
+        loop {
+            while let Some((node_index, parent, depth)) = self.stack.pop() {
+                // Skip nodes already visited and ids past the graph, such as
+                // an out-of-range start
+                if node_index >= self.graph.node_count() || !self.visited.insert(node_index) {
+                    continue;
+                }
+
+                let id = NodeId::new(node_index);
+                if self.walls.blocks(self.graph, id) {
+                    continue;
+                }
+
+                // Add all unvisited neighbors to the stack (in reverse order),
+                // unless they would pass the depth limit
+                if self.depth_limit.is_none_or(|limit| depth < limit) {
+                    for neighbor in expansion(self.graph, id, &mut self.rng, &mut self.order).rev()
+                    {
+                        let neighbor_index = neighbor.index();
+                        if neighbor_index < self.graph.node_count()
+                            && !self.visited.contains(neighbor_index)
+                        {
+                            self.stack.push((neighbor_index, node_index, depth + 1));
+                        }
+                    }
+                }
+
+                // Return the current node
+                self.parent = parent_of_entry(parent);
+                self.depth = depth;
+                self.yielded += 1;
+                if parent == NO_PARENT {
+                    self.roots += 1;
+                }
+                return Some(node_index);
+            }
+            let root = next_unvisited(&mut self.restart, self.graph.node_count(), &self.visited)?;
+            self.stack.push((root, NO_PARENT, 0));
+        }
+    }
+}
+
+impl<'a, T: 'a, S: VisitedSet, G: GraphBase<Value = T> + 'a> Iterator for DfsIterator<'a, T, S, G> {
+    type Item = &'a G::Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+        self.next_index()
+            .map(|index| graph.node(NodeId::new(index)))
+    }
+}
+
+/// Iterative deepening DFS: depth-limited DFS from the start with limits
+/// 0, 1, 2, ..., re-expanding a node whenever a pass reaches it by a shorter
+/// path. Every node is yielded once, in the pass whose limit is its hop
+/// distance, so nodes come in BFS depth order while only the current DFS path
+/// is kept on the stack. Stops after a pass finds nothing new.
+pub struct IddfsIterator<'a, T = i32> {
+    graph: &'a Graph<T>,
+    start: usize,
+    limit: usize,
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+    stack: Vec<(usize, usize, usize)>, // (node index, parent index, depth)
+    reached: Vec<usize>,               // Shallowest depth reached at in this pass
+    yielded: Vec<bool>,
+    yielded_count: usize,
+    found_new: bool, // Whether this pass has yielded anything yet
+    parent: Option<usize>,
+    depth: usize,
+}
+
+impl<T> Clone for IddfsIterator<'_, T> {
+    fn clone(&self) -> Self {
+        IddfsIterator {
+            graph: self.graph,
+            start: self.start,
+            limit: self.limit,
+            max_depth: self.max_depth,
+            max_nodes: self.max_nodes,
+            stack: self.stack.clone(),
+            reached: self.reached.clone(),
+            yielded: self.yielded.clone(),
+            yielded_count: self.yielded_count,
+            found_new: self.found_new,
+            parent: self.parent,
+            depth: self.depth,
+        }
+    }
+}
+
+impl<'a, T> IddfsIterator<'a, T> {
+    pub fn new(graph: &'a Graph<T>, start: NodeId) -> Self {
+        let len = graph.nodes.len();
+        let mut stack = Vec::new();
+        if start.index() < len {
+            stack.push((start.index(), NO_PARENT, 0));
+        }
+        Self {
+            graph,
+            start: start.index(),
+            limit: 0,
+            max_depth: None,
+            max_nodes: None,
+            stack,
+            reached: vec![usize::MAX; len],
+            yielded: vec![false; len],
+            yielded_count: 0,
+            found_new: false,
+            parent: None,
+            depth: 0,
+        }
+    }
+
+    /// Stop deepening after the pass with limit `max_depth`
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// End after yielding `max_nodes` nodes
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Hop distance from the start of the node last yielded
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Current depth limit
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl<'a, T> Traversal<'a> for IddfsIterator<'a, T> {
+    type Value = T;
+    type Graph = Graph<T>;
+
+    fn graph(&self) -> &'a Graph<T> {
+        self.graph
+    }
+
+    fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.stack.len()
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        if self
+            .max_nodes
+            .is_some_and(|max_nodes| self.yielded_count >= max_nodes)
+        {
+            return None;
+        }
+        loop {
+            while let Some((node_index, parent, depth)) = self.stack.pop() {
+                // Skip nodes this pass has already reached by a path no longer
+                if self.reached[node_index] <= depth {
+                    continue;
+                }
+                self.reached[node_index] = depth;
+
+                if depth < self.limit {
+                    for &neighbor_index in self.graph.nodes[node_index].neighbors.iter().rev() {
+                        if self
+                            .reached
+                            .get(neighbor_index)
+                            .is_some_and(|&reached| reached > depth + 1)
+                        {
+                            self.stack.push((neighbor_index, node_index, depth + 1));
+                        }
+                    }
+                }
+
+                if !std::mem::replace(&mut self.yielded[node_index], true) {
+                    self.found_new = true;
+                    self.parent = parent_of_entry(parent);
+                    self.depth = depth;
+                    self.yielded_count += 1;
+                    return Some(node_index);
+                }
+            }
+
+            // Pass finished: deepen, unless it found nothing new or hit the cap
+            let capped = self
+                .max_depth
+                .is_some_and(|max_depth| self.limit >= max_depth);
+            if !self.found_new || capped || self.start >= self.graph.nodes.len() {
+                return None;
+            }
+            self.limit += 1;
+            self.found_new = false;
+            self.reached.fill(usize::MAX);
+            self.stack.push((self.start, NO_PARENT, 0));
+        }
+    }
+}
+
+impl<'a, T> Iterator for IddfsIterator<'a, T> {
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+        self.next_index().map(|index| &graph.nodes[index])
+    }
+}
+
+/// Next smallest node iterator for the graph. Nodes are ordered by a key,
+/// by default a reference to the node value.
+///
+/// Ordering guarantee: each step yields, among the frontier (nodes not yet
+/// yielded with an edge from a yielded node), the one with the smallest
+/// (key, index), or (key, seeded random rank) once `shuffled`. Its tree parent
+/// is the earliest-yielded node with an edge to it.
+///
+/// The heap uses lazy deletion: a node is pushed once per discovering
+/// neighbor and counts as visited only when it is popped, like in the BFS and
+/// DFS iterators. Nodes in a seeded visited set are never yielded or expanded.
+///
+/// `max_nodes`, `max_depth` and `fuel` bound the traversal, for graphs too big
+/// to exhaust: the first caps the nodes yielded, the second the tree depth,
+/// and the third the total work of yielding nodes and examining edges.
+pub struct NextSmallestIterator<'a, T = i32, S = HashSet<usize>, K = &'a T, G = Graph<T>>
+where
+    G: GraphBase<Value = T>,
+{
+    graph: &'a G,
+    heap: BinaryHeap<Reverse<HeapEntry<K>>>,
+    parent: Option<usize>,
+    depth: usize,
+    yielded: usize,
+    max_nodes: Option<usize>,
+    max_depth: Option<usize>,
+    fuel: Option<usize>,
+    visited: S,
+    key: Rc<dyn Fn(&'a G::Node) -> K + 'a>,
+    walls: Walls<'a, G::Node>,
+    tie_seed: Option<u64>,
+}
+
+/// (node key, tie rank, node index, yield rank of the parent, parent index,
+/// depth). The tie rank is the node index, or a seeded permutation of it once
+/// `shuffled`. Between two entries for the same node, the one pushed by the
+/// earlier parent pops first.
+type HeapEntry<K> = (K, u64, usize, usize, usize, usize);
+
+/// Tie rank of the node at `index`: the index itself, or with a seed a
+/// SplitMix64 mix of it, distinct for distinct indices
+fn tie_rank(seed: Option<u64>, index: usize) -> u64 {
+    match seed {
+        None => index as u64,
+        Some(seed) => {
+            Rng::new(seed ^ (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)).next_u64()
+        }
+    }
+}
+
+/// Ordering key of a node of a `Graph<T>`
+pub type NodeKey<'a, T, K> = Rc<dyn Fn(&'a Node<T>) -> K + 'a>;
+
+impl<T, S: Clone, K: Clone, G: GraphBase<Value = T>> Clone
+    for NextSmallestIterator<'_, T, S, K, G>
+{
+    fn clone(&self) -> Self {
+        NextSmallestIterator {
+            graph: self.graph,
+            heap: self.heap.clone(),
+            parent: self.parent,
+            depth: self.depth,
+            yielded: self.yielded,
+            max_nodes: self.max_nodes,
+            max_depth: self.max_depth,
+            fuel: self.fuel,
+            visited: self.visited.clone(),
+            key: Rc::clone(&self.key),
+            walls: self.walls.clone(),
+            tie_seed: self.tie_seed,
+        }
+    }
+}
+
+impl<'a, T: Ord> NextSmallestIterator<'a, T> {
+    /// Like `new`, but errors on an out-of-range start or neighbor index
+    /// instead of skipping it
+    pub fn try_new(graph: &'a Graph<T>, start: NodeId) -> Result<Self, GraphError> {
+        graph.check_node(start)?;
+        graph.validate()?;
+        Ok(Self::new(graph, start))
+    }
+}
+
+impl<'a, T: Ord + 'a, G: GraphBase<Value = T>>
+    NextSmallestIterator<'a, T, HashSet<usize>, &'a T, G>
+{
+    /// Create a new iterator starting from a given node
+    pub fn new(graph: &'a G, start: NodeId) -> Self {
+        Self::with_visited(graph, start, HashSet::new())
+    }
+
+    /// Create an iterator whose visited set iterates in index order
+    pub fn deterministic(
+        graph: &'a G,
+        start: NodeId,
+    ) -> NextSmallestIterator<'a, T, BTreeSet<usize>, &'a T, G> {
+        NextSmallestIterator::with_visited(graph, start, BTreeSet::new())
+    }
+
+    /// Create an iterator tracking visited nodes in a bit set sized for the
+    /// graph, which beats hashing when the traversal covers much of the graph
+    pub fn dense(graph: &'a G, start: NodeId) -> NextSmallestIterator<'a, T, BitVisited, &'a T, G> {
+        let visited = BitVisited::with_len(graph.node_count());
+        NextSmallestIterator::with_visited(graph, start, visited)
+    }
+}
+
+impl<'a, T: Ord + 'a, S: VisitedSet, G: GraphBase<Value = T>>
+    NextSmallestIterator<'a, T, S, &'a T, G>
+{
+    /// Create an iterator that records yielded nodes in the given set
+    pub fn with_visited(graph: &'a G, start: NodeId, visited: S) -> Self {
+        Self::with_key(
+            graph,
+            start,
+            visited,
+            Rc::new(|node: &'a G::Node| G::node_value(node)),
+        )
+    }
+}
+
+impl<'a, T, S: VisitedSet, K: Ord, G: GraphBase<Value = T>> NextSmallestIterator<'a, T, S, K, G> {
+    /// Create an iterator that orders nodes by `key` instead of their value
+    pub fn with_key(
+        graph: &'a G,
+        start: NodeId,
+        visited: S,
+        key: Rc<dyn Fn(&'a G::Node) -> K + 'a>,
+    ) -> Self {
+        let mut heap = BinaryHeap::new();
+
+        // Push the starting node into the heap
+        if start.index() < graph.node_count() {
+            let start_key = key(graph.node(start));
+            let tie = start.index() as u64;
+            heap.push(Reverse((start_key, tie, start.index(), 0, NO_PARENT, 0)));
+        }
+
+        Self {
+            graph,
+            heap,
+            parent: None,
+            depth: 0,
+            yielded: 0,
+            max_nodes: None,
+            max_depth: None,
+            fuel: None,
+            visited,
+            key,
+            walls: Walls::default(),
+            tie_seed: None,
+        }
+    }
+
+    /// Nodes visited so far
+    pub fn visited(&self) -> &S {
+        &self.visited
+    }
+
+    /// Consume the traversal and return its visited set, e.g. to seed the next one
+    pub fn into_visited(self) -> S {
+        self.visited
+    }
+
+    /// Treat nodes matching `predicate` as walls: they are never yielded and
+    /// the traversal never continues through them
+    pub fn prune_if<P: Fn(&G::Node) -> bool + 'a>(mut self, predicate: P) -> Self {
+        self.walls.add(predicate);
+        self
+    }
+
+    /// End after yielding `max_nodes` nodes
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Never expand nodes `max_depth` edges from the start, so deeper nodes
+    /// are never yielded
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Spend at most `units` of work: one per node yielded and one per edge
+    /// examined. The node whose expansion spends the last unit is still
+    /// yielded, with its remaining edges left unexamined, and then the
+    /// traversal ends.
+    pub fn fuel(mut self, units: usize) -> Self {
+        self.fuel = Some(units);
+        self
+    }
+
+    /// Break ties between equal keys in a random order fixed by `seed`
+    /// rather than by index, for randomized traversals that are
+    /// reproducible from the seed
+    pub fn shuffled(mut self, seed: u64) -> Self {
+        self.tie_seed = Some(seed);
+        self
+    }
+
+    /// Units of fuel left, if a budget was set
+    pub fn remaining_fuel(&self) -> Option<usize> {
+        self.fuel
+    }
+
+    /// Length of the tree path from the start to the node last yielded
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl<'a, T: 'a, S: VisitedSet, K: Ord, G: GraphBase<Value = T> + 'a> Traversal<'a>
+    for NextSmallestIterator<'a, T, S, K, G>
+{
+    type Value = T;
+    type Graph = G;
+
+    fn graph(&self) -> &'a G {
+        self.graph
+    }
+
+    fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        if self.fuel == Some(0) || self.max_nodes.is_some_and(|max| self.yielded >= max) {
+            return None;
+        }
+        while let Some(Reverse((_, _, node_index, _, parent, depth))) = self.heap.pop() {
+            // Lazy deletion: skip entries for nodes popped before
+            if !self.visited.insert(node_index) {
+                continue;
+            }
+
+            let id = NodeId::new(node_index);
+            if self.walls.blocks(self.graph, id) {
+                continue;
+            }
+            spend(&mut self.fuel);
+
+            // Add all unvisited neighbors to the heap, ranked after earlier parents
+            if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                for neighbor in self.graph.neighbors(id) {
+                    if self.fuel == Some(0) {
+                        break;
+                    }
+                    spend(&mut self.fuel);
+                    let neighbor_index = neighbor.index();
+                    if neighbor_index < self.graph.node_count()
+                        && !self.visited.contains(neighbor_index)
+                    {
+                        self.heap.push(Reverse((
+                            (self.key)(self.graph.node(neighbor)),
+                            tie_rank(self.tie_seed, neighbor_index),
+                            neighbor_index,
+                            self.yielded,
+                            node_index,
+                            depth + 1,
+                        )));
+                    }
+                }
+            }
+
+            // Return the current node
+            self.parent = parent_of_entry(parent);
+            self.depth = depth;
+            self.yielded += 1;
+            return Some(node_index);
+        }
+        None
+    }
+}
+
+impl<'a, T: 'a, S: VisitedSet, K: Ord, G: GraphBase<Value = T> + 'a> Iterator
+    for NextSmallestIterator<'a, T, S, K, G>
+{
+    type Item = &'a G::Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+        self.next_index()
+            .map(|index| graph.node(NodeId::new(index)))
+    }
+}
+
+/// NextSmallest over cumulative path cost: uniform-cost search. Each node
+/// carries a non-negative cost, and the priority of a frontier node is the sum
+/// of costs along its cheapest discovery path, the start's own cost included,
+/// rather than its single value. Nodes come out in non-decreasing path cost,
+/// ties by index unless `shuffled`, and `path_cost` gives the cost of the node last yielded;
+/// the tree parent is the yielded node that reached it most cheaply.
+///
+/// Like `NextSmallestIterator`, the heap uses lazy deletion, and `max_nodes`,
+/// `max_depth` and `fuel` bound the traversal; `max_cost` also stops it before
+/// any node dearer than the bound. Costs add up saturating at `u64::MAX`.
+pub struct UniformCostIterator<'a, T = i32, S = HashSet<usize>, G = Graph<T>>
+where
+    G: GraphBase<Value = T>,
+{
+    graph: &'a G,
+    heap: BinaryHeap<Reverse<HeapEntry<u64>>>,
+    parent: Option<usize>,
+    depth: usize,
+    path_cost: u64,
+    yielded: usize,
+    max_nodes: Option<usize>,
+    max_depth: Option<usize>,
+    max_cost: Option<u64>,
+    fuel: Option<usize>,
+    visited: S,
+    cost: Rc<dyn Fn(&'a G::Node) -> u64 + 'a>,
+    walls: Walls<'a, G::Node>,
+    tie_seed: Option<u64>,
+}
+
+impl<T, S: Clone, G: GraphBase<Value = T>> Clone for UniformCostIterator<'_, T, S, G> {
+    fn clone(&self) -> Self {
+        UniformCostIterator {
+            graph: self.graph,
+            heap: self.heap.clone(),
+            parent: self.parent,
+            depth: self.depth,
+            path_cost: self.path_cost,
+            yielded: self.yielded,
+            max_nodes: self.max_nodes,
+            max_depth: self.max_depth,
+            max_cost: self.max_cost,
+            fuel: self.fuel,
+            visited: self.visited.clone(),
+            cost: Rc::clone(&self.cost),
+            walls: self.walls.clone(),
+            tie_seed: self.tie_seed,
+        }
+    }
+}
+
+impl<'a, T, G: GraphBase<Value = T>> UniformCostIterator<'a, T, HashSet<usize>, G> {
+    /// Create an iterator from `start` where entering a node costs `cost`
+    pub fn new<C: Fn(&'a G::Node) -> u64 + 'a>(graph: &'a G, start: NodeId, cost: C) -> Self {
+        Self::with_visited(graph, start, HashSet::new(), cost)
+    }
+}
+
+impl<'a, T, S: VisitedSet, G: GraphBase<Value = T>> UniformCostIterator<'a, T, S, G> {
+    /// Create an iterator that records yielded nodes in the given set
+    pub fn with_visited<C: Fn(&'a G::Node) -> u64 + 'a>(
+        graph: &'a G,
+        start: NodeId,
+        visited: S,
+        cost: C,
+    ) -> Self {
+        let mut heap = BinaryHeap::new();
+        if start.index() < graph.node_count() {
+            let start_cost = cost(graph.node(start));
+            let tie = start.index() as u64;
+            heap.push(Reverse((start_cost, tie, start.index(), 0, NO_PARENT, 0)));
+        }
+
+        Self {
+            graph,
+            heap,
+            parent: None,
+            depth: 0,
+            path_cost: 0,
+            yielded: 0,
+            max_nodes: None,
+            max_depth: None,
+            max_cost: None,
+            fuel: None,
+            visited,
+            cost: Rc::new(cost),
+            walls: Walls::default(),
+            tie_seed: None,
+        }
+    }
+
+    /// Nodes visited so far
+    pub fn visited(&self) -> &S {
+        &self.visited
+    }
+
+    /// Consume the traversal and return its visited set, e.g. to seed the next one
+    pub fn into_visited(self) -> S {
+        self.visited
+    }
+
+    /// Treat nodes matching `predicate` as walls: they are never yielded and
+    /// the traversal never continues through them
+    pub fn prune_if<P: Fn(&G::Node) -> bool + 'a>(mut self, predicate: P) -> Self {
+        self.walls.add(predicate);
+        self
+    }
+
+    /// End after yielding `max_nodes` nodes
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Never expand nodes `max_depth` edges from the start, so deeper nodes
+    /// are never yielded
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// End before the first node whose path cost exceeds `max_cost`
+    pub fn max_cost(mut self, max_cost: u64) -> Self {
+        self.max_cost = Some(max_cost);
+        self
+    }
+
+    /// Spend at most `units` of work: one per node yielded and one per edge
+    /// examined, as for `NextSmallestIterator::fuel`
+    pub fn fuel(mut self, units: usize) -> Self {
+        self.fuel = Some(units);
+        self
+    }
+
+    /// Break ties between equal path costs in a random order fixed by
+    /// `seed` rather than by index
+    pub fn shuffled(mut self, seed: u64) -> Self {
+        self.tie_seed = Some(seed);
+        self
+    }
+
+    /// Units of fuel left, if a budget was set
+    pub fn remaining_fuel(&self) -> Option<usize> {
+        self.fuel
+    }
+
+    /// Length of the tree path from the start to the node last yielded
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Total cost of the cheapest path from the start to the node last
+    /// yielded, both ends included
+    pub fn path_cost(&self) -> u64 {
+        self.path_cost
+    }
+}
+
+impl<'a, T: 'a, S: VisitedSet, G: GraphBase<Value = T> + 'a> Traversal<'a>
+    for UniformCostIterator<'a, T, S, G>
+{
+    type Value = T;
+    type Graph = G;
+
+    fn graph(&self) -> &'a G {
+        self.graph
+    }
+
+    fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        if self.fuel == Some(0) || self.max_nodes.is_some_and(|max| self.yielded >= max) {
+            return None;
+        }
+        while let Some(Reverse((path_cost, _, node_index, _, parent, depth))) = self.heap.pop() {
+            // Lazy deletion: skip entries for nodes popped before
+            if self.visited.contains(node_index) {
+                continue;
+            }
+            // Entries pop in cost order, so every later node is dearer too
+            if self.max_cost.is_some_and(|max| path_cost > max) {
+                self.heap.clear();
+                return None;
+            }
+            self.visited.insert(node_index);
+
+            let id = NodeId::new(node_index);
+            if self.walls.blocks(self.graph, id) {
+                continue;
+            }
+            spend(&mut self.fuel);
+
+            if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                for neighbor in self.graph.neighbors(id) {
+                    if self.fuel == Some(0) {
+                        break;
+                    }
+                    spend(&mut self.fuel);
+                    let neighbor_index = neighbor.index();
+                    if neighbor_index < self.graph.node_count()
+                        && !self.visited.contains(neighbor_index)
+                    {
+                        let step = (self.cost)(self.graph.node(neighbor));
+                        self.heap.push(Reverse((
+                            path_cost.saturating_add(step),
+                            tie_rank(self.tie_seed, neighbor_index),
+                            neighbor_index,
+                            self.yielded,
+                            node_index,
+                            depth + 1,
+                        )));
+                    }
+                }
+            }
+
+            self.parent = parent_of_entry(parent);
+            self.depth = depth;
+            self.path_cost = path_cost;
+            self.yielded += 1;
+            return Some(node_index);
+        }
+        None
+    }
+}
+
+impl<'a, T: 'a, S: VisitedSet, G: GraphBase<Value = T> + 'a> Iterator
+    for UniformCostIterator<'a, T, S, G>
+{
+    type Item = &'a G::Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+        self.next_index()
+            .map(|index| graph.node(NodeId::new(index)))
+    }
+}
+
+/// Use up one unit of an optional budget
+fn spend(fuel: &mut Option<usize>) {
+    if let Some(units) = fuel {
+        *units -= 1;
+    }
+}
+
+/// Which end of the key order `BestFirstIterator` takes first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOrder {
+    /// Smallest key first, as in `NextSmallestIterator`
+    Ascending,
+    /// Largest key first
+    Descending,
+}
+
+/// A key compared in the direction of its iterator
+#[derive(Clone)]
+struct Ranked<K> {
+    key: K,
+    order: KeyOrder,
+}
+
+impl<K: Ord> Ord for Ranked<K> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match self.order {
+            KeyOrder::Ascending => self.key.cmp(&other.key),
+            KeyOrder::Descending => other.key.cmp(&self.key),
+        }
+    }
+}
+
+impl<K: Ord> PartialOrd for Ranked<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord> PartialEq for Ranked<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Ord> Eq for Ranked<K> {}
+
+/// Best-first iterator: always yields the frontier node whose key comes first
+/// in `order`, ties broken by smaller index or, once `shuffled`, at random. With the node value as key and
+/// `KeyOrder::Ascending` this is `NextSmallestIterator`; other keys give
+/// next-largest, priority-by-attribute or greedy best-first search.
+pub struct BestFirstIterator<'a, T = i32, K = i32, S = HashSet<usize>> {
+    inner: NextSmallestIterator<'a, T, S, Ranked<K>>,
+}
+
+impl<T, K: Clone, S: Clone> Clone for BestFirstIterator<'_, T, K, S> {
+    fn clone(&self) -> Self {
+        BestFirstIterator {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, T, K: Ord + 'a> BestFirstIterator<'a, T, K> {
+    /// Best-first traversal from `start`, ranking nodes by `key` in `order`
+    pub fn new<F>(graph: &'a Graph<T>, start: NodeId, key: F, order: KeyOrder) -> Self
+    where
+        F: Fn(&'a Node<T>) -> K + 'a,
+    {
+        Self::with_visited(graph, start, HashSet::new(), key, order)
+    }
+}
+
+impl<'a, T, K: Ord + 'a, S: VisitedSet> BestFirstIterator<'a, T, K, S> {
+    /// Like `new`, recording yielded nodes in the given set
+    pub fn with_visited<F>(
+        graph: &'a Graph<T>,
+        start: NodeId,
+        visited: S,
+        key: F,
+        order: KeyOrder,
+    ) -> Self
+    where
+        F: Fn(&'a Node<T>) -> K + 'a,
+    {
+        let key: NodeKey<'a, T, Ranked<K>> = Rc::new(move |node| Ranked {
+            key: key(node),
+            order,
+        });
+        BestFirstIterator {
+            inner: NextSmallestIterator::with_key(graph, start, visited, key),
+        }
+    }
+
+    /// Nodes visited so far
+    pub fn visited(&self) -> &S {
+        self.inner.visited()
+    }
+
+    /// Consume the traversal and return its visited set, e.g. to seed the next one
+    pub fn into_visited(self) -> S {
+        self.inner.into_visited()
+    }
+
+    /// Treat nodes matching `predicate` as walls: they are never yielded and
+    /// the traversal never continues through them
+    pub fn prune_if<P: Fn(&Node<T>) -> bool + 'a>(self, predicate: P) -> Self {
+        BestFirstIterator {
+            inner: self.inner.prune_if(predicate),
+        }
+    }
+
+    /// End after yielding `max_nodes` nodes
+    pub fn max_nodes(self, max_nodes: usize) -> Self {
+        BestFirstIterator {
+            inner: self.inner.max_nodes(max_nodes),
+        }
+    }
+
+    /// Never expand nodes `max_depth` edges from the start
+    pub fn max_depth(self, max_depth: usize) -> Self {
+        BestFirstIterator {
+            inner: self.inner.max_depth(max_depth),
+        }
+    }
+
+    /// Spend at most `units` of work, as in `NextSmallestIterator::fuel`
+    pub fn fuel(self, units: usize) -> Self {
+        BestFirstIterator {
+            inner: self.inner.fuel(units),
+        }
+    }
+
+    /// Break key ties in a random order fixed by `seed`, as in
+    /// `NextSmallestIterator::shuffled`
+    pub fn shuffled(self, seed: u64) -> Self {
+        BestFirstIterator {
+            inner: self.inner.shuffled(seed),
+        }
+    }
+
+    /// Units of fuel left, if a budget was set
+    pub fn remaining_fuel(&self) -> Option<usize> {
+        self.inner.remaining_fuel()
+    }
+
+    /// Length of the tree path from the start to the node last yielded
+    pub fn depth(&self) -> usize {
+        self.inner.depth()
+    }
+}
+
+impl<'a, T, K: Ord, S: VisitedSet> Traversal<'a> for BestFirstIterator<'a, T, K, S> {
+    type Value = T;
+    type Graph = Graph<T>;
+
+    fn graph(&self) -> &'a Graph<T> {
+        self.inner.graph
+    }
+
+    fn parent(&self) -> Option<usize> {
+        self.inner.parent
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.inner.heap.len()
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        self.inner.next_index()
+    }
+}
+
+impl<'a, T, K: Ord, S: VisitedSet> Iterator for BestFirstIterator<'a, T, K, S> {
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+/// Topological order by Kahn's algorithm, smallest ready index first. Nodes on
+/// or downstream of a cycle never become ready; once the iterator is exhausted,
+/// `has_cycle` tells whether that cut the order short.
+pub struct TopoSortIterator<'a, T = i32> {
+    graph: &'a Graph<T>,
+    indegree: Vec<usize>,
+    ready: BinaryHeap<Reverse<usize>>,
+    emitted: usize,
+}
+
+impl<T> Clone for TopoSortIterator<'_, T> {
+    fn clone(&self) -> Self {
+        TopoSortIterator {
+            graph: self.graph,
+            indegree: self.indegree.clone(),
+            ready: self.ready.clone(),
+            emitted: self.emitted,
+        }
+    }
+}
+
+impl<'a, T> TopoSortIterator<'a, T> {
+    pub fn new(graph: &'a Graph<T>) -> Self {
+        let mut indegree = vec![0usize; graph.nodes.len()];
+        for node in &graph.nodes {
+            for &w in &node.neighbors {
+                if let Some(count) = indegree.get_mut(w) {
+                    *count += 1;
+                }
+            }
+        }
+        let ready = (0..graph.nodes.len())
+            .filter(|&v| indegree[v] == 0)
+            .map(Reverse)
+            .collect();
+        Self {
+            graph,
+            indegree,
+            ready,
+            emitted: 0,
+        }
+    }
+
+    /// Whether the graph has a cycle; exact once the iterator is exhausted
+    pub fn has_cycle(&self) -> bool {
+        self.ready.is_empty() && self.emitted < self.graph.nodes.len()
+    }
+}
+
+impl<'a, T> Traversal<'a> for TopoSortIterator<'a, T> {
+    type Value = T;
+    type Graph = Graph<T>;
+
+    fn graph(&self) -> &'a Graph<T> {
+        self.graph
+    }
+
+    fn parent(&self) -> Option<usize> {
+        None
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.ready.len()
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        let Reverse(v) = self.ready.pop()?;
+        for &w in &self.graph.nodes[v].neighbors {
+            let Some(count) = self.indegree.get_mut(w) else {
+                continue;
+            };
+            *count -= 1;
+            if *count == 0 {
+                self.ready.push(Reverse(w));
+            }
+        }
+        self.emitted += 1;
+        Some(v)
+    }
+}
+
+impl<'a, T> Iterator for TopoSortIterator<'a, T> {
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+        self.next_index().map(|index| &graph.nodes[index])
+    }
+}
+
+/// A read-only view of a graph whose node values are projected through a closure.
+/// Nothing is copied: values are computed on demand from the underlying nodes.
+pub struct MappedGraph<'a, U, T = i32> {
+    graph: &'a Graph<T>,
+    project: NodeProjection<'a, T, U>,
+}
+
+type NodeProjection<'a, T, U> = Rc<dyn Fn(&Node<T>) -> U + 'a>;
+
+impl<U, T> Clone for MappedGraph<'_, U, T> {
+    fn clone(&self) -> Self {
+        MappedGraph {
+            graph: self.graph,
+            project: Rc::clone(&self.project),
+        }
+    }
+}
+
+impl<T> Graph<T> {
+    /// Create a view of this graph with node values mapped through `f`
+    pub fn map<'a, U, F>(&'a self, f: F) -> MappedGraph<'a, U, T>
+    where
+        F: Fn(&Node<T>) -> U + 'a,
+    {
+        MappedGraph {
+            graph: self,
+            project: Rc::new(f),
+        }
+    }
+}
+
+impl<'a, U: 'a, T> MappedGraph<'a, U, T> {
+    /// Number of nodes in the view
+    pub fn len(&self) -> usize {
+        self.graph.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.graph.nodes.is_empty()
+    }
+
+    /// Projected value of the node at `index`
+    pub fn value(&self, index: usize) -> U {
+        (self.project)(&self.graph.nodes[index])
+    }
+
+    /// Neighbor indices of the node at `index`
+    pub fn neighbors(&self, index: usize) -> &'a [usize] {
+        &self.graph.nodes[index].neighbors
+    }
+
+    /// Projected values of all nodes, in index order
+    pub fn values(&self) -> impl Iterator<Item = U> + '_ {
+        self.graph.nodes.iter().map(|node| (self.project)(node))
+    }
+
+    /// Compose another projection on top of this one
+    pub fn map<V, F>(&self, f: F) -> MappedGraph<'a, V, T>
+    where
+        F: Fn(U) -> V + 'a,
+    {
+        let project = Rc::clone(&self.project);
+        MappedGraph {
+            graph: self.graph,
+            project: Rc::new(move |node: &Node<T>| f(project(node))),
+        }
+    }
+
+    /// NextSmallest traversal ordered by the projected values
+    pub fn next_smallest(&self, start: NodeId) -> NextSmallestIterator<'a, T, HashSet<usize>, U>
+    where
+        U: Ord,
+    {
+        NextSmallestIterator::with_key(self.graph, start, HashSet::new(), Rc::clone(&self.project))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(nodes: &[(i32, &[usize])]) -> Graph {
+        Graph::from_nodes(
+            nodes
+                .iter()
+                .map(|&(value, neighbors)| Node::new(value, neighbors.to_vec()))
+                .collect(),
+        )
+    }
+
+    /// Visit order and parents straight from the ordering guarantee: repeatedly
+    /// take the frontier node with the smallest (value, index), parented by the
+    /// earliest-yielded node with an edge to it
+    fn reference_order(graph: &Graph, start: usize) -> Vec<(usize, Option<usize>)> {
+        let mut order: Vec<(usize, Option<usize>)> = vec![(start, None)];
+        loop {
+            let yielded: Vec<usize> = order.iter().map(|&(v, _)| v).collect();
+            let next = yielded
+                .iter()
+                .flat_map(|&v| graph.nodes[v].neighbors.iter().map(move |&w| (w, v)))
+                .filter(|(w, _)| !yielded.contains(w))
+                .min_by_key(|&(w, _)| (graph.nodes[w].value, w));
+            match next {
+                Some((w, _)) => {
+                    let parent = yielded
+                        .iter()
+                        .find(|&&v| graph.nodes[v].neighbors.contains(&w))
+                        .copied();
+                    order.push((w, parent));
+                }
+                None => return order,
+            }
+        }
+    }
+
+    fn visit_order_with_parents<'a, I: Traversal<'a>>(
+        mut traversal: I,
+    ) -> Vec<(usize, Option<usize>)> {
+        std::iter::from_fn(|| traversal.next_index().map(|v| (v, traversal.parent()))).collect()
+    }
+
+    #[test]
+    fn next_smallest_takes_the_smallest_frontier_node() {
+        // 0 -> 1 (value 5) -> 3 (value 1); 0 -> 2 (value 3)
+        let g = graph(&[(0, &[1, 2]), (5, &[3]), (3, &[]), (1, &[])]);
+        let order: Vec<i32> = NextSmallestIterator::new(&g, NodeId::new(0))
+            .map(|node| node.value)
+            .collect();
+        // Node 3 is smallest overall but only joins the frontier after node 1
+        assert_eq!(order, vec![0, 3, 5, 1]);
+    }
+
+    #[test]
+    fn next_smallest_breaks_ties_by_index() {
+        let g = graph(&[(0, &[3, 1, 2]), (7, &[]), (7, &[]), (7, &[])]);
+        let order: Vec<usize> =
+            visit_order_with_parents(NextSmallestIterator::new(&g, NodeId::new(0)))
+                .into_iter()
+                .map(|(v, _)| v)
+                .collect();
+        assert_eq!(order, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn next_smallest_yields_rediscovered_nodes_once_with_first_parent() {
+        // Node 3 is discovered from 1 and later from 2; both precede it
+        let g = graph(&[(0, &[2, 1]), (1, &[3]), (2, &[3, 0]), (9, &[1])]);
+        let visits = visit_order_with_parents(NextSmallestIterator::new(&g, NodeId::new(0)));
+        assert_eq!(
+            visits,
+            vec![(0, None), (1, Some(0)), (2, Some(0)), (3, Some(1))]
+        );
+    }
+
+    #[test]
+    fn next_smallest_matches_reference_order() {
+        let graphs = [
+            graph(&[(4, &[1, 2]), (2, &[3, 4]), (1, &[4]), (3, &[0]), (0, &[1])]),
+            graph(&[(0, &[1, 2, 3]), (9, &[4]), (8, &[4]), (7, &[4]), (1, &[2])]),
+            graph(&[
+                (5, &[1]),
+                (3, &[2, 5]),
+                (3, &[3]),
+                (1, &[4, 1]),
+                (2, &[]),
+                (0, &[3]),
+            ]),
+        ];
+        for g in &graphs {
+            for start in 0..g.len() {
+                assert_eq!(
+                    visit_order_with_parents(NextSmallestIterator::new(g, NodeId::new(start))),
+                    reference_order(g, start),
+                    "start {}",
+                    start
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn next_smallest_skips_seeded_visited_nodes() {
+        let g = graph(&[(0, &[1, 2]), (1, &[3]), (2, &[]), (3, &[])]);
+        // Node 1 counts as already visited, so its neighbor 3 is never reached
+        let seeded = HashSet::from([1]);
+        let order: Vec<i32> = NextSmallestIterator::with_visited(&g, NodeId::new(0), seeded)
+            .map(|node| node.value)
+            .collect();
+        assert_eq!(order, vec![0, 2]);
+
+        let mut traversal = NextSmallestIterator::new(&g, NodeId::new(0));
+        traversal.next();
+        // Only popped nodes are visited, not the ones waiting in the heap
+        assert_eq!(traversal.visited(), &HashSet::from([0]));
+    }
+
+    #[test]
+    fn next_smallest_never_yields_or_expands_walls() {
+        let g = graph(&[(0, &[1, 2]), (1, &[3]), (2, &[3]), (3, &[])]);
+        let visits = visit_order_with_parents(
+            NextSmallestIterator::new(&g, NodeId::new(0)).prune_if(|node| node.value == 1),
+        );
+        assert_eq!(visits, vec![(0, None), (2, Some(0)), (3, Some(2))]);
+    }
+
+    #[test]
+    fn best_first_descending_takes_the_largest_frontier_node() {
+        let g = graph(&[(0, &[1, 2]), (5, &[3]), (3, &[]), (1, &[])]);
+        let order: Vec<i32> =
+            BestFirstIterator::new(&g, NodeId::new(0), |node| node.value, KeyOrder::Descending)
+                .map(|node| node.value)
+                .collect();
+        assert_eq!(order, vec![0, 5, 3, 1]);
+    }
+}
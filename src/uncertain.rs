@@ -0,0 +1,132 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::graph::Graph;
+use crate::sampling::Rng;
+use crate::union_find::UnionFind;
+
+/// Existence probabilities of uncertain edges, keyed by (from, to); edges
+/// without an entry exist with probability `default`. Each adjacency entry is
+/// realized independently, so parallel edges are independent trials.
+#[derive(Debug, Clone)]
+pub struct EdgeProbabilities {
+    probabilities: HashMap<(usize, usize), f64>,
+    default: f64,
+}
+
+impl Default for EdgeProbabilities {
+    fn default() -> Self {
+        Self::with_default(1.0)
+    }
+}
+
+impl EdgeProbabilities {
+    /// No per-edge entries; every edge exists with probability `default`
+    pub fn with_default(default: f64) -> Self {
+        Self {
+            probabilities: HashMap::new(),
+            default: default.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn set(&mut self, from: usize, to: usize, probability: f64) {
+        self.probabilities
+            .insert((from, to), probability.clamp(0.0, 1.0));
+    }
+
+    pub fn get(&self, from: usize, to: usize) -> f64 {
+        self.probabilities
+            .get(&(from, to))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// Monte Carlo estimate of component sizes over random edge realizations
+#[derive(Debug, Clone)]
+pub struct ComponentSizeEstimate {
+    /// Per node: expected size of its weakly connected component
+    pub expected_size: Vec<f64>,
+    /// Expected size of the largest component
+    pub expected_largest: f64,
+}
+
+impl<T> Graph<T> {
+    /// Probability that `to` is reachable from `from` over out-edges when each
+    /// edge exists independently, estimated from `samples` realizations. Edges
+    /// are only drawn when the BFS reaches them, which samples the same
+    /// distribution as realizing the whole graph first.
+    pub fn reachability_probability(
+        &self,
+        probabilities: &EdgeProbabilities,
+        from: usize,
+        to: usize,
+        samples: usize,
+        seed: u64,
+    ) -> f64 {
+        if from == to {
+            return 1.0;
+        }
+        let mut rng = Rng::new(seed);
+        let mut stamp = vec![usize::MAX; self.nodes.len()];
+        let mut hits = 0;
+        for sample in 0..samples {
+            // Stamp visited nodes with the sample number instead of clearing
+            stamp[from] = sample;
+            let mut queue = VecDeque::from([from]);
+            'search: while let Some(v) = queue.pop_front() {
+                for &w in &self.nodes[v].neighbors {
+                    if stamp[w] != sample && rng.next_f64() < probabilities.get(v, w) {
+                        if w == to {
+                            hits += 1;
+                            break 'search;
+                        }
+                        stamp[w] = sample;
+                        queue.push_back(w);
+                    }
+                }
+            }
+        }
+        hits as f64 / samples.max(1) as f64
+    }
+
+    /// Expected weakly connected component sizes over `samples` realizations
+    pub fn expected_component_sizes(
+        &self,
+        probabilities: &EdgeProbabilities,
+        samples: usize,
+        seed: u64,
+    ) -> ComponentSizeEstimate {
+        let n = self.nodes.len();
+        let mut rng = Rng::new(seed);
+        let mut expected_size = vec![0.0; n];
+        let mut expected_largest = 0.0;
+        for _ in 0..samples {
+            let mut sets = UnionFind::new(n);
+            for (v, node) in self.nodes.iter().enumerate() {
+                for &w in &node.neighbors {
+                    if rng.next_f64() < probabilities.get(v, w) {
+                        sets.union(v, w);
+                    }
+                }
+            }
+            let mut size = vec![0usize; n];
+            for v in 0..n {
+                let r = sets.find(v);
+                size[r] += 1;
+            }
+            for (v, expected) in expected_size.iter_mut().enumerate() {
+                let r = sets.find(v);
+                *expected += size[r] as f64;
+            }
+            expected_largest += size.iter().copied().max().unwrap_or(0) as f64;
+        }
+        let samples = samples.max(1) as f64;
+        for size in &mut expected_size {
+            *size /= samples;
+        }
+        ComponentSizeEstimate {
+            expected_size,
+            expected_largest: expected_largest / samples,
+        }
+    }
+}
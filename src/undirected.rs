@@ -0,0 +1,118 @@
+use crate::error::GraphError;
+use crate::graph::{Graph, Node, NodeId};
+
+/// An undirected graph. Every edge is stored in the adjacency lists of both
+/// endpoints (a self-loop once), so the lists are always symmetric.
+///
+/// `Graph` is the directed counterpart (also available as `DiGraph`). The
+/// traversals and structural algorithms run on the symmetric view returned by
+/// `as_graph`; those that only make sense one way, such as undirected cycle
+/// detection here or topological order on `Graph`, live on that type alone.
+#[derive(Debug, Clone)]
+pub struct UnGraph<T = i32> {
+    graph: Graph<T>,
+}
+
+impl<T> UnGraph<T> {
+    /// Empty graph
+    pub fn new() -> Self {
+        UnGraph {
+            graph: Graph::new(),
+        }
+    }
+
+    /// Read every edge of `graph` as undirected. Parallel edges and the two
+    /// directions of an edge are merged into one, and self-loops are dropped.
+    pub fn from_directed(graph: Graph<T>) -> Self {
+        let adjacency = graph.undirected_adjacency();
+        let nodes = graph
+            .nodes
+            .into_iter()
+            .zip(adjacency)
+            .map(|(node, neighbors)| Node {
+                value: node.value,
+                neighbors,
+            })
+            .collect();
+        UnGraph {
+            graph: Graph { nodes },
+        }
+    }
+
+    /// Append a node without neighbors and return its id
+    pub fn add_node(&mut self, value: T) -> NodeId {
+        self.graph.add_node(value)
+    }
+
+    /// Add the edge a - b, listing each endpoint as a neighbor of the other
+    pub fn add_edge(&mut self, a: NodeId, b: NodeId) -> Result<(), GraphError> {
+        self.graph.add_undirected_edge(a, b)
+    }
+
+    /// The graph with each edge as a pair of arcs, for traversals and the
+    /// algorithms on `Graph`
+    pub fn as_graph(&self) -> &Graph<T> {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> Graph<T> {
+        self.graph
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&Node<T>> {
+        self.graph.node(id)
+    }
+
+    /// Number of edges at a node, a self-loop counting once
+    pub fn degree(&self, id: NodeId) -> usize {
+        self.graph.nodes[id.index()].neighbors.len()
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.graph.is_empty()
+    }
+
+    /// Each edge once as (a, b) with a <= b, in order of a
+    pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        self.graph.nodes.iter().enumerate().flat_map(|(a, node)| {
+            node.neighbors
+                .iter()
+                .filter(move |&&b| a <= b)
+                .map(move |&b| (NodeId::new(a), NodeId::new(b)))
+        })
+    }
+
+    /// Connected components, each sorted by node index
+    pub fn connected_components(&self) -> Vec<Vec<NodeId>> {
+        self.graph.connected_components()
+    }
+
+    /// Whether the graph has a cycle of three or more nodes
+    pub fn has_cycle(&self) -> bool {
+        self.graph.has_undirected_cycle()
+    }
+
+    /// A cycle of three or more nodes, in order around it
+    pub fn find_cycle(&self) -> Option<Vec<NodeId>> {
+        self.graph.find_undirected_cycle()
+    }
+}
+
+impl<T> std::ops::Index<NodeId> for UnGraph<T> {
+    type Output = Node<T>;
+
+    fn index(&self, id: NodeId) -> &Node<T> {
+        &self.graph[id]
+    }
+}
+
+impl<T> Default for UnGraph<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
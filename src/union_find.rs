@@ -0,0 +1,98 @@
+/// Disjoint sets over the elements `0..len`, with path compression and union
+/// by rank, so any sequence of m operations runs in O(m α(len)): effectively
+/// constant time each. Elements can be added later, which suits incremental
+/// connectivity, e.g. merging components as edges arrive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u8>, // Upper bound on each root's tree height
+    sets: usize,
+}
+
+impl UnionFind {
+    /// `len` elements, each in a set of its own
+    pub fn new(len: usize) -> Self {
+        UnionFind {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            sets: len,
+        }
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+
+    /// Append an element in a set of its own and return it
+    pub fn add(&mut self) -> usize {
+        let element = self.parent.len();
+        self.parent.push(element);
+        self.rank.push(0);
+        self.sets += 1;
+        element
+    }
+
+    /// Representative of the set holding `element`; panics if it is out of range
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // Point every element on the way straight at the root
+        let mut current = element;
+        while self.parent[current] != root {
+            current = std::mem::replace(&mut self.parent[current], root);
+        }
+        root
+    }
+
+    /// Merge the sets holding `a` and `b`, returning false if they already were one
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a == root_b {
+            return false;
+        }
+        // Hang the shallower tree under the deeper one
+        let (child, root) = if self.rank[root_a] < self.rank[root_b] {
+            (root_a, root_b)
+        } else {
+            (root_b, root_a)
+        };
+        self.parent[child] = root;
+        if self.rank[child] == self.rank[root] {
+            self.rank[root] += 1;
+        }
+        self.sets -= 1;
+        true
+    }
+
+    /// Whether `a` and `b` are in the same set
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The sets, each sorted, ordered by smallest element
+    pub fn sets(&mut self) -> Vec<Vec<usize>> {
+        let mut index_of_root = vec![usize::MAX; self.len()];
+        let mut sets: Vec<Vec<usize>> = Vec::new();
+        for element in 0..self.len() {
+            let root = self.find(element);
+            if index_of_root[root] == usize::MAX {
+                index_of_root[root] = sets.len();
+                sets.push(Vec::new());
+            }
+            sets[index_of_root[root]].push(element);
+        }
+        sets
+    }
+}
@@ -0,0 +1,375 @@
+use std::collections::VecDeque;
+
+use crate::graph::{Graph, Node, NodeId};
+use crate::traversal::{BfsIterator, Traversal, TraversalTree};
+
+/// Callbacks for `dfs_visit`, each a no-op by default. Every edge out of a
+/// discovered node is reported exactly once, under one of the edge callbacks.
+pub trait DfsVisitor {
+    /// First time the search reaches a node
+    fn discover_node(&mut self, _node: NodeId) {}
+
+    /// All of a node's edges have been explored
+    fn finish_node(&mut self, _node: NodeId) {}
+
+    /// Edge to an undiscovered node, which is discovered next
+    fn tree_edge(&mut self, _from: NodeId, _to: NodeId) {}
+
+    /// Edge to a node that is still being explored, closing a cycle
+    fn back_edge(&mut self, _from: NodeId, _to: NodeId) {}
+
+    /// Edge to a finished node: a descendant reached earlier, or a node in
+    /// another subtree
+    fn forward_or_cross_edge(&mut self, _from: NodeId, _to: NodeId) {}
+}
+
+/// DFS state of a node: not yet reached, on the current path, or finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Color {
+    White,
+    Gray,
+    Black,
+}
+
+/// Depth-first search from `start` that reports its events to `visitor`.
+/// Neighbors are explored in list order, as a recursive DFS would, but without
+/// recursion. Panics if `start` is not a node of the graph.
+pub fn dfs_visit<T, V: DfsVisitor>(graph: &Graph<T>, start: NodeId, visitor: &mut V) {
+    let mut color = vec![Color::White; graph.nodes.len()];
+    dfs_visit_from(graph, start, &mut color, visitor);
+}
+
+/// `dfs_visit` from every node not yet reached, in index order, so that every
+/// edge of the graph is reported once
+pub fn dfs_visit_all<T, V: DfsVisitor>(graph: &Graph<T>, visitor: &mut V) {
+    let mut color = vec![Color::White; graph.nodes.len()];
+    for root in 0..graph.nodes.len() {
+        if color[root] == Color::White {
+            dfs_visit_from(graph, NodeId::new(root), &mut color, visitor);
+        }
+    }
+}
+
+/// One search of `dfs_visit`, leaving alone nodes that `color` marks reached
+fn dfs_visit_from<T, V: DfsVisitor>(
+    graph: &Graph<T>,
+    start: NodeId,
+    color: &mut [Color],
+    visitor: &mut V,
+) {
+    color[start.index()] = Color::Gray;
+    visitor.discover_node(start);
+    let mut stack = vec![(start.index(), 0)]; // (node, next neighbor position)
+
+    while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+        let Some(&w) = graph.nodes[v].neighbors.get(*next) else {
+            color[v] = Color::Black;
+            visitor.finish_node(NodeId::new(v));
+            stack.pop();
+            continue;
+        };
+        *next += 1;
+        let (from, to) = (NodeId::new(v), NodeId::new(w));
+        match color[w] {
+            Color::White => {
+                visitor.tree_edge(from, to);
+                color[w] = Color::Gray;
+                visitor.discover_node(to);
+                stack.push((w, 0));
+            }
+            Color::Gray => visitor.back_edge(from, to),
+            Color::Black => visitor.forward_or_cross_edge(from, to),
+        }
+    }
+}
+
+/// Depth-first forest of a whole graph, searched from each unreached node in
+/// index order: parents, depths, and discovery and finish times on one clock
+/// that ticks at every event, so the time intervals of two nodes are nested
+/// exactly when one is an ancestor of the other
+#[derive(Debug, Clone)]
+pub struct DfsForest {
+    roots: Vec<NodeId>,
+    parents: Vec<Option<usize>>,
+    depths: Vec<usize>,
+    discovered: Vec<usize>,
+    finished: Vec<usize>,
+    preorder: Vec<NodeId>,
+}
+
+impl DfsForest {
+    /// Roots of the trees, in the order they were searched from
+    pub fn roots(&self) -> &[NodeId] {
+        &self.roots
+    }
+
+    /// Every node in order of discovery
+    pub fn preorder(&self) -> &[NodeId] {
+        &self.preorder
+    }
+
+    /// The node `node` was discovered from; None for roots
+    pub fn parent_of(&self, node: NodeId) -> Option<NodeId> {
+        self.parents[node.index()].map(NodeId::new)
+    }
+
+    /// Tree edges from the root of the node's tree
+    pub fn depth_of(&self, node: NodeId) -> usize {
+        self.depths[node.index()]
+    }
+
+    pub fn discovery_time(&self, node: NodeId) -> usize {
+        self.discovered[node.index()]
+    }
+
+    pub fn finish_time(&self, node: NodeId) -> usize {
+        self.finished[node.index()]
+    }
+
+    /// Whether `ancestor` is `node` or on its tree path from the root
+    pub fn is_ancestor(&self, ancestor: NodeId, node: NodeId) -> bool {
+        self.discovered[ancestor.index()] <= self.discovered[node.index()]
+            && self.finished[node.index()] <= self.finished[ancestor.index()]
+    }
+}
+
+/// Builds a `DfsForest` from the events of `dfs_visit_all`
+struct ForestBuilder {
+    forest: DfsForest,
+    clock: usize,
+}
+
+impl DfsVisitor for ForestBuilder {
+    fn discover_node(&mut self, node: NodeId) {
+        let forest = &mut self.forest;
+        match forest.parents[node.index()] {
+            Some(parent) => forest.depths[node.index()] = forest.depths[parent] + 1,
+            None => forest.roots.push(node),
+        }
+        forest.discovered[node.index()] = self.clock;
+        forest.preorder.push(node);
+        self.clock += 1;
+    }
+
+    fn finish_node(&mut self, node: NodeId) {
+        self.forest.finished[node.index()] = self.clock;
+        self.clock += 1;
+    }
+
+    fn tree_edge(&mut self, from: NodeId, to: NodeId) {
+        self.forest.parents[to.index()] = Some(from.index());
+    }
+}
+
+/// Kind of an edge in a depth-first search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// Edge the search discovered a node along
+    Tree,
+    /// Edge to an ancestor on the current path (a self-loop included): the
+    /// graph has a cycle exactly when a search finds one
+    Back,
+    /// Edge to an already finished descendant
+    Forward,
+    /// Edge to a finished node that is not a descendant
+    Cross,
+}
+
+/// Records each edge's kind, splitting `forward_or_cross_edge` by discovery
+/// order: a finished node discovered after `from` must descend from it
+struct EdgeClassifier {
+    discovered: Vec<usize>,
+    clock: usize,
+    edges: Vec<(NodeId, NodeId, EdgeKind)>,
+}
+
+impl DfsVisitor for EdgeClassifier {
+    fn discover_node(&mut self, node: NodeId) {
+        self.discovered[node.index()] = self.clock;
+        self.clock += 1;
+    }
+
+    fn tree_edge(&mut self, from: NodeId, to: NodeId) {
+        self.edges.push((from, to, EdgeKind::Tree));
+    }
+
+    fn back_edge(&mut self, from: NodeId, to: NodeId) {
+        self.edges.push((from, to, EdgeKind::Back));
+    }
+
+    fn forward_or_cross_edge(&mut self, from: NodeId, to: NodeId) {
+        let kind = if self.discovered[from.index()] < self.discovered[to.index()] {
+            EdgeKind::Forward
+        } else {
+            EdgeKind::Cross
+        };
+        self.edges.push((from, to, kind));
+    }
+}
+
+impl<T> Graph<T> {
+    /// Kind of every edge out of the nodes reachable from `start`, in the
+    /// order a depth-first search exploring neighbors in list order examines
+    /// them; parallel edges are classified one by one. Panics if `start` is
+    /// out of range.
+    pub fn classify_edges(&self, start: NodeId) -> Vec<(NodeId, NodeId, EdgeKind)> {
+        self.check_node(start).expect("start node out of range");
+        let mut classifier = self.edge_classifier();
+        dfs_visit(self, start, &mut classifier);
+        classifier.edges
+    }
+
+    /// Like `classify_edges`, over the depth-first forest of `dfs_forest`, so
+    /// every edge of the graph is classified
+    pub fn classify_all_edges(&self) -> Vec<(NodeId, NodeId, EdgeKind)> {
+        let mut classifier = self.edge_classifier();
+        dfs_visit_all(self, &mut classifier);
+        classifier.edges
+    }
+
+    fn edge_classifier(&self) -> EdgeClassifier {
+        EdgeClassifier {
+            discovered: vec![0; self.nodes.len()],
+            clock: 0,
+            edges: Vec::new(),
+        }
+    }
+
+    /// Breadth-first tree from `start`, with parents and hop depths. Panics if
+    /// `start` is out of range.
+    pub fn bfs_tree(&self, start: NodeId) -> TraversalTree {
+        self.check_node(start).expect("start node out of range");
+        BfsIterator::new(self, start).tree()
+    }
+
+    /// Depth-first forest covering every node; see `DfsForest`
+    pub fn dfs_forest(&self) -> DfsForest {
+        let n = self.nodes.len();
+        let mut builder = ForestBuilder {
+            forest: DfsForest {
+                roots: Vec::new(),
+                parents: vec![None; n],
+                depths: vec![0; n],
+                discovered: vec![0; n],
+                finished: vec![0; n],
+                preorder: Vec::with_capacity(n),
+            },
+            clock: 0,
+        };
+        dfs_visit_all(self, &mut builder);
+        builder.forest
+    }
+    /// Breadth-first traversal from `start` handing out each node mutably;
+    /// see `BfsMut`. Panics if `start` is out of range.
+    pub fn bfs_mut(&mut self, start: NodeId) -> BfsMut<'_, T> {
+        self.check_node(start).expect("start node out of range");
+        BfsMut {
+            pending: MutPending::new(self, start),
+        }
+    }
+
+    /// Depth-first counterpart of `bfs_mut`; see `DfsMut`
+    pub fn dfs_mut(&mut self, start: NodeId) -> DfsMut<'_, T> {
+        self.check_node(start).expect("start node out of range");
+        DfsMut {
+            pending: MutPending::new(self, start),
+        }
+    }
+
+    /// Call `f` on each node reachable from `start`, in `BfsIterator` order.
+    /// Panics if `start` is out of range.
+    pub fn bfs_for_each_mut(&mut self, start: NodeId, mut f: impl FnMut(&mut Node<T>)) {
+        let mut bfs = self.bfs_mut(start);
+        while let Some((_, node)) = bfs.next_mut() {
+            f(node);
+        }
+    }
+
+    /// Call `f` on each node reachable from `start`, in `DfsIterator` order.
+    /// Panics if `start` is out of range.
+    pub fn dfs_for_each_mut(&mut self, start: NodeId, mut f: impl FnMut(&mut Node<T>)) {
+        let mut dfs = self.dfs_mut(start);
+        while let Some((_, node)) = dfs.next_mut() {
+            f(node);
+        }
+    }
+}
+
+/// Traversal state shared by `BfsMut` and `DfsMut`
+struct MutPending<'a, T> {
+    graph: &'a mut Graph<T>,
+    pending: VecDeque<usize>,
+    visited: Vec<bool>,
+}
+
+impl<'a, T> MutPending<'a, T> {
+    fn new(graph: &'a mut Graph<T>, start: NodeId) -> Self {
+        MutPending {
+            visited: vec![false; graph.nodes.len()],
+            pending: VecDeque::from([start.index()]),
+            graph,
+        }
+    }
+
+    /// Mark the popped node visited and queue its unvisited neighbors, read
+    /// before the node is handed out so changes to it cannot derail the
+    /// traversal
+    fn visit(&mut self, depth_first: bool) -> Option<(NodeId, &mut Node<T>)> {
+        loop {
+            let index = if depth_first {
+                self.pending.pop_back()?
+            } else {
+                self.pending.pop_front()?
+            };
+            // If the node has already been visited, skip it
+            if std::mem::replace(&mut self.visited[index], true) {
+                continue;
+            }
+            let neighbors = &self.graph.nodes[index].neighbors;
+            if depth_first {
+                // Stack them in reverse order, so the first neighbor pops first
+                for &neighbor in neighbors.iter().rev() {
+                    if !self.visited[neighbor] {
+                        self.pending.push_back(neighbor);
+                    }
+                }
+            } else {
+                for &neighbor in neighbors {
+                    if !self.visited[neighbor] {
+                        self.pending.push_back(neighbor);
+                    }
+                }
+            }
+            return Some((NodeId::new(index), &mut self.graph.nodes[index]));
+        }
+    }
+}
+
+/// Breadth-first traversal yielding `&mut Node`, in the order of
+/// `BfsIterator`. It is a lending iterator: each node borrows the traversal
+/// until the next call, so it has `next_mut` rather than `Iterator::next`.
+/// Each node's neighbors are read when it is reached, before it is handed
+/// out, so the traversal follows the graph as it was at that point.
+pub struct BfsMut<'a, T> {
+    pending: MutPending<'a, T>,
+}
+
+impl<T> BfsMut<'_, T> {
+    /// The next node with its id, or None when the traversal is done
+    pub fn next_mut(&mut self) -> Option<(NodeId, &mut Node<T>)> {
+        self.pending.visit(false)
+    }
+}
+
+/// Depth-first traversal yielding `&mut Node`, in the order of `DfsIterator`;
+/// see `BfsMut`
+pub struct DfsMut<'a, T> {
+    pending: MutPending<'a, T>,
+}
+
+impl<T> DfsMut<'_, T> {
+    /// The next node with its id, or None when the traversal is done
+    pub fn next_mut(&mut self) -> Option<(NodeId, &mut Node<T>)> {
+        self.pending.visit(true)
+    }
+}
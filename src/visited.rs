@@ -0,0 +1,179 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::sampling::Rng;
+
+/// Visited-node bookkeeping shared by the iterators.
+/// HashSet is the default; BTreeSet keeps the visited nodes in index order, so
+/// anything derived from the visited set is identical across runs and platforms.
+/// Any store can take part, such as a bit set, a Bloom filter or an external
+/// table: the iterators take the set by value through `with_visited`, and a
+/// `&mut` reference works too, leaving the set with the caller afterwards.
+pub trait VisitedSet {
+    /// Mark a node as visited, returning false if it already was
+    fn insert(&mut self, index: usize) -> bool;
+    fn contains(&self, index: usize) -> bool;
+}
+
+impl<S: VisitedSet + ?Sized> VisitedSet for &mut S {
+    fn insert(&mut self, index: usize) -> bool {
+        (**self).insert(index)
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        (**self).contains(index)
+    }
+}
+
+impl VisitedSet for HashSet<usize> {
+    fn insert(&mut self, index: usize) -> bool {
+        HashSet::insert(self, index)
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        HashSet::contains(self, &index)
+    }
+}
+
+impl VisitedSet for BTreeSet<usize> {
+    fn insert(&mut self, index: usize) -> bool {
+        BTreeSet::insert(self, index)
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        BTreeSet::contains(self, &index)
+    }
+}
+
+/// Dense flags indexed by node, grown on demand; cheaper than hashing for
+/// traversals that touch most of the graph
+impl VisitedSet for Vec<bool> {
+    fn insert(&mut self, index: usize) -> bool {
+        if index >= self.len() {
+            self.resize(index + 1, false);
+        }
+        !std::mem::replace(&mut self[index], true)
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        self.get(index).copied().unwrap_or(false)
+    }
+}
+
+/// Dense bit set indexed by node, one bit per node and grown on demand: an
+/// eighth the memory of `Vec<bool>`, so large traversals stay in cache.
+/// `with_len` preallocates for a graph's node count.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitVisited {
+    words: Vec<u64>,
+}
+
+impl BitVisited {
+    /// Empty set with room for node indices below `len`
+    pub fn with_len(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    /// Number of visited nodes
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+}
+
+impl VisitedSet for BitVisited {
+    fn insert(&mut self, index: usize) -> bool {
+        let (word, bit) = (index / 64, 1u64 << (index % 64));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let added = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        added
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        self.words
+            .get(index / 64)
+            .is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+}
+
+/// Approximate visited set backed by a Bloom filter, for traversals too large
+/// to remember every visited id. A node once inserted always reads as visited,
+/// so traversals never revisit a node or loop; a false positive makes a node
+/// that was never visited look visited, and the traversal skips it along with
+/// anything reachable only through it.
+#[derive(Debug, Clone)]
+pub struct BloomVisited {
+    bits: Vec<u64>,
+    hashes: u32,
+    inserted: usize,
+}
+
+impl BloomVisited {
+    /// Filter sized for `expected` insertions at the given false-positive rate
+    pub fn with_capacity(expected: usize, false_positive_rate: f64) -> Self {
+        let ln2 = std::f64::consts::LN_2;
+        let bits = (-(expected.max(1) as f64) * false_positive_rate.clamp(1e-12, 0.5).ln()
+            / (ln2 * ln2))
+            .ceil();
+        let words = (bits as usize).div_ceil(64).max(1);
+        let hashes = ((words * 64) as f64 / expected.max(1) as f64 * ln2)
+            .round()
+            .clamp(1.0, 32.0) as u32;
+        Self {
+            bits: vec![0; words],
+            hashes,
+            inserted: 0,
+        }
+    }
+
+    /// Bit positions by double hashing of a SplitMix64 mix of the index
+    fn positions(&self, index: usize) -> impl Iterator<Item = usize> {
+        let hash = Rng::new(index as u64).next_u64();
+        let (h1, h2) = (hash & 0xFFFF_FFFF, (hash >> 32) | 1);
+        let len = self.bits.len() as u64 * 64;
+        (0..u64::from(self.hashes))
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % len) as usize)
+    }
+
+    /// Expected probability that a never-inserted node reads as visited
+    pub fn false_positive_rate(&self) -> f64 {
+        let bits = (self.bits.len() * 64) as f64;
+        (1.0 - (-f64::from(self.hashes) * self.inserted as f64 / bits).exp())
+            .powi(self.hashes as i32)
+    }
+}
+
+/// One million expected nodes at a 1% false-positive rate (about 1.2 MB)
+impl Default for BloomVisited {
+    fn default() -> Self {
+        Self::with_capacity(1 << 20, 0.01)
+    }
+}
+
+impl VisitedSet for BloomVisited {
+    fn insert(&mut self, index: usize) -> bool {
+        let mut added = false;
+        for position in self.positions(index) {
+            let (word, bit) = (position / 64, 1u64 << (position % 64));
+            added |= self.bits[word] & bit == 0;
+            self.bits[word] |= bit;
+        }
+        self.inserted += usize::from(added);
+        added
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        self.positions(index)
+            .all(|position| self.bits[position / 64] & (1 << (position % 64)) != 0)
+    }
+}
@@ -0,0 +1,169 @@
+use crate::graph::{Graph, NodeId};
+use crate::sampling::Rng;
+
+/// Options for `Graph::biased_walk`: transitions are proportional to edge
+/// weight times the node2vec bias, which from the previous node t at node v
+/// scales the step to x by 1 / `return_param` if x is t, by 1 if x is a
+/// neighbor of t, and by 1 / `in_out_param` otherwise. Both parameters at 1
+/// give a plain weighted walk; a low return parameter keeps walks local, a low
+/// in-out parameter pushes them outward (DFS-like), a high one inward
+/// (BFS-like).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WalkOptions {
+    /// Nodes per walk, the start included
+    pub length: usize,
+    /// node2vec p
+    pub return_param: f64,
+    /// node2vec q
+    pub in_out_param: f64,
+}
+
+impl Default for WalkOptions {
+    /// Walks of 80 nodes, unbiased
+    fn default() -> Self {
+        WalkOptions {
+            length: 80,
+            return_param: 1.0,
+            in_out_param: 1.0,
+        }
+    }
+}
+
+impl<T> Graph<T> {
+    /// Random walk of up to `length` nodes from `start`, each step along an
+    /// out-edge chosen uniformly (parallel edges count separately); it ends
+    /// early at a node without out-edges. Panics if `start` is out of range.
+    pub fn random_walk(&self, start: NodeId, length: usize, seed: u64) -> Vec<NodeId> {
+        let options = WalkOptions {
+            length,
+            ..WalkOptions::default()
+        };
+        self.biased_walk(start, &options, |_, _| 1, seed)
+    }
+
+    /// Random walk from `start` with steps weighted by `weight` and biased as
+    /// `options` describes; it ends early at a node whose out-edges all have
+    /// weight 0. Panics if `start` is out of range.
+    pub fn biased_walk<W>(
+        &self,
+        start: NodeId,
+        options: &WalkOptions,
+        weight: W,
+        seed: u64,
+    ) -> Vec<NodeId>
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        self.check_node(start).expect("start node out of range");
+        let walker = Walker::new(self, options, weight);
+        walker.walk(start.index(), &mut Rng::new(seed))
+    }
+
+    /// `walks_per_node` biased walks from every node, as node2vec feeds an
+    /// embedding: walk r * n + v is the r-th walk from node v, and each walk
+    /// draws from its own generator seeded from `seed` and its position, so
+    /// the batch is reproducible and `parallel::par_biased_walks` returns the
+    /// same walks
+    pub fn biased_walks<W>(
+        &self,
+        walks_per_node: usize,
+        options: &WalkOptions,
+        weight: W,
+        seed: u64,
+    ) -> Vec<Vec<NodeId>>
+    where
+        W: Fn(NodeId, NodeId) -> u64,
+    {
+        let walker = Walker::new(self, options, weight);
+        (0..walks_per_node * self.nodes.len())
+            .map(|position| walker.walk_at(position, seed))
+            .collect()
+    }
+}
+
+/// Shared state for a batch of walks over one graph
+pub(crate) struct Walker<'a, T, W> {
+    graph: &'a Graph<T>,
+    options: WalkOptions,
+    weight: W,
+    // Sorted out-lists for the neighbor test of the node2vec bias; empty when
+    // the walk is unbiased
+    sorted: Vec<Vec<usize>>,
+}
+
+impl<'a, T, W: Fn(NodeId, NodeId) -> u64> Walker<'a, T, W> {
+    pub(crate) fn new(graph: &'a Graph<T>, options: &WalkOptions, weight: W) -> Self {
+        let biased = options.return_param != 1.0 || options.in_out_param != 1.0;
+        let sorted = if biased {
+            (0..graph.nodes.len())
+                .map(|v| graph.simple_neighbor_indices(v))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Walker {
+            graph,
+            options: *options,
+            weight,
+            sorted,
+        }
+    }
+
+    /// Walk `position` of a batch: from node position % n, with its own seed
+    pub(crate) fn walk_at(&self, position: usize, seed: u64) -> Vec<NodeId> {
+        let start = position % self.graph.nodes.len();
+        let mut rng = Rng::new(
+            Rng::new(seed ^ (position as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)).next_u64(),
+        );
+        self.walk(start, &mut rng)
+    }
+
+    fn walk(&self, start: usize, rng: &mut Rng) -> Vec<NodeId> {
+        let mut walk = Vec::with_capacity(self.options.length);
+        if self.options.length == 0 {
+            return walk;
+        }
+        walk.push(NodeId::new(start));
+        let (mut previous, mut current) = (None, start);
+        let mut chances = Vec::new();
+        while walk.len() < self.options.length {
+            let neighbors = &self.graph.nodes[current].neighbors;
+            chances.clear();
+            chances.extend(neighbors.iter().map(|&next| {
+                let weight = (self.weight)(NodeId::new(current), NodeId::new(next)) as f64;
+                weight * self.bias(previous, next)
+            }));
+            let total: f64 = chances.iter().sum();
+            if total <= 0.0 {
+                break;
+            }
+            let mut pick = rng.next_f64() * total;
+            // Fall back to the last positive option if rounding overshoots
+            let mut chosen = neighbors[chances.iter().rposition(|&c| c > 0.0).unwrap()];
+            for (&next, &chance) in neighbors.iter().zip(&chances) {
+                if pick < chance {
+                    chosen = next;
+                    break;
+                }
+                pick -= chance;
+            }
+            walk.push(NodeId::new(chosen));
+            (previous, current) = (Some(current), chosen);
+        }
+        walk
+    }
+
+    /// node2vec factor for stepping to `next` after coming from `previous`
+    fn bias(&self, previous: Option<usize>, next: usize) -> f64 {
+        let Some(previous) = previous.filter(|_| !self.sorted.is_empty()) else {
+            return 1.0;
+        };
+        if next == previous {
+            1.0 / self.options.return_param
+        } else if self.sorted[previous].binary_search(&next).is_ok() {
+            1.0
+        } else {
+            1.0 / self.options.in_out_param
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::VecDeque;
+
+use crate::base::GraphBase;
+use crate::graph::NodeId;
+use crate::traversal::Traversal;
+
+/// Marks the start entry, which has no parent, in the pending buffer
+const NO_PARENT: usize = usize::MAX;
+
+/// Buffers for running many traversals back to back without allocating for
+/// each one, as all-pairs computations do with a BFS per node. Visited nodes
+/// are stamped with the current run's number, so starting the next traversal
+/// clears the previous one's marks in constant time; the pending buffer keeps
+/// its capacity. Works with any `GraphBase` and grows to the largest graph
+/// traversed.
+#[derive(Debug, Clone, Default)]
+pub struct TraversalWorkspace {
+    stamps: Vec<u32>, // Run number that last visited each node
+    run: u32,
+    pending: VecDeque<(usize, usize, usize)>, // (node index, parent index, depth)
+}
+
+impl TraversalWorkspace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Workspace already sized for graphs of up to `nodes` nodes
+    pub fn with_capacity(nodes: usize) -> Self {
+        TraversalWorkspace {
+            stamps: vec![0; nodes],
+            run: 0,
+            pending: VecDeque::with_capacity(nodes),
+        }
+    }
+
+    /// Forget the last traversal: no node is visited afterwards. The
+    /// traversal functions call this themselves.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.run = self.run.wrapping_add(1);
+        if self.run == 0 {
+            // The run number wrapped around; old stamps could match again
+            self.stamps.fill(0);
+            self.run = 1;
+        }
+    }
+
+    /// Whether the last traversal visited `id`, counting only nodes it has
+    /// yielded so far
+    pub fn visited(&self, id: NodeId) -> bool {
+        self.stamps.get(id.index()) == Some(&self.run) && self.run != 0
+    }
+
+    fn start(&mut self, len: usize, start: NodeId) {
+        self.clear();
+        if self.stamps.len() < len {
+            self.stamps.resize(len, 0);
+        }
+        self.pending.push_back((start.index(), NO_PARENT, 0));
+    }
+
+    /// Mark a node as visited, returning false if it already was
+    fn insert(&mut self, index: usize) -> bool {
+        let stamp = &mut self.stamps[index];
+        if *stamp == self.run {
+            return false;
+        }
+        *stamp = self.run;
+        true
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        self.stamps[index] == self.run
+    }
+}
+
+/// BFS from `start` using the buffers of `workspace`, in the order
+/// `BfsIterator::new` visits. The workspace is cleared first and stays
+/// borrowed until the iterator is dropped.
+pub fn bfs_iter_with<'a, 'w, G: GraphBase>(
+    graph: &'a G,
+    start: NodeId,
+    workspace: &'w mut TraversalWorkspace,
+) -> WorkspaceBfs<'a, 'w, G> {
+    workspace.start(graph.node_count(), start);
+    WorkspaceBfs {
+        graph,
+        workspace,
+        parent: None,
+        depth: 0,
+    }
+}
+
+/// DFS from `start` using the buffers of `workspace`, in the order
+/// `DfsIterator::new` visits. The workspace is cleared first and stays
+/// borrowed until the iterator is dropped.
+pub fn dfs_iter_with<'a, 'w, G: GraphBase>(
+    graph: &'a G,
+    start: NodeId,
+    workspace: &'w mut TraversalWorkspace,
+) -> WorkspaceDfs<'a, 'w, G> {
+    workspace.start(graph.node_count(), start);
+    WorkspaceDfs {
+        graph,
+        workspace,
+        parent: None,
+        depth: 0,
+    }
+}
+
+/// BFS iterator returned by `bfs_iter_with`
+pub struct WorkspaceBfs<'a, 'w, G: GraphBase> {
+    graph: &'a G,
+    workspace: &'w mut TraversalWorkspace,
+    parent: Option<usize>,
+    depth: usize,
+}
+
+impl<G: GraphBase> WorkspaceBfs<'_, '_, G> {
+    /// Hop distance from the start of the node last yielded
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Whether `id` has been yielded
+    pub fn visited(&self, id: NodeId) -> bool {
+        self.workspace.visited(id)
+    }
+}
+
+impl<'a, G: GraphBase + 'a> Traversal<'a> for WorkspaceBfs<'a, '_, G>
+where
+    G::Value: 'a,
+{
+    type Value = G::Value;
+    type Graph = G;
+
+    fn graph(&self) -> &'a G {
+        self.graph
+    }
+
+    fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.workspace.pending.len()
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        while let Some((node_index, parent, depth)) = self.workspace.pending.pop_front() {
+            // Skip nodes already visited and ids past the graph, such as an
+            // out-of-range start
+            if node_index >= self.graph.node_count() || !self.workspace.insert(node_index) {
+                continue;
+            }
+
+            // Add all unvisited neighbors to the queue
+            for neighbor in self.graph.neighbors(NodeId::new(node_index)) {
+                let neighbor_index = neighbor.index();
+                if neighbor_index < self.graph.node_count()
+                    && !self.workspace.contains(neighbor_index)
+                {
+                    self.workspace
+                        .pending
+                        .push_back((neighbor_index, node_index, depth + 1));
+                }
+            }
+
+            self.parent = (parent != NO_PARENT).then_some(parent);
+            self.depth = depth;
+            return Some(node_index);
+        }
+        None
+    }
+}
+
+impl<'a, G: GraphBase + 'a> Iterator for WorkspaceBfs<'a, '_, G>
+where
+    G::Value: 'a,
+{
+    type Item = &'a G::Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+        self.next_index()
+            .map(|index| graph.node(NodeId::new(index)))
+    }
+}
+
+/// DFS iterator returned by `dfs_iter_with`
+pub struct WorkspaceDfs<'a, 'w, G: GraphBase> {
+    graph: &'a G,
+    workspace: &'w mut TraversalWorkspace,
+    parent: Option<usize>,
+    depth: usize,
+}
+
+impl<G: GraphBase> WorkspaceDfs<'_, '_, G> {
+    /// Depth in the DFS tree of the node last yielded
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Whether `id` has been yielded
+    pub fn visited(&self, id: NodeId) -> bool {
+        self.workspace.visited(id)
+    }
+}
+
+impl<'a, G: GraphBase + 'a> Traversal<'a> for WorkspaceDfs<'a, '_, G>
+where
+    G::Value: 'a,
+{
+    type Value = G::Value;
+    type Graph = G;
+
+    fn graph(&self) -> &'a G {
+        self.graph
+    }
+
+    fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.workspace.pending.len()
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        while let Some((node_index, parent, depth)) = self.workspace.pending.pop_back() {
+            // Skip nodes already visited and ids past the graph, such as an
+            // out-of-range start
+            if node_index >= self.graph.node_count() || !self.workspace.insert(node_index) {
+                continue;
+            }
+
+            // Add all unvisited neighbors to the stack (in reverse order)
+            for neighbor in self.graph.neighbors(NodeId::new(node_index)).rev() {
+                let neighbor_index = neighbor.index();
+                if neighbor_index < self.graph.node_count()
+                    && !self.workspace.contains(neighbor_index)
+                {
+                    self.workspace
+                        .pending
+                        .push_back((neighbor_index, node_index, depth + 1));
+                }
+            }
+
+            self.parent = (parent != NO_PARENT).then_some(parent);
+            self.depth = depth;
+            return Some(node_index);
+        }
+        None
+    }
+}
+
+impl<'a, G: GraphBase + 'a> Iterator for WorkspaceDfs<'a, '_, G>
+where
+    G::Value: 'a,
+{
+    type Item = &'a G::Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+        self.next_index()
+            .map(|index| graph.node(NodeId::new(index)))
+    }
+}